@@ -75,7 +75,7 @@ impl BinarySymmetricChannel {
     ///
     /// Panic if `prob` is not between 0 and 1.
     pub fn new(prob: f64) -> Self {
-        if 0.0 <= prob && prob <= 1.0 {
+        if (0.0..=1.0).contains(&prob) {
             Self {
                 prob,
                 log_likelyhood: (prob / (1.0 - prob)).log2(),
@@ -91,7 +91,7 @@ impl BinaryChannel for BinarySymmetricChannel {
         if output == GF2::B0 {
             self.log_likelyhood
         } else {
-            -1.0 * self.log_likelyhood
+            -self.log_likelyhood
         }
     }
 
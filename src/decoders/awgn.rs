@@ -0,0 +1,223 @@
+//! Soft decision decoding on the additive white Gaussian noise channel.
+
+use super::{AwgnChannel, Decoder, NoiseModel, SoftDecoder};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use crate::{BPResult, BscResult};
+use rand::Rng;
+use std::sync::Arc;
+
+/// A decoder for the additive white Gaussian noise channel with BPSK modulation that gives the
+/// channel log likelihood ratios to a belief propagation decoder.
+///
+/// Since the codes are linear, the all zero codeword is always sent and the errors are the
+/// channel log likelihood ratios sampled by an `AwgnChannel`. The decoding succeeds if the hard
+/// decision of the wrapped decoder is the all zero codeword. The crossover probability of the
+/// wrapped decoder isn't used.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// let channel = AwgnChannel::with_eb_n0_db_and_rate(6.0, 4.0 / 7.0);
+/// let mut decoder = AwgnDecoder::with_channel(channel)
+///     .wrapping(BscDecoder::with_crossover_prob(0.1))
+///     .for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![2.0; 7]).get_outcome(), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![-2.0; 7]).get_outcome(), BscResult::Failure);
+///
+/// let result = decoder.simulate_n_iterations(100);
+/// assert_eq!(result.get_n_iterations(), 100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AwgnDecoder<D> {
+    decoder: D,
+    channel: AwgnChannel,
+}
+
+impl AwgnDecoder<()> {
+    /// Creates a decoder sampling its random errors from `channel`.
+    pub fn with_channel(channel: AwgnChannel) -> Self {
+        Self {
+            decoder: (),
+            channel,
+        }
+    }
+
+    /// Wraps the belief propagation `decoder` used to decode the log likelihood ratios.
+    ///
+    /// The code of `decoder`, if any, is kept.
+    pub fn wrapping<D>(self, decoder: D) -> AwgnDecoder<D>
+    where
+        D: SoftDecoder<Code = ParityCheckMatrix>,
+    {
+        AwgnDecoder {
+            decoder,
+            channel: self.channel,
+        }
+    }
+}
+
+impl<D> AwgnDecoder<D> {
+    /// Returns the channel sampling the random errors.
+    pub fn get_channel(&self) -> AwgnChannel {
+        self.channel
+    }
+}
+
+impl<D> Decoder for AwgnDecoder<D>
+where
+    D: SoftDecoder<Code = ParityCheckMatrix>,
+{
+    type Error = Vec<f64>; // Channel log likelihood ratios.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.decoder = self.decoder.for_shared_code(code);
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        self.decoder.take_code()
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.decoder.get_n_bits()
+    }
+
+    // The decoding succeeds if the hard decision is the all zero codeword that was sent.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let result = self.decoder.decode_from_llrs(error);
+        let n_residual_errors = result
+            .get_hard_decision()
+            .iter()
+            .filter(|value| **value == GF2::B1)
+            .count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations: result.get_n_iterations(),
+            stopping_criterion: result.get_stopping_criterion(),
+            n_residual_errors,
+        }
+    }
+
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        self.channel.sample_error_with_rng(self.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        BscDecoder, CodeGenerator, MinSumDecoder, RegularLDPCCodeGenerator, StoppingCriterion,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // The probability that a standard normal variable is larger than `x`, integrated with
+    // Simpson's rule.
+    fn get_q_function_of(x: f64) -> f64 {
+        let n_steps = 100_000;
+        let step = 10.0 / n_steps as f64;
+        let density = |t: f64| (-t * t / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let sum: f64 = (0..=n_steps)
+            .map(|index| {
+                let weight = if index == 0 || index == n_steps {
+                    1.0
+                } else if index % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                weight * density(x + index as f64 * step)
+            })
+            .sum();
+        sum * step / 3.0
+    }
+
+    // Without checks, the hard decision is the sign of each channel log likelihood ratio, so
+    // the bit error rate is Q(sqrt(2 Eb/N0)).
+    #[test]
+    fn uncoded_bit_error_rate_is_the_q_function() {
+        let eb_n0_db = 6.0;
+        let expected = get_q_function_of((2.0 * 10_f64.powf(eb_n0_db / 10.0)).sqrt());
+        let mut decoder =
+            AwgnDecoder::with_channel(AwgnChannel::with_eb_n0_db_and_rate(eb_n0_db, 1.0))
+                .wrapping(BscDecoder::with_crossover_prob(0.1))
+                .for_code(ParityCheckMatrix::with_n_bits(100));
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let result = decoder.simulate_n_iterations_with_rng(10_000, &mut rng);
+
+        let n_bits = 1e6;
+        let std = (expected * (1.0 - expected) / n_bits).sqrt();
+        assert!((result.get_bit_error_rate() - expected).abs() < 4.0 * std);
+        assert_eq!(result.get_n_unconverged_decodings(), 0);
+    }
+
+    #[test]
+    fn coding_reduces_the_bit_error_rate() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 4, 6).generate_with_rng(&mut rng);
+        let rate = 1.0 - code.get_rank() as f64 / code.get_n_bits() as f64;
+        let channel = AwgnChannel::with_eb_n0_db_and_rate(4.0, rate);
+        let uncoded_n_errors: usize = (0..200)
+            .map(|_| {
+                channel
+                    .sample_error_with_rng(code.get_n_bits(), &mut rng)
+                    .iter()
+                    .filter(|llr| **llr < 0.0)
+                    .count()
+            })
+            .sum();
+        let mut decoder = AwgnDecoder::with_channel(channel)
+            .wrapping(MinSumDecoder::with_crossover_prob(0.1).with_max_iterations(20))
+            .for_code(code);
+        let result = decoder.simulate_n_iterations_with_rng(200, &mut rng);
+        let uncoded_rate = uncoded_n_errors as f64 / (200 * decoder.get_n_bits()) as f64;
+        assert!(result.get_bit_error_rate() < uncoded_rate);
+    }
+
+    #[test]
+    fn decoding_reports_the_belief_propagation_result() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = AwgnDecoder::with_channel(AwgnChannel::with_noise_std(1.0))
+            .wrapping(BscDecoder::with_crossover_prob(0.1))
+            .for_code(code.clone());
+
+        let result = decoder.decode(&vec![1.0, -0.5, 2.0]);
+        assert_eq!(result.get_outcome(), BscResult::Success);
+        assert_eq!(
+            result.get_stopping_criterion(),
+            StoppingCriterion::SatisfiedSyndrome
+        );
+        assert_eq!(result.get_n_iterations(), 1);
+
+        let result = decoder.decode(&vec![-1.0, -0.5, 1.0]);
+        assert_eq!(result.get_outcome(), BscResult::Failure);
+        assert_eq!(result.get_n_residual_errors(), 3);
+        assert_eq!(decoder.take_code(), code);
+    }
+
+    #[test]
+    fn parallel_simulations_are_reproducible() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let decoder = AwgnDecoder::with_channel(AwgnChannel::with_noise_std(0.8))
+            .wrapping(BscDecoder::with_crossover_prob(0.1))
+            .for_code(code);
+        let first = decoder
+            .simulate_n_iterations_in_parallel_with_rng(1000, &mut ChaCha8Rng::seed_from_u64(123));
+        let second = decoder
+            .simulate_n_iterations_in_parallel_with_rng(1000, &mut ChaCha8Rng::seed_from_u64(123));
+        assert_eq!(first, second);
+        assert!(first.get_n_failures() > 0);
+    }
+}
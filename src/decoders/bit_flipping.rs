@@ -0,0 +1,457 @@
+//! A bit flipping decoder for the binary symmetric channel.
+
+use super::{into_owned, Bsc, Decoder, NoiseModel};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use crate::{BPResult, BscResult, StoppingCriterion};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Which bits a `BitFlippingDecoder` flips at each iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlipCriterion {
+    /// Flips a single bit with the largest metric. Ties are broken in favor of the bit with
+    /// the smallest index.
+    Maximum,
+    /// Flips all the bits whose metric is at least the given value.
+    Threshold(f64),
+}
+
+/// Decoder for the binary symmetric channel flipping the bits involved in many unsatisfied
+/// checks.
+///
+/// At each iteration, the decoder computes a metric for each bit and flips some bits according
+/// to a `FlipCriterion`. For `decode`, the metric of a bit is its number of unsatisfied checks.
+/// The decoder stops as soon as the syndrome is zero, after the maximum number of iterations or
+/// when no bit reaches the threshold since every later iteration would be the same.
+///
+/// The decoding only updates the syndrome of the flipped bits, so it is much faster than belief
+/// propagation. This makes it a cheap filter before more expensive decoders, see
+/// `BestCodeFinder::prefiltering_with_bit_flipping`.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+///     vec![0, 1, 2],
+///     vec![3, 4, 5],
+///     vec![6, 7, 8],
+///     vec![0, 3, 6],
+///     vec![1, 4, 7],
+///     vec![2, 5, 8],
+/// ]);
+/// let mut decoder = BitFlippingDecoder::with_crossover_prob(0.05).for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![4]).get_outcome(), BscResult::Success);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitFlippingDecoder {
+    code: Arc<ParityCheckMatrix>,
+    crossover_prob: f64,
+    max_iterations: usize,
+    criterion: FlipCriterion,
+}
+
+impl BitFlippingDecoder {
+    /// Creates a bit flipping decoder for the binary symmetric channel that flips each bit with
+    /// probability `crossover_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations and flips a single bit with the
+    /// maximum metric at each iteration.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        Self {
+            code: Arc::new(ParityCheckMatrix::new()),
+            crossover_prob,
+            max_iterations: 10,
+            criterion: FlipCriterion::Maximum,
+        }
+    }
+
+    /// Sets the maximum number of iterations.
+    ///
+    /// The decoder stops earlier as soon as the syndrome is zero. With 0 iterations, the
+    /// decision is the channel output.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets which bits are flipped at each iteration.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the threshold of `criterion` is not finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BitFlippingDecoder::with_crossover_prob(0.1)
+    ///     .with_flip_criterion(FlipCriterion::Threshold(2.0))
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_flip_criterion(mut self, criterion: FlipCriterion) -> Self {
+        if let FlipCriterion::Threshold(threshold) = criterion {
+            if !threshold.is_finite() {
+                panic!("invalid threshold");
+            }
+        }
+        self.criterion = criterion;
+        self
+    }
+
+    /// Decodes a `received` word and returns the decision together with the number of
+    /// iterations done and the reason why the decoder stopped.
+    ///
+    /// The metric of a bit is its number of unsatisfied checks. Unlike `decode`, this doesn't
+    /// assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = BitFlippingDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let (decision, n_iterations, stopping_criterion) =
+    ///     decoder.decode_hard(&[GF2::B1, GF2::B0, GF2::B1]);
+    ///
+    /// assert_eq!(decision, vec![GF2::B1; 3]);
+    /// assert_eq!(n_iterations, 1);
+    /// assert_eq!(stopping_criterion, StoppingCriterion::SatisfiedSyndrome);
+    /// ```
+    pub fn decode_hard(&self, received: &[GF2]) -> (Vec<GF2>, usize, StoppingCriterion) {
+        self.flip_bits_of(received, |checks, syndrome| {
+            checks
+                .iter()
+                .filter(|check| syndrome[**check] == GF2::B1)
+                .count() as f64
+        })
+    }
+
+    /// Decodes a `received` word using the `reliabilities` of its bits, such as the magnitudes
+    /// of their log likelihood ratios, and returns the decision together with the number of
+    /// iterations done and the reason why the decoder stopped.
+    ///
+    /// This is the weighted bit flipping algorithm. The weight of a check is the smallest
+    /// reliability of its bits. The metric of a bit is the sum of the weights of its
+    /// unsatisfied checks minus the sum of the weights of its satisfied checks. Thus, even with
+    /// equal reliabilities, the satisfied checks of a bit count against flipping it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` or of `reliabilities` is not the number of bits of
+    /// the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = BitFlippingDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let received = [GF2::B0, GF2::B0, GF2::B1];
+    /// let (decision, _, _) = decoder.decode_weighted(&received, &[2.0, 2.0, 0.5]);
+    ///
+    /// assert_eq!(decision, vec![GF2::B0; 3]);
+    /// ```
+    pub fn decode_weighted(
+        &self,
+        received: &[GF2],
+        reliabilities: &[f64],
+    ) -> (Vec<GF2>, usize, StoppingCriterion) {
+        if reliabilities.len() != self.code.get_n_bits() {
+            panic!("reliabilities don't have the right length");
+        }
+        let weights: Vec<f64> = self
+            .code
+            .checks_iter()
+            .map(|check| {
+                check
+                    .iter()
+                    .map(|bit| reliabilities[*bit])
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        self.flip_bits_of(received, |checks, syndrome| {
+            checks
+                .iter()
+                .map(|check| {
+                    if syndrome[*check] == GF2::B1 {
+                        weights[*check]
+                    } else {
+                        -weights[*check]
+                    }
+                })
+                .sum()
+        })
+    }
+
+    // The metric of a bit is computed from its checks and the current syndrome.
+    fn flip_bits_of<F>(
+        &self,
+        received: &[GF2],
+        get_metric: F,
+    ) -> (Vec<GF2>, usize, StoppingCriterion)
+    where
+        F: Fn(&[usize], &[GF2]) -> f64,
+    {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let mut decision = received.to_vec();
+        let mut syndrome = self.code.get_syndrome_of(&decision);
+        let mut metrics = vec![0.0; self.code.get_n_bits()];
+        let mut n_iterations = 0;
+        let stopping_criterion = loop {
+            if syndrome.iter().all(|value| *value == GF2::B0) {
+                break StoppingCriterion::SatisfiedSyndrome;
+            }
+            if n_iterations == self.max_iterations {
+                break StoppingCriterion::MaxIterations;
+            }
+            for (bit, metric) in metrics.iter_mut().enumerate() {
+                *metric = get_metric(self.get_checks_of(bit), &syndrome);
+            }
+            let n_flips = match self.criterion {
+                FlipCriterion::Maximum => {
+                    let bit = get_first_maximum_of(&metrics);
+                    self.flip(bit, &mut decision, &mut syndrome);
+                    1
+                }
+                FlipCriterion::Threshold(threshold) => {
+                    let mut n_flips = 0;
+                    for (bit, metric) in metrics.iter().enumerate() {
+                        if *metric >= threshold {
+                            self.flip(bit, &mut decision, &mut syndrome);
+                            n_flips += 1;
+                        }
+                    }
+                    n_flips
+                }
+            };
+            if n_flips == 0 {
+                break StoppingCriterion::Stalled;
+            }
+            n_iterations += 1;
+        };
+        (decision, n_iterations, stopping_criterion)
+    }
+
+    fn flip(&self, bit: usize, decision: &mut [GF2], syndrome: &mut [GF2]) {
+        decision[bit] = decision[bit] + GF2::B1;
+        for check in self.get_checks_of(bit) {
+            syndrome[*check] = syndrome[*check] + GF2::B1;
+        }
+    }
+
+    fn get_checks_of(&self, bit: usize) -> &[usize] {
+        self.code.get_checks_adjacent_to_bit(bit).unwrap_or(&[])
+    }
+}
+
+// The syndrome is not zero, so there is at least one bit.
+fn get_first_maximum_of(metrics: &[f64]) -> usize {
+    let mut maximum = 0;
+    for (bit, metric) in metrics.iter().enumerate() {
+        if *metric > metrics[maximum] {
+            maximum = bit;
+        }
+    }
+    maximum
+}
+
+impl Decoder for BitFlippingDecoder {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.code = code;
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        into_owned(std::mem::take(&mut self.code))
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the decision after the last iteration is the all zero codeword
+    // that was sent.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let mut received = vec![GF2::B0; self.code.get_n_bits()];
+        error.iter().for_each(|bit| received[*bit] = GF2::B1);
+        let (decision, n_iterations, stopping_criterion) = self.decode_hard(&received);
+        let n_residual_errors = decision.iter().filter(|value| **value == GF2::B1).count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations,
+            stopping_criterion,
+            n_residual_errors,
+        }
+    }
+
+    // Flip random bits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        Bsc::with_crossover_prob(self.crossover_prob)
+            .sample_error_with_rng(self.code.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CodeGenerator, RegularLDPCCodeGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    fn received_with_flips(n_bits: usize, flipped_bits: &[usize]) -> Vec<GF2> {
+        let mut received = vec![GF2::B0; n_bits];
+        flipped_bits.iter().for_each(|bit| received[*bit] = GF2::B1);
+        received
+    }
+
+    fn get_ones_of(decision: &[GF2]) -> Vec<usize> {
+        (0..decision.len())
+            .filter(|bit| decision[*bit] == GF2::B1)
+            .collect()
+    }
+
+    // Bit 0 is in the 3 unsatisfied checks while the other bits are in at most 2 of them.
+    #[test]
+    fn bit_with_most_unsatisfied_checks_is_flipped() {
+        let mut decoder = BitFlippingDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        let result = decoder.decode(&vec![0]);
+        assert_eq!(result.get_outcome(), BscResult::Success);
+        assert_eq!(result.get_n_iterations(), 1);
+    }
+
+    // A flip of bit 4 only makes check 0 unsatisfied, so bits 0, 1, 2 and 4 are tied and bit
+    // 0 is flipped. Then, checks 1 and 2 are unsatisfied and bit 0 is flipped back since it is
+    // the first of the two bits in both checks. The decoder alternates between these states.
+    #[test]
+    fn ties_are_broken_by_the_smallest_index() {
+        let decoder = BitFlippingDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        for &max_iterations in &[3, 4] {
+            let decoder = decoder.clone().with_max_iterations(max_iterations);
+            let (decision, n_iterations, stopping_criterion) =
+                decoder.decode_hard(&received_with_flips(7, &[4]));
+            let expected = if max_iterations % 2 == 0 {
+                vec![4]
+            } else {
+                vec![0, 4]
+            };
+            assert_eq!(get_ones_of(&decision), expected);
+            assert_eq!(n_iterations, max_iterations);
+            assert_eq!(stopping_criterion, StoppingCriterion::MaxIterations);
+        }
+    }
+
+    // With a threshold of 2, bits 0 and 1 are flipped when checks 0 and 1 are unsatisfied.
+    // Then, all checks are unsatisfied and bits 0 to 3 are flipped, which gives a codeword.
+    #[test]
+    fn all_bits_reaching_the_threshold_are_flipped() {
+        let decoder = BitFlippingDecoder::with_crossover_prob(0.1)
+            .with_flip_criterion(FlipCriterion::Threshold(2.0))
+            .for_code(hamming_code());
+        let (decision, n_iterations, stopping_criterion) =
+            decoder.decode_hard(&received_with_flips(7, &[1]));
+        assert_eq!(get_ones_of(&decision), vec![1, 2, 3]);
+        assert_eq!(n_iterations, 2);
+        assert_eq!(stopping_criterion, StoppingCriterion::SatisfiedSyndrome);
+    }
+
+    // With a threshold of 3, no bit is flipped when only check 0 is unsatisfied.
+    #[test]
+    fn decoder_stops_when_no_bit_reaches_the_threshold() {
+        let decoder = BitFlippingDecoder::with_crossover_prob(0.1)
+            .with_flip_criterion(FlipCriterion::Threshold(3.0))
+            .for_code(hamming_code());
+        let (decision, n_iterations, stopping_criterion) =
+            decoder.decode_hard(&received_with_flips(7, &[4]));
+        assert_eq!(get_ones_of(&decision), vec![4]);
+        assert_eq!(n_iterations, 0);
+        assert_eq!(stopping_criterion, StoppingCriterion::Stalled);
+    }
+
+    // After a flip of bit 2, bits 1 and 2 are in the only unsatisfied check. Counting the
+    // unsatisfied checks flips bit 1, while the weighted metric counts the satisfied check of
+    // bit 1 against it.
+    #[test]
+    fn weighted_metric_counts_the_satisfied_checks() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = BitFlippingDecoder::with_crossover_prob(0.1).for_code(code);
+        let received = received_with_flips(3, &[2]);
+
+        let (decision, n_iterations, _) = decoder.decode_hard(&received);
+        assert_eq!(decision, vec![GF2::B1; 3]);
+        assert_eq!(n_iterations, 2);
+
+        let (decision, n_iterations, _) = decoder.decode_weighted(&received, &[1.0; 3]);
+        assert_eq!(decision, vec![GF2::B0; 3]);
+        assert_eq!(n_iterations, 1);
+    }
+
+    // The weights of checks 0, 1 and 2 are 0.5, 2.0 and 1.0. With bit 0 flipped, all checks
+    // are unsatisfied and the metrics are 3.5 for bit 0, 2.5 for bit 1, 1.5 for bit 2 and 3.0
+    // for bit 3. With a threshold of 3.0, bits 0 and 3 are flipped.
+    #[test]
+    fn weights_are_the_smallest_reliabilities_of_the_checks() {
+        let decoder = BitFlippingDecoder::with_crossover_prob(0.1)
+            .with_flip_criterion(FlipCriterion::Threshold(3.0))
+            .with_max_iterations(1)
+            .for_code(hamming_code());
+        let reliabilities = [4.0, 4.0, 4.0, 4.0, 0.5, 2.0, 1.0];
+        let (decision, _, _) =
+            decoder.decode_weighted(&received_with_flips(7, &[0]), &reliabilities);
+        assert_eq!(get_ones_of(&decision), vec![3]);
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 20, 6).generate_with_rng(&mut rng.clone());
+        let mut decoder = BitFlippingDecoder::with_crossover_prob(0.02).for_code(code);
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+        assert!(result_0.get_n_successes() > 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn infinite_threshold_panics() {
+        BitFlippingDecoder::with_crossover_prob(0.1)
+            .with_flip_criterion(FlipCriterion::Threshold(f64::INFINITY));
+    }
+}
@@ -0,0 +1,616 @@
+//! A sum-product belief propagation decoder for the binary symmetric channel.
+
+use super::message_passing::{
+    get_channel_llrs_of, get_channel_llrs_of_given, get_channel_llrs_of_received, CheckRule,
+    Iterations, TannerGraph,
+};
+use super::{into_owned, Bsc, Decoder, NoiseModel, Schedule, SoftDecoder};
+use crate::{BPResult, SoftDecodingResult};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Decoder for the binary symmetric channel using sum-product belief propagation.
+///
+/// Since the codes are linear, the all zero codeword is always sent and the errors are the
+/// positions of the flipped bits. As usual, a positive log likelihood ratio means that a bit
+/// is more likely to be 0.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+/// let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), BscResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BscDecoder {
+    code: Arc<ParityCheckMatrix>,
+    crossover_prob: f64,
+    iterations: Iterations,
+    graph: TannerGraph,
+}
+
+impl BscDecoder {
+    /// Creates a decoder for the binary symmetric channel that flips each bit with probability
+    /// `crossover_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        let code = Arc::new(ParityCheckMatrix::new());
+        Self {
+            graph: TannerGraph::from(&code),
+            code,
+            crossover_prob,
+            iterations: Iterations::with_max_iterations(10),
+        }
+    }
+
+    /// Sets the maximum number of iterations of belief propagation.
+    ///
+    /// The decoder stops before as soon as the hard decision has a zero syndrome. With 0
+    /// iterations, the hard decision is the channel output.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.iterations.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the order in which the messages are updated.
+    ///
+    /// If not specified, default to the flooding schedule. The maximum number of iterations
+    /// is a number of full sweeps over the checks for every schedule.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.iterations.schedule = schedule;
+        self
+    }
+
+    /// Sets the damping of the messages from the bits to the checks.
+    ///
+    /// At each iteration, a new message `m` from a bit to a check is replaced by
+    /// `damping * m + (1 - damping) * previous` where `previous` is the message sent at the
+    /// previous iteration. This slows down the updates and reduces the oscillations of the
+    /// messages on codes with short cycles. By default, the damping is 1.0 which means no
+    /// damping.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `damping` is not in the range (0.0, 1.0].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BscDecoder::with_crossover_prob(0.1)
+    ///     .with_damping(0.5)
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_damping(mut self, damping: f64) -> Self {
+        if damping <= 0.0 || damping > 1.0 {
+            panic!("damping is not in (0, 1]");
+        }
+        self.iterations.damping = damping;
+        self
+    }
+
+    /// Decodes a `received` word and returns the posterior log likelihood ratios together with
+    /// the hard decision.
+    ///
+    /// Unlike `decode`, this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_soft(&[GF2::B0, GF2::B1, GF2::B0]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert!(result.has_converged());
+    /// ```
+    pub fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_received(received, self.crossover_prob);
+        self.graph
+            .decode_soft_with(&SumProduct, &self.code, &channel_llrs, self.iterations)
+    }
+
+    /// Decodes the channel log likelihood ratios `llrs` of a received word, such as the output
+    /// of a demodulator, and returns the posterior log likelihood ratios together with the
+    /// hard decision.
+    ///
+    /// The crossover probability of the decoder isn't used. A positive log likelihood ratio
+    /// means that a bit is more likely to be 0. Infinite values are clamped like the messages.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `llrs` is not the number of bits of the code or if some of them
+    /// are NaN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_from_llrs(&[1.5, -0.5, f64::INFINITY]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert!(result.has_converged());
+    /// ```
+    pub fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        if llrs.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_given(llrs);
+        self.graph
+            .decode_soft_with(&SumProduct, &self.code, &channel_llrs, self.iterations)
+    }
+
+    /// Estimates an error with the given `syndrome`.
+    ///
+    /// The decoder starts from the all zero word biased by the crossover probability and the
+    /// checks with a non zero syndrome enforce an odd parity. Returns the hard decision if its
+    /// syndrome is `syndrome` before the maximum number of iterations or `None` otherwise.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `syndrome` is not the number of checks of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+    ///
+    /// let error = decoder.decode_syndrome(&[GF2::B1, GF2::B0]);
+    /// assert_eq!(error, Some(vec![GF2::B1, GF2::B0, GF2::B0]));
+    /// ```
+    pub fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        if syndrome.len() != self.code.get_n_checks() {
+            panic!("syndrome doesn't have the right length");
+        }
+        let prior_llrs = get_channel_llrs_of(&[], self.code.get_n_bits(), self.crossover_prob);
+        let result = self.graph.decode_syndrome_with(
+            &SumProduct,
+            &self.code,
+            syndrome,
+            &prior_llrs,
+            self.iterations,
+        );
+        if result.has_converged() {
+            Some(result.decision)
+        } else {
+            None
+        }
+    }
+}
+
+// Each check sends to a bit 2 atanh of the product of tanh(m / 2) over the messages m of the
+// other bits. The products are split in prefixes and suffixes to avoid dividing by 0.
+pub(super) struct SumProduct;
+
+impl CheckRule for SumProduct {
+    fn update_check_to_bit(&self, bit_to_check: &[f64], check_to_bit: &mut [f64]) {
+        let tanhs: Vec<f64> = bit_to_check
+            .iter()
+            .map(|message| (message / 2.0).tanh())
+            .collect();
+        let mut suffixes = vec![1.0; tanhs.len() + 1];
+        for index in (0..tanhs.len()).rev() {
+            suffixes[index] = suffixes[index + 1] * tanhs[index];
+        }
+        let mut prefix = 1.0;
+        for (index, tanh) in tanhs.iter().enumerate() {
+            let product: f64 = prefix * suffixes[index + 1];
+            check_to_bit[index] = 2.0 * product.atanh();
+            prefix *= tanh;
+        }
+    }
+}
+
+impl SoftDecoder for BscDecoder {
+    fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        BscDecoder::decode_soft(self, received)
+    }
+
+    fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        BscDecoder::decode_from_llrs(self, llrs)
+    }
+
+    fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        BscDecoder::decode_syndrome(self, syndrome)
+    }
+}
+
+impl Decoder for BscDecoder {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.graph = TannerGraph::from(&code);
+        self.code = code;
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        let code = std::mem::take(&mut self.code);
+        self.graph = TannerGraph::from(&self.code);
+        into_owned(code)
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the hard decision after the last iteration is the all zero
+    // codeword that was sent. It fails if the hard decision has a non zero syndrome or if it
+    // is an other codeword.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let channel_llrs = get_channel_llrs_of(error, self.code.get_n_bits(), self.crossover_prob);
+        self.graph
+            .decode_with(&SumProduct, &self.code, &channel_llrs, self.iterations)
+    }
+
+    // Flip random bits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        Bsc::with_crossover_prob(self.crossover_prob)
+            .sample_error_with_rng(self.code.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BscResult, CodeGenerator, RegularLDPCCodeGenerator, StoppingCriterion};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    #[test]
+    fn repetition_code_corrects_minority_flips() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+        let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+
+        assert_eq!(decoder.decode(&vec![]).get_outcome(), BscResult::Success);
+        for i in 0..5 {
+            assert_eq!(decoder.decode(&vec![i]).get_outcome(), BscResult::Success);
+            for j in (i + 1)..5 {
+                assert_eq!(decoder.decode(&vec![i, j]).get_outcome(), BscResult::Success);
+            }
+        }
+        assert_eq!(decoder.decode(&vec![0, 2, 4]).get_outcome(), BscResult::Failure);
+    }
+
+    // The checks of the rows and the columns of a 3 by 3 grid of bits. The Tanner graph has no
+    // cycle of length 4 and each bit is in a row and a column.
+    fn product_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0, 3, 6],
+            vec![1, 4, 7],
+            vec![2, 5, 8],
+        ])
+    }
+
+    #[test]
+    fn product_code_corrects_single_flips() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.05).for_code(product_code());
+
+        for i in 0..9 {
+            assert_eq!(decoder.decode(&vec![i]).get_outcome(), BscResult::Success);
+        }
+        assert_eq!(decoder.decode(&vec![0, 4]).get_outcome(), BscResult::Failure);
+    }
+
+    #[test]
+    fn without_iterations_only_the_error_free_word_is_decoded() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(0)
+            .for_code(hamming_code());
+
+        let result = decoder.decode(&vec![]);
+        assert_eq!(result.get_outcome(), BscResult::Success);
+        assert_eq!(result.get_n_iterations(), 0);
+        assert_eq!(
+            result.get_stopping_criterion(),
+            StoppingCriterion::SatisfiedSyndrome
+        );
+
+        let result = decoder.decode(&vec![3]);
+        assert_eq!(result.get_outcome(), BscResult::Failure);
+        assert_eq!(result.get_n_iterations(), 0);
+        assert_eq!(
+            result.get_stopping_criterion(),
+            StoppingCriterion::MaxIterations
+        );
+    }
+
+    #[test]
+    fn error_free_words_terminate_without_iterations() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        let result = decoder.decode(&vec![]);
+        assert_eq!(result.get_n_iterations(), 0);
+        assert!(result.has_converged());
+    }
+
+    #[test]
+    fn syndromes_of_single_flips_give_back_the_flips() {
+        let code = product_code();
+        let decoder = BscDecoder::with_crossover_prob(0.05).for_code(code.clone());
+        for bit in 0..9 {
+            let mut error = vec![GF2::B0; 9];
+            error[bit] = GF2::B1;
+            let syndrome = code.get_syndrome_of(&error);
+            assert_eq!(decoder.decode_syndrome(&syndrome), Some(error));
+        }
+        assert_eq!(decoder.decode_syndrome(&[GF2::B0; 6]), Some(vec![GF2::B0; 9]));
+    }
+
+    // The estimates always have the target syndrome, even if it is not the syndrome of the
+    // most likely error.
+    #[test]
+    fn estimated_errors_reproduce_the_syndrome() {
+        let code = hamming_code();
+        let decoder = BscDecoder::with_crossover_prob(0.05).for_code(code.clone());
+        for index in 0..8 {
+            let syndrome: Vec<GF2> = (0..3)
+                .map(|check| if index >> check & 1 == 1 { GF2::B1 } else { GF2::B0 })
+                .collect();
+            let error = decoder.decode_syndrome(&syndrome).unwrap();
+            assert_eq!(code.get_syndrome_of(&error), syndrome);
+        }
+    }
+
+    #[test]
+    fn unsolved_syndromes_give_none() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = BscDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(0)
+            .for_code(code);
+        assert_eq!(decoder.decode_syndrome(&[GF2::B1, GF2::B0]), None);
+
+        // Bits without checks can't produce a syndrome.
+        let code = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![0], vec![0]]);
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+        assert_eq!(decoder.decode_syndrome(&[GF2::B1, GF2::B0]), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn syndromes_of_wrong_length_panic() {
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        decoder.decode_syndrome(&[GF2::B0; 7]);
+    }
+
+    #[test]
+    fn decoding_reports_the_iterations_until_convergence() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.05).for_code(product_code());
+        let result = decoder.decode(&vec![4]);
+        assert!(result.has_converged());
+        assert_eq!(result.get_n_iterations(), 1);
+
+        let mut decoder = decoder.with_max_iterations(3);
+        let result = decoder.decode(&vec![0, 4]);
+        assert_eq!(result.get_outcome(), BscResult::Failure);
+        assert_eq!(result.get_n_iterations(), 3);
+        assert!(!result.has_converged());
+    }
+
+    #[test]
+    fn messages_stay_finite_for_tiny_and_extreme_probabilities() {
+        for &prob in &[0.0, 1e-300, 1e-12, 0.5] {
+            let mut decoder = BscDecoder::with_crossover_prob(prob).for_code(product_code());
+            let channel_llrs = get_channel_llrs_of(&[2], 9, prob);
+            let bit_to_check = decoder.graph.get_messages_from_bits(&channel_llrs);
+            let mut check_to_bit = vec![0.0; decoder.graph.get_n_edges()];
+            decoder
+                .graph
+                .update_check_to_bit_with(&SumProduct, &bit_to_check, &mut check_to_bit);
+            assert!(check_to_bit.iter().all(|message| message.is_finite()));
+
+            if prob > 0.0 && prob < 0.5 {
+                assert_eq!(decoder.decode(&vec![2]).get_outcome(), BscResult::Success);
+            }
+        }
+    }
+
+    #[test]
+    fn soft_decoding_recovers_a_non_zero_codeword() {
+        let mut codeword = vec![GF2::B0; 9];
+        [0, 1, 3, 4].iter().for_each(|bit| codeword[*bit] = GF2::B1);
+        let mut received = codeword.clone();
+        received[8] = GF2::B1;
+
+        for &prob in &[1e-12, 0.05] {
+            let decoder = BscDecoder::with_crossover_prob(prob).for_code(product_code());
+            let result = decoder.decode_soft(&received);
+            assert_eq!(result.get_hard_decision(), &codeword[..]);
+            assert!(result.has_converged());
+            assert!(result.get_posteriors().iter().all(|llr| llr.is_finite()));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn soft_decoding_a_word_of_wrong_length_panics() {
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(product_code());
+        decoder.decode_soft(&[GF2::B0; 8]);
+    }
+
+    #[test]
+    fn decoding_the_channel_llrs_agrees_with_soft_decoding() {
+        let mut received = vec![GF2::B0; 9];
+        [0, 1, 3, 4, 8].iter().for_each(|bit| received[*bit] = GF2::B1);
+        let decoder = BscDecoder::with_crossover_prob(0.05).for_code(product_code());
+        let llr = (0.95_f64 / 0.05).ln();
+        let llrs: Vec<f64> = received
+            .iter()
+            .map(|value| if *value == GF2::B0 { llr } else { -llr })
+            .collect();
+        assert_eq!(decoder.decode_from_llrs(&llrs), decoder.decode_soft(&received));
+    }
+
+    #[test]
+    fn infinite_llrs_are_clamped() {
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        let mut llrs = vec![f64::INFINITY; 7];
+        llrs[0] = f64::NEG_INFINITY;
+        llrs[6] = -1.0;
+        let result = decoder.decode_from_llrs(&llrs);
+        assert!(result.get_posteriors().iter().all(|llr| llr.is_finite()));
+        assert!(!result.has_converged());
+        assert_eq!(result.get_hard_decision()[0], GF2::B1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_llrs_panic() {
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        decoder.decode_from_llrs(&[0.0, 1.0, f64::NAN, 1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn llrs_of_wrong_length_panic() {
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        decoder.decode_from_llrs(&[1.0; 6]);
+    }
+
+    #[test]
+    fn taking_the_code_clears_the_edges() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        assert_eq!(decoder.take_code(), hamming_code());
+        assert_eq!(decoder.graph.get_n_edges(), 0);
+        assert_eq!(decoder.decode(&vec![]).get_outcome(), BscResult::Success);
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+
+        let result = decoder.simulate_until_n_events_are_found_with_rng(10, &mut rng.clone());
+        assert!(result.get_failure_rate() > 0.0);
+        assert!(result.get_failure_rate() < 1.0);
+    }
+
+    #[test]
+    fn layered_schedule_converges_in_fewer_iterations_than_flooding() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 20, 6).generate_with_rng(&mut rng.clone());
+        let mut flooding = BscDecoder::with_crossover_prob(0.02)
+            .with_max_iterations(20)
+            .for_code(code);
+        let mut layered = flooding.clone().with_schedule(Schedule::LayeredByCheck);
+
+        let flooding_result = flooding.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let layered_result = layered.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert!(
+            layered_result.get_mean_n_decoding_iterations()
+                <= flooding_result.get_mean_n_decoding_iterations()
+        );
+        assert!(layered_result.get_n_failures() <= flooding_result.get_n_failures());
+    }
+
+    #[test]
+    fn layered_schedule_corrects_single_flips() {
+        let mut decoder = BscDecoder::with_crossover_prob(0.05)
+            .with_schedule(Schedule::LayeredByCheck)
+            .for_code(product_code());
+
+        for i in 0..9 {
+            let result = decoder.decode(&vec![i]);
+            assert_eq!(result.get_outcome(), BscResult::Success);
+            assert_eq!(result.get_n_iterations(), 1);
+        }
+    }
+
+    // Dense checks on few bits, so the Tanner graph has many cycles of length 4.
+    fn cycle_heavy_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(8).with_checks(vec![
+            vec![0, 1, 2, 3, 4],
+            vec![2, 3, 4, 5, 6],
+            vec![0, 1, 5, 6, 7],
+            vec![0, 2, 4, 6, 7],
+            vec![1, 3, 4, 5, 7],
+        ])
+    }
+
+    #[test]
+    fn damping_reduces_the_failure_rate_on_a_code_with_short_cycles() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let decoder = BscDecoder::with_crossover_prob(0.05)
+            .with_max_iterations(30)
+            .for_code(cycle_heavy_code());
+        let undamped = decoder
+            .clone()
+            .simulate_n_iterations_with_rng(2000, &mut rng.clone())
+            .get_failure_rate();
+
+        for &damping in &[0.9, 0.75, 0.5] {
+            let mut damped = decoder.clone().with_damping(damping);
+            let result = damped.simulate_n_iterations_with_rng(2000, &mut rng.clone());
+            assert!(result.get_failure_rate() < 0.75 * undamped);
+        }
+    }
+
+    #[test]
+    fn no_damping_is_the_default() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = BscDecoder::with_crossover_prob(0.05).for_code(cycle_heavy_code());
+        let result = decoder.simulate_n_iterations_with_rng(500, &mut rng.clone());
+        let mut decoder = decoder.with_damping(1.0);
+        assert_eq!(
+            decoder.simulate_n_iterations_with_rng(500, &mut rng.clone()),
+            result
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_damping_panics() {
+        BscDecoder::with_crossover_prob(0.1).with_damping(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_probability_panics() {
+        BscDecoder::with_crossover_prob(1.5);
+    }
+}
@@ -0,0 +1,329 @@
+//! A quantum erasure decoder for CSS codes.
+
+use super::{into_owned, Decoder};
+use crate::ErasureDecodingCache;
+use crate::ErasureResult;
+use crate::ParityCheckMatrix;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Decoder for the quantum erasure channel with a CSS code given by its X and Z checks.
+///
+/// Each erased qubit is replaced by a random Pauli. The X and Z components of the error are
+/// decoded independently with the Z and X checks. The decoding fails if the erased qubits
+/// support a logical operator. That is, an operator commuting with all the checks which is
+/// not a product of checks.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let checks = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+///     vec![0, 1, 2, 4],
+///     vec![0, 1, 3, 5],
+///     vec![0, 2, 3, 6],
+/// ]);
+/// let mut decoder = CssErasureDecoder::with_prob(0.25).for_css_code(checks.clone(), checks);
+///
+/// assert_eq!(decoder.decode(&vec![0, 1]), ErasureResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 2, 5]), ErasureResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CssErasureDecoder {
+    x_checks: ParityCheckMatrix,
+    z_checks: ParityCheckMatrix,
+    erasure_prob: f64,
+    x_rank: usize,
+    z_rank: usize,
+    x_cache: ErasureDecodingCache,
+    z_cache: ErasureDecodingCache,
+}
+
+impl CssErasureDecoder {
+    /// Creates a CSS erasure decoder.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `erasure_prob` is not between 0.0 and 1.0.
+    pub fn with_prob(erasure_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&erasure_prob) {
+            panic!("invalid probability");
+        }
+        let checks = ParityCheckMatrix::new();
+        Self {
+            erasure_prob,
+            x_rank: 0,
+            z_rank: 0,
+            x_cache: checks.get_erasure_decoding_cache(),
+            z_cache: checks.get_erasure_decoding_cache(),
+            x_checks: checks.clone(),
+            z_checks: checks,
+        }
+    }
+
+    /// Creates a new decoder similar to `self` for the CSS code with the given `x_checks` and
+    /// `z_checks`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `x_checks` and `z_checks` have different numbers of bits or if some X check
+    /// and some Z check share an odd number of bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let (x_checks, z_checks) = ParityCheckMatrix::repetition_code_with_n_bits(3)
+    ///     .get_hypergraph_product_with(&ParityCheckMatrix::repetition_code_with_n_bits(3));
+    /// let decoder = CssErasureDecoder::with_prob(0.1).for_css_code(x_checks, z_checks);
+    /// ```
+    pub fn for_css_code(self, x_checks: ParityCheckMatrix, z_checks: ParityCheckMatrix) -> Self {
+        if !x_checks.is_orthogonal_to(&z_checks) {
+            panic!("checks don't commute");
+        }
+        Self {
+            x_rank: x_checks.get_rank(),
+            z_rank: z_checks.get_rank(),
+            x_cache: x_checks.get_erasure_decoding_cache(),
+            z_cache: z_checks.get_erasure_decoding_cache(),
+            x_checks,
+            z_checks,
+            ..self
+        }
+    }
+
+    /// Returns the number of qubits of the code.
+    pub fn get_n_qubits(&self) -> usize {
+        self.x_checks.get_n_bits()
+    }
+
+    fn next_qubit_is_erased<R: Rng>(&self, rng: &mut R) -> bool {
+        rng.gen::<f64>() < self.erasure_prob
+    }
+
+    // The X operators on the erased qubits commuting with the Z checks form the kernel of the Z
+    // checks restricted to these qubits. The X checks on the erased qubits form a subspace of
+    // this kernel. A logical operator exists if the subspace is smaller than the kernel.
+    fn erasure_supports_x_logical(&mut self, qubits: &[usize]) -> bool {
+        if self.z_cache.can_correct_erasure_of(qubits) {
+            return false;
+        }
+        supports_logical(&self.x_checks, self.x_rank, &self.z_checks, qubits)
+    }
+
+    fn erasure_supports_z_logical(&mut self, qubits: &[usize]) -> bool {
+        if self.x_cache.can_correct_erasure_of(qubits) {
+            return false;
+        }
+        supports_logical(&self.z_checks, self.z_rank, &self.x_checks, qubits)
+    }
+}
+
+fn supports_logical(
+    checks: &ParityCheckMatrix,
+    rank: usize,
+    dual_checks: &ParityCheckMatrix,
+    qubits: &[usize],
+) -> bool {
+    let kernel_dimension = qubits.len() - dual_checks.keep_bits(qubits).get_rank();
+    let checks_dimension = rank - checks.without_bits(qubits).get_rank();
+    checks_dimension < kernel_dimension
+}
+
+impl Decoder for CssErasureDecoder {
+    type Error = Vec<usize>; // Positions of erased qubits.
+    type Result = ErasureResult;
+    type Code = (ParityCheckMatrix, ParityCheckMatrix); // X and Z checks.
+
+    // The checks are copied if they are shared since they are stored separately.
+    fn for_shared_code(self, code: Arc<Self::Code>) -> Self {
+        let (x_checks, z_checks) = into_owned(code);
+        self.for_css_code(x_checks, z_checks)
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        self.x_rank = 0;
+        self.z_rank = 0;
+        (
+            std::mem::take(&mut self.x_checks),
+            std::mem::take(&mut self.z_checks),
+        )
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.get_n_qubits()
+    }
+
+    // The panics of the erasure decoding cache apply for out of bounds qubits. Duplicated
+    // qubits are only counted once.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let mut qubits = error.clone();
+        qubits.sort_unstable();
+        qubits.dedup();
+        if self.erasure_supports_x_logical(&qubits) || self.erasure_supports_z_logical(&qubits) {
+            ErasureResult::Failure
+        } else {
+            ErasureResult::Success
+        }
+    }
+
+    // Erase random qubits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        (0..self.get_n_qubits())
+            .filter(|_| self.next_qubit_is_erased(rng))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GF4Stabilizers;
+    use crate::Pauli::{X, Z};
+    use crate::QuantumErasureDecoder;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    fn steane_code_decoder() -> CssErasureDecoder {
+        CssErasureDecoder::with_prob(0.25).for_css_code(hamming_code(), hamming_code())
+    }
+
+    fn get_qubits_of(pattern: usize, n_qubits: usize) -> Vec<usize> {
+        (0..n_qubits)
+            .filter(|qubit| pattern & (1 << qubit) != 0)
+            .collect()
+    }
+
+    #[test]
+    fn steane_code() {
+        let mut decoder = steane_code_decoder();
+
+        assert_eq!(decoder.decode(&vec![]), ErasureResult::Success);
+        for i in 0..=6 {
+            assert_eq!(decoder.decode(&vec![i]), ErasureResult::Success);
+            for j in (i + 1)..=6 {
+                assert_eq!(decoder.decode(&vec![i, j]), ErasureResult::Success);
+            }
+        }
+
+        assert_eq!(decoder.decode(&vec![0, 1, 2]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![2, 4, 5]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![0, 1, 4]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![3, 4, 5]), ErasureResult::Success);
+
+        assert_eq!(decoder.decode(&vec![2, 4, 6]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![1, 2, 3]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 3, 4]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 2, 5]), ErasureResult::Failure);
+
+        assert_eq!(
+            decoder.decode(&vec![0, 1, 2, 3, 4, 5, 6]),
+            ErasureResult::Failure
+        );
+    }
+
+    #[test]
+    fn duplicated_qubits_are_counted_once() {
+        let mut decoder = steane_code_decoder();
+
+        assert_eq!(decoder.decode(&vec![3, 0, 3]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![5, 2, 0, 5, 2]), ErasureResult::Failure);
+    }
+
+    #[test]
+    fn steane_code_agrees_with_the_quantum_erasure_decoder() {
+        let stabilizers = GF4Stabilizers::from_sparse_paulis(
+            vec![
+                vec![(X, 0), (X, 1), (X, 2), (X, 4)],
+                vec![(X, 0), (X, 1), (X, 3), (X, 5)],
+                vec![(X, 0), (X, 2), (X, 3), (X, 6)],
+                vec![(Z, 0), (Z, 1), (Z, 2), (Z, 4)],
+                vec![(Z, 0), (Z, 1), (Z, 3), (Z, 5)],
+                vec![(Z, 0), (Z, 2), (Z, 3), (Z, 6)],
+            ],
+            7,
+        );
+        let mut quantum_decoder = QuantumErasureDecoder::new(stabilizers, 0.25);
+        let mut decoder = steane_code_decoder();
+
+        for pattern in 0..(1 << 7) {
+            let qubits = get_qubits_of(pattern, 7);
+            assert_eq!(decoder.decode(&qubits), quantum_decoder.decode(&qubits));
+        }
+    }
+
+    #[test]
+    fn shor_code_with_different_x_and_z_checks() {
+        let x_checks = ParityCheckMatrix::with_n_bits(9)
+            .with_checks(vec![vec![0, 1, 2, 3, 4, 5], vec![3, 4, 5, 6, 7, 8]]);
+        let z_checks = ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+            vec![0, 1],
+            vec![1, 2],
+            vec![3, 4],
+            vec![4, 5],
+            vec![6, 7],
+            vec![7, 8],
+        ]);
+        let mut decoder = CssErasureDecoder::with_prob(0.25).for_css_code(x_checks, z_checks);
+
+        for i in 0..9 {
+            for j in (i + 1)..9 {
+                assert_eq!(decoder.decode(&vec![i, j]), ErasureResult::Success);
+            }
+        }
+        assert_eq!(decoder.decode(&vec![0, 1, 2]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 3, 6]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 1, 3, 4]), ErasureResult::Success);
+    }
+
+    #[test]
+    fn code_is_taken_back() {
+        let mut decoder = steane_code_decoder();
+        assert_eq!(decoder.get_n_qubits(), 7);
+
+        assert_eq!(decoder.take_code(), (hamming_code(), hamming_code()));
+        assert_eq!(decoder.get_n_qubits(), 0);
+    }
+
+    #[test]
+    fn simulation_is_reproducible() {
+        let mut decoder = steane_code_decoder();
+        let result =
+            decoder.simulate_n_iterations_with_rng(1000, &mut ChaCha8Rng::seed_from_u64(123));
+        let other_result =
+            decoder.simulate_n_iterations_with_rng(1000, &mut ChaCha8Rng::seed_from_u64(123));
+
+        assert_eq!(result, other_result);
+        assert!(result.get_n_failures() > 0);
+        assert!(result.get_n_successes() > result.get_n_failures());
+
+        let result = decoder
+            .simulate_until_n_events_are_found_with_rng(10, &mut ChaCha8Rng::seed_from_u64(123));
+        assert!(result.get_n_failures() >= 10);
+        assert!(result.get_n_successes() >= 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn anticommuting_checks_panic() {
+        let x_checks = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1]]);
+        let z_checks = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![1, 2]]);
+        CssErasureDecoder::with_prob(0.25).for_css_code(x_checks, z_checks);
+    }
+
+    #[test]
+    #[should_panic]
+    fn different_numbers_of_qubits_panic() {
+        let x_checks = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1]]);
+        let z_checks = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1]]);
+        CssErasureDecoder::with_prob(0.25).for_css_code(x_checks, z_checks);
+    }
+}
@@ -0,0 +1,426 @@
+//! A belief propagation decoder for CSS codes over the depolarizing channel.
+
+use super::bsc::SumProduct;
+use super::message_passing::{get_channel_llrs_of, Iterations, TannerGraph};
+use super::{into_owned, Decoder, DepolarizingResult, QuantumOutcome, Schedule};
+use crate::{add_checks, ParityCheckMatrix, Pauli, GF2};
+use rand::Rng;
+use std::sync::Arc;
+
+/// How the X and Z components of an error are decoded.
+///
+/// The depolarizing channel correlates the components since a Y error flips both of them. Only
+/// the independent decoding is available for now. The other variants are reserved for a joint
+/// decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum XZDecoding {
+    /// Decodes each component on its own as if it was flipped with probability 2p/3, ignoring
+    /// the correlations.
+    Independent,
+}
+
+/// Decoder for the depolarizing channel with a CSS code given by its X and Z checks.
+///
+/// Each qubit is affected by a X, Y or Z error, each with probability p/3. The X component of
+/// the error is estimated from the syndrome of the Z checks with sum-product belief
+/// propagation and the Z component from the syndrome of the X checks. A limitation is that the
+/// components are decoded independently, see `XZDecoding`.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let (x_checks, z_checks) = ParityCheckMatrix::repetition_code_with_n_bits(3)
+///     .get_hypergraph_product_with(&ParityCheckMatrix::repetition_code_with_n_bits(3));
+/// let mut decoder = DepolarizingDecoder::with_prob(0.01).for_css_code(x_checks, z_checks);
+///
+/// let result = decoder.decode(&vec![(4, Pauli::Y)]);
+/// assert_eq!(result.get_outcome(), QuantumOutcome::Success);
+///
+/// let result = decoder.decode(&vec![(0, Pauli::Z), (3, Pauli::Z), (6, Pauli::Z)]);
+/// assert_eq!(result.get_outcome(), QuantumOutcome::LogicalFailure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DepolarizingDecoder {
+    x_checks: ParityCheckMatrix,
+    z_checks: ParityCheckMatrix,
+    x_graph: TannerGraph,
+    z_graph: TannerGraph,
+    x_stabilizers: RowSpace,
+    z_stabilizers: RowSpace,
+    error_prob: f64,
+    iterations: Iterations,
+    xz_decoding: XZDecoding,
+}
+
+impl DepolarizingDecoder {
+    /// Creates a decoder for the depolarizing channel that affects each qubit with probability
+    /// `error_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations for each component.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `error_prob` is not between 0.0 and 1.0.
+    pub fn with_prob(error_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&error_prob) {
+            panic!("invalid probability");
+        }
+        let checks = ParityCheckMatrix::new();
+        Self {
+            x_graph: TannerGraph::from(&checks),
+            z_graph: TannerGraph::from(&checks),
+            x_stabilizers: RowSpace::from(&checks),
+            z_stabilizers: RowSpace::from(&checks),
+            x_checks: checks.clone(),
+            z_checks: checks,
+            error_prob,
+            iterations: Iterations::with_max_iterations(10),
+            xz_decoding: XZDecoding::Independent,
+        }
+    }
+
+    /// Sets the maximum number of iterations of belief propagation for each component.
+    ///
+    /// The decoder of a component stops before as soon as its hard decision has the measured
+    /// syndrome.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.iterations.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the order in which the messages are updated by the decoder of each component.
+    ///
+    /// If not specified, default to the flooding schedule.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.iterations.schedule = schedule;
+        self
+    }
+
+    /// Sets how the X and Z components of the errors are decoded.
+    ///
+    /// If not specified, default to independent decoding.
+    pub fn with_xz_decoding(mut self, xz_decoding: XZDecoding) -> Self {
+        self.xz_decoding = xz_decoding;
+        self
+    }
+
+    /// Creates a new decoder similar to `self` for the CSS code with the given `x_checks` and
+    /// `z_checks`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `x_checks` and `z_checks` have different numbers of bits or if some X check
+    /// and some Z check share an odd number of bits.
+    pub fn for_css_code(self, x_checks: ParityCheckMatrix, z_checks: ParityCheckMatrix) -> Self {
+        if !x_checks.is_orthogonal_to(&z_checks) {
+            panic!("checks don't commute");
+        }
+        Self {
+            x_graph: TannerGraph::from(&x_checks),
+            z_graph: TannerGraph::from(&z_checks),
+            x_stabilizers: RowSpace::from(&x_checks),
+            z_stabilizers: RowSpace::from(&z_checks),
+            x_checks,
+            z_checks,
+            ..self
+        }
+    }
+
+    /// Returns the number of qubits of the code.
+    pub fn get_n_qubits(&self) -> usize {
+        self.x_checks.get_n_bits()
+    }
+
+    // A component is flipped by 2 of the 3 Paulis.
+    fn get_component_prior_llrs(&self) -> Vec<f64> {
+        get_channel_llrs_of(&[], self.get_n_qubits(), 2.0 * self.error_prob / 3.0)
+    }
+
+    // The X component of an error is detected by the Z checks and is harmless if it is a product
+    // of X checks, and conversely for the Z component.
+    fn decode_component(
+        &self,
+        graph: &TannerGraph,
+        checks: &ParityCheckMatrix,
+        stabilizers: &RowSpace,
+        flips: &[usize],
+        prior_llrs: &[f64],
+    ) -> DepolarizingResult {
+        let mut error = vec![GF2::B0; checks.get_n_bits()];
+        flips.iter().for_each(|qubit| error[*qubit] = GF2::B1);
+        let syndrome = checks.get_syndrome_of(&error);
+        let result = graph.decode_syndrome_with(
+            &SumProduct,
+            checks,
+            &syndrome,
+            prior_llrs,
+            self.iterations,
+        );
+        let outcome = if !result.has_converged() {
+            QuantumOutcome::DetectedFailure
+        } else {
+            let residual: Vec<usize> = result
+                .get_hard_decision()
+                .iter()
+                .zip(error.iter())
+                .enumerate()
+                .filter(|(_, (estimated, actual))| estimated != actual)
+                .map(|(qubit, _)| qubit)
+                .collect();
+            if stabilizers.contains(&residual) {
+                QuantumOutcome::Success
+            } else {
+                QuantumOutcome::LogicalFailure
+            }
+        };
+        DepolarizingResult {
+            outcome,
+            n_iterations: result.get_n_iterations(),
+        }
+    }
+
+    fn get_random_pauli_with_rng<R: Rng>(&self, rng: &mut R) -> Pauli {
+        let sample = rng.gen::<f64>();
+        if sample >= self.error_prob {
+            Pauli::I
+        } else if sample < self.error_prob / 3.0 {
+            Pauli::X
+        } else if sample < 2.0 * self.error_prob / 3.0 {
+            Pauli::Y
+        } else {
+            Pauli::Z
+        }
+    }
+}
+
+// A detected failure of a component is reported even if the other has a logical failure since
+// the decoder knows that it failed.
+fn combine(x_result: DepolarizingResult, z_result: DepolarizingResult) -> DepolarizingResult {
+    use QuantumOutcome::*;
+    let outcome = match (x_result.outcome, z_result.outcome) {
+        (DetectedFailure, _) | (_, DetectedFailure) => DetectedFailure,
+        (LogicalFailure, _) | (_, LogicalFailure) => LogicalFailure,
+        (Success, Success) => Success,
+    };
+    DepolarizingResult {
+        outcome,
+        n_iterations: x_result.n_iterations + z_result.n_iterations,
+    }
+}
+
+// The row space of a matrix given by its reduced row echelon form. Since each pivot bit is only
+// in its row, a vector in the row space is the sum of the rows of the pivots it contains.
+#[derive(Debug, Clone)]
+struct RowSpace {
+    reduced: ParityCheckMatrix,
+    pivots: Vec<usize>,
+}
+
+impl RowSpace {
+    fn from(matrix: &ParityCheckMatrix) -> Self {
+        let (reduced, pivots) = matrix.get_reduced_row_echelon_form();
+        Self { reduced, pivots }
+    }
+
+    // The bits of the vector must be sorted.
+    fn contains(&self, bits: &[usize]) -> bool {
+        let sum = self
+            .reduced
+            .checks_iter()
+            .zip(self.pivots.iter())
+            .filter(|(_, pivot)| bits.binary_search(pivot).is_ok())
+            .fold(Vec::new(), |sum, (row, _)| add_checks(&sum, row.as_ref()));
+        sum == bits
+    }
+}
+
+impl Decoder for DepolarizingDecoder {
+    type Error = Vec<(usize, Pauli)>; // Qubits with their Paulis.
+    type Result = DepolarizingResult;
+    type Code = (ParityCheckMatrix, ParityCheckMatrix); // X and Z checks.
+
+    // The checks are copied if they are shared since they are stored separately.
+    fn for_shared_code(self, code: Arc<Self::Code>) -> Self {
+        let (x_checks, z_checks) = into_owned(code);
+        self.for_css_code(x_checks, z_checks)
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        let code = (
+            std::mem::take(&mut self.x_checks),
+            std::mem::take(&mut self.z_checks),
+        );
+        self.x_graph = TannerGraph::from(&self.x_checks);
+        self.z_graph = TannerGraph::from(&self.z_checks);
+        self.x_stabilizers = RowSpace::from(&self.x_checks);
+        self.z_stabilizers = RowSpace::from(&self.z_checks);
+        code
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.get_n_qubits()
+    }
+
+    // Panics if some qubits are out of bounds.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let x_flips: Vec<usize> = error
+            .iter()
+            .filter(|(_, pauli)| *pauli == Pauli::X || *pauli == Pauli::Y)
+            .map(|(qubit, _)| *qubit)
+            .collect();
+        let z_flips: Vec<usize> = error
+            .iter()
+            .filter(|(_, pauli)| *pauli == Pauli::Z || *pauli == Pauli::Y)
+            .map(|(qubit, _)| *qubit)
+            .collect();
+        let prior_llrs = self.get_component_prior_llrs();
+        match self.xz_decoding {
+            XZDecoding::Independent => combine(
+                self.decode_component(
+                    &self.z_graph,
+                    &self.z_checks,
+                    &self.x_stabilizers,
+                    &x_flips,
+                    &prior_llrs,
+                ),
+                self.decode_component(
+                    &self.x_graph,
+                    &self.x_checks,
+                    &self.z_stabilizers,
+                    &z_flips,
+                    &prior_llrs,
+                ),
+            ),
+        }
+    }
+
+    // Apply a random Pauli to each qubit with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        (0..self.get_n_qubits())
+            .map(|qubit| (qubit, self.get_random_pauli_with_rng(rng)))
+            .filter(|(_, pauli)| *pauli != Pauli::I)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DecodingResult;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    fn steane_code_decoder() -> DepolarizingDecoder {
+        DepolarizingDecoder::with_prob(0.01).for_css_code(hamming_code(), hamming_code())
+    }
+
+    fn surface_code_decoder() -> DepolarizingDecoder {
+        let (x_checks, z_checks) = ParityCheckMatrix::repetition_code_with_n_bits(3)
+            .get_hypergraph_product_with(&ParityCheckMatrix::repetition_code_with_n_bits(3));
+        DepolarizingDecoder::with_prob(0.01).for_css_code(x_checks, z_checks)
+    }
+
+    #[test]
+    fn stabilizers_are_not_failures() {
+        let mut decoder = steane_code_decoder();
+        let error = vec![(0, Pauli::Y), (1, Pauli::Y), (2, Pauli::Y), (4, Pauli::Y)];
+
+        let result = decoder.decode(&error);
+        assert_eq!(result.get_outcome(), QuantumOutcome::Success);
+        assert_eq!(result.get_n_iterations(), 0);
+    }
+
+    #[test]
+    fn logical_operators_are_logical_failures() {
+        let mut decoder = steane_code_decoder();
+        for pauli in &[Pauli::X, Pauli::Y, Pauli::Z] {
+            let error = vec![(2, *pauli), (4, *pauli), (6, *pauli)];
+            let result = decoder.decode(&error);
+            assert_eq!(result.get_outcome(), QuantumOutcome::LogicalFailure);
+            assert!(result.is_failure());
+        }
+    }
+
+    #[test]
+    fn without_iterations_errors_are_detected_failures() {
+        let mut decoder = steane_code_decoder().with_max_iterations(0);
+
+        assert!(decoder.decode(&vec![]).is_success());
+        let result = decoder.decode(&vec![(3, Pauli::Z)]);
+        assert_eq!(result.get_outcome(), QuantumOutcome::DetectedFailure);
+        assert_eq!(result.get_n_iterations(), 0);
+    }
+
+    #[test]
+    fn surface_code_corrects_single_qubit_errors() {
+        let mut decoder = surface_code_decoder();
+
+        for qubit in 0..decoder.get_n_qubits() {
+            for pauli in &[Pauli::X, Pauli::Y, Pauli::Z] {
+                let result = decoder.decode(&vec![(qubit, *pauli)]);
+                assert_eq!(result.get_outcome(), QuantumOutcome::Success);
+            }
+        }
+    }
+
+    #[test]
+    fn rows_of_the_reduced_form_span_the_stabilizers() {
+        let stabilizers = RowSpace::from(&hamming_code());
+
+        assert!(stabilizers.contains(&[]));
+        assert!(stabilizers.contains(&[0, 1, 2, 4]));
+        assert!(stabilizers.contains(&[1, 2, 5, 6]));
+        assert!(!stabilizers.contains(&[2, 4, 6]));
+        assert!(!stabilizers.contains(&[0]));
+    }
+
+    #[test]
+    fn random_errors_follow_the_depolarizing_channel() {
+        let decoder =
+            DepolarizingDecoder::with_prob(0.3).for_css_code(hamming_code(), hamming_code());
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let mut counts = [0; 4];
+        for _ in 0..1000 {
+            for (_, pauli) in decoder.get_random_error_with_rng(&mut rng) {
+                counts[pauli as usize] += 1;
+            }
+        }
+        assert_eq!(counts[Pauli::I as usize], 0);
+        for pauli in &[Pauli::X, Pauli::Y, Pauli::Z] {
+            assert!((counts[*pauli as usize] as f64 - 700.0).abs() < 100.0);
+        }
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = surface_code_decoder();
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+
+        let mut decoder = DepolarizingDecoder::with_prob(0.2).for_code(decoder.take_code());
+        let result = decoder.simulate_until_n_events_are_found_with_rng(10, &mut rng.clone());
+        assert!(result.get_failure_rate() > 0.0);
+        assert!(result.get_failure_rate() < 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn anticommuting_checks_panic() {
+        let x_checks = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1]]);
+        let z_checks = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![1, 2]]);
+        DepolarizingDecoder::with_prob(0.1).for_css_code(x_checks, z_checks);
+    }
+}
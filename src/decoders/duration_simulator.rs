@@ -0,0 +1,100 @@
+use super::n_iterations_simulator::BATCH_SIZE;
+use super::{Decoder, SimulationResult};
+use crate::SeedSequence;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::time::{Duration, Instant};
+
+// The errors are sampled by `sample_error` from the decoder and a random number generator.
+// The elapsed time is checked after each batch of errors, so the budget can be exceeded by
+// the time of one batch.
+pub(super) struct DurationSimulator<'a, D, S> {
+    decoder: &'a mut D,
+    sample_error: S,
+    result: SimulationResult,
+}
+
+impl<'a, D, S> DurationSimulator<'a, D, S>
+where
+    D: Decoder,
+    S: Fn(&D, &mut ChaCha8Rng) -> D::Error,
+{
+    pub(super) fn from(decoder: &'a mut D, sample_error: S) -> Self {
+        Self {
+            decoder,
+            sample_error,
+            result: SimulationResult::new(),
+        }
+    }
+
+    pub(super) fn simulate_for_duration_with_rng<R: Rng>(
+        mut self,
+        budget: Duration,
+        rng: &mut R,
+    ) -> Self {
+        let start = Instant::now();
+        let mut result = SimulationResult::new();
+        let n_bits = self.decoder.get_n_bits();
+        let mut errors = Vec::with_capacity(BATCH_SIZE);
+        let seeds = SeedSequence::from_rng(rng);
+        let mut n_iterations = 0;
+        while start.elapsed() < budget {
+            errors.clear();
+            for index in n_iterations..n_iterations + BATCH_SIZE as u64 {
+                let mut rng = seeds.get_rng(index);
+                errors.push((self.sample_error)(self.decoder, &mut rng));
+            }
+            n_iterations += BATCH_SIZE as u64;
+            self.decoder
+                .decode_batch(&errors)
+                .into_iter()
+                .for_each(|decoding_result| {
+                    result.add_decoding_result_on_n_bits(decoding_result, n_bits)
+                });
+        }
+        self.result = result;
+        self
+    }
+
+    pub(super) fn get_result(self) -> SimulationResult {
+        self.result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{sample_from_decoder, ErasureDecoder};
+    use super::*;
+    use crate::ParityCheckMatrix;
+    use rand::SeedableRng;
+
+    #[test]
+    fn simulation_runs_whole_batches_until_the_budget_is_spent() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let budget = Duration::from_millis(20);
+
+        let start = Instant::now();
+        let result = DurationSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_for_duration_with_rng(budget, &mut rng)
+            .get_result();
+
+        assert!(start.elapsed() >= budget);
+        assert!(result.get_n_iterations() > 0);
+        assert_eq!(result.get_n_iterations() as usize % BATCH_SIZE, 0);
+    }
+
+    #[test]
+    fn empty_budget_runs_no_iterations() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+
+        let result = DurationSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_for_duration_with_rng(Duration::from_secs(0), &mut rng)
+            .get_result();
+
+        assert_eq!(result, SimulationResult::new());
+    }
+}
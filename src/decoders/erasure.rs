@@ -1,10 +1,40 @@
 //! A classical erasure decoder.
 
-use super::{Decoder, DecodingResult};
-use crate::ErasureResult;
-use crate::Ressources;
+use super::peeling::Peeler;
+use super::{
+    into_owned, BurstErasure, Decoder, DecodingResult, ErasureDecodingResult, NoiseModel,
+    PairedErasure, PerBitErasure, PerformanceCurve, ResidualSetCounter, SimulationResult,
+    UniformErasure,
+};
+use crate::ErasureDecodingCache;
 use crate::ParityCheckMatrix;
-use rand::Rng;
+use crate::SeedSequence;
+use crate::GF2;
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// The strategy used by an `ErasureDecoder` to recover the erased bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErasureStrategy {
+    /// Recovers the erased bits whenever it is possible. That is, when the columns of the
+    /// erased bits are linearly independent.
+    MaximumLikelihood,
+    /// Repeatedly recovers the only erased bit of some check. This is faster, but fails when
+    /// the erased bits contain a stopping set, even if maximum likelihood decoding succeeds.
+    Peeling,
+}
+
+// The noise sampling the erasures, either with the same probability for all bits, bit by bit,
+// in bursts or in pairs.
+#[derive(Debug, Clone, PartialEq)]
+enum ErasureNoise {
+    Uniform(UniformErasure),
+    PerBit(PerBitErasure),
+    Burst(BurstErasure),
+    Paired(PairedErasure),
+}
 
 /// Decoder for classical erasure channel.
 ///
@@ -12,15 +42,18 @@ use rand::Rng;
 ///
 /// ```
 /// # use believer::*;
-/// let code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
-/// let decoder = ErasureDecoder::with_prob(0.25).for_code(code);
-/// decoder.decode(&decoder.get_random_error());
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+/// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+/// let error = decoder.get_random_error();
+/// decoder.decode(&error);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ErasureDecoder {
-    code: ParityCheckMatrix,
-    erasure_prob: f64,
-    ressources: Ressources,
+    code: Arc<ParityCheckMatrix>,
+    noise: ErasureNoise,
+    strategy: ErasureStrategy,
+    cache: ErasureDecodingCache,
+    peeler: Peeler,
 }
 
 impl ErasureDecoder {
@@ -30,97 +63,659 @@ impl ErasureDecoder {
     ///
     /// Panics if `erasure_prob` is not between 0.0 and 1.0.
     pub fn with_prob(erasure_prob: f64) -> Self {
-        if erasure_prob < 0.0 || erasure_prob > 1.0 {
-            panic!("invalid probability");
-        }
+        Self::with_noise_model(ErasureNoise::Uniform(UniformErasure::with_prob(erasure_prob)))
+    }
+
+    /// Creates an erasure decoder where bit `i` is erased with probability `erasure_probs[i]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0.0 and 1.0. Using the decoder for a code
+    /// panics if the number of probabilities is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = ErasureDecoder::with_probs(vec![1.0, 0.0, 1.0]).for_code(code);
+    ///
+    /// assert_eq!(decoder.get_random_error(), vec![0, 2]);
+    /// ```
+    pub fn with_probs(erasure_probs: Vec<f64>) -> Self {
+        Self::with_noise_model(ErasureNoise::PerBit(PerBitErasure::with_probs(erasure_probs)))
+    }
+
+    /// Creates an erasure decoder where the bits are erased in bursts sampled from `noise`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.1, 4.0);
+    /// let decoder = ErasureDecoder::with_burst_erasure(noise).for_code(code);
+    ///
+    /// assert_eq!(decoder.get_erasure_prob(), noise.get_erasure_prob());
+    /// ```
+    pub fn with_burst_erasure(noise: BurstErasure) -> Self {
+        Self::with_noise_model(ErasureNoise::Burst(noise))
+    }
 
+    /// Creates an erasure decoder where pairs of bits are erased together as sampled from
+    /// `noise`.
+    ///
+    /// # Panic
+    ///
+    /// Using the decoder for a code panics if the number of bits of `noise` is not the number
+    /// of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+    /// let noise = PairedErasure::with_pairs(4, vec![(0, 3)], 1.0);
+    /// let decoder = ErasureDecoder::with_paired_erasure(noise).for_code(code);
+    ///
+    /// assert_eq!(decoder.get_random_error(), vec![0, 3]);
+    /// ```
+    pub fn with_paired_erasure(noise: PairedErasure) -> Self {
+        Self::with_noise_model(ErasureNoise::Paired(noise))
+    }
+
+    fn with_noise_model(noise: ErasureNoise) -> Self {
+        let code = Arc::new(ParityCheckMatrix::new());
         Self {
-            erasure_prob,
-            code: ParityCheckMatrix::new(),
-            ressources: Ressources{
-                rank_mtx: None,
-                sum_vec: None,
-            },
-            
+            noise,
+            strategy: ErasureStrategy::MaximumLikelihood,
+            cache: code.get_erasure_decoding_cache(),
+            peeler: Peeler::from(&code),
+            code,
         }
     }
 
-    fn next_bit_is_erased<R: Rng>(&self, rng: &mut R) -> bool {
-        rng.gen::<f64>() < self.erasure_prob
+    /// Returns the erasure probability of the bits. For per bit probabilities, this is their
+    /// mean and for bursts or pairs, this is the expected fraction of erased bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// assert_eq!(ErasureDecoder::with_prob(0.25).get_erasure_prob(), 0.25);
+    /// assert_eq!(ErasureDecoder::with_probs(vec![0.0, 0.5]).get_erasure_prob(), 0.25);
+    /// ```
+    pub fn get_erasure_prob(&self) -> f64 {
+        match &self.noise {
+            ErasureNoise::Uniform(noise) => noise.get_prob(),
+            ErasureNoise::PerBit(noise) => {
+                let probs = noise.get_probs();
+                probs.iter().sum::<f64>() / probs.len() as f64
+            }
+            ErasureNoise::Burst(noise) => noise.get_erasure_prob(),
+            ErasureNoise::Paired(noise) => noise.get_erasure_prob(),
+        }
     }
-}
 
-impl Decoder for ErasureDecoder {
-    type Error = Vec<usize>; // Positions of erased bits.
-    type Result = ErasureResult;
-    type Code = ParityCheckMatrix;
+    /// Sets the `strategy` used to recover the erased bits.
+    ///
+    /// If not specified, default to maximum likelihood.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    /// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+    /// assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), ErasureResult::Success);
+    ///
+    /// let mut decoder = decoder.with_strategy(ErasureStrategy::Peeling);
+    /// assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), ErasureResult::Failure);
+    /// ```
+    pub fn with_strategy(mut self, strategy: ErasureStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Recovers the erased bits of a `received` word where the erased bits are `None`.
+    ///
+    /// Returns the corrected word or `None` if the erased bits can't be recovered with the
+    /// strategy of `self` or if the received bits are not part of a codeword.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    /// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+    ///
+    /// let (b0, b1) = (Some(GF2::B0), Some(GF2::B1));
+    /// let received = vec![None, b1, None, b0, b1, b0, None];
+    /// let corrected = vec![GF2::B1, GF2::B1, GF2::B1, GF2::B0, GF2::B1, GF2::B0, GF2::B0];
+    /// assert_eq!(decoder.decode_received(&received), Some(corrected));
+    ///
+    /// let received = vec![None, None, None, None, None, None, Some(GF2::B0)];
+    /// assert_eq!(decoder.decode_received(&received), None);
+    /// ```
+    pub fn decode_received(&mut self, received: &[Option<GF2>]) -> Option<Vec<GF2>> {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let erased_bits: Vec<usize> = (0..received.len())
+            .filter(|bit| received[*bit].is_none())
+            .collect();
+        if self.decode(&erased_bits).is_failure() {
+            return None;
+        }
+        let mut word: Vec<GF2> = received
+            .iter()
+            .map(|value| value.unwrap_or(GF2::B0))
+            .collect();
+        let syndrome = self.code.get_syndrome_of(&word);
+        let erased_values = self.solve_erased_values(&erased_bits, &syndrome)?;
+        erased_bits
+            .iter()
+            .zip(erased_values)
+            .for_each(|(bit, value)| word[*bit] = value);
+        Some(word)
+    }
+
+    /// Estimates an error supported on the `erased_bits` with the given `syndrome`.
+    ///
+    /// Unlike the belief propagation decoders, the decoder needs the erased bits since the
+    /// other bits are known to be correct. Returns the error if the erased bits can be
+    /// recovered with the strategy of `self` and some error on them has the given syndrome, or
+    /// `None` otherwise.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `syndrome` is not the number of checks of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+    ///
+    /// let (b0, b1) = (GF2::B0, GF2::B1);
+    /// assert_eq!(decoder.decode_syndrome(&[1, 2], &[b1, b0]), Some(vec![b0, b1, b1]));
+    /// assert_eq!(decoder.decode_syndrome(&[2], &[b1, b0]), None);
+    /// ```
+    pub fn decode_syndrome(
+        &mut self,
+        erased_bits: &[usize],
+        syndrome: &[GF2],
+    ) -> Option<Vec<GF2>> {
+        if syndrome.len() != self.code.get_n_checks() {
+            panic!("syndrome doesn't have the right length");
+        }
+        let erased_bits = get_distinct_bits_of(erased_bits).into_owned();
+        if self.decode(&erased_bits).is_failure() {
+            return None;
+        }
+        let erased_values = self.solve_erased_values(&erased_bits, syndrome)?;
+        let mut error = vec![GF2::B0; self.code.get_n_bits()];
+        erased_bits
+            .iter()
+            .zip(erased_values)
+            .for_each(|(bit, value)| error[*bit] = value);
+        Some(error)
+    }
+
+    // The erased columns of the code times the erased values must give the syndrome. This
+    // system is reduced with the syndrome as an extra last column. Since the erased columns are
+    // independent, each of them is a pivot and its value is in the last column of its row. If
+    // the last column is also a pivot, there is no solution.
+    fn solve_erased_values(&self, erased_bits: &[usize], syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        let last_column = erased_bits.len();
+        let checks = self
+            .code
+            .keep_bits(erased_bits)
+            .checks_iter()
+            .zip(syndrome.iter().cloned())
+            .map(|(check, value)| {
+                let mut check = check.to_vec();
+                if value == GF2::B1 {
+                    check.push(last_column);
+                }
+                check
+            })
+            .collect();
+        let (reduced, pivots) = ParityCheckMatrix::with_n_bits(last_column + 1)
+            .with_checks(checks)
+            .get_reduced_row_echelon_form();
+        if pivots.last() == Some(&last_column) {
+            return None;
+        }
+        Some(
+            reduced
+                .checks_iter()
+                .map(|row| {
+                    if row.as_ref().last() == Some(&last_column) {
+                        GF2::B1
+                    } else {
+                        GF2::B0
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    // The erased bits that are in the support of a codeword restricted to the erased bits can't
+    // be recovered. In the reduced form of the erased columns, these are the free columns and
+    // the pivots whose row contains a free column. The erased bits must be sorted and distinct.
+    fn get_unresolved_bits_of(&self, erased_bits: &[usize]) -> Vec<usize> {
+        let (reduced, pivots) = self
+            .code
+            .keep_bits(erased_bits)
+            .get_reduced_row_echelon_form();
+        let mut is_free = vec![true; erased_bits.len()];
+        pivots.iter().for_each(|pivot| is_free[*pivot] = false);
+        let mut is_unresolved = is_free.clone();
+        reduced
+            .checks_iter()
+            .zip(pivots.iter())
+            .for_each(|(row, pivot)| {
+                if row.iter().any(|column| is_free[*column]) {
+                    is_unresolved[*pivot] = true;
+                }
+            });
+        erased_bits
+            .iter()
+            .zip(is_unresolved)
+            .filter(|(_, is_unresolved)| *is_unresolved)
+            .map(|(bit, _)| *bit)
+            .collect()
+    }
+
+    /// Simulates decoding `n_iterations_per_point` random erasures for each erasure
+    /// probability in `probs` with random number generator `rng`.
+    ///
+    /// The points are simulated in parallel by clones of `self` sharing the code and the
+    /// decoding caches. Each point uses its own seed drawn from `rng`, so the curve only depends
+    /// on `rng`. The bits are erased with the same probability even if `self` has per bit
+    /// probabilities.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    /// let decoder = ErasureDecoder::with_prob(0.1).for_code(code);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let curve = decoder.sweep_probabilities_with_rng(&[0.1, 0.3, 0.5], 1000, &mut rng);
+    ///
+    /// let rates = curve.get_failure_rates();
+    /// assert!(rates[0] < rates[1] && rates[1] < rates[2]);
+    /// println!("{}", curve.to_csv());
+    /// ```
+    pub fn sweep_probabilities_with_rng<R: Rng>(
+        &self,
+        probs: &[f64],
+        n_iterations_per_point: usize,
+        rng: &mut R,
+    ) -> PerformanceCurve {
+        let decoders: Vec<Self> = probs
+            .iter()
+            .map(|prob| self.with_uniform_prob(*prob))
+            .collect();
+        let seeds = SeedSequence::from_rng(rng);
+        let points = decoders
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, mut decoder)| {
+                let mut rng = seeds.get_rng(index as u64);
+                let result =
+                    decoder.simulate_n_iterations_with_rng(n_iterations_per_point, &mut rng);
+                (decoder.get_erasure_prob(), result)
+            })
+            .collect();
+        PerformanceCurve::with_points(points)
+    }
 
-    fn for_code(mut self, code: Self::Code) -> Self {
+    /// Simulates decoding `n_iterations_per_point` random erasures for each erasure
+    /// probability in `probs` with the thread random number generator.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0.0 and 1.0.
+    pub fn sweep_probabilities(
+        &self,
+        probs: &[f64],
+        n_iterations_per_point: usize,
+    ) -> PerformanceCurve {
+        self.sweep_probabilities_with_rng(probs, n_iterations_per_point, &mut thread_rng())
+    }
+
+    /// Simulates decoding `n_iterations` random erasures sampled with the biased erasure
+    /// probability `bias` with random number generator `rng`.
+    ///
+    /// Each decoding is weighted by the likelihood ratio of its erasure between the erasure
+    /// probabilities of `self` and the biased probability. Thus, the failure rate of the result
+    /// is an unbiased estimate of the failure rate of `self` even if failures are too rare to
+    /// be found by a plain simulation. The result also carries the effective sample size of the
+    /// weights.
+    ///
+    /// The variance of the estimate depends a lot on the bias. A bias a bit above the erasure
+    /// probability makes failures frequent while keeping the weights balanced. A bias too close
+    /// to the erasure probability finds few failures and a bias too large gives tiny weights to
+    /// most failures, so the estimate is dominated by a few rare erasures. In both cases the
+    /// relative standard error and the effective sample size of the result show the problem.
+    /// The counts of failures and successes, the bit error rate and the histogram are those of
+    /// the biased erasures.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `bias` is not strictly between 0.0 and 1.0 or if the decoder samples paired
+    /// erasures whose pairs share some bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(4);
+    /// let mut decoder = ErasureDecoder::with_prob(0.001).for_code(code);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let result = decoder.simulate_with_importance_sampling_with_rng(0.1, 10_000, &mut rng);
+    ///
+    /// assert!(result.get_failure_rate() > 0.0);
+    /// assert!(result.get_failure_rate() < 1e-3);
+    /// assert!(result.get_relative_standard_error() < 0.1);
+    /// ```
+    pub fn simulate_with_importance_sampling_with_rng<R: Rng>(
+        &mut self,
+        bias: f64,
+        n_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult {
+        if bias <= 0.0 || bias >= 1.0 {
+            panic!("invalid bias");
+        }
+        let biased_noise = UniformErasure::with_prob(bias);
+        let mut result = SimulationResult::new();
+        for _ in 0..n_iterations {
+            let error = biased_noise.sample_error_with_rng(self.code.get_n_bits(), rng);
+            let weight = self.get_likelihood_ratio_of(&error, bias);
+            result.add_weighted_decoding_result(self.decode(&error), weight);
+        }
+        result
+    }
+
+    /// Simulates decoding `n_iterations` random erasures sampled with the biased erasure
+    /// probability `bias` with the thread random number generator.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `bias` is not strictly between 0.0 and 1.0.
+    pub fn simulate_with_importance_sampling(
+        &mut self,
+        bias: f64,
+        n_iterations: usize,
+    ) -> SimulationResult {
+        self.simulate_with_importance_sampling_with_rng(bias, n_iterations, &mut thread_rng())
+    }
+
+    /// Simulates decoding `n_iterations` random erasures with random number generator `rng`
+    /// and counts the unresolved bits of the failures in a counter keeping at most `capacity`
+    /// sets.
+    ///
+    /// The unresolved bits of a failure of the peeling strategy form a stopping set of the
+    /// code, so the most frequent sets show the structures limiting the code. For maximum
+    /// likelihood decoding, they are the erased bits that are not fixed by the other erased
+    /// bits.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let (result, counter) =
+    ///     decoder.simulate_n_iterations_collecting_residual_sets_with_rng(1000, 4, &mut rng);
+    ///
+    /// assert_eq!(counter.get_most_frequent(4), vec![(vec![0, 1, 2], result.get_n_failures())]);
+    /// ```
+    pub fn simulate_n_iterations_collecting_residual_sets_with_rng<R: Rng>(
+        &mut self,
+        n_iterations: usize,
+        capacity: usize,
+        rng: &mut R,
+    ) -> (SimulationResult, ResidualSetCounter) {
+        let mut counter = ResidualSetCounter::with_capacity(capacity);
+        let mut result = SimulationResult::new();
+        for _ in 0..n_iterations {
+            let decoding_result = self.decode_random_error_with_rng(rng);
+            if decoding_result.is_failure() {
+                counter.add(decoding_result.get_unresolved_bits());
+            }
+            result.add_decoding_result(decoding_result);
+        }
+        (result, counter)
+    }
 
-        let rank_mtx = Some(code.tmp_rank_pcm());
-        let sum_vec = Some(Vec::with_capacity(code.get_n_bits()));
+    /// Simulates decoding `n_iterations` random erasures with the thread random number
+    /// generator and counts the unresolved bits of the failures in a counter keeping at most
+    /// `capacity` sets.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn simulate_n_iterations_collecting_residual_sets(
+        &mut self,
+        n_iterations: usize,
+        capacity: usize,
+    ) -> (SimulationResult, ResidualSetCounter) {
+        self.simulate_n_iterations_collecting_residual_sets_with_rng(
+            n_iterations,
+            capacity,
+            &mut thread_rng(),
+        )
+    }
 
-        self.ressources = Ressources{
-            rank_mtx,
-            sum_vec,
+    // The probability of the sorted `erased_bits` with the erasure probabilities of `self`
+    // divided by their probability when all bits are erased with probability `bias`.
+    // The bits of burst and paired erasures are not independent, so their probability is
+    // computed from the whole erasure.
+    fn get_likelihood_ratio_of(&self, erased_bits: &[usize], bias: f64) -> f64 {
+        let n_bits = self.code.get_n_bits();
+        let log_prob = match &self.noise {
+            ErasureNoise::Burst(noise) => Some(noise.get_log_prob_of(erased_bits, n_bits)),
+            ErasureNoise::Paired(noise) => Some(noise.get_log_prob_of(erased_bits)),
+            _ => None,
         };
+        if let Some(log_prob) = log_prob {
+            let n_erased_bits = erased_bits.len() as f64;
+            let biased_log_prob =
+                n_erased_bits * bias.ln() + (n_bits as f64 - n_erased_bits) * (1.0 - bias).ln();
+            return (log_prob - biased_log_prob).exp();
+        }
+        let mut erased_bits = erased_bits.iter().peekable();
+        let log_ratio: f64 = (0..n_bits)
+            .map(|bit| {
+                let prob = match &self.noise {
+                    ErasureNoise::Uniform(noise) => noise.get_prob(),
+                    ErasureNoise::PerBit(noise) => noise.get_probs()[bit],
+                    ErasureNoise::Burst(_) | ErasureNoise::Paired(_) => unreachable!(),
+                };
+                if erased_bits.next_if_eq(&&bit).is_some() {
+                    prob.ln() - bias.ln()
+                } else {
+                    (1.0 - prob).ln() - (1.0 - bias).ln()
+                }
+            })
+            .sum();
+        log_ratio.exp()
+    }
 
-        self.code = code;
+    // A clone of `self` sharing the code and the caches that erases all bits with `prob`.
+    pub(super) fn with_uniform_prob(&self, prob: f64) -> Self {
+        let mut decoder = self.clone();
+        decoder.noise = ErasureNoise::Uniform(UniformErasure::with_prob(prob));
+        decoder
+    }
 
+    // `self` erasing bits like `other` with the same strategy. This is meant for decoders
+    // without a code, since the noise is only checked against the code set by `for_code`.
+    pub(crate) fn with_noise_of(mut self, other: Self) -> Self {
+        self.noise = other.noise;
         self
     }
 
-    fn take_code(&mut self) -> Self::Code {
-        std::mem::replace(&mut self.code, ParityCheckMatrix::new())
+    fn panic_if_noise_doesnt_match(&self, code: &ParityCheckMatrix) {
+        match &self.noise {
+            ErasureNoise::PerBit(noise) if noise.get_probs().len() != code.get_n_bits() => {
+                panic!("probabilities don't match the number of bits")
+            }
+            ErasureNoise::Paired(noise) if noise.get_n_bits() != code.get_n_bits() => {
+                panic!("pairs don't match the number of bits")
+            }
+            _ => (),
+        }
     }
+}
 
-    // An erasure error can be corrected if there is no information in the erased submatrix. That
-    // is, the number of erased bits is equal to the rank of the parity check matrix restricted to
-    // the erased bit columns.
-    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+impl Decoder for ErasureDecoder {
+    type Error = Vec<usize>; // Positions of erased bits.
+    type Result = ErasureDecodingResult;
+    type Code = ParityCheckMatrix;
 
-        let mut rank_mtx = self.ressources.rank_mtx.take().unwrap();
-        let mut sum_vec = self.ressources.sum_vec.take().unwrap();
+    // Panics if the decoder has per bit probabilities or pairs that don't match the number of
+    // bits of the code.
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.panic_if_noise_doesnt_match(&code);
+        self.cache = code.get_erasure_decoding_cache();
+        self.peeler = Peeler::from(&code);
+        self.code = code;
+        self
+    }
 
-        let erased_parity_check = self.code.keep(error);
+    fn take_code(&mut self) -> Self::Code {
+        into_owned(std::mem::take(&mut self.code))
+    }
 
-        let erased_rank = erased_parity_check.rank_mut(&mut rank_mtx, &mut sum_vec);
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
 
-        self.ressources.rank_mtx = Some(rank_mtx);
-        self.ressources.sum_vec= Some(sum_vec);
+    // With maximum likelihood, an erasure error can be corrected if there is no information in
+    // the erased submatrix. That is, the number of erased bits is equal to the rank of the parity
+    // check matrix restricted to the erased bit columns.
+    // The unresolved bits are only computed when the fast check fails. Duplicated erased bits
+    // are removed first, since the cache would count them as dependent columns.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let erased_bits = get_distinct_bits_of(error);
+        let unresolved_bits = match self.strategy {
+            ErasureStrategy::MaximumLikelihood
+                if self.cache.can_correct_erasure_of(&erased_bits) =>
+            {
+                Vec::new()
+            }
+            ErasureStrategy::MaximumLikelihood => self.get_unresolved_bits_of(&erased_bits),
+            ErasureStrategy::Peeling => {
+                let mut unresolved_bits = self.peeler.get_unresolved_bits_of(&erased_bits);
+                unresolved_bits.sort_unstable();
+                unresolved_bits
+            }
+        };
+        ErasureDecodingResult::from(unresolved_bits)
+    }
 
-        if error.len() - erased_rank == 0 {
-            ErasureResult::Success
-        } else {
-            ErasureResult::Failure
+    // With maximum likelihood, the unresolved bits of a batch are computed with the buffers of
+    // the cache instead of reducing a new submatrix for each failure, so the whole batch is
+    // decoded without building any matrix. This gives the same results as `decode`.
+    fn decode_batch(&mut self, errors: &[Self::Error]) -> Vec<Self::Result> {
+        if self.strategy == ErasureStrategy::Peeling {
+            return errors.iter().map(|error| self.decode(error)).collect();
         }
+        errors
+            .iter()
+            .map(|error| {
+                let erased_bits = get_distinct_bits_of(error);
+                if self.cache.can_correct_erasure_of(&erased_bits) {
+                    ErasureDecodingResult::from(Vec::new())
+                } else {
+                    ErasureDecodingResult::from(self.cache.get_unresolved_bits_of(&erased_bits))
+                }
+            })
+            .collect()
     }
 
-    // Erase random bits with given probability.
+    // Erase random bits with given probabilities.
     fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
-        (0..self.code.get_n_bits())
-            .filter(|_| self.next_bit_is_erased(rng))
-            .collect()
+        let n_bits = self.code.get_n_bits();
+        match &self.noise {
+            ErasureNoise::Uniform(noise) => noise.sample_error_with_rng(n_bits, rng),
+            ErasureNoise::PerBit(noise) => noise.sample_error_with_rng(n_bits, rng),
+            ErasureNoise::Burst(noise) => noise.sample_error_with_rng(n_bits, rng),
+            ErasureNoise::Paired(noise) => noise.sample_error_with_rng(n_bits, rng),
+        }
+    }
+}
+
+// Sorts and dedups the bits, without copying them if they are already sorted and distinct as
+// the sampled errors are.
+fn get_distinct_bits_of(bits: &[usize]) -> Cow<'_, [usize]> {
+    if bits.windows(2).all(|pair| pair[0] < pair[1]) {
+        Cow::Borrowed(bits)
+    } else {
+        let mut bits = bits.to_vec();
+        bits.sort_unstable();
+        bits.dedup();
+        Cow::Owned(bits)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{DecodingResult, ErasureResult};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn repetition_code() {
-        let code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
         let mut decoder = ErasureDecoder::with_prob(0.2).for_code(code);
 
-        assert_eq!(decoder.decode(&vec![]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![]).get_outcome(), ErasureResult::Success);
         for i in 0..=2 {
-            assert_eq!(decoder.decode(&vec![i]), ErasureResult::Success);
+            assert_eq!(decoder.decode(&vec![i]).get_outcome(), ErasureResult::Success);
             for j in (i + 1)..=2 {
-                assert_eq!(decoder.decode(&vec![i, j]), ErasureResult::Success);
+                assert_eq!(decoder.decode(&vec![i, j]).get_outcome(), ErasureResult::Success);
             }
         }
-        assert_eq!(decoder.decode(&vec![0, 1, 2]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), ErasureResult::Failure);
     }
 
     #[test]
@@ -132,26 +727,437 @@ mod test {
         ]);
         let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
 
-        assert_eq!(decoder.decode(&vec![]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![]).get_outcome(), ErasureResult::Success);
         for i in 0..=6 {
-            assert_eq!(decoder.decode(&vec![i]), ErasureResult::Success);
+            assert_eq!(decoder.decode(&vec![i]).get_outcome(), ErasureResult::Success);
             for j in (i + 1)..=6 {
-                assert_eq!(decoder.decode(&vec![i, j]), ErasureResult::Success);
+                assert_eq!(decoder.decode(&vec![i, j]).get_outcome(), ErasureResult::Success);
             }
         }
-        assert_eq!(decoder.decode(&vec![0, 1, 2]), ErasureResult::Success);
-        assert_eq!(decoder.decode(&vec![2, 4, 5]), ErasureResult::Success);
-        assert_eq!(decoder.decode(&vec![0, 1, 4]), ErasureResult::Success);
-        assert_eq!(decoder.decode(&vec![3, 4, 5]), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![2, 4, 5]).get_outcome(), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![0, 1, 4]).get_outcome(), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![3, 4, 5]).get_outcome(), ErasureResult::Success);
 
-        assert_eq!(decoder.decode(&vec![2, 4, 6]), ErasureResult::Failure);
-        assert_eq!(decoder.decode(&vec![1, 2, 3]), ErasureResult::Failure);
-        assert_eq!(decoder.decode(&vec![0, 3, 4]), ErasureResult::Failure);
-        assert_eq!(decoder.decode(&vec![0, 2, 5]), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![2, 4, 6]).get_outcome(), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![1, 2, 3]).get_outcome(), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 3, 4]).get_outcome(), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 2, 5]).get_outcome(), ErasureResult::Failure);
 
         assert_eq!(
-            decoder.decode(&vec![0, 1, 2, 3, 4, 5, 6]),
+            decoder.decode(&vec![0, 1, 2, 3, 4, 5, 6]).get_outcome(),
             ErasureResult::Failure
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn peeling_fails_on_stopping_sets_that_maximum_likelihood_corrects() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let mut maximum_likelihood = ErasureDecoder::with_prob(0.25).for_code(code.clone());
+        let mut peeling = ErasureDecoder::with_prob(0.25)
+            .with_strategy(ErasureStrategy::Peeling)
+            .for_code(code);
+
+        assert!(maximum_likelihood.decode(&vec![0, 1, 2]).is_success());
+        assert_eq!(peeling.decode(&vec![0, 1, 2]).get_outcome(), ErasureResult::Failure);
+
+        assert_eq!(peeling.decode(&vec![0, 4, 5]).get_outcome(), ErasureResult::Success);
+        assert_eq!(peeling.decode(&vec![2, 4, 6]).get_outcome(), ErasureResult::Failure);
+    }
+
+    #[test]
+    fn unresolved_bits_are_the_support_of_the_erased_codewords() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let mut maximum_likelihood = ErasureDecoder::with_prob(0.25).for_code(code.clone());
+        let mut peeling = ErasureDecoder::with_prob(0.25)
+            .with_strategy(ErasureStrategy::Peeling)
+            .for_code(code);
+
+        let result = maximum_likelihood.decode(&vec![5, 1, 0, 2]);
+        assert_eq!(result.get_outcome(), ErasureResult::Failure);
+        assert_eq!(result.get_unresolved_bits(), &[0, 2, 5]);
+        assert_eq!(result.get_n_residual_errors(), Some(3));
+
+        let result = peeling.decode(&vec![5, 1, 0, 2]);
+        assert_eq!(result.get_unresolved_bits(), &[0, 1, 2, 5]);
+        assert!(maximum_likelihood.decode(&vec![4, 0]).get_unresolved_bits().is_empty());
+    }
+
+    #[test]
+    fn sweeps_simulate_each_probability_with_its_own_seed() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let decoder = ErasureDecoder::with_probs(vec![0.9; 7]).for_code(code.clone());
+        let probs = [0.4, 0.1, 0.25];
+        let rng = ChaCha8Rng::seed_from_u64(123);
+
+        let curve = decoder.sweep_probabilities_with_rng(&probs, 200, &mut rng.clone());
+        assert_eq!(curve, decoder.sweep_probabilities_with_rng(&probs, 200, &mut rng.clone()));
+
+        let seeds = SeedSequence::from_rng(&mut rng.clone());
+        let mut expected: Vec<(f64, SimulationResult)> = probs
+            .iter()
+            .zip(seeds.get_n_rngs(3))
+            .map(|(prob, mut rng)| {
+                let mut decoder = ErasureDecoder::with_prob(*prob).for_code(code.clone());
+                (*prob, decoder.simulate_n_iterations_with_rng(200, &mut rng))
+            })
+            .collect();
+        expected.sort_by(|(prob, _), (other_prob, _)| prob.partial_cmp(other_prob).unwrap());
+        assert_eq!(curve.get_points(), &expected[..]);
+    }
+
+    #[test]
+    fn importance_sampling_agrees_with_plain_simulation() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut decoder = ErasureDecoder::with_prob(0.1).for_code(code);
+        let mut exact_failure_rate = 0.0;
+        for pattern in 0..(1 << 7) {
+            let error: Vec<usize> = (0..7).filter(|bit| pattern & (1 << bit) != 0).collect();
+            if decoder.decode(&error).is_failure() {
+                exact_failure_rate += 0.1f64.powi(error.len() as i32)
+                    * 0.9f64.powi(7 - error.len() as i32);
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let plain = decoder.simulate_n_iterations_with_rng(20_000, &mut rng);
+        let weighted = decoder.simulate_with_importance_sampling_with_rng(0.3, 20_000, &mut rng);
+        assert!(!plain.is_weighted());
+        assert!(weighted.is_weighted());
+        for result in &[&plain, &weighted] {
+            let standard_error = result.get_relative_standard_error() * result.get_failure_rate();
+            assert!((result.get_failure_rate() - exact_failure_rate).abs() < 4.0 * standard_error);
+        }
+        assert!(weighted.get_relative_standard_error() < plain.get_relative_standard_error());
+        assert!(weighted.get_effective_sample_size() < 20_000.0);
+        assert!(weighted.get_effective_sample_size() > 1000.0);
+    }
+
+    #[test]
+    fn importance_sampling_of_bursts_agrees_with_the_exact_failure_rate() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.05, 2.0);
+        let mut decoder = ErasureDecoder::with_burst_erasure(noise).for_code(code);
+        let mut exact_failure_rate = 0.0;
+        for pattern in 0..(1 << 7) {
+            let error: Vec<usize> = (0..7).filter(|bit| pattern & (1 << bit) != 0).collect();
+            if decoder.decode(&error).is_failure() {
+                exact_failure_rate += noise.get_log_prob_of(&error, 7).exp();
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let weighted = decoder.simulate_with_importance_sampling_with_rng(0.3, 20_000, &mut rng);
+        let standard_error = weighted.get_relative_standard_error() * weighted.get_failure_rate();
+        assert!((weighted.get_failure_rate() - exact_failure_rate).abs() < 4.0 * standard_error);
+        let plain = decoder.simulate_n_iterations_with_rng(20_000, &mut rng);
+        let standard_error = plain.get_relative_standard_error() * plain.get_failure_rate();
+        assert!((plain.get_failure_rate() - exact_failure_rate).abs() < 4.0 * standard_error);
+    }
+
+    #[test]
+    fn importance_sampling_of_pairs_agrees_with_the_exact_failure_rate() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let noise = PairedErasure::with_adjacent_pairs(7, 0.05).with_background_prob(0.02);
+        let mut decoder = ErasureDecoder::with_paired_erasure(noise.clone()).for_code(code);
+        let mut exact_failure_rate = 0.0;
+        for pattern in 0..(1 << 7) {
+            let error: Vec<usize> = (0..7).filter(|bit| pattern & (1 << bit) != 0).collect();
+            if decoder.decode(&error).is_failure() {
+                exact_failure_rate += noise.get_log_prob_of(&error).exp();
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let weighted = decoder.simulate_with_importance_sampling_with_rng(0.3, 20_000, &mut rng);
+        let standard_error = weighted.get_relative_standard_error() * weighted.get_failure_rate();
+        assert!((weighted.get_failure_rate() - exact_failure_rate).abs() < 4.0 * standard_error);
+    }
+
+    // The bits 0 and 1 are in more of the smallest stopping sets than the bits 4 and 5, so the
+    // failure rates are about 0.044 and 0.020.
+    #[test]
+    fn pairs_inside_small_stopping_sets_are_harder_to_recover() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let mut get_failure_rate = |pair: (usize, usize)| {
+            let noise = PairedErasure::with_pairs(7, vec![pair], 0.3).with_background_prob(0.05);
+            ErasureDecoder::with_paired_erasure(noise)
+                .with_strategy(ErasureStrategy::Peeling)
+                .for_code(code.clone())
+                .simulate_n_iterations_with_rng(20_000, &mut rng)
+                .get_failure_rate()
+        };
+        let inside = get_failure_rate((0, 1));
+        let outside = get_failure_rate((4, 5));
+        assert!((inside - 0.0443).abs() < 0.006);
+        assert!((outside - 0.0198).abs() < 0.004);
+    }
+
+    #[test]
+    #[should_panic]
+    fn paired_erasures_of_the_wrong_number_of_bits_panic() {
+        let noise = PairedErasure::with_adjacent_pairs(6, 0.1);
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        ErasureDecoder::with_paired_erasure(noise).for_code(code);
+    }
+
+    #[test]
+    fn likelihood_ratios_compare_the_erasure_probabilities() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = ErasureDecoder::with_probs(vec![0.1, 0.2, 0.0]).for_code(code);
+
+        let ratio = decoder.get_likelihood_ratio_of(&[0, 1], 0.5);
+        assert!((ratio - 0.1 * 0.2 * 1.0 / 0.125).abs() < 1e-12);
+        assert_eq!(decoder.get_likelihood_ratio_of(&[2], 0.5), 0.0);
+        let decoder = decoder.with_uniform_prob(0.5);
+        assert!((decoder.get_likelihood_ratio_of(&[1], 0.5) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn peeling_never_corrects_more_than_maximum_likelihood() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = ParityCheckMatrix::random_with_density_with_rng(20, 10, 0.3, true, &mut rng);
+        let mut maximum_likelihood = ErasureDecoder::with_prob(0.3).for_code(code);
+        let mut peeling = maximum_likelihood.clone().with_strategy(ErasureStrategy::Peeling);
+
+        for _ in 0..200 {
+            let error = maximum_likelihood.get_random_error_with_rng(&mut rng);
+            if peeling.decode(&error).is_success() {
+                assert!(maximum_likelihood.decode(&error).is_success());
+            }
+        }
+
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let peeling_result = peeling.simulate_n_iterations_with_rng(100, &mut rng.clone());
+        let maximum_likelihood_result =
+            maximum_likelihood.simulate_n_iterations_with_rng(100, &mut rng.clone());
+        assert!(peeling_result.get_n_failures() >= maximum_likelihood_result.get_n_failures());
+        assert_eq!(
+            peeling.simulate_n_iterations_with_rng(100, &mut rng.clone()),
+            peeling_result
+        );
+    }
+
+    #[test]
+    fn duplicated_erased_bits_are_counted_once() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        for strategy in &[ErasureStrategy::MaximumLikelihood, ErasureStrategy::Peeling] {
+            let mut decoder = ErasureDecoder::with_prob(0.25)
+                .with_strategy(*strategy)
+                .for_code(code.clone());
+            assert!(decoder.decode(&vec![4, 4]).is_success());
+            assert_eq!(decoder.decode(&vec![6, 2, 4, 2]), decoder.decode(&vec![2, 4, 6]));
+        }
+    }
+
+    #[test]
+    fn batches_are_decoded_like_single_errors() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let errors: Vec<Vec<usize>> = (0..(1 << 7))
+            .map(|pattern| (0..7).filter(|bit| pattern & (1 << bit) != 0).rev().collect())
+            .collect();
+        for strategy in &[ErasureStrategy::MaximumLikelihood, ErasureStrategy::Peeling] {
+            let mut decoder = ErasureDecoder::with_prob(0.25)
+                .with_strategy(*strategy)
+                .for_code(code.clone());
+            let expected: Vec<ErasureDecodingResult> =
+                errors.iter().map(|e| decoder.decode(e)).collect();
+            assert_eq!(decoder.decode_batch(&errors), expected);
+        }
+    }
+
+    #[test]
+    fn erasures_of_unchecked_last_bits_are_not_recoverable() {
+        let code = ParityCheckMatrix::with_n_bits(5).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let mut decoder = ErasureDecoder::with_prob(1.0).for_code(code);
+
+        assert_eq!(decoder.decode(&vec![0]).get_outcome(), ErasureResult::Success);
+        assert_eq!(decoder.decode(&vec![3]).get_outcome(), ErasureResult::Failure);
+        assert_eq!(decoder.decode(&vec![4]).get_outcome(), ErasureResult::Failure);
+        assert_eq!(decoder.get_random_error(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn erased_bits_of_codewords_are_recovered() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let generator = code.get_generator_matrix();
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = ErasureDecoder::with_prob(0.3).for_code(code.clone());
+
+        for _ in 0..50 {
+            let message: Vec<GF2> = (0..generator.get_n_checks())
+                .map(|_| if rng.gen() { GF2::B1 } else { GF2::B0 })
+                .collect();
+            let codeword = generator.encode(&message);
+            let erased_bits = decoder.get_random_error_with_rng(&mut rng);
+            let mut received: Vec<Option<GF2>> = codeword.iter().cloned().map(Some).collect();
+            erased_bits.iter().for_each(|bit| received[*bit] = None);
+
+            match decoder.decode_received(&received) {
+                Some(word) => assert_eq!(word, codeword),
+                None => assert!(!code.can_correct_erasure_of(&erased_bits)),
+            }
+        }
+    }
+
+    #[test]
+    fn received_words_follow_the_strategy_and_the_known_bits() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+        let mut received = vec![Some(GF2::B0); 7];
+        received[0] = None;
+        received[1] = None;
+        received[2] = None;
+
+        assert_eq!(decoder.decode_received(&received), Some(vec![GF2::B0; 7]));
+        assert!(decoder.decode_received(&[Some(GF2::B0); 7]).is_some());
+
+        let mut decoder = decoder.with_strategy(ErasureStrategy::Peeling);
+        assert_eq!(decoder.decode_received(&received), None);
+
+        received[0] = Some(GF2::B1);
+        received[1] = Some(GF2::B1);
+        received[2] = Some(GF2::B1);
+        received[3] = None;
+        assert_eq!(decoder.decode_received(&received), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn received_words_of_wrong_length_panic() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        ErasureDecoder::with_prob(0.1)
+            .for_code(code)
+            .decode_received(&[None; 4]);
+    }
+
+    #[test]
+    fn syndromes_of_erased_errors_are_decoded_on_the_erased_bits() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = ErasureDecoder::with_prob(0.3).for_code(code.clone());
+
+        for _ in 0..50 {
+            let erased_bits = decoder.get_random_error_with_rng(&mut rng);
+            let mut error = vec![GF2::B0; 7];
+            erased_bits
+                .iter()
+                .for_each(|bit| error[*bit] = if rng.gen() { GF2::B1 } else { GF2::B0 });
+            let syndrome = code.get_syndrome_of(&error);
+
+            match decoder.decode_syndrome(&erased_bits, &syndrome) {
+                Some(estimate) => assert_eq!(estimate, error),
+                None => assert!(!code.can_correct_erasure_of(&erased_bits)),
+            }
+        }
+        let syndrome = vec![GF2::B1, GF2::B1, GF2::B0];
+        assert_eq!(decoder.decode_syndrome(&[0, 1, 2], &syndrome), None);
+        assert_eq!(decoder.decode_syndrome(&[], &[GF2::B0; 3]), Some(vec![GF2::B0; 7]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn syndromes_of_wrong_length_panic() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        ErasureDecoder::with_prob(0.1)
+            .for_code(code)
+            .decode_syndrome(&[0], &[GF2::B0; 3]);
+    }
+
+    #[test]
+    fn bits_are_erased_with_their_own_probabilities() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let mut decoder = ErasureDecoder::with_probs(vec![1.0, 0.0, 0.0, 0.0]).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+
+        for _ in 0..100 {
+            assert_eq!(decoder.get_random_error_with_rng(&mut rng), vec![0]);
+        }
+        let result = decoder.simulate_n_iterations_with_rng(100, &mut rng);
+        assert_eq!(result.get_n_successes(), 100);
+    }
+
+    #[test]
+    fn per_bit_probabilities_give_the_uniform_simulation() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut uniform = ErasureDecoder::with_prob(0.3).for_code(code.clone());
+        let mut per_bit = ErasureDecoder::with_probs(vec![0.3; 7]).for_code(code);
+
+        assert_eq!(
+            uniform.simulate_n_iterations_with_rng(200, &mut rng.clone()),
+            per_bit.simulate_n_iterations_with_rng(200, &mut rng.clone())
+        );
+    }
+
+    #[test]
+    fn shared_code_gives_the_same_simulation_as_an_owned_code() {
+        let code = Arc::new(ParityCheckMatrix::hamming_code_with_n_checks(3));
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut owned = ErasureDecoder::with_prob(0.3).for_code((*code).clone());
+        let mut shared = ErasureDecoder::with_prob(0.3).for_shared_code(Arc::clone(&code));
+        let mut other = ErasureDecoder::with_prob(0.1).for_shared_code(Arc::clone(&code));
+
+        assert_eq!(
+            shared.simulate_n_iterations_with_rng(200, &mut rng.clone()),
+            owned.simulate_n_iterations_with_rng(200, &mut rng.clone())
+        );
+        assert_eq!(shared.take_code(), *code);
+        assert_eq!(other.decode(&vec![0, 1]).get_outcome(), ErasureResult::Success);
+        assert_eq!(Arc::strong_count(&code), 2);
+    }
+
+    #[test]
+    fn residual_sets_of_peeling_failures_are_stopping_sets() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut decoder = ErasureDecoder::with_prob(0.3)
+            .with_strategy(ErasureStrategy::Peeling)
+            .for_code(code.clone());
+        let (result, counter) =
+            decoder.simulate_n_iterations_collecting_residual_sets_with_rng(500, 64, &mut rng);
+
+        assert_eq!(counter.get_n_added_sets(), result.get_n_failures());
+        let sets = counter.get_most_frequent(64);
+        assert_eq!(sets.iter().map(|(_, count)| count).sum::<u64>(), result.get_n_failures());
+        for (set, _) in sets {
+            assert!(code.checks_iter().all(|check| {
+                check.iter().filter(|bit| set.contains(bit)).count() != 1
+            }));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn per_bit_probabilities_of_wrong_length_panic() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        ErasureDecoder::with_probs(vec![0.1; 4]).for_code(code);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_per_bit_probabilities_panic() {
+        ErasureDecoder::with_probs(vec![0.1, -0.1]);
+    }
+}
@@ -0,0 +1,297 @@
+//! An exact maximum likelihood decoder for codes of small dimension.
+
+use super::{into_owned, Bsc, Decoder, NoiseModel, UniformErasure};
+use crate::ParityCheckMatrix;
+use crate::{BscResult, ErasureResult};
+use rand::Rng;
+use std::sync::Arc;
+
+/// The largest dimension of the codes decoded by an `ExhaustiveMlDecoder`.
+///
+/// A decoding enumerates up to 2 to the power of this number of codewords.
+pub const MAX_EXHAUSTIVE_ML_DIMENSION: usize = 20;
+
+/// A maximum likelihood decoder enumerating all the codewords of a code.
+///
+/// The decoder is a reference to measure how far other decoders are from optimal. Since the
+/// codes are linear, the all zero codeword is always sent. Each decoding enumerates the
+/// codewords generated by the generator matrix of the code and succeeds if the zero codeword
+/// is strictly more likely than every other codeword given the error. Ties are failures, so
+/// the failure rate is an upper bound on the failure rate of maximum likelihood decoding with
+/// random tie breaking.
+///
+/// The decoder supports two noise models:
+///
+/// - `Bsc`: the errors are the flipped bits. Below a crossover probability of 0.5, the most
+///   likely codewords are the closest ones to the received word in Hamming distance.
+/// - `UniformErasure`: the errors are the erased bits. The likely codewords are the ones that
+///   are compatible with the unerased bits, that is the codewords supported on the erased bits.
+///
+/// This takes a time exponential in the dimension of the code, which is limited to
+/// `MAX_EXHAUSTIVE_ML_DIMENSION`.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// let noise = Bsc::with_crossover_prob(0.1);
+/// let mut decoder = ExhaustiveMlDecoder::with_noise(noise).for_code(code);
+///
+/// // Every word is at distance at most 1 of a single codeword of a Hamming code.
+/// assert_eq!(decoder.decode(&vec![4]), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 1]), BscResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExhaustiveMlDecoder<N> {
+    noise: N,
+    code: Arc<ParityCheckMatrix>,
+    generators: Vec<Vec<usize>>,
+}
+
+impl<N: NoiseModel> ExhaustiveMlDecoder<N> {
+    /// Creates a decoder for the errors sampled by `noise`.
+    pub fn with_noise(noise: N) -> Self {
+        Self {
+            noise,
+            code: Arc::new(ParityCheckMatrix::new()),
+            generators: Vec::new(),
+        }
+    }
+
+    /// Returns the noise model sampling the random errors.
+    pub fn get_noise(&self) -> &N {
+        &self.noise
+    }
+
+    /// Returns the dimension of the code of `self`.
+    pub fn get_dimension(&self) -> usize {
+        self.generators.len()
+    }
+
+    // # Panic
+    //
+    // Panics if the dimension of `code` is larger than MAX_EXHAUSTIVE_ML_DIMENSION.
+    fn set_code(mut self, code: Arc<ParityCheckMatrix>) -> Self {
+        let generators: Vec<Vec<usize>> = code
+            .get_generator_matrix()
+            .checks_iter()
+            .map(|generator| generator.to_vec())
+            .collect();
+        if generators.len() > MAX_EXHAUSTIVE_ML_DIMENSION {
+            panic!("code dimension is too large");
+        }
+        self.code = code;
+        self.generators = generators;
+        self
+    }
+
+    fn take_the_code(&mut self) -> ParityCheckMatrix {
+        self.generators = Vec::new();
+        into_owned(std::mem::take(&mut self.code))
+    }
+
+    // The cost of a bit is the log likelihood ratio, up to a positive factor, between the
+    // error given that the bit was 0 and given that the bit was 1. The cost of a codeword is
+    // the sum of the costs of its bits, so the zero codeword has cost 0 and is the unique most
+    // likely codeword if every other codeword has a positive cost.
+    //
+    // The codewords are enumerated in Gray code order, so consecutive codewords differ by a
+    // single generator.
+    fn is_zero_codeword_the_unique_most_likely(&self, bit_costs: &[i64]) -> bool {
+        let mut codeword = vec![false; self.code.get_n_bits()];
+        let mut cost = 0;
+        for index in 1..1_u64 << self.generators.len() {
+            for bit in self.generators[index.trailing_zeros() as usize].iter() {
+                if codeword[*bit] {
+                    cost -= bit_costs[*bit];
+                } else {
+                    cost += bit_costs[*bit];
+                }
+                codeword[*bit] = !codeword[*bit];
+            }
+            if cost <= 0 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Decoder for ExhaustiveMlDecoder<Bsc> {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BscResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(self, code: Arc<Self::Code>) -> Self {
+        self.set_code(code)
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        self.take_the_code()
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // Above a crossover probability of 0.5, the most likely codewords are the farthest ones.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let crossover_prob = self.noise.get_crossover_prob();
+        let cost_of_a_distance = if crossover_prob < 0.5 {
+            1
+        } else if crossover_prob > 0.5 {
+            -1
+        } else {
+            0
+        };
+        let mut bit_costs = vec![cost_of_a_distance; self.code.get_n_bits()];
+        error
+            .iter()
+            .for_each(|bit| bit_costs[*bit] = -cost_of_a_distance);
+        if self.is_zero_codeword_the_unique_most_likely(&bit_costs) {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        }
+    }
+
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        self.noise.sample_error_with_rng(self.get_n_bits(), rng)
+    }
+}
+
+impl Decoder for ExhaustiveMlDecoder<UniformErasure> {
+    type Error = Vec<usize>; // Positions of erased bits.
+    type Result = ErasureResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(self, code: Arc<Self::Code>) -> Self {
+        self.set_code(code)
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        self.take_the_code()
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // A codeword with a 1 on an unerased bit is not compatible with the received word. The
+    // compatible codewords are equally likely.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let mut bit_costs = vec![1; self.code.get_n_bits()];
+        error.iter().for_each(|bit| bit_costs[*bit] = 0);
+        if self.is_zero_codeword_the_unique_most_likely(&bit_costs) {
+            ErasureResult::Success
+        } else {
+            ErasureResult::Failure
+        }
+    }
+
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        self.noise.sample_error_with_rng(self.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        BscDecoder, CodeGenerator, DecodingResult, ErasureDecoder, ErasureStrategy,
+        RegularLDPCCodeGenerator,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn get_bits_of(index: usize, n_bits: usize) -> Vec<usize> {
+        (0..n_bits).filter(|bit| index >> bit & 1 == 1).collect()
+    }
+
+    // The Hamming code is perfect, so the zero codeword is the unique closest codeword to
+    // every word of weight at most 1 and to no other word.
+    #[test]
+    fn bsc_decoding_of_hamming_code_corrects_single_errors() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut decoder =
+            ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(0.1)).for_code(code.clone());
+        assert_eq!(decoder.get_dimension(), 4);
+        for index in 0..1 << 7 {
+            let error = get_bits_of(index, 7);
+            assert_eq!(decoder.decode(&error).is_success(), error.len() <= 1);
+        }
+        assert_eq!(decoder.take_code(), code);
+    }
+
+    // A word of weight 2 is at the same distance of the two codewords of a repetition code
+    // with 4 bits.
+    #[test]
+    fn bsc_ties_are_failures_and_large_crossover_probs_prefer_far_codewords() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let mut decoder =
+            ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(0.1)).for_code(code.clone());
+        assert_eq!(decoder.decode(&vec![1]), BscResult::Success);
+        assert_eq!(decoder.decode(&vec![1, 3]), BscResult::Failure);
+        assert_eq!(decoder.decode(&vec![0, 1, 3]), BscResult::Failure);
+
+        let mut decoder =
+            ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(0.9)).for_code(code.clone());
+        assert_eq!(decoder.decode(&vec![0, 1, 3]), BscResult::Success);
+        assert_eq!(decoder.decode(&vec![1]), BscResult::Failure);
+
+        let mut decoder =
+            ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(0.5)).for_code(code);
+        assert_eq!(decoder.decode(&vec![]), BscResult::Failure);
+    }
+
+    #[test]
+    fn erasure_decoding_agrees_with_gaussian_elimination() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 2, 4).generate_with_rng(&mut rng);
+        let n_bits = code.get_n_bits();
+        let mut decoder =
+            ExhaustiveMlDecoder::with_noise(UniformErasure::with_prob(0.3)).for_code(code.clone());
+        let mut elimination = ErasureDecoder::with_prob(0.3)
+            .with_strategy(ErasureStrategy::MaximumLikelihood)
+            .for_code(code);
+        for index in 0..1 << n_bits {
+            let erasure = get_bits_of(index, n_bits);
+            assert_eq!(
+                decoder.decode(&erasure).is_success(),
+                elimination.decode(&erasure).is_success()
+            );
+        }
+    }
+
+    // Both decoders see the same errors since they are sampled from the same noise model with
+    // the same random number generator. Maximum likelihood decoding of a Hamming code fails
+    // exactly on the errors of weight at least 2.
+    #[test]
+    fn belief_propagation_is_no_better_than_maximum_likelihood() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(4);
+        let crossover_prob = 0.05;
+        let mut ml_decoder =
+            ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(crossover_prob))
+                .for_code(code.clone());
+        let mut bp_decoder = BscDecoder::with_crossover_prob(crossover_prob).for_code(code);
+        let rng = ChaCha8Rng::seed_from_u64(123);
+
+        let ml_result = ml_decoder.simulate_n_iterations_with_rng(1000, &mut rng.clone());
+        let bp_result = bp_decoder.simulate_n_iterations_with_rng(1000, &mut rng.clone());
+        assert!(ml_result.get_n_failures() <= bp_result.get_n_failures());
+
+        let expected = 1.0
+            - (1.0 - crossover_prob).powi(15)
+            - 15.0 * crossover_prob * (1.0 - crossover_prob).powi(14);
+        let std = (expected * (1.0 - expected) / 1000.0).sqrt();
+        assert!((ml_result.get_failure_rate() - expected).abs() < 4.0 * std);
+    }
+
+    #[test]
+    #[should_panic]
+    fn codes_of_large_dimension_panic() {
+        let code = ParityCheckMatrix::with_n_bits(MAX_EXHAUSTIVE_ML_DIMENSION + 1);
+        ExhaustiveMlDecoder::with_noise(Bsc::with_crossover_prob(0.1)).for_code(code);
+    }
+}
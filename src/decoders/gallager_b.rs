@@ -0,0 +1,378 @@
+//! A Gallager-B hard decision decoder for the binary symmetric channel.
+
+use super::message_passing::TannerGraph;
+use super::{into_owned, Bsc, Decoder, NoiseModel};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use crate::{BPResult, BscResult, StoppingCriterion};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Decoder for the binary symmetric channel using the Gallager-B algorithm.
+///
+/// The messages are bits. Each check sends to a bit the sum of the messages of its other bits.
+/// Each bit sends to a check its received value, unless at least a threshold number of its
+/// other checks send the complement, in which case it sends the complement. After each
+/// iteration, a bit is decided as the majority of its received value and of the messages of
+/// all its checks, with ties going to the received value. The decoder stops as soon as the
+/// decision is a codeword.
+///
+/// This is much cheaper than the soft decoders and can be used to quickly screen codes.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+///     vec![0, 1, 2],
+///     vec![3, 4, 5],
+///     vec![6, 7, 8],
+///     vec![0, 3, 6],
+///     vec![1, 4, 7],
+///     vec![2, 5, 8],
+/// ]);
+/// let mut decoder = GallagerBDecoder::with_crossover_prob(0.05).for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![4]).get_outcome(), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 4, 8]).get_outcome(), BscResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GallagerBDecoder {
+    code: Arc<ParityCheckMatrix>,
+    crossover_prob: f64,
+    max_iterations: usize,
+    thresholds: Option<Vec<usize>>,
+    graph: TannerGraph,
+}
+
+impl GallagerBDecoder {
+    /// Creates a Gallager-B decoder for the binary symmetric channel that flips each bit with
+    /// probability `crossover_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations and a bit sends the complement of
+    /// its received value when more than half of its other checks send it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        let code = Arc::new(ParityCheckMatrix::new());
+        Self {
+            graph: TannerGraph::from(&code),
+            code,
+            crossover_prob,
+            max_iterations: 10,
+            thresholds: None,
+        }
+    }
+
+    /// Sets the maximum number of iterations.
+    ///
+    /// The decoder stops before as soon as the decision has a zero syndrome. With 0
+    /// iterations, the decision is the channel output.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the number of other checks that must disagree with the received value of a bit
+    /// for the bit to send the complement of its received value.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `threshold` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = GallagerBDecoder::with_crossover_prob(0.1)
+    ///     .with_threshold(1)
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![2]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_threshold(self, threshold: usize) -> Self {
+        self.with_thresholds(vec![threshold])
+    }
+
+    /// Sets the threshold of each iteration. The threshold of the first iteration is used for
+    /// the messages sent by the bits at the second iteration and so on. The last threshold is
+    /// used for all the remaining iterations.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `thresholds` is empty or contains 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = GallagerBDecoder::with_crossover_prob(0.1)
+    ///     .with_thresholds(vec![2, 1])
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![2]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_thresholds(mut self, thresholds: Vec<usize>) -> Self {
+        if thresholds.is_empty() || thresholds.contains(&0) {
+            panic!("invalid threshold");
+        }
+        self.thresholds = Some(thresholds);
+        self
+    }
+
+    /// Decodes a `received` word and returns the decision together with the number of
+    /// iterations done and the reason why the decoder stopped.
+    ///
+    /// Unlike `decode`, this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = GallagerBDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let (decision, n_iterations, stopping_criterion) =
+    ///     decoder.decode_hard(&[GF2::B1, GF2::B0, GF2::B1]);
+    ///
+    /// assert_eq!(decision, vec![GF2::B1; 3]);
+    /// assert_eq!(n_iterations, 1);
+    /// assert_eq!(stopping_criterion, StoppingCriterion::SatisfiedSyndrome);
+    /// ```
+    pub fn decode_hard(&self, received: &[GF2]) -> (Vec<GF2>, usize, StoppingCriterion) {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let edge_bits = self.graph.get_edge_bits();
+        let mut bit_to_check: Vec<GF2> = edge_bits.iter().map(|bit| received[*bit]).collect();
+        let mut check_to_bit = vec![GF2::B0; self.graph.get_n_edges()];
+        let mut decision = received.to_vec();
+        let mut n_iterations = 0;
+        let stopping_criterion = loop {
+            if self.code.has_codeword(&decision) {
+                break StoppingCriterion::SatisfiedSyndrome;
+            }
+            if n_iterations == self.max_iterations {
+                break StoppingCriterion::MaxIterations;
+            }
+            for edges in self.graph.check_edges_iter() {
+                let sum = bit_to_check[edges.clone()]
+                    .iter()
+                    .fold(GF2::B0, |sum, message| sum + *message);
+                for edge in edges {
+                    check_to_bit[edge] = sum + bit_to_check[edge];
+                }
+            }
+            for (bit, edges) in self.graph.get_bit_edges().iter().enumerate() {
+                let n_disagreeing = edges
+                    .iter()
+                    .filter(|edge| check_to_bit[**edge] != received[bit])
+                    .count();
+                let threshold = self.get_threshold(n_iterations, edges.len());
+                for edge in edges {
+                    let is_disagreeing = check_to_bit[*edge] != received[bit];
+                    let n_others_disagreeing = n_disagreeing - usize::from(is_disagreeing);
+                    bit_to_check[*edge] = if n_others_disagreeing >= threshold {
+                        received[bit] + GF2::B1
+                    } else {
+                        received[bit]
+                    };
+                }
+                decision[bit] = if 2 * n_disagreeing > edges.len() + 1 {
+                    received[bit] + GF2::B1
+                } else {
+                    received[bit]
+                };
+            }
+            n_iterations += 1;
+        };
+        (decision, n_iterations, stopping_criterion)
+    }
+
+    // Without thresholds, a bit of degree d sends the complement when more than half of its
+    // d - 1 other checks disagree.
+    fn get_threshold(&self, iteration: usize, degree: usize) -> usize {
+        match &self.thresholds {
+            Some(thresholds) => thresholds[iteration.min(thresholds.len() - 1)],
+            None => degree.saturating_sub(1) / 2 + 1,
+        }
+    }
+}
+
+impl Decoder for GallagerBDecoder {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.graph = TannerGraph::from(&code);
+        self.code = code;
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        let code = std::mem::take(&mut self.code);
+        self.graph = TannerGraph::from(&self.code);
+        into_owned(code)
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the decision after the last iteration is the all zero codeword
+    // that was sent.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let mut received = vec![GF2::B0; self.code.get_n_bits()];
+        error.iter().for_each(|bit| received[*bit] = GF2::B1);
+        let (decision, n_iterations, stopping_criterion) = self.decode_hard(&received);
+        let n_residual_errors = decision.iter().filter(|value| **value == GF2::B1).count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations,
+            stopping_criterion,
+            n_residual_errors,
+        }
+    }
+
+    // Flip random bits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        Bsc::with_crossover_prob(self.crossover_prob)
+            .sample_error_with_rng(self.code.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BestCodeFinder, CodeGenerator, RegularLDPCCodeGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // Bit 0 is in the 3 checks, bits 1, 2 and 3 in 2 checks and bits 4, 5 and 6 in a single
+    // check.
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    fn decode(decoder: &GallagerBDecoder, flipped_bits: &[usize]) -> (Vec<usize>, usize) {
+        let mut received = vec![GF2::B0; 7];
+        flipped_bits.iter().for_each(|bit| received[*bit] = GF2::B1);
+        let (decision, n_iterations, _) = decoder.decode_hard(&received);
+        let ones = (0..7).filter(|bit| decision[*bit] == GF2::B1).collect();
+        (ones, n_iterations)
+    }
+
+    // A flip of bit 1 makes checks 0 and 1 send 1 to bit 1 and to their other bits. Only
+    // bit 1 has a majority of disagreeing checks and is decided as 0.
+    #[test]
+    fn flips_of_bits_in_two_checks_are_corrected_in_one_iteration() {
+        let decoder = GallagerBDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        for bit in 1..4 {
+            assert_eq!(decode(&decoder, &[bit]), (vec![], 1));
+        }
+    }
+
+    // A flip of bit 0 makes all checks send 1 to bits 1, 2 and 3, which are decided as 1.
+    // Together with bit 0 decided as 0, this gives the codeword 0111000.
+    #[test]
+    fn flip_of_the_bit_in_all_checks_is_decoded_to_an_other_codeword() {
+        let mut decoder = GallagerBDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        assert_eq!(decode(&decoder, &[0]), (vec![1, 2, 3], 1));
+
+        let result = decoder.decode(&vec![0]);
+        assert_eq!(result.get_outcome(), BscResult::Failure);
+        assert_eq!(result.get_n_residual_errors(), 3);
+        assert!(result.has_converged());
+    }
+
+    // A flip of bit 4 makes check 0 send 1 to bits 0, 1 and 2. At the second iteration, bits 1
+    // and 2 send 1 to their other check, while bit 0 only has one disagreeing other check and
+    // keeps sending 0. Then, checks 1 and 2 both send 1 to bits 0 and 3, which gives the
+    // codeword 1001100.
+    #[test]
+    fn flip_of_a_bit_in_a_single_check_is_decoded_to_an_other_codeword() {
+        let decoder = GallagerBDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        assert_eq!(decode(&decoder, &[4]), (vec![0, 3, 4], 2));
+    }
+
+    // With a threshold of 1, bit 0 also sends 1 to checks 1 and 2 at the second iteration, so
+    // they send 1 to bits 0, 1 and 2 and 0 to bit 3, which gives the codeword 1110100. With a
+    // threshold of 2, no bit ever sends the complement of its received value.
+    #[test]
+    fn thresholds_change_the_messages_of_the_bits() {
+        let decoder = GallagerBDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(5)
+            .for_code(hamming_code());
+
+        let low_threshold = decoder.clone().with_threshold(1);
+        assert_eq!(decode(&low_threshold, &[4]), (vec![0, 1, 2, 4], 2));
+
+        let mut high_threshold = decoder.clone().with_threshold(2);
+        assert_eq!(decode(&high_threshold, &[4]), (vec![4], 5));
+        assert_eq!(
+            high_threshold.decode(&vec![4]).get_stopping_criterion(),
+            StoppingCriterion::MaxIterations
+        );
+
+        // The first iteration sends the same messages as the received values, so the decoder is
+        // one iteration late.
+        let increasing_threshold = decoder.with_thresholds(vec![2, 1]);
+        assert_eq!(decode(&increasing_threshold, &[4]), (vec![0, 1, 2, 4], 3));
+    }
+
+    #[test]
+    fn received_codewords_are_decided_without_iterations() {
+        let decoder = GallagerBDecoder::with_crossover_prob(0.1).for_code(hamming_code());
+        assert_eq!(decode(&decoder, &[]), (vec![], 0));
+        assert_eq!(decode(&decoder, &[1, 2, 3]), (vec![1, 2, 3], 0));
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = GallagerBDecoder::with_crossover_prob(0.05).for_code(
+            RegularLDPCCodeGenerator::new(3, 6, 20, 6).generate_with_rng(&mut rng.clone()),
+        );
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+        assert!(result_0.get_n_successes() > 0);
+    }
+
+    #[test]
+    fn codes_can_be_screened_with_gallager_b() {
+        let generator = RegularLDPCCodeGenerator::new(3, 6, 4, 2);
+        let decoder = GallagerBDecoder::with_crossover_prob(0.02);
+        let code_finder =
+            BestCodeFinder::from_code_generator_and_decoder(&generator, decoder).among_n_codes(3);
+        let (_, result) = code_finder.find_best_code_simulating_n_iterations(50);
+        assert_eq!(result.get_n_iterations(), 50);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_threshold_panics() {
+        GallagerBDecoder::with_crossover_prob(0.1).with_thresholds(vec![2, 0]);
+    }
+}
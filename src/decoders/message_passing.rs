@@ -0,0 +1,542 @@
+//! Message passing over the Tanner graph of a code for the belief propagation decoders.
+//!
+//! The decoders only differ by the rule used to compute the messages from the checks to the
+//! bits. The messages from the bits to the checks and the decision are always the same.
+
+use super::{BPResult, BscResult, Schedule, SoftDecodingResult, StoppingCriterion};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use std::ops::Range;
+
+// Log likelihood ratios are clamped to this magnitude so that messages stay finite when the
+// crossover probability is very small or when the messages saturate.
+const MAX_LLR: f64 = 25.0;
+
+/// The rule used by each check to send messages to its bits.
+pub(super) trait CheckRule {
+    /// Fills `check_to_bit` with the messages of a check from the messages of its bits.
+    fn update_check_to_bit(&self, bit_to_check: &[f64], check_to_bit: &mut [f64]);
+}
+
+// How the messages are iterated. The damping is the weight of a new message from a bit to a
+// check against the message it replaces, so 1.0 means no damping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Iterations {
+    pub(super) schedule: Schedule,
+    pub(super) max_iterations: usize,
+    pub(super) damping: f64,
+}
+
+impl Iterations {
+    pub(super) fn with_max_iterations(max_iterations: usize) -> Self {
+        Self {
+            schedule: Schedule::Flooding,
+            max_iterations,
+            damping: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TannerGraph {
+    // The edges of check c are the edges from check_ranges[c] to check_ranges[c + 1].
+    check_ranges: Vec<usize>,
+    edge_bits: Vec<usize>,
+    bit_edges: Vec<Vec<usize>>,
+}
+
+impl TannerGraph {
+    pub(super) fn from(code: &ParityCheckMatrix) -> Self {
+        let mut check_ranges = vec![0];
+        let mut edge_bits = Vec::with_capacity(code.get_n_edges());
+        let mut bit_edges = vec![Vec::new(); code.get_n_bits()];
+        for check in code.checks_iter() {
+            for bit in check.iter() {
+                bit_edges[*bit].push(edge_bits.len());
+                edge_bits.push(*bit);
+            }
+            check_ranges.push(edge_bits.len());
+        }
+        Self {
+            check_ranges,
+            edge_bits,
+            bit_edges,
+        }
+    }
+
+    pub(super) fn get_n_edges(&self) -> usize {
+        self.edge_bits.len()
+    }
+
+    // Iterates over the ranges of the edges of each check.
+    pub(super) fn check_edges_iter(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.check_ranges.windows(2).map(|range| range[0]..range[1])
+    }
+
+    pub(super) fn get_edge_bits(&self) -> &[usize] {
+        &self.edge_bits
+    }
+
+    pub(super) fn get_bit_edges(&self) -> &[Vec<usize>] {
+        &self.bit_edges
+    }
+
+    // Since the all zero codeword is sent, the decoding succeeds if the last hard decision is
+    // zero.
+    pub(super) fn decode_with<C: CheckRule>(
+        &self,
+        rule: &C,
+        code: &ParityCheckMatrix,
+        channel_llrs: &[f64],
+        iterations: Iterations,
+    ) -> BPResult {
+        let result = self.decode_soft_with(rule, code, channel_llrs, iterations);
+        let n_residual_errors = result
+            .decision
+            .iter()
+            .filter(|value| **value == GF2::B1)
+            .count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations: result.n_iterations,
+            stopping_criterion: result.stopping_criterion,
+            n_residual_errors,
+        }
+    }
+
+    // Stops as soon as the hard decision is a codeword of `code`, possibly before the first
+    // iteration, or after `max_iterations`.
+    pub(super) fn decode_soft_with<C: CheckRule>(
+        &self,
+        rule: &C,
+        code: &ParityCheckMatrix,
+        channel_llrs: &[f64],
+        iterations: Iterations,
+    ) -> SoftDecodingResult {
+        let is_solved = |decision: &[GF2]| code.has_codeword(decision);
+        self.iterate_with(rule, channel_llrs, None, iterations, is_solved)
+    }
+
+    // Estimates an error of the given `syndrome` from the prior log likelihood ratios of the
+    // bits. The messages of the checks with a non zero syndrome are flipped and the decoder
+    // stops as soon as the hard decision has this syndrome.
+    pub(super) fn decode_syndrome_with<C: CheckRule>(
+        &self,
+        rule: &C,
+        code: &ParityCheckMatrix,
+        syndrome: &[GF2],
+        prior_llrs: &[f64],
+        iterations: Iterations,
+    ) -> SoftDecodingResult {
+        let is_solved = |decision: &[GF2]| code.get_syndrome_of(decision) == syndrome;
+        self.iterate_with(rule, prior_llrs, Some(syndrome), iterations, is_solved)
+    }
+
+    fn iterate_with<C, F>(
+        &self,
+        rule: &C,
+        channel_llrs: &[f64],
+        syndrome: Option<&[GF2]>,
+        iterations: Iterations,
+        is_solved: F,
+    ) -> SoftDecodingResult
+    where
+        C: CheckRule,
+        F: Fn(&[GF2]) -> bool,
+    {
+        let mut bit_to_check = self.get_messages_from_bits(channel_llrs);
+        let mut check_to_bit = vec![0.0; self.get_n_edges()];
+        let mut posteriors = channel_llrs.to_vec();
+        let mut decision = get_hard_decision_of(&posteriors);
+        let mut n_iterations = 0;
+        let stopping_criterion = loop {
+            if is_solved(&decision) {
+                break StoppingCriterion::SatisfiedSyndrome;
+            }
+            if n_iterations == iterations.max_iterations {
+                break StoppingCriterion::MaxIterations;
+            }
+            match iterations.schedule {
+                Schedule::Flooding => {
+                    self.update_check_to_bit_with(rule, &bit_to_check, &mut check_to_bit);
+                    if let Some(syndrome) = syndrome {
+                        self.flip_messages_of_unsatisfied_checks(syndrome, &mut check_to_bit);
+                    }
+                    posteriors = self.get_posteriors_from(channel_llrs, &check_to_bit);
+                    self.update_bit_to_check(
+                        &posteriors,
+                        &check_to_bit,
+                        iterations.damping,
+                        &mut bit_to_check,
+                    );
+                }
+                Schedule::LayeredByCheck => self.sweep_checks_with(
+                    rule,
+                    syndrome,
+                    iterations.damping,
+                    &mut posteriors,
+                    &mut bit_to_check,
+                    &mut check_to_bit,
+                ),
+            }
+            decision = get_hard_decision_of(&posteriors);
+            n_iterations += 1;
+        };
+        SoftDecodingResult {
+            posteriors: posteriors.into_iter().map(clamp).collect(),
+            decision,
+            n_iterations,
+            stopping_criterion,
+        }
+    }
+
+    pub(super) fn update_check_to_bit_with<C: CheckRule>(
+        &self,
+        rule: &C,
+        bit_to_check: &[f64],
+        check_to_bit: &mut [f64],
+    ) {
+        for range in self.check_ranges.windows(2) {
+            rule.update_check_to_bit(
+                &bit_to_check[range[0]..range[1]],
+                &mut check_to_bit[range[0]..range[1]],
+            );
+        }
+        check_to_bit
+            .iter_mut()
+            .for_each(|message| *message = clamp(*message));
+    }
+
+    // The checks are updated one after the other from the latest posteriors, and the posteriors
+    // of their bits are updated right away from the damped messages.
+    fn sweep_checks_with<C: CheckRule>(
+        &self,
+        rule: &C,
+        syndrome: Option<&[GF2]>,
+        damping: f64,
+        posteriors: &mut [f64],
+        bit_to_check: &mut [f64],
+        check_to_bit: &mut [f64],
+    ) {
+        for (check, range) in self.check_ranges.windows(2).enumerate() {
+            let edges = range[0]..range[1];
+            for edge in edges.clone() {
+                let bit = self.edge_bits[edge];
+                let message = clamp(posteriors[bit] - check_to_bit[edge]);
+                bit_to_check[edge] = damp(message, bit_to_check[edge], damping);
+            }
+            rule.update_check_to_bit(
+                &bit_to_check[edges.clone()],
+                &mut check_to_bit[edges.clone()],
+            );
+            let is_unsatisfied = syndrome.is_some_and(|syndrome| syndrome[check] == GF2::B1);
+            for edge in edges {
+                check_to_bit[edge] = clamp(check_to_bit[edge]);
+                if is_unsatisfied {
+                    check_to_bit[edge] = -check_to_bit[edge];
+                }
+                posteriors[self.edge_bits[edge]] = bit_to_check[edge] + check_to_bit[edge];
+            }
+        }
+    }
+
+    // A check with a non zero syndrome has an odd number of flipped bits, so the sign of its
+    // messages is reversed.
+    fn flip_messages_of_unsatisfied_checks(&self, syndrome: &[GF2], check_to_bit: &mut [f64]) {
+        for (range, value) in self.check_ranges.windows(2).zip(syndrome.iter()) {
+            if *value == GF2::B1 {
+                check_to_bit[range[0]..range[1]]
+                    .iter_mut()
+                    .for_each(|message| *message = -*message);
+            }
+        }
+    }
+
+    fn get_posteriors_from(&self, channel_llrs: &[f64], check_to_bit: &[f64]) -> Vec<f64> {
+        channel_llrs
+            .iter()
+            .zip(self.bit_edges.iter())
+            .map(|(llr, edges)| llr + edges.iter().map(|edge| check_to_bit[*edge]).sum::<f64>())
+            .collect()
+    }
+
+    // Each bit sends to a check its posterior without the message of this check.
+    fn update_bit_to_check(
+        &self,
+        posteriors: &[f64],
+        check_to_bit: &[f64],
+        damping: f64,
+        bit_to_check: &mut [f64],
+    ) {
+        for (edge, bit) in self.edge_bits.iter().enumerate() {
+            let message = clamp(posteriors[*bit] - check_to_bit[edge]);
+            bit_to_check[edge] = damp(message, bit_to_check[edge], damping);
+        }
+    }
+
+    pub(super) fn get_messages_from_bits(&self, llrs: &[f64]) -> Vec<f64> {
+        self.edge_bits.iter().map(|bit| llrs[*bit]).collect()
+    }
+}
+
+// Since the all zero codeword is sent, the flipped bits have negative channel log likelihood
+// ratios.
+pub(super) fn get_channel_llrs_of(error: &[usize], n_bits: usize, crossover_prob: f64) -> Vec<f64> {
+    let llr = get_channel_llr_of_zero(crossover_prob);
+    let mut llrs = vec![llr; n_bits];
+    error.iter().for_each(|bit| llrs[*bit] = -llr);
+    llrs
+}
+
+pub(super) fn get_channel_llrs_of_received(received: &[GF2], crossover_prob: f64) -> Vec<f64> {
+    let llr = get_channel_llr_of_zero(crossover_prob);
+    received
+        .iter()
+        .map(|value| if *value == GF2::B0 { llr } else { -llr })
+        .collect()
+}
+
+// Log likelihood ratios given by the user, such as the output of a demodulator. Infinities are
+// clamped like every other value.
+pub(super) fn get_channel_llrs_of_given(llrs: &[f64]) -> Vec<f64> {
+    if llrs.iter().any(|llr| llr.is_nan()) {
+        panic!("invalid log likelihood ratio");
+    }
+    llrs.iter().map(|llr| clamp(*llr)).collect()
+}
+
+fn get_channel_llr_of_zero(crossover_prob: f64) -> f64 {
+    clamp(((1.0 - crossover_prob) / crossover_prob).ln())
+}
+
+pub(super) fn get_hard_decision_of(posteriors: &[f64]) -> Vec<GF2> {
+    posteriors
+        .iter()
+        .map(|posterior| if *posterior < 0.0 { GF2::B1 } else { GF2::B0 })
+        .collect()
+}
+
+// The previous message is the one being replaced, so damping doesn't need other buffers.
+fn damp(message: f64, previous: f64, damping: f64) -> f64 {
+    if damping < 1.0 {
+        damping * message + (1.0 - damping) * previous
+    } else {
+        message
+    }
+}
+
+fn clamp(llr: f64) -> f64 {
+    llr.clamp(-MAX_LLR, MAX_LLR)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Constant;
+
+    impl CheckRule for Constant {
+        fn update_check_to_bit(&self, _bit_to_check: &[f64], check_to_bit: &mut [f64]) {
+            check_to_bit.iter_mut().for_each(|message| *message = 1e9);
+        }
+    }
+
+    struct SumOfOthers;
+
+    impl CheckRule for SumOfOthers {
+        fn update_check_to_bit(&self, bit_to_check: &[f64], check_to_bit: &mut [f64]) {
+            let sum: f64 = bit_to_check.iter().sum();
+            for (message, other) in check_to_bit.iter_mut().zip(bit_to_check.iter()) {
+                *message = sum - other;
+            }
+        }
+    }
+
+    fn flooding(max_iterations: usize) -> Iterations {
+        Iterations::with_max_iterations(max_iterations)
+    }
+
+    fn layered(max_iterations: usize) -> Iterations {
+        Iterations {
+            schedule: Schedule::LayeredByCheck,
+            ..Iterations::with_max_iterations(max_iterations)
+        }
+    }
+
+    #[test]
+    fn edges_are_ordered_by_checks() {
+        let code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let graph = TannerGraph::from(&code);
+
+        assert_eq!(graph.check_ranges, vec![0, 2, 4]);
+        assert_eq!(graph.edge_bits, vec![0, 1, 1, 2]);
+        assert_eq!(graph.bit_edges, vec![vec![0], vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn check_messages_are_clamped() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let mut check_to_bit = vec![0.0; graph.get_n_edges()];
+        graph.update_check_to_bit_with(&Constant, &[0.0; 4], &mut check_to_bit);
+
+        assert_eq!(check_to_bit, vec![MAX_LLR; 4]);
+    }
+
+    #[test]
+    fn channel_llrs_are_finite_and_negative_on_flipped_bits() {
+        for &prob in &[0.0, 1e-300, 0.1, 1.0] {
+            let llrs = get_channel_llrs_of(&[1], 3, prob);
+            assert!(llrs.iter().all(|llr| llr.is_finite()));
+            assert_eq!(llrs[0], -llrs[1]);
+        }
+        assert_eq!(get_channel_llrs_of(&[1], 3, 0.5), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn given_llrs_are_clamped() {
+        assert_eq!(
+            get_channel_llrs_of_given(&[f64::INFINITY, -1.5, f64::NEG_INFINITY, -100.0]),
+            vec![MAX_LLR, -1.5, -MAX_LLR, -MAX_LLR]
+        );
+    }
+
+    #[test]
+    fn without_iterations_the_channel_output_is_decided() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let llrs = get_channel_llrs_of(&[0, 2], 3, 0.1);
+
+        assert_eq!(get_hard_decision_of(&llrs), vec![GF2::B1, GF2::B0, GF2::B1]);
+        let result = graph.decode_with(&Constant, &code, &llrs, flooding(0));
+        assert_eq!(result.get_outcome(), BscResult::Failure);
+        assert_eq!(result.get_n_iterations(), 0);
+        assert_eq!(result.get_n_residual_errors(), 2);
+        assert_eq!(
+            result.get_stopping_criterion(),
+            StoppingCriterion::MaxIterations
+        );
+    }
+
+    #[test]
+    fn codewords_are_decided_without_iterations() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+
+        for error in &[vec![], vec![0, 1, 2]] {
+            let llrs = get_channel_llrs_of(error, 3, 0.1);
+            let result = graph.decode_with(&Constant, &code, &llrs, flooding(10));
+            assert_eq!(result.get_n_iterations(), 0);
+            assert!(result.has_converged());
+        }
+    }
+
+    #[test]
+    fn soft_posteriors_are_clamped() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let llrs = get_channel_llrs_of(&[0], 3, 0.1);
+
+        let result =
+            graph.decode_soft_with(&Constant, &code, &llrs, flooding(10));
+        assert_eq!(result.get_posteriors()[0], MAX_LLR - llrs[1]);
+        assert_eq!(result.get_posteriors()[1..], [MAX_LLR; 2]);
+        assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    }
+
+    #[test]
+    fn received_words_are_converted_to_llrs() {
+        let received = vec![GF2::B1, GF2::B0, GF2::B1];
+        assert_eq!(
+            get_channel_llrs_of_received(&received, 0.1),
+            get_channel_llrs_of(&[0, 2], 3, 0.1)
+        );
+    }
+
+    #[test]
+    fn checks_with_non_zero_syndrome_flip_their_messages() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let prior_llrs = get_channel_llrs_of(&[], 3, 0.1);
+        let syndrome = vec![GF2::B1, GF2::B0];
+
+        for &iterations in &[flooding(10), layered(10)] {
+            let result =
+                graph.decode_syndrome_with(&Constant, &code, &syndrome, &prior_llrs, iterations);
+            assert_eq!(result.get_hard_decision(), &[GF2::B1, GF2::B0, GF2::B0]);
+            assert_eq!(result.get_n_iterations(), 1);
+            assert!(result.has_converged());
+
+            let zero_syndrome = [GF2::B0; 2];
+            let result = graph.decode_syndrome_with(
+                &Constant,
+                &code,
+                &zero_syndrome,
+                &prior_llrs,
+                iterations,
+            );
+            assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+            assert_eq!(result.get_n_iterations(), 0);
+        }
+    }
+
+    #[test]
+    fn layered_schedule_uses_the_latest_messages() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let llrs = get_channel_llrs_of(&[0], 3, 0.1);
+        let llr = llrs[1];
+
+        let result = graph.decode_soft_with(&SumOfOthers, &code, &llrs, flooding(1));
+        assert_eq!(result.get_posteriors(), &[0.0, llr, 2.0 * llr]);
+
+        // After the first check, bit 1 has a zero posterior and sends it to the second check.
+        let result =
+            graph.decode_soft_with(&SumOfOthers, &code, &llrs, layered(1));
+        assert_eq!(result.get_posteriors(), &[0.0, llr, llr]);
+        assert!(result.has_converged());
+    }
+
+    #[test]
+    fn damped_messages_mix_the_new_and_previous_messages() {
+        assert_eq!(damp(2.0, 4.0, 0.75), 2.5);
+        assert_eq!(damp(-2.0, 4.0, 1.0), -2.0);
+
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let llrs = get_channel_llrs_of(&[0, 1], 3, 0.1);
+        let llr = llrs[2];
+        let damped = Iterations {
+            damping: 0.5,
+            ..Iterations::with_max_iterations(2)
+        };
+
+        // Without damping, the second iteration sends 0 from bit 1 to the first check and -2 llr
+        // from bit 1 to the second check. With damping, these are averaged with the -llr sent
+        // at the first iteration.
+        let result = graph.decode_soft_with(&SumOfOthers, &code, &llrs, damped);
+        let expected = [-1.5 * llr, -llr, -0.5 * llr];
+        for (posterior, expected) in result.get_posteriors().iter().zip(expected.iter()) {
+            assert!((posterior - expected).abs() < 1e-12);
+        }
+        let result = graph.decode_soft_with(&SumOfOthers, &code, &llrs, flooding(2));
+        assert_eq!(result.get_posteriors(), &[-llr; 3]);
+    }
+
+    #[test]
+    fn decoding_stops_when_the_syndrome_is_satisfied() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let graph = TannerGraph::from(&code);
+        let llrs = get_channel_llrs_of(&[0, 2], 3, 0.1);
+
+        let result = graph.decode_with(&Constant, &code, &llrs, flooding(10));
+        assert_eq!(result.get_outcome(), BscResult::Success);
+        assert_eq!(result.get_n_iterations(), 1);
+        assert!(result.has_converged());
+    }
+}
@@ -0,0 +1,594 @@
+//! A min-sum belief propagation decoder for the binary symmetric channel.
+
+use super::message_passing::{
+    get_channel_llrs_of, get_channel_llrs_of_given, get_channel_llrs_of_received, CheckRule,
+    Iterations, TannerGraph,
+};
+use super::{into_owned, Bsc, Decoder, NoiseModel, Schedule, SoftDecoder};
+use crate::{BPResult, SoftDecodingResult};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Decoder for the binary symmetric channel using min-sum belief propagation.
+///
+/// This is the same decoder as the `BscDecoder` except that each check sends to a bit the
+/// product of the signs and the minimum of the magnitudes of the messages of the other bits.
+/// This approximates the sum-product rule and is faster to compute.
+///
+/// The magnitudes of the messages can be corrected with an offset and a normalization. The
+/// offset is first subtracted from the minimum magnitude and the result is floored at 0. Then,
+/// it is multiplied by the normalization. That is, a check sends a message of magnitude
+/// `normalization * max(minimum - offset, 0)`.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+/// let mut decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), BscResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinSumDecoder {
+    code: Arc<ParityCheckMatrix>,
+    crossover_prob: f64,
+    iterations: Iterations,
+    rule: MinSum,
+    graph: TannerGraph,
+}
+
+impl MinSumDecoder {
+    /// Creates a min-sum decoder for the binary symmetric channel that flips each bit with
+    /// probability `crossover_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        let code = Arc::new(ParityCheckMatrix::new());
+        Self {
+            graph: TannerGraph::from(&code),
+            code,
+            crossover_prob,
+            iterations: Iterations::with_max_iterations(10),
+            rule: MinSum {
+                normalization: 1.0,
+                offset: 0.0,
+            },
+        }
+    }
+
+    /// Sets the maximum number of iterations of belief propagation.
+    ///
+    /// The decoder stops before as soon as the hard decision has a zero syndrome. With 0
+    /// iterations, the hard decision is the channel output.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.iterations.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the order in which the messages are updated.
+    ///
+    /// If not specified, default to the flooding schedule. The maximum number of iterations
+    /// is a number of full sweeps over the checks for every schedule.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.iterations.schedule = schedule;
+        self
+    }
+
+    /// Sets the damping of the messages from the bits to the checks.
+    ///
+    /// At each iteration, a new message `m` from a bit to a check is replaced by
+    /// `damping * m + (1 - damping) * previous` where `previous` is the message sent at the
+    /// previous iteration. This slows down the updates and reduces the oscillations of the
+    /// messages on codes with short cycles. By default, the damping is 1.0 which means no
+    /// damping.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `damping` is not in the range (0.0, 1.0].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = MinSumDecoder::with_crossover_prob(0.1)
+    ///     .with_damping(0.5)
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_damping(mut self, damping: f64) -> Self {
+        if damping <= 0.0 || damping > 1.0 {
+            panic!("damping is not in (0, 1]");
+        }
+        self.iterations.damping = damping;
+        self
+    }
+
+    /// Sets the factor by which the messages from the checks to the bits are multiplied after
+    /// removing the offset.
+    ///
+    /// By default, the normalization is 1.0 which gives plain min-sum. The Tanner graph of the
+    /// code is kept, so this can be used to try many factors on the same code.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `normalization` is not in the range (0.0, 1.0].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    ///
+    /// for &normalization in &[0.75, 0.8, 0.9] {
+    ///     let mut decoder = decoder.clone().with_normalization(normalization);
+    ///     assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+    /// }
+    /// ```
+    pub fn with_normalization(mut self, normalization: f64) -> Self {
+        if normalization <= 0.0 || normalization > 1.0 {
+            panic!("normalization is not in (0, 1]");
+        }
+        self.rule.normalization = normalization;
+        self
+    }
+
+    /// Sets the offset subtracted from the magnitudes of the messages from the checks to the
+    /// bits before the normalization. The magnitudes are floored at 0.
+    ///
+    /// By default, the offset is 0.0 which gives plain min-sum. The Tanner graph of the code is
+    /// kept, so this can be used to try many offsets on the same code.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `offset` is negative or not finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = MinSumDecoder::with_crossover_prob(0.1)
+    ///     .with_offset(0.5)
+    ///     .with_normalization(0.75)
+    ///     .for_code(code);
+    ///
+    /// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+    /// ```
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        if !(0.0..f64::INFINITY).contains(&offset) {
+            panic!("offset is not a non negative number");
+        }
+        self.rule.offset = offset;
+        self
+    }
+
+    /// Decodes a `received` word and returns the posterior log likelihood ratios together with
+    /// the hard decision.
+    ///
+    /// Unlike `decode`, this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_soft(&[GF2::B0, GF2::B1, GF2::B0]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert!(result.has_converged());
+    /// ```
+    pub fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_received(received, self.crossover_prob);
+        self.graph
+            .decode_soft_with(&self.rule, &self.code, &channel_llrs, self.iterations)
+    }
+
+    /// Decodes the channel log likelihood ratios `llrs` of a received word, such as the output
+    /// of a demodulator, and returns the posterior log likelihood ratios together with the
+    /// hard decision.
+    ///
+    /// The crossover probability of the decoder isn't used. A positive log likelihood ratio
+    /// means that a bit is more likely to be 0. Infinite values are clamped like the messages.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `llrs` is not the number of bits of the code or if some of them
+    /// are NaN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_from_llrs(&[1.5, -0.5, f64::INFINITY]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert!(result.has_converged());
+    /// ```
+    pub fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        if llrs.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_given(llrs);
+        self.graph
+            .decode_soft_with(&self.rule, &self.code, &channel_llrs, self.iterations)
+    }
+
+    /// Estimates an error with the given `syndrome`.
+    ///
+    /// The decoder starts from the all zero word biased by the crossover probability and the
+    /// checks with a non zero syndrome enforce an odd parity. Returns the hard decision if its
+    /// syndrome is `syndrome` before the maximum number of iterations or `None` otherwise.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `syndrome` is not the number of checks of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    ///
+    /// let error = decoder.decode_syndrome(&[GF2::B0, GF2::B1]);
+    /// assert_eq!(error, Some(vec![GF2::B0, GF2::B0, GF2::B1]));
+    /// ```
+    pub fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        if syndrome.len() != self.code.get_n_checks() {
+            panic!("syndrome doesn't have the right length");
+        }
+        let prior_llrs = get_channel_llrs_of(&[], self.code.get_n_bits(), self.crossover_prob);
+        let result = self.graph.decode_syndrome_with(
+            &self.rule,
+            &self.code,
+            syndrome,
+            &prior_llrs,
+            self.iterations,
+        );
+        if result.has_converged() {
+            Some(result.decision)
+        } else {
+            None
+        }
+    }
+}
+
+// Only the two smallest magnitudes are needed since the minimum over the other bits is the
+// smallest magnitude for all bits except the one having it.
+#[derive(Debug, Clone, Copy)]
+struct MinSum {
+    normalization: f64,
+    offset: f64,
+}
+
+impl MinSum {
+    fn get_corrected(&self, magnitude: f64) -> f64 {
+        self.normalization * (magnitude - self.offset).max(0.0)
+    }
+}
+
+impl CheckRule for MinSum {
+    fn update_check_to_bit(&self, bit_to_check: &[f64], check_to_bit: &mut [f64]) {
+        let mut smallest = f64::INFINITY;
+        let mut second_smallest = f64::INFINITY;
+        let mut smallest_index = 0;
+        let mut is_negative = false;
+        for (index, message) in bit_to_check.iter().enumerate() {
+            is_negative ^= *message < 0.0;
+            let magnitude = message.abs();
+            if magnitude < smallest {
+                second_smallest = smallest;
+                smallest = magnitude;
+                smallest_index = index;
+            } else if magnitude < second_smallest {
+                second_smallest = magnitude;
+            }
+        }
+        for (index, message) in bit_to_check.iter().enumerate() {
+            let magnitude = self.get_corrected(if index == smallest_index {
+                second_smallest
+            } else {
+                smallest
+            });
+            check_to_bit[index] = if is_negative ^ (*message < 0.0) {
+                -magnitude
+            } else {
+                magnitude
+            };
+        }
+    }
+}
+
+impl SoftDecoder for MinSumDecoder {
+    fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        MinSumDecoder::decode_soft(self, received)
+    }
+
+    fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        MinSumDecoder::decode_from_llrs(self, llrs)
+    }
+
+    fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        MinSumDecoder::decode_syndrome(self, syndrome)
+    }
+}
+
+impl Decoder for MinSumDecoder {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.graph = TannerGraph::from(&code);
+        self.code = code;
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        let code = std::mem::take(&mut self.code);
+        self.graph = TannerGraph::from(&self.code);
+        into_owned(code)
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the hard decision after the last iteration is the all zero
+    // codeword that was sent.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let channel_llrs = get_channel_llrs_of(error, self.code.get_n_bits(), self.crossover_prob);
+        self.graph
+            .decode_with(&self.rule, &self.code, &channel_llrs, self.iterations)
+    }
+
+    // Flip random bits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        Bsc::with_crossover_prob(self.crossover_prob)
+            .sample_error_with_rng(self.code.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BscResult;
+    use crate::BscDecoder;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn product_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0, 3, 6],
+            vec![1, 4, 7],
+            vec![2, 5, 8],
+        ])
+    }
+
+    #[test]
+    fn check_messages_use_the_signs_and_the_minimum_of_the_other_bits() {
+        let rule = MinSum {
+            normalization: 1.0,
+            offset: 0.0,
+        };
+        let mut check_to_bit = vec![0.0; 4];
+        rule.update_check_to_bit(&[2.0, -1.0, 3.0, -0.5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![0.5, -0.5, 0.5, -1.0]);
+
+        let mut check_to_bit = vec![0.0; 2];
+        rule.update_check_to_bit(&[1.5, 1.5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![1.5, 1.5]);
+    }
+
+    #[test]
+    fn layered_schedule_corrects_single_flips() {
+        let mut decoder = MinSumDecoder::with_crossover_prob(0.05)
+            .with_schedule(Schedule::LayeredByCheck)
+            .for_code(product_code());
+
+        for i in 0..9 {
+            assert_eq!(decoder.decode(&vec![i]).get_outcome(), BscResult::Success);
+        }
+    }
+
+    #[test]
+    fn check_messages_are_scaled_by_the_normalization() {
+        let rule = MinSum {
+            normalization: 0.75,
+            offset: 0.0,
+        };
+        let mut check_to_bit = vec![0.0; 4];
+        rule.update_check_to_bit(&[2.0, -1.0, 3.0, -0.5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![0.375, -0.375, 0.375, -0.75]);
+    }
+
+    #[test]
+    fn offset_is_removed_before_the_normalization() {
+        let rule = MinSum {
+            normalization: 0.5,
+            offset: 0.25,
+        };
+        let mut check_to_bit = vec![0.0; 4];
+        rule.update_check_to_bit(&[2.0, -1.0, 3.0, -0.5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![0.125, -0.125, 0.125, -0.375]);
+    }
+
+    #[test]
+    fn magnitudes_are_floored_at_zero() {
+        let rule = MinSum {
+            normalization: 1.0,
+            offset: 0.75,
+        };
+        let mut check_to_bit = vec![0.0; 4];
+        rule.update_check_to_bit(&[2.0, -1.0, 3.0, -0.5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![0.0, 0.0, 0.0, -0.25]);
+    }
+
+    // On the 3 bits repetition code with the first bit flipped, after one iteration the first
+    // bit receives the channel message of the second bit through the first check. This exactly
+    // cancels its own channel message, which is decided as 0. With an offset, the first bit
+    // receives less and stays decided as 1.
+    #[test]
+    fn offset_changes_the_hard_decision_of_a_tie() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = MinSumDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(1)
+            .for_code(code);
+        assert_eq!(decoder.decode(&vec![0]).get_outcome(), BscResult::Success);
+
+        let mut decoder = decoder.with_offset(0.5);
+        assert_eq!(decoder.decode(&vec![0]).get_outcome(), BscResult::Failure);
+
+        let mut decoder = decoder.with_max_iterations(2);
+        assert_eq!(decoder.decode(&vec![0]).get_outcome(), BscResult::Success);
+    }
+
+    #[test]
+    fn changing_the_normalization_keeps_the_tanner_graph() {
+        let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(product_code());
+        let graph = decoder.graph.clone();
+        let decoder = decoder.with_normalization(0.8);
+        assert_eq!(decoder.graph, graph);
+    }
+
+    #[test]
+    fn reproductibility_of_simulations_with_normalization() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        for &normalization in &[0.5, 0.75, 0.9] {
+            let mut decoder = MinSumDecoder::with_crossover_prob(0.1)
+                .for_code(product_code())
+                .with_normalization(normalization);
+
+            let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+            let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+            assert_eq!(result_0, result_1);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_normalization_panics() {
+        MinSumDecoder::with_crossover_prob(0.1).with_normalization(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn normalization_above_one_panics() {
+        MinSumDecoder::with_crossover_prob(0.1).with_normalization(1.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn damping_above_one_panics() {
+        MinSumDecoder::with_crossover_prob(0.1).with_damping(1.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_offset_panics() {
+        MinSumDecoder::with_crossover_prob(0.1).with_offset(-0.5);
+    }
+
+    #[test]
+    fn min_sum_and_sum_product_agree_on_single_flips() {
+        let mut min_sum = MinSumDecoder::with_crossover_prob(0.05).for_code(product_code());
+        let mut sum_product = BscDecoder::with_crossover_prob(0.05).for_code(product_code());
+
+        for i in 0..9 {
+            assert_eq!(min_sum.decode(&vec![i]).get_outcome(), BscResult::Success);
+            assert_eq!(sum_product.decode(&vec![i]).get_outcome(), BscResult::Success);
+        }
+    }
+
+    #[test]
+    fn error_free_words_terminate_without_iterations() {
+        let mut decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(product_code());
+        let result = decoder.decode(&vec![]);
+        assert_eq!(result.get_n_iterations(), 0);
+        assert!(result.has_converged());
+    }
+
+    #[test]
+    fn syndromes_of_single_flips_give_back_the_flips() {
+        let code = product_code();
+        let decoder = MinSumDecoder::with_crossover_prob(0.05).for_code(code.clone());
+        for bit in 0..9 {
+            let mut error = vec![GF2::B0; 9];
+            error[bit] = GF2::B1;
+            let syndrome = code.get_syndrome_of(&error);
+            assert_eq!(decoder.decode_syndrome(&syndrome), Some(error));
+        }
+    }
+
+    #[test]
+    fn soft_decoding_agrees_with_decoding_of_errors() {
+        let decoder = MinSumDecoder::with_crossover_prob(0.05).for_code(product_code());
+        let mut received = vec![GF2::B0; 9];
+        received[4] = GF2::B1;
+
+        let result = decoder.decode_soft(&received);
+        assert_eq!(result.get_hard_decision(), &[GF2::B0; 9]);
+        assert_eq!(
+            result.get_n_iterations(),
+            decoder.clone().decode(&vec![4]).get_n_iterations()
+        );
+    }
+
+    #[test]
+    fn the_most_reliable_bits_win_when_decoding_llrs() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(code);
+        let result = decoder.decode_from_llrs(&[0.5, 0.5, -2.0]);
+        assert_eq!(result.get_hard_decision(), &[GF2::B1; 3]);
+        assert!(result.has_converged());
+    }
+
+    #[test]
+    fn same_seeds_give_the_same_errors_for_both_decoders() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let min_sum = MinSumDecoder::with_crossover_prob(0.1).for_code(product_code());
+        let sum_product = BscDecoder::with_crossover_prob(0.1).for_code(product_code());
+
+        assert_eq!(
+            min_sum.get_random_error_with_rng(&mut rng.clone()),
+            sum_product.get_random_error_with_rng(&mut rng.clone())
+        );
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = MinSumDecoder::with_crossover_prob(0.1).for_code(product_code());
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+    }
+}
@@ -1,22 +1,87 @@
 //! Toolbox for decoding.
 
+use crate::GF2;
 use rand::{thread_rng, Rng};
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub mod simulation_results;
-pub use simulation_results::SimulationResult;
+pub use simulation_results::{BetaPrior, PrecisionStoppingCondition, SimulationResult};
+
+pub mod results_table;
+pub use results_table::{ResultsTable, RESULTS_TABLE_HEADER};
+
+pub mod performance_curve;
+pub use performance_curve::PerformanceCurve;
+
+pub mod residual_sets;
+pub use residual_sets::ResidualSetCounter;
+
+pub mod threshold_estimator;
+pub use threshold_estimator::{ThresholdEstimate, ThresholdEstimator};
+
+pub mod trapping_sets;
+pub use trapping_sets::{TrappingSet, TrappingSetFinder};
 
 mod n_iterations_simulator;
-use n_iterations_simulator::NIterationsSimulator;
+use n_iterations_simulator::{simulate_n_iterations_in_parallel_with_rng, NIterationsSimulator};
 
 mod n_events_simulator;
 use n_events_simulator::NEventsSimulator;
 
+mod precision_simulator;
+use precision_simulator::PrecisionSimulator;
+
+mod duration_simulator;
+use duration_simulator::DurationSimulator;
+
+mod message_passing;
+
+mod peeling;
+
+pub mod noise_models;
+pub use noise_models::*;
+
+pub mod simulator;
+pub use simulator::Simulator;
+
 // pub mod belief_propagation;
 // pub use belief_propagation::*;
 
+pub mod awgn;
+pub use awgn::*;
+
+pub mod bit_flipping;
+pub use bit_flipping::*;
+
+pub mod bsc;
+pub use bsc::*;
+
+pub mod css_erasure;
+pub use css_erasure::*;
+
+pub mod depolarizing;
+pub use depolarizing::*;
+
 pub mod erasure;
 pub use erasure::*;
 
+pub mod exhaustive_ml;
+pub use exhaustive_ml::*;
+
+pub mod gallager_b;
+pub use gallager_b::*;
+
+pub mod min_sum;
+pub use min_sum::*;
+
+pub mod osd;
+pub use osd::*;
+
+pub mod quantized_min_sum;
+pub use quantized_min_sum::*;
+
 pub mod quantum_erasure;
 pub use quantum_erasure::*;
 
@@ -33,16 +98,66 @@ pub trait Decoder: Send + Sync + Sized {
     /// The type of result the decoder is returning.
     type Result: DecodingResult;
 
+    /// Creates a new decoder similar to `self` that use the shared `code` without changing the
+    /// other parameters.
+    ///
+    /// This is the preferred way to give a code to a decoder since the same code can be used by
+    /// many decoders, for example at different probabilities or on different threads, without
+    /// moving it in and out of each decoder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    /// use std::sync::Arc;
+    ///
+    /// let code = Arc::new(ParityCheckMatrix::hamming_code_with_n_checks(3));
+    /// let mut low_noise = ErasureDecoder::with_prob(0.1).for_shared_code(Arc::clone(&code));
+    /// let mut high_noise = ErasureDecoder::with_prob(0.4).for_shared_code(Arc::clone(&code));
+    ///
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let low_result = low_noise.simulate_n_iterations_with_rng(1000, &mut rng);
+    /// let high_result = high_noise.simulate_n_iterations_with_rng(1000, &mut rng);
+    ///
+    /// assert!(low_result.get_failure_rate() < high_result.get_failure_rate());
+    /// assert_eq!(code.get_n_bits(), 7);
+    /// ```
+    fn for_shared_code(self, code: Arc<Self::Code>) -> Self;
+
     /// Creates a new decoder similar to `self` that use `code` without changing the other 
     /// parameter. This consume `code`.
-    fn for_code(self, code: Self::Code) -> Self;
+    ///
+    /// This is the same as `for_shared_code` with a code that is not shared.
+    fn for_code(self, code: Self::Code) -> Self {
+        self.for_shared_code(Arc::new(code))
+    }
 
     /// Takes the `code` out of the decoder leaving an empty set of code instead.
+    ///
+    /// If the code is shared with other decoders, this returns a copy of the code. Sharing the
+    /// code with `for_shared_code` avoids taking it back.
     fn take_code(&mut self) -> Self::Code;
 
+    /// Returns the number of bits of the code, or the number of qubits for a quantum code.
+    fn get_n_bits(&self) -> usize;
+
     /// Tries to decode a given error.
+    ///
+    /// The error doesn't need to come from `get_random_error`, so specific errors such as the
+    /// failures found during a simulation can be replayed.
     fn decode(&mut self, error: &Self::Error) -> Self::Result;
 
+    /// Tries to decode each of the given `errors`.
+    ///
+    /// By default, this decodes the errors one by one with `decode`. The simulators decode
+    /// their sampled errors through this method, so a decoder can override it to share work
+    /// between the errors of a batch.
+    fn decode_batch(&mut self, errors: &[Self::Error]) -> Vec<Self::Result> {
+        errors.iter().map(|error| self.decode(error)).collect()
+    }
+
     /// Generates a random error with random number generator `rng`.
     fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error;
 
@@ -68,7 +183,7 @@ pub trait Decoder: Send + Sync + Sized {
         n_iterations: usize,
         rng: &mut R,
     ) -> SimulationResult {
-        NIterationsSimulator::from(self)
+        NIterationsSimulator::from(self, sample_from_decoder)
             .simulate_n_iterations_with_rng(n_iterations, rng)
             .get_result()
     }
@@ -79,6 +194,92 @@ pub trait Decoder: Send + Sync + Sized {
         self.simulate_n_iterations_with_rng(n_iterations, &mut thread_rng())
     }
 
+    /// Simulates decoding random error using `self` for `n_iterations` with random number
+    /// generator `rng` and calls `progress` with the partial result every `interval`
+    /// iterations.
+    ///
+    /// This gives the same result as `simulate_n_iterations_with_rng`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `interval` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::thread_rng;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+    /// decoder.simulate_n_iterations_reporting_progress_with_rng(
+    ///     1000,
+    ///     100,
+    ///     |result| println!("{} errors decoded", result.get_n_iterations()),
+    ///     &mut thread_rng(),
+    /// );
+    /// ```
+    fn simulate_n_iterations_reporting_progress_with_rng<R, F>(
+        &mut self,
+        n_iterations: usize,
+        interval: usize,
+        progress: F,
+        rng: &mut R,
+    ) -> SimulationResult
+    where
+        R: Rng,
+        F: Fn(&SimulationResult) + Sync,
+    {
+        NIterationsSimulator::from(self, sample_from_decoder)
+            .with_progress(interval, &progress)
+            .simulate_n_iterations_with_rng(n_iterations, rng)
+            .get_result()
+    }
+
+    /// Simulates decoding random error using clones of `self` on all threads for
+    /// `n_iterations` with random number generator `rng`.
+    ///
+    /// The iterations are split into chunks of fixed size with one seed per chunk drawn from
+    /// `rng`. Thus, the result only depends on `rng` and not on the number of threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+    /// let rng = ChaCha8Rng::seed_from_u64(123);
+    ///
+    /// let result = decoder.simulate_n_iterations_in_parallel_with_rng(10_000, &mut rng.clone());
+    /// assert_eq!(result.get_n_iterations(), 10_000);
+    /// assert_eq!(
+    ///     result,
+    ///     decoder.simulate_n_iterations_in_parallel_with_rng(10_000, &mut rng.clone())
+    /// );
+    /// ```
+    fn simulate_n_iterations_in_parallel_with_rng<R: Rng>(
+        &self,
+        n_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult
+    where
+        Self: Clone,
+    {
+        simulate_n_iterations_in_parallel_with_rng(self, sample_from_decoder, n_iterations, rng)
+    }
+
+    /// Simulates decoding random error using clones of `self` on all threads for
+    /// `n_iterations` with the thread random number generator.
+    fn simulate_n_iterations_in_parallel(&self, n_iterations: usize) -> SimulationResult
+    where
+        Self: Clone,
+    {
+        self.simulate_n_iterations_in_parallel_with_rng(n_iterations, &mut thread_rng())
+    }
+
     /// Simulates the decoder until `n_events` are found with random number
     /// generator `rng`.
     ///
@@ -88,7 +289,7 @@ pub trait Decoder: Send + Sync + Sized {
         n_events: usize,
         rng: &mut R,
     ) -> SimulationResult {
-        NEventsSimulator::from(self)
+        NEventsSimulator::from(self, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(n_events, rng)
             .get_result()
     }
@@ -100,7 +301,152 @@ pub trait Decoder: Send + Sync + Sized {
     fn simulate_until_n_events_are_found(&mut self, n_events: usize) -> SimulationResult {
         self.simulate_until_n_events_are_found_with_rng(n_events, &mut thread_rng())
     }
-    
+
+    /// Simulates the decoder until the relative standard error of the failure rate is at most
+    /// `relative_error` or `max_iterations` random errors are decoded with random number
+    /// generator `rng`.
+    ///
+    /// The precision is checked after each batch of errors and the returned result records
+    /// which of the two conditions stopped the simulation. As long as no failures are found,
+    /// the relative error is infinite and the simulation goes on until `max_iterations`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::thread_rng;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+    /// let result = decoder.simulate_until_precision_with_rng(0.1, 100_000, &mut thread_rng());
+    ///
+    /// assert!(result.get_relative_standard_error() <= 0.1);
+    /// assert_eq!(
+    ///     result.get_precision_stopping_condition(),
+    ///     Some(PrecisionStoppingCondition::TargetPrecision)
+    /// );
+    /// ```
+    fn simulate_until_precision_with_rng<R: Rng>(
+        &mut self,
+        relative_error: f64,
+        max_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult {
+        PrecisionSimulator::from(self, sample_from_decoder)
+            .simulate_until_precision_with_rng(relative_error, max_iterations, rng)
+            .get_result()
+    }
+
+    /// Simulates the decoder until the relative standard error of the failure rate is at most
+    /// `relative_error` or `max_iterations` random errors are decoded with the thread random
+    /// number generator.
+    fn simulate_until_precision(
+        &mut self,
+        relative_error: f64,
+        max_iterations: usize,
+    ) -> SimulationResult {
+        self.simulate_until_precision_with_rng(relative_error, max_iterations, &mut thread_rng())
+    }
+
+    /// Simulates decoding random errors for the given time `budget` with random number
+    /// generator `rng`.
+    ///
+    /// The elapsed time is checked after each batch of errors, so the simulation can exceed the
+    /// budget by the time needed to decode one batch. The number of decoded errors is given by
+    /// the number of iterations of the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// use rand::thread_rng;
+    /// use std::time::Duration;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+    /// let result =
+    ///     decoder.simulate_for_duration_with_rng(Duration::from_millis(10), &mut thread_rng());
+    ///
+    /// assert!(result.get_n_iterations() > 0);
+    /// ```
+    fn simulate_for_duration_with_rng<R: Rng>(
+        &mut self,
+        budget: Duration,
+        rng: &mut R,
+    ) -> SimulationResult {
+        DurationSimulator::from(self, sample_from_decoder)
+            .simulate_for_duration_with_rng(budget, rng)
+            .get_result()
+    }
+
+    /// Simulates decoding random errors for the given time `budget` with the thread random
+    /// number generator.
+    fn simulate_for_duration(&mut self, budget: Duration) -> SimulationResult {
+        self.simulate_for_duration_with_rng(budget, &mut thread_rng())
+    }
+
+    /// Creates a simulator decoding with `self` the errors sampled by `noise` instead of the
+    /// errors sampled by `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = ErasureDecoder::with_prob(0.0).for_code(code);
+    /// let mut simulator = decoder.with_noise(PerBitErasure::with_probs(vec![1.0, 1.0, 0.0]));
+    ///
+    /// assert_eq!(simulator.simulate_n_iterations(10).get_n_successes(), 10);
+    /// ```
+    fn with_noise<N: NoiseModel<Error = Self::Error>>(self, noise: N) -> Simulator<Self, N> {
+        Simulator::from(self, noise)
+    }
+
+}
+
+/// An interface for the belief propagation decoders of the binary symmetric channel that can
+/// output the posterior log likelihood ratios of the bits.
+///
+/// This is what post-processors such as `OsdPostProcessor` need from the decoder they wrap.
+pub trait SoftDecoder: Decoder {
+    /// Decodes a `received` word and returns the posterior log likelihood ratios together with
+    /// the hard decision.
+    ///
+    /// Unlike `decode`, this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult;
+
+    /// Decodes the channel log likelihood ratios `llrs` of a received word and returns the
+    /// posterior log likelihood ratios together with the hard decision.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `llrs` is not the number of bits of the code or if some of them
+    /// are NaN.
+    fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult;
+
+    /// Estimates an error with the given `syndrome` starting from the all zero word biased by
+    /// the channel parameter.
+    ///
+    /// Returns `None` if the estimated error doesn't have the given syndrome.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `syndrome` is not the number of checks of the code.
+    fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>>;
+}
+
+// Takes a shared code out of its `Arc`, cloning it if it is still used elsewhere.
+fn into_owned<C: Clone>(code: Arc<C>) -> C {
+    Arc::try_unwrap(code).unwrap_or_else(|code| (*code).clone())
+}
+
+// Samples an error with the noise of the decoder.
+fn sample_from_decoder<D: Decoder>(decoder: &D, rng: &mut ChaCha8Rng) -> D::Error {
+    decoder.get_random_error_with_rng(rng)
 }
 
 /// An interface for decoder outcome.
@@ -115,6 +461,30 @@ pub trait DecodingResult: Send + Sync {
     fn is_failure(&self) -> bool {
         !self.is_success()
     }
+
+    /// Returns the number of iterations used by an iterative decoder. This is 0 for the other
+    /// decoders.
+    fn get_n_decoding_iterations(&self) -> usize {
+        0
+    }
+
+    /// Returns the value recorded in the histogram of a simulation. This is the number of
+    /// iterations for an iterative decoder and `None` for the decoders without such a value.
+    fn get_histogram_value(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the number of bits that are still wrong or erased after decoding, or `None` for
+    /// the decoders that don't report it.
+    fn get_n_residual_errors(&self) -> Option<usize> {
+        None
+    }
+
+    /// Checks if an iterative decoder converged to an estimate with the measured syndrome. This
+    /// is always `true` for the other decoders.
+    fn has_converged(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug)]
@@ -125,7 +495,7 @@ pub struct Ressources {
 
 /// An erasure decoder can either result in a `Success` when no logical bits are erased or in a
 /// `Failure` when some logical bits are erased.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ErasureResult {
     Failure,
     Success,
@@ -135,4 +505,295 @@ impl DecodingResult for ErasureResult {
     fn is_success(&self) -> bool {
         self == &Self::Success
     }
-}
\ No newline at end of file
+}
+
+/// The result of an erasure decoder for a classical code.
+///
+/// It contains the outcome of the decoding together with the erased bits that the decoder
+/// could not recover.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+/// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+/// let result = decoder.decode(&vec![0, 1, 2]);
+///
+/// assert_eq!(result.get_outcome(), ErasureResult::Failure);
+/// assert_eq!(result.get_unresolved_bits(), &[0, 1, 2]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ErasureDecodingResult {
+    outcome: ErasureResult,
+    unresolved_bits: Vec<usize>,
+}
+
+impl ErasureDecodingResult {
+    pub(super) fn from(unresolved_bits: Vec<usize>) -> Self {
+        let outcome = if unresolved_bits.is_empty() {
+            ErasureResult::Success
+        } else {
+            ErasureResult::Failure
+        };
+        Self {
+            outcome,
+            unresolved_bits,
+        }
+    }
+
+    /// Returns whether the decoding succeeded or failed.
+    pub fn get_outcome(&self) -> ErasureResult {
+        self.outcome
+    }
+
+    /// Returns the sorted erased bits that were not recovered. This is empty for a success.
+    pub fn get_unresolved_bits(&self) -> &[usize] {
+        &self.unresolved_bits
+    }
+}
+
+impl DecodingResult for ErasureDecodingResult {
+    fn is_success(&self) -> bool {
+        self.outcome.is_success()
+    }
+
+    fn get_n_residual_errors(&self) -> Option<usize> {
+        Some(self.unresolved_bits.len())
+    }
+
+    // The residual erasure contains a stopping set of the code.
+    fn get_histogram_value(&self) -> Option<usize> {
+        Some(self.unresolved_bits.len())
+    }
+}
+
+/// A decoder for the binary symmetric channel can either result in a `Success` when the
+/// decoded error is the channel error or in a `Failure` otherwise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BscResult {
+    Failure,
+    Success,
+}
+
+impl DecodingResult for BscResult {
+    fn is_success(&self) -> bool {
+        self == &Self::Success
+    }
+}
+
+/// The reason why a belief propagation decoder stopped.
+///
+/// More reasons may be added for new decoders, so matches need a wildcard arm.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum StoppingCriterion {
+    /// The hard decision has a zero syndrome.
+    SatisfiedSyndrome,
+    /// The maximum number of iterations was done.
+    MaxIterations,
+    /// The decoder couldn't make progress, so every later iteration would be the same. For
+    /// example, no bit reached the flip threshold of a bit flipping decoder.
+    Stalled,
+}
+
+/// The order in which a belief propagation decoder updates its messages.
+///
+/// In both schedules, an iteration is a full sweep over the checks and the decoder checks the
+/// syndrome of its hard decision after each sweep.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Schedule {
+    /// All checks are updated at once from the messages of the previous iteration and then all
+    /// bits are updated.
+    Flooding,
+    /// The checks are updated one after the other and the beliefs of their bits are updated
+    /// right away, so later checks use the newest messages. This usually converges in fewer
+    /// iterations.
+    LayeredByCheck,
+}
+
+/// The soft output of a belief propagation decoder.
+///
+/// It contains the posterior log likelihood ratio of each bit, the hard decision obtained from
+/// them, the number of iterations done and the reason why the decoder stopped. A positive log
+/// likelihood ratio means that a bit is more likely to be 0. They are clamped, so they are
+/// finite even when the messages saturate.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+/// let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+/// let result = decoder.decode_soft(&[GF2::B1, GF2::B0, GF2::B1]);
+///
+/// assert_eq!(result.get_hard_decision(), &[GF2::B1; 3]);
+/// assert!(result.get_posteriors().iter().all(|llr| *llr < 0.0));
+/// assert!(result.has_converged());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct SoftDecodingResult {
+    posteriors: Vec<f64>,
+    decision: Vec<GF2>,
+    n_iterations: usize,
+    stopping_criterion: StoppingCriterion,
+}
+
+impl SoftDecodingResult {
+    /// Returns the posterior log likelihood ratio of each bit.
+    pub fn get_posteriors(&self) -> &[f64] {
+        &self.posteriors
+    }
+
+    /// Returns the hard decision on each bit obtained from the posteriors.
+    pub fn get_hard_decision(&self) -> &[GF2] {
+        &self.decision
+    }
+
+    /// Returns the number of iterations done by the decoder.
+    pub fn get_n_iterations(&self) -> usize {
+        self.n_iterations
+    }
+
+    /// Returns the reason why the decoder stopped.
+    pub fn get_stopping_criterion(&self) -> StoppingCriterion {
+        self.stopping_criterion
+    }
+
+    /// Checks if the decoder stopped because the hard decision has a zero syndrome.
+    pub fn has_converged(&self) -> bool {
+        self.stopping_criterion == StoppingCriterion::SatisfiedSyndrome
+    }
+}
+
+/// The result of a belief propagation decoder.
+///
+/// It contains the outcome of the decoding together with the number of iterations done and the
+/// reason why the decoder stopped.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+/// let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+/// let result = decoder.decode(&vec![2]);
+///
+/// assert_eq!(result.get_outcome(), BscResult::Success);
+/// assert_eq!(result.get_n_iterations(), 1);
+/// assert_eq!(result.get_stopping_criterion(), StoppingCriterion::SatisfiedSyndrome);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BPResult {
+    outcome: BscResult,
+    n_iterations: usize,
+    stopping_criterion: StoppingCriterion,
+    n_residual_errors: usize,
+}
+
+impl BPResult {
+    /// Returns whether the decoding succeeded or failed.
+    pub fn get_outcome(&self) -> BscResult {
+        self.outcome
+    }
+
+    /// Returns the number of iterations done by the decoder.
+    pub fn get_n_iterations(&self) -> usize {
+        self.n_iterations
+    }
+
+    /// Returns the reason why the decoder stopped.
+    pub fn get_stopping_criterion(&self) -> StoppingCriterion {
+        self.stopping_criterion
+    }
+
+    /// Checks if the decoder stopped because the hard decision has a zero syndrome.
+    ///
+    /// This doesn't mean that the decoding succeeded since the hard decision can be an other
+    /// codeword than the one that was sent.
+    pub fn has_converged(&self) -> bool {
+        self.stopping_criterion == StoppingCriterion::SatisfiedSyndrome
+    }
+
+    /// Returns the number of bits where the hard decision differs from the codeword that was
+    /// sent.
+    pub fn get_n_residual_errors(&self) -> usize {
+        self.n_residual_errors
+    }
+}
+
+impl DecodingResult for BPResult {
+    fn is_success(&self) -> bool {
+        self.outcome.is_success()
+    }
+
+    fn get_n_decoding_iterations(&self) -> usize {
+        self.n_iterations
+    }
+
+    fn get_histogram_value(&self) -> Option<usize> {
+        Some(self.n_iterations)
+    }
+
+    fn get_n_residual_errors(&self) -> Option<usize> {
+        Some(self.n_residual_errors)
+    }
+
+    fn has_converged(&self) -> bool {
+        self.stopping_criterion == StoppingCriterion::SatisfiedSyndrome
+    }
+}
+
+/// The outcome of a decoder for a quantum code.
+///
+/// The decoding is a `Success` if the estimated error is the channel error up to a product of
+/// stabilizers. It is a `DetectedFailure` if the decoder didn't find an error with the measured
+/// syndrome and a `LogicalFailure` if the estimated error differs from the channel error by a
+/// logical operator.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QuantumOutcome {
+    Success,
+    DetectedFailure,
+    LogicalFailure,
+}
+
+/// The result of a belief propagation decoder for a quantum code.
+///
+/// It contains the outcome of the decoding together with the total number of iterations done
+/// by the decoders of the X and Z components.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DepolarizingResult {
+    outcome: QuantumOutcome,
+    n_iterations: usize,
+}
+
+impl DepolarizingResult {
+    /// Returns the outcome of the decoding.
+    pub fn get_outcome(&self) -> QuantumOutcome {
+        self.outcome
+    }
+
+    /// Returns the total number of iterations done by the decoders of the X and Z components.
+    pub fn get_n_iterations(&self) -> usize {
+        self.n_iterations
+    }
+}
+
+impl DecodingResult for DepolarizingResult {
+    fn is_success(&self) -> bool {
+        self.outcome == QuantumOutcome::Success
+    }
+
+    fn get_n_decoding_iterations(&self) -> usize {
+        self.n_iterations
+    }
+
+    fn get_histogram_value(&self) -> Option<usize> {
+        Some(self.n_iterations)
+    }
+
+    // A component that didn't converge is reported as a detected failure.
+    fn has_converged(&self) -> bool {
+        self.outcome != QuantumOutcome::DetectedFailure
+    }
+}
@@ -1,23 +1,29 @@
 use super::{Decoder, SimulationResult};
-use rand::distributions::Standard;
-use rand::{Rng, SeedableRng};
+use crate::SeedSequence;
+use rand::Rng;
 use rand_chacha::ChaCha8Rng;
-use rayon::prelude::*;
 
-pub(super) struct NEventsSimulator<'a, D> {
+// The errors are sampled by `sample_error` from the decoder and a random number generator.
+pub(super) struct NEventsSimulator<'a, D, S> {
     decoder: &'a mut D,
+    sample_error: S,
     n_events: usize,
     result: SimulationResult,
-    random_seeds: Vec<u64>,
+    seeds: Option<SeedSequence>,
 }
 
-impl<'a, D: Decoder> NEventsSimulator<'a, D> {
-    pub(super) fn from(decoder: &'a mut D) -> Self {
+impl<'a, D, S> NEventsSimulator<'a, D, S>
+where
+    D: Decoder,
+    S: Fn(&D, &mut ChaCha8Rng) -> D::Error,
+{
+    pub(super) fn from(decoder: &'a mut D, sample_error: S) -> Self {
         Self {
             decoder,
+            sample_error,
             n_events: 0,
             result: SimulationResult::new(),
-            random_seeds: Vec::new(),
+            seeds: None,
         }
     }
 
@@ -41,39 +47,31 @@ impl<'a, D: Decoder> NEventsSimulator<'a, D> {
     }
 
     fn run_the_simulation(&mut self) {
-        let results = (0..self.n_events)
-            // .into_par_iter()
-            .map(|thread_index| self.simulate_thread_until_one_event_is_found(thread_index));
-
-        let mut n_successes: u64 = 0;
-        let mut n_failures: u64 = 0;
-
-        for simres in results {
-            n_successes += simres.get_n_successes();
-            n_failures += simres.get_n_failures();
-        }
-
-        self.result = SimulationResult::with_n_successes_and_failures(n_successes, n_failures);
+        self.result = (0..self.n_events)
+            .map(|thread_index| self.simulate_thread_until_one_event_is_found(thread_index))
+            .sum();
     }
 
     fn simulate_thread_until_one_event_is_found(&mut self, thread_index: usize) -> SimulationResult {
         let mut rng = self.get_thread_rng(thread_index);
         let mut result = SimulationResult::new();
+        let n_bits = self.decoder.get_n_bits();
         while result.has_not_at_least_one_success_and_one_failure() {
-            let decoding_result = self.decoder.decode_random_error_with_rng(&mut rng);
-            result.add_decoding_result(decoding_result);
+            let error = (self.sample_error)(self.decoder, &mut rng);
+            let decoding_result = self.decoder.decode(&error);
+            result.add_decoding_result_on_n_bits(decoding_result, n_bits);
         }
         result
     }
 
     fn initialize_random_seeds_with_rng<R: Rng>(&mut self, rng: &mut R) {
-        self.random_seeds = rng.sample_iter(Standard).take(self.n_events).collect()
+        self.seeds = Some(SeedSequence::from_rng(rng));
     }
 
     // Yep, I'm imposing ChaCha8Rng with different seeds for each thread.
     // I don't have a better solution for now that preserve reproductability.
     fn get_thread_rng(&self, thread_index: usize) -> ChaCha8Rng {
-        ChaCha8Rng::seed_from_u64(self.random_seeds[thread_index])
+        self.seeds.as_ref().unwrap().get_rng(thread_index as u64)
     }
 
     pub(super) fn get_result(self) -> SimulationResult {
@@ -84,7 +82,7 @@ impl<'a, D: Decoder> NEventsSimulator<'a, D> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use super::super::ErasureDecoder;
+    use super::super::{sample_from_decoder, ErasureDecoder};
     use crate::ParityCheckMatrix;
     use rand_chacha::ChaCha8Rng;
     use rand::SeedableRng;
@@ -98,7 +96,7 @@ mod test {
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
 
-        let result = NEventsSimulator::from(&mut decoder)
+        let result = NEventsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
             .get_result();
 
@@ -114,12 +112,12 @@ mod test {
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
 
-        let result_0 = NEventsSimulator::from(&mut decoder)
+        let result_0 = NEventsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
             .get_result()
             .get_success_rate();
 
-        let result_1 = NEventsSimulator::from(&mut decoder)
+        let result_1 = NEventsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
             .get_result()
             .get_success_rate();
@@ -135,12 +133,12 @@ mod test {
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
 
-        let result_0 = NEventsSimulator::from(&mut decoder)
+        let result_0 = NEventsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
             .get_result()
             .get_success_rate();
 
-        let result_1 = NEventsSimulator::from(&mut decoder)
+        let result_1 = NEventsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
             .get_result()
             .get_success_rate();
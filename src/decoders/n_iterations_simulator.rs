@@ -1,26 +1,83 @@
-use super::{Decoder, DecodingResult, SimulationResult};
-use rand::distributions::Standard;
-use rand::{Rng, SeedableRng};
+use super::{Decoder, SimulationResult};
+use crate::SeedSequence;
+use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
 
-pub(super) struct NIterationsSimulator<'a, D: Decoder> {
+// The errors are sampled and decoded by batches of this size.
+pub(super) const BATCH_SIZE: usize = 256;
+
+// The number of errors simulated by each parallel task. It doesn't depend on the number of
+// threads, so a parallel simulation gives the same result on any machine.
+pub(super) const CHUNK_SIZE: usize = 16 * BATCH_SIZE;
+
+// Simulates chunks of errors with clones of `decoder` in parallel. Each chunk uses the random
+// number generator of its index in a seed sequence drawn from `rng`.
+pub(super) fn simulate_n_iterations_in_parallel_with_rng<D, S, R>(
+    decoder: &D,
+    sample_error: S,
+    n_iterations: usize,
+    rng: &mut R,
+) -> SimulationResult
+where
+    D: Decoder + Clone,
+    S: Fn(&D, &mut ChaCha8Rng) -> D::Error + Sync,
+    R: Rng,
+{
+    let n_chunks = n_iterations.div_ceil(CHUNK_SIZE);
+    let seeds = SeedSequence::from_rng(rng);
+    (0..n_chunks)
+        .into_par_iter()
+        .map(|chunk| {
+            let mut decoder = decoder.clone();
+            let n_iterations_of_chunk = CHUNK_SIZE.min(n_iterations - chunk * CHUNK_SIZE);
+            NIterationsSimulator::from(&mut decoder, &sample_error)
+                .simulate_n_iterations_with_rng(
+                    n_iterations_of_chunk,
+                    &mut seeds.get_rng(chunk as u64),
+                )
+                .get_result()
+        })
+        .reduce(SimulationResult::empty, |left, right| left + right)
+}
+
+type Progress<'a> = &'a (dyn Fn(&SimulationResult) + Sync);
+
+// The errors are sampled by `sample_error` from the decoder and a random number generator.
+pub(super) struct NIterationsSimulator<'a, D: Decoder, S> {
     decoder: &'a mut D,
+    sample_error: S,
     n_iterations: usize,
-    n_successes: usize,
-    random_seeds: Vec<u64>,
+    result: SimulationResult,
+    seeds: Option<SeedSequence>,
+    // The partial result is given to the callback every `interval` iterations.
+    progress: Option<(usize, Progress<'a>)>,
 }
 
-impl<'a, D: Decoder> NIterationsSimulator<'a, D> {
-    pub(super) fn from(decoder: &'a mut D) -> Self {
+impl<'a, D, S> NIterationsSimulator<'a, D, S>
+where
+    D: Decoder,
+    S: Fn(&D, &mut ChaCha8Rng) -> D::Error,
+{
+    pub(super) fn from(decoder: &'a mut D, sample_error: S) -> Self {
         Self {
             decoder,
+            sample_error,
             n_iterations: 0,
-            n_successes: 0,
-            random_seeds: Vec::new(),
+            result: SimulationResult::new(),
+            seeds: None,
+            progress: None,
         }
     }
 
+    pub(super) fn with_progress(mut self, interval: usize, progress: Progress<'a>) -> Self {
+        if interval == 0 {
+            panic!("invalid progress interval");
+        }
+        self.progress = Some((interval, progress));
+        self
+    }
+
     pub(super) fn simulate_n_iterations_with_rng<R: Rng>(
         mut self,
         n_iterations: usize,
@@ -41,37 +98,62 @@ impl<'a, D: Decoder> NIterationsSimulator<'a, D> {
     }
 
     fn initialize_random_seeds_with_rng<R: Rng>(&mut self, rng: &mut R) {
-        self.random_seeds = rng.sample_iter(Standard).take(self.n_iterations).collect()
+        self.seeds = Some(SeedSequence::from_rng(rng));
     }
 
     fn run_the_simulation(&mut self) {
-        self.n_successes = (0..self.n_iterations)
-            // .into_par_iter()
-            .filter(|thread_index| {
-                let mut rng = self.get_thread_rng(*thread_index);
-                self.decoder
-                    .decode_random_error_with_rng(&mut rng)
-                    .is_success()
-            })
-            .count();
+        let mut result = SimulationResult::new();
+        let n_bits = self.decoder.get_n_bits();
+        let mut errors = Vec::with_capacity(BATCH_SIZE);
+        let mut start = 0;
+        while start < self.n_iterations {
+            let end = self.get_end_of_batch_from(start);
+            errors.clear();
+            for thread_index in start..end {
+                let mut rng = self.get_thread_rng(thread_index);
+                errors.push((self.sample_error)(self.decoder, &mut rng));
+            }
+            self.decoder
+                .decode_batch(&errors)
+                .into_iter()
+                .for_each(|decoding_result| {
+                    result.add_decoding_result_on_n_bits(decoding_result, n_bits)
+                });
+            if let Some((interval, progress)) = self.progress {
+                if end.is_multiple_of(interval) {
+                    progress(&result);
+                }
+            }
+            start = end;
+        }
+        self.result = result;
+    }
+
+    // Batches stop at the multiples of the progress interval. This doesn't change the result
+    // since each error has its own random number generator.
+    fn get_end_of_batch_from(&self, start: usize) -> usize {
+        let end = (start + BATCH_SIZE).min(self.n_iterations);
+        match self.progress {
+            Some((interval, _)) => end.min((start / interval + 1) * interval),
+            None => end,
+        }
     }
 
     // Yep, I'm imposing ChaCha8Rng for each thread.
     // I don't have a better solution for now that preserve reproductability.
     fn get_thread_rng(&self, thread_index: usize) -> ChaCha8Rng {
-        ChaCha8Rng::seed_from_u64(self.random_seeds[thread_index])
+        self.seeds.as_ref().unwrap().get_rng(thread_index as u64)
     }
 
-    pub(super) fn get_result(&self) -> SimulationResult {
-        let n_failures = self.n_iterations - self.n_successes;
-        SimulationResult::with_n_successes_and_failures(self.n_successes as u64, n_failures as u64)
+    pub(super) fn get_result(self) -> SimulationResult {
+        self.result
     }
 }
 
 
 #[cfg(test)]
 mod test {
-    use super::super::ErasureDecoder;
+    use super::super::{sample_from_decoder, ErasureDecoder};
     use super::*;
     use crate::ParityCheckMatrix;
     use rand::SeedableRng;
@@ -79,29 +161,130 @@ mod test {
 
     #[test]
     fn there_is_n_iterations() {
-        let code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
 
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
-        let n_iterations = NIterationsSimulator::from(&mut decoder)
+        let n_iterations = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_n_iterations_with_rng(1000, &mut rng.clone())
             .get_result()
             .get_n_iterations();
         assert_eq!(n_iterations, 1000);
     }
 
+    #[test]
+    fn batches_give_the_same_result_as_decoding_one_error_at_a_time() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let mut decoder = ErasureDecoder::with_prob(0.4).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let n_iterations = 2 * BATCH_SIZE + 3;
+
+        let result = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_n_iterations_with_rng(n_iterations, &mut rng.clone())
+            .get_result();
+
+        let mut expected = SimulationResult::new();
+        let seeds = SeedSequence::from_rng(&mut rng);
+        for mut rng in seeds.get_n_rngs(n_iterations) {
+            let decoding_result = decoder.decode_random_error_with_rng(&mut rng);
+            expected.add_decoding_result_on_n_bits(decoding_result, 7);
+        }
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn progress_is_reported_every_interval_without_changing_the_result() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let reports = std::sync::Mutex::new(Vec::new());
+        let progress = |result: &SimulationResult| reports.lock().unwrap().push(result.clone());
+
+        let result = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
+            .with_progress(100, &progress)
+            .simulate_n_iterations_with_rng(1050, &mut rng.clone())
+            .get_result();
+        let expected = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_n_iterations_with_rng(1050, &mut rng.clone())
+            .get_result();
+        assert_eq!(result, expected);
+
+        let reports = reports.into_inner().unwrap();
+        let n_iterations: Vec<u64> = reports.iter().map(|r| r.get_n_iterations()).collect();
+        assert_eq!(n_iterations, (1..=10).map(|i| 100 * i).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn parallel_simulation_doesnt_depend_on_the_number_of_threads() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let n_iterations = 5 * CHUNK_SIZE + 7;
+
+        let result = simulate_n_iterations_in_parallel_with_rng(
+            &decoder,
+            sample_from_decoder,
+            n_iterations,
+            &mut rng.clone(),
+        );
+        let single_thread_result = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| {
+                simulate_n_iterations_in_parallel_with_rng(
+                    &decoder,
+                    sample_from_decoder,
+                    n_iterations,
+                    &mut rng.clone(),
+                )
+            });
+
+        assert_eq!(result.get_n_iterations(), n_iterations as u64);
+        assert_eq!(result, single_thread_result);
+    }
+
+    #[test]
+    fn parallel_simulation_is_the_sum_of_the_chunks() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let n_iterations = CHUNK_SIZE + 10;
+
+        let result = simulate_n_iterations_in_parallel_with_rng(
+            &decoder,
+            sample_from_decoder,
+            n_iterations,
+            &mut rng.clone(),
+        );
+
+        let seeds = SeedSequence::from_rng(&mut rng);
+        let expected = [CHUNK_SIZE, 10]
+            .iter()
+            .zip(seeds.get_n_rngs(2))
+            .map(|(n_iterations, mut rng)| {
+                decoder.simulate_n_iterations_with_rng(*n_iterations, &mut rng)
+            })
+            .sum::<SimulationResult>();
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn reproductibility_for_repetition_code() {
-        let code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
 
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
-        let result_0 = NIterationsSimulator::from(&mut decoder)
+        let result_0 = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_n_iterations_with_rng(1000, &mut rng.clone())
             .get_result()
             .get_success_rate();
 
-        let result_1 = NIterationsSimulator::from(&mut decoder)
+        let result_1 = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_n_iterations_with_rng(1000, &mut rng.clone())
             .get_result()
             .get_success_rate();
@@ -119,12 +302,12 @@ mod test {
 
         let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
         let rng = ChaCha8Rng::seed_from_u64(123);
-        let result_0 = NIterationsSimulator::from(&mut decoder)
+        let result_0 = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_n_iterations_with_rng(1000, &mut rng.clone())
             .get_result()
             .get_success_rate();
 
-        let result_1 = NIterationsSimulator::from(&mut decoder)
+        let result_1 = NIterationsSimulator::from(&mut decoder, sample_from_decoder)
             .simulate_n_iterations_with_rng(1000, &mut rng.clone())
             .get_result()
             .get_success_rate();
@@ -0,0 +1,726 @@
+//! Noise models sampling the errors of a simulation independently of the decoder.
+//!
+//! A decoder can be simulated with any noise model producing its type of errors using
+//! `Decoder::with_noise`.
+
+use rand::{thread_rng, Rng};
+
+/// An interface to sample random errors on a given number of bits.
+pub trait NoiseModel: Send + Sync {
+    /// The type of error sampled by the noise model.
+    type Error;
+
+    /// Samples an error on `n_bits` bits with random number generator `rng`.
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error;
+
+    /// Samples an error on `n_bits` bits with the thread random number generator.
+    fn sample_error(&self, n_bits: usize) -> Self::Error {
+        self.sample_error_with_rng(n_bits, &mut thread_rng())
+    }
+}
+
+/// Erases each bit independently with the same probability.
+///
+/// The errors are the positions of the erased bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = UniformErasure::with_prob(1.0);
+/// assert_eq!(noise.sample_error(3), vec![0, 1, 2]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformErasure {
+    prob: f64,
+}
+
+impl UniformErasure {
+    /// Creates a noise model erasing each bit with probability `prob`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `prob` is not between 0.0 and 1.0.
+    pub fn with_prob(prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&prob) {
+            panic!("invalid probability");
+        }
+        Self { prob }
+    }
+
+    /// Returns the erasure probability of each bit.
+    pub fn get_prob(&self) -> f64 {
+        self.prob
+    }
+}
+
+impl NoiseModel for UniformErasure {
+    type Error = Vec<usize>; // Positions of erased bits.
+
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        (0..n_bits).filter(|_| rng.gen::<f64>() < self.prob).collect()
+    }
+}
+
+/// Erases each bit independently with its own probability.
+///
+/// The errors are the positions of the erased bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = PerBitErasure::with_probs(vec![0.0, 1.0, 0.0]);
+/// assert_eq!(noise.sample_error(3), vec![1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerBitErasure {
+    probs: Vec<f64>,
+}
+
+impl PerBitErasure {
+    /// Creates a noise model erasing bit `i` with probability `probs[i]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0.0 and 1.0.
+    pub fn with_probs(probs: Vec<f64>) -> Self {
+        if probs.iter().any(|prob| !(0.0..=1.0).contains(prob)) {
+            panic!("invalid probability");
+        }
+        Self { probs }
+    }
+
+    /// Returns the erasure probability of each bit.
+    pub fn get_probs(&self) -> &[f64] {
+        &self.probs
+    }
+}
+
+impl NoiseModel for PerBitErasure {
+    type Error = Vec<usize>; // Positions of erased bits.
+
+    // Panics if `n_bits` is not the number of probabilities.
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        if n_bits != self.probs.len() {
+            panic!("probabilities don't match the number of bits");
+        }
+        (0..n_bits)
+            .filter(|bit| rng.gen::<f64>() < self.probs[*bit])
+            .collect()
+    }
+}
+
+/// Flips each bit independently with the same probability.
+///
+/// The errors are the positions of the flipped bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = Bsc::with_crossover_prob(0.0);
+/// assert!(noise.sample_error(3).is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bsc {
+    crossover_prob: f64,
+}
+
+impl Bsc {
+    /// Creates a noise model flipping each bit with probability `crossover_prob`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        Self { crossover_prob }
+    }
+
+    /// Returns the flip probability of each bit.
+    pub fn get_crossover_prob(&self) -> f64 {
+        self.crossover_prob
+    }
+}
+
+impl NoiseModel for Bsc {
+    type Error = Vec<usize>; // Positions of flipped bits.
+
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        (0..n_bits)
+            .filter(|_| rng.gen::<f64>() < self.crossover_prob)
+            .collect()
+    }
+}
+
+/// Erases bursts of consecutive bits.
+///
+/// The bits are erased by a two state Markov chain over the positions, as in the Gilbert-Elliott
+/// model. In the good state, a bit is not erased and a burst starts at the next bit with
+/// probability `burst_start_prob`. In the bad state, a bit is erased and the burst ends at the
+/// next bit with probability `1 / mean_burst_length`, so the lengths of the bursts are geometric
+/// with mean `mean_burst_length`. The state of the first bit is sampled from the stationary
+/// distribution of the chain, so every bit is erased with the probability given by
+/// `get_erasure_prob`.
+///
+/// The errors are the positions of the erased bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.1, 4.0);
+/// assert!((noise.get_erasure_prob() - 0.4 / 1.4).abs() < 1e-12);
+///
+/// let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.0, 4.0);
+/// assert!(noise.sample_error(10).is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurstErasure {
+    burst_start_prob: f64,
+    mean_burst_length: f64,
+}
+
+impl BurstErasure {
+    /// Creates a noise model starting a burst after each bit that is not erased with
+    /// probability `burst_start_prob` and with bursts of `mean_burst_length` bits on average.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `burst_start_prob` is not between 0.0 and 1.0 or if `mean_burst_length` is
+    /// not finite and at least 1.0.
+    pub fn with_burst_start_prob_and_mean_length(
+        burst_start_prob: f64,
+        mean_burst_length: f64,
+    ) -> Self {
+        if !(0.0..=1.0).contains(&burst_start_prob) {
+            panic!("invalid probability");
+        }
+        if !(1.0..f64::INFINITY).contains(&mean_burst_length) {
+            panic!("invalid burst length");
+        }
+        Self {
+            burst_start_prob,
+            mean_burst_length,
+        }
+    }
+
+    /// Returns the probability that a burst starts after a bit that is not erased.
+    pub fn get_burst_start_prob(&self) -> f64 {
+        self.burst_start_prob
+    }
+
+    /// Returns the mean number of bits of a burst.
+    pub fn get_mean_burst_length(&self) -> f64 {
+        self.mean_burst_length
+    }
+
+    /// Returns the probability that a bit is erased. This is the expected fraction of erased
+    /// bits, `p L / (1 + p L)` for a burst start probability `p` and a mean length `L`.
+    pub fn get_erasure_prob(&self) -> f64 {
+        let expected_burst_bits = self.burst_start_prob * self.mean_burst_length;
+        expected_burst_bits / (1.0 + expected_burst_bits)
+    }
+
+    // The natural logarithm of the probability of erasing exactly the sorted `erased_bits`
+    // among `n_bits` bits.
+    pub(super) fn get_log_prob_of(&self, erased_bits: &[usize], n_bits: usize) -> f64 {
+        let mut erased_bits = erased_bits.iter().peekable();
+        let is_erased: Vec<bool> = (0..n_bits)
+            .map(|bit| erased_bits.next_if_eq(&&bit).is_some())
+            .collect();
+        let first = match is_erased.first() {
+            Some(true) => self.get_erasure_prob().ln(),
+            Some(false) => (1.0 - self.get_erasure_prob()).ln(),
+            None => 0.0,
+        };
+        let end_prob = self.get_burst_end_prob();
+        let transitions: f64 = is_erased
+            .windows(2)
+            .map(|pair| match (pair[0], pair[1]) {
+                (false, false) => (1.0 - self.burst_start_prob).ln(),
+                (false, true) => self.burst_start_prob.ln(),
+                (true, false) => end_prob.ln(),
+                (true, true) => (1.0 - end_prob).ln(),
+            })
+            .sum();
+        first + transitions
+    }
+
+    fn get_burst_end_prob(&self) -> f64 {
+        self.mean_burst_length.recip()
+    }
+}
+
+impl NoiseModel for BurstErasure {
+    type Error = Vec<usize>; // Positions of erased bits.
+
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        let mut is_in_burst = rng.gen::<f64>() < self.get_erasure_prob();
+        let mut erased_bits = Vec::new();
+        for bit in 0..n_bits {
+            if bit > 0 {
+                is_in_burst = if is_in_burst {
+                    rng.gen::<f64>() >= self.get_burst_end_prob()
+                } else {
+                    rng.gen::<f64>() < self.burst_start_prob
+                };
+            }
+            if is_in_burst {
+                erased_bits.push(bit);
+            }
+        }
+        erased_bits
+    }
+}
+
+/// Erases pairs of bits together, such as the bits of a physical cell, and each bit with an
+/// optional background probability.
+///
+/// Each pair is erased with probability `pair_prob` independently of the other pairs, and each
+/// bit is also erased with the background probability. A bit can be in many pairs.
+///
+/// The errors are the sorted positions of the erased bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = PairedErasure::with_adjacent_pairs(5, 1.0);
+/// assert_eq!(noise.get_pairs(), &[(0, 1), (2, 3)]);
+/// assert_eq!(noise.sample_error(5), vec![0, 1, 2, 3]);
+///
+/// let noise = PairedErasure::with_pairs(5, vec![(1, 4)], 0.0).with_background_prob(1.0);
+/// assert_eq!(noise.sample_error(5), vec![0, 1, 2, 3, 4]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairedErasure {
+    n_bits: usize,
+    pairs: Vec<(usize, usize)>,
+    pair_prob: f64,
+    background_prob: f64,
+}
+
+impl PairedErasure {
+    /// Creates a noise model on `n_bits` bits erasing both bits of each of the `pairs` with
+    /// probability `pair_prob`.
+    ///
+    /// By default, there is no background erasure.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `pair_prob` is not between 0.0 and 1.0 or if a pair has a bit out of range
+    /// or twice the same bit.
+    pub fn with_pairs(n_bits: usize, pairs: Vec<(usize, usize)>, pair_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&pair_prob) {
+            panic!("invalid probability");
+        }
+        if pairs
+            .iter()
+            .any(|(bit_0, bit_1)| *bit_0 >= n_bits || *bit_1 >= n_bits || bit_0 == bit_1)
+        {
+            panic!("invalid pair");
+        }
+        Self {
+            n_bits,
+            pairs,
+            pair_prob,
+            background_prob: 0.0,
+        }
+    }
+
+    /// Creates a noise model on `n_bits` bits erasing the pairs of bits `(2 i, 2 i + 1)` with
+    /// probability `pair_prob`. If `n_bits` is odd, the last bit is in no pair.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `pair_prob` is not between 0.0 and 1.0.
+    pub fn with_adjacent_pairs(n_bits: usize, pair_prob: f64) -> Self {
+        let pairs = (0..n_bits / 2).map(|pair| (2 * pair, 2 * pair + 1)).collect();
+        Self::with_pairs(n_bits, pairs, pair_prob)
+    }
+
+    /// Sets the probability that each bit is erased on its own.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `background_prob` is not between 0.0 and 1.0.
+    pub fn with_background_prob(mut self, background_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&background_prob) {
+            panic!("invalid probability");
+        }
+        self.background_prob = background_prob;
+        self
+    }
+
+    /// Returns the number of bits of the noise model.
+    pub fn get_n_bits(&self) -> usize {
+        self.n_bits
+    }
+
+    /// Returns the pairs of bits erased together.
+    pub fn get_pairs(&self) -> &[(usize, usize)] {
+        &self.pairs
+    }
+
+    /// Returns the probability that a pair is erased.
+    pub fn get_pair_prob(&self) -> f64 {
+        self.pair_prob
+    }
+
+    /// Returns the probability that a bit is erased on its own.
+    pub fn get_background_prob(&self) -> f64 {
+        self.background_prob
+    }
+
+    /// Returns the expected fraction of erased bits.
+    ///
+    /// A bit in `k` pairs is not erased with probability `(1 - b) (1 - p)^k` for a background
+    /// probability `b` and a pair probability `p`.
+    pub fn get_erasure_prob(&self) -> f64 {
+        if self.n_bits == 0 {
+            return 0.0;
+        }
+        let mut n_pairs_of_bit = vec![0; self.n_bits];
+        for (bit_0, bit_1) in self.pairs.iter() {
+            n_pairs_of_bit[*bit_0] += 1;
+            n_pairs_of_bit[*bit_1] += 1;
+        }
+        let total: f64 = n_pairs_of_bit
+            .into_iter()
+            .map(|n_pairs| {
+                1.0 - (1.0 - self.background_prob) * (1.0 - self.pair_prob).powi(n_pairs)
+            })
+            .sum();
+        total / self.n_bits as f64
+    }
+
+    // The natural logarithm of the probability of erasing exactly the sorted `erased_bits`.
+    // Each pair and each bit outside the pairs are independent, which requires disjoint pairs.
+    //
+    // Panics if some pairs share a bit.
+    pub(super) fn get_log_prob_of(&self, erased_bits: &[usize]) -> f64 {
+        let mut is_erased = vec![false; self.n_bits];
+        erased_bits.iter().for_each(|bit| is_erased[*bit] = true);
+        let mut is_in_pair = vec![false; self.n_bits];
+        let (pair_prob, background_prob) = (self.pair_prob, self.background_prob);
+        let mut log_prob = 0.0;
+        for (bit_0, bit_1) in self.pairs.iter() {
+            if is_in_pair[*bit_0] || is_in_pair[*bit_1] {
+                panic!("pairs are not disjoint");
+            }
+            is_in_pair[*bit_0] = true;
+            is_in_pair[*bit_1] = true;
+            let prob = match (is_erased[*bit_0], is_erased[*bit_1]) {
+                (true, true) => pair_prob + (1.0 - pair_prob) * background_prob * background_prob,
+                (false, false) => (1.0 - pair_prob) * (1.0 - background_prob).powi(2),
+                _ => (1.0 - pair_prob) * background_prob * (1.0 - background_prob),
+            };
+            log_prob += prob.ln();
+        }
+        let log_prob_of_single_bits: f64 = (0..self.n_bits)
+            .filter(|bit| !is_in_pair[*bit])
+            .map(|bit| {
+                if is_erased[bit] {
+                    background_prob.ln()
+                } else {
+                    (1.0 - background_prob).ln()
+                }
+            })
+            .sum();
+        log_prob + log_prob_of_single_bits
+    }
+}
+
+impl NoiseModel for PairedErasure {
+    type Error = Vec<usize>; // Positions of erased bits.
+
+    // Panics if `n_bits` is not the number of bits of the noise model.
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        if n_bits != self.n_bits {
+            panic!("pairs don't match the number of bits");
+        }
+        let mut is_erased = vec![false; n_bits];
+        for (bit_0, bit_1) in self.pairs.iter() {
+            if rng.gen::<f64>() < self.pair_prob {
+                is_erased[*bit_0] = true;
+                is_erased[*bit_1] = true;
+            }
+        }
+        for bit_is_erased in is_erased.iter_mut() {
+            if rng.gen::<f64>() < self.background_prob {
+                *bit_is_erased = true;
+            }
+        }
+        (0..n_bits).filter(|bit| is_erased[*bit]).collect()
+    }
+}
+
+/// Sends each bit with BPSK modulation on an additive white Gaussian noise channel.
+///
+/// Since the codes are linear, the all zero codeword is sent and each bit is mapped to +1. The
+/// channel adds to it a Gaussian noise of standard deviation `sigma` and the received value `y`
+/// gives the channel log likelihood ratio `2 y / sigma^2`. The errors are these log likelihood
+/// ratios, so that a positive value means that a bit is more likely to be 0.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let noise = AwgnChannel::with_eb_n0_db_and_rate(0.0, 0.5);
+/// assert_eq!(noise.get_noise_std(), 1.0);
+///
+/// let llrs = noise.sample_error(100);
+/// assert_eq!(llrs.len(), 100);
+/// assert!(llrs.iter().filter(|llr| **llr > 0.0).count() > 50);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AwgnChannel {
+    noise_std: f64,
+}
+
+impl AwgnChannel {
+    /// Creates a channel adding a Gaussian noise of standard deviation `noise_std` to each
+    /// modulated bit.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `noise_std` is not positive and finite.
+    pub fn with_noise_std(noise_std: f64) -> Self {
+        if !(noise_std > 0.0 && noise_std.is_finite()) {
+            panic!("invalid noise standard deviation");
+        }
+        Self { noise_std }
+    }
+
+    /// Creates a channel with a signal to noise ratio `eb_n0_db` in decibels per information
+    /// bit for a code of the given `rate`.
+    ///
+    /// The energy per information bit is `1 / rate` since each modulated bit has energy 1,
+    /// so the noise has variance `1 / (2 rate Eb/N0)`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `rate` is not in the range (0.0, 1.0] or if `eb_n0_db` is not finite.
+    pub fn with_eb_n0_db_and_rate(eb_n0_db: f64, rate: f64) -> Self {
+        if !(rate > 0.0 && rate <= 1.0) {
+            panic!("invalid rate");
+        }
+        if !eb_n0_db.is_finite() {
+            panic!("invalid signal to noise ratio");
+        }
+        let eb_n0 = 10_f64.powf(eb_n0_db / 10.0);
+        Self::with_noise_std((2.0 * rate * eb_n0).recip().sqrt())
+    }
+
+    /// Returns the standard deviation of the noise.
+    pub fn get_noise_std(&self) -> f64 {
+        self.noise_std
+    }
+}
+
+impl NoiseModel for AwgnChannel {
+    type Error = Vec<f64>; // Channel log likelihood ratios.
+
+    fn sample_error_with_rng<R: Rng>(&self, n_bits: usize, rng: &mut R) -> Self::Error {
+        let variance = self.noise_std * self.noise_std;
+        (0..n_bits)
+            .map(|_| {
+                let received = 1.0 + self.noise_std * sample_standard_normal_with_rng(rng);
+                2.0 * received / variance
+            })
+            .collect()
+    }
+}
+
+// Box-Muller transform. The first uniform is in (0, 1] to avoid the logarithm of 0.
+fn sample_standard_normal_with_rng<R: Rng>(rng: &mut R) -> f64 {
+    let radius = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+    let angle = 2.0 * std::f64::consts::PI * rng.gen::<f64>();
+    radius * angle.cos()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn uniform_and_per_bit_erasures_sample_the_same_errors() {
+        let uniform = UniformErasure::with_prob(0.3);
+        let per_bit = PerBitErasure::with_probs(vec![0.3; 20]);
+        let bsc = Bsc::with_crossover_prob(0.3);
+        let rng = ChaCha8Rng::seed_from_u64(123);
+
+        let error = uniform.sample_error_with_rng(20, &mut rng.clone());
+        assert!(!error.is_empty());
+        assert_eq!(per_bit.sample_error_with_rng(20, &mut rng.clone()), error);
+        assert_eq!(bsc.sample_error_with_rng(20, &mut rng.clone()), error);
+    }
+
+    #[test]
+    fn degenerate_per_bit_erasures_always_erase_the_same_bits() {
+        let noise = PerBitErasure::with_probs(vec![1.0, 0.0, 0.0, 1.0]);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        for _ in 0..100 {
+            assert_eq!(noise.sample_error_with_rng(4, &mut rng), vec![0, 3]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn per_bit_erasures_of_wrong_length_panic() {
+        PerBitErasure::with_probs(vec![0.1; 3]).sample_error(4);
+    }
+
+    #[test]
+    fn burst_erasures_erase_the_expected_fraction_of_bits() {
+        let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.05, 5.0);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let errors: Vec<Vec<usize>> = (0..2000)
+            .map(|_| noise.sample_error_with_rng(100, &mut rng))
+            .collect();
+        let n_erased_bits: usize = errors.iter().map(|error| error.len()).sum();
+        let fraction = n_erased_bits as f64 / 200_000.0;
+        assert!((fraction - 0.2).abs() < 0.01);
+
+        // Only the bursts that end before the last bit have their full length.
+        let n_bursts: usize = errors
+            .iter()
+            .map(|error| {
+                error
+                    .iter()
+                    .zip(error.iter().skip(1))
+                    .filter(|(bit, next)| *next - *bit > 1)
+                    .count()
+                    + usize::from(!error.is_empty())
+            })
+            .sum();
+        let mean_length = n_erased_bits as f64 / n_bursts as f64;
+        assert!(mean_length > 4.0 && mean_length < 5.2);
+    }
+
+    #[test]
+    fn burst_erasures_are_reproducible() {
+        let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.1, 3.0);
+        let first = noise.sample_error_with_rng(50, &mut ChaCha8Rng::seed_from_u64(123));
+        let second = noise.sample_error_with_rng(50, &mut ChaCha8Rng::seed_from_u64(123));
+        assert_eq!(first, second);
+        assert!(!first.is_empty());
+    }
+
+    #[test]
+    fn probabilities_of_all_burst_erasures_sum_to_one() {
+        let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.3, 2.5);
+        let total: f64 = (0..1 << 5)
+            .map(|pattern: usize| {
+                let erased_bits: Vec<usize> =
+                    (0..5).filter(|bit| pattern >> bit & 1 == 1).collect();
+                noise.get_log_prob_of(&erased_bits, 5).exp()
+            })
+            .sum();
+        assert!((total - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bursts_shorter_than_a_bit_panic() {
+        BurstErasure::with_burst_start_prob_and_mean_length(0.1, 0.5);
+    }
+
+    #[test]
+    fn paired_erasures_erase_both_bits_of_a_pair() {
+        let noise = PairedErasure::with_pairs(6, vec![(0, 5), (2, 3)], 0.3);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let mut n_erased_pairs = 0;
+        for _ in 0..10_000 {
+            let error = noise.sample_error_with_rng(6, &mut rng);
+            assert!(error.iter().all(|bit| [0, 2, 3, 5].contains(bit)));
+            assert_eq!(error.contains(&0), error.contains(&5));
+            assert_eq!(error.contains(&2), error.contains(&3));
+            n_erased_pairs += error.len() / 2;
+        }
+        assert!((n_erased_pairs as f64 / 20_000.0 - 0.3).abs() < 0.015);
+        assert!((noise.get_erasure_prob() - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn background_erasures_are_added_to_the_pairs() {
+        let noise = PairedErasure::with_adjacent_pairs(5, 0.2).with_background_prob(0.1);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let n_erased_bits: usize = (0..10_000)
+            .map(|_| noise.sample_error_with_rng(5, &mut rng).len())
+            .sum();
+        let expected = (4.0 * (1.0 - 0.9 * 0.8) + 0.1) / 5.0;
+        assert!((noise.get_erasure_prob() - expected).abs() < 1e-12);
+        assert!((n_erased_bits as f64 / 50_000.0 - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn probabilities_of_all_paired_erasures_sum_to_one() {
+        let noise = PairedErasure::with_pairs(5, vec![(3, 0), (1, 4)], 0.3)
+            .with_background_prob(0.2);
+        let total: f64 = (0..1 << 5)
+            .map(|pattern: usize| {
+                let erased_bits: Vec<usize> =
+                    (0..5).filter(|bit| pattern >> bit & 1 == 1).collect();
+                noise.get_log_prob_of(&erased_bits).exp()
+            })
+            .sum();
+        assert!((total - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pairs_out_of_range_panic() {
+        PairedErasure::with_pairs(4, vec![(0, 1), (2, 4)], 0.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn paired_erasures_of_wrong_length_panic() {
+        PairedErasure::with_adjacent_pairs(4, 0.1).sample_error(6);
+    }
+
+    #[test]
+    fn awgn_llrs_have_the_mean_and_variance_of_the_channel() {
+        let noise = AwgnChannel::with_noise_std(0.8);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let llrs = noise.sample_error_with_rng(100_000, &mut rng);
+        let mean = llrs.iter().sum::<f64>() / llrs.len() as f64;
+        let variance =
+            llrs.iter().map(|llr| (llr - mean).powi(2)).sum::<f64>() / llrs.len() as f64;
+
+        // The llrs are 2 y / sigma^2, with mean 2 / sigma^2 and variance 4 / sigma^2.
+        assert!((mean - 2.0 / 0.64).abs() < 0.02);
+        assert!((variance - 4.0 / 0.64).abs() < 0.1);
+    }
+
+    #[test]
+    fn eb_n0_accounts_for_the_rate() {
+        let noise = AwgnChannel::with_eb_n0_db_and_rate(10.0 * 2_f64.log10(), 0.25);
+        assert!((noise.get_noise_std() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_noise_std_panics() {
+        AwgnChannel::with_noise_std(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_rate_panics() {
+        AwgnChannel::with_eb_n0_db_and_rate(1.0, 1.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_probabilities_panic() {
+        Bsc::with_crossover_prob(-0.5);
+    }
+}
@@ -0,0 +1,381 @@
+//! An ordered statistics post-processor for belief propagation decoders.
+
+use super::{Decoder, SoftDecoder};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use crate::{BPResult, BscResult, SoftDecodingResult};
+use itertools::Itertools;
+use rand::Rng;
+use std::sync::Arc;
+
+/// A decoder for the binary symmetric channel that runs ordered statistics decoding (OSD)
+/// when the belief propagation decoder it wraps fails.
+///
+/// If belief propagation converges to a hard decision with a zero syndrome, this hard decision
+/// is kept. Otherwise, the bits are sorted by the magnitude of their posterior log likelihood
+/// ratios and the most reliable bits that form an information set are found by a Gaussian
+/// elimination, see `ParityCheckMatrix::get_reduced_row_echelon_form_with_bit_order`. The hard
+/// decisions of these bits are re-encoded into a codeword. With an order `w`, the codewords
+/// obtained by flipping up to `w` bits of the information set are also tried. The decision is
+/// the candidate closest to the received word, which is the most likely one on the binary
+/// symmetric channel.
+///
+/// The post-processing always gives a codeword. With an order equal to the dimension of the
+/// code, it tries all the codewords and is a maximum likelihood decoder.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// // Without iterations, belief propagation fails on every error.
+/// let bp_decoder = BscDecoder::with_crossover_prob(0.1).with_max_iterations(0);
+/// let mut decoder = OsdPostProcessor::order(1).wrapping(bp_decoder).for_code(code);
+///
+/// for bit in 0..7 {
+///     let result = decoder.decode(&vec![bit]);
+///     assert!(!result.has_converged());
+///     assert_eq!(result.get_outcome(), BscResult::Success);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OsdPostProcessor<D> {
+    decoder: D,
+    order: usize,
+    code: Arc<ParityCheckMatrix>,
+}
+
+impl OsdPostProcessor<()> {
+    /// Creates a post-processor trying the codewords obtained by flipping up to `order` bits
+    /// of the information set.
+    ///
+    /// Order 0 only re-encodes the hard decisions of the information set. The number of
+    /// candidates grows as the number of bits of the information set to the power `order`.
+    pub fn order(order: usize) -> Self {
+        Self {
+            decoder: (),
+            order,
+            code: Arc::new(ParityCheckMatrix::new()),
+        }
+    }
+
+    /// Wraps a belief propagation `decoder` whose failures are post-processed.
+    ///
+    /// The code of `decoder`, if any, is used by the post-processor.
+    pub fn wrapping<D>(self, mut decoder: D) -> OsdPostProcessor<D>
+    where
+        D: SoftDecoder<Code = ParityCheckMatrix>,
+    {
+        let code = Arc::new(decoder.take_code());
+        OsdPostProcessor {
+            decoder: decoder.for_shared_code(Arc::clone(&code)),
+            order: self.order,
+            code,
+        }
+    }
+}
+
+impl<D> OsdPostProcessor<D>
+where
+    D: SoftDecoder<Code = ParityCheckMatrix>,
+{
+    /// Returns the order of the post-processor.
+    pub fn get_order(&self) -> usize {
+        self.order
+    }
+
+    /// Decodes a `received` word and returns the decision together with the soft output of
+    /// belief propagation.
+    ///
+    /// The post-processing was used if belief propagation didn't converge. Unlike `decode`,
+    /// this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let bp_decoder = BscDecoder::with_crossover_prob(0.1).with_max_iterations(0);
+    /// let decoder = OsdPostProcessor::order(0).wrapping(bp_decoder).for_code(code);
+    /// let (decision, bp_result) = decoder.decode_hard(&[GF2::B1, GF2::B0, GF2::B1]);
+    ///
+    /// assert!(!bp_result.has_converged());
+    /// assert_eq!(decision, vec![GF2::B1; 3]);
+    /// ```
+    pub fn decode_hard(&self, received: &[GF2]) -> (Vec<GF2>, SoftDecodingResult) {
+        let bp_result = self.decoder.decode_soft(received);
+        let decision = if bp_result.has_converged() {
+            bp_result.get_hard_decision().to_vec()
+        } else {
+            self.post_process(received, &bp_result)
+        };
+        (decision, bp_result)
+    }
+
+    fn post_process(&self, received: &[GF2], bp_result: &SoftDecodingResult) -> Vec<GF2> {
+        let posteriors = bp_result.get_posteriors();
+        let mut bit_order: Vec<usize> = (0..self.code.get_n_bits()).collect();
+        bit_order.sort_by(|bit, other| {
+            posteriors[*bit]
+                .abs()
+                .partial_cmp(&posteriors[*other].abs())
+                .unwrap()
+        });
+        let (reduced, pivots) = self
+            .code
+            .get_reduced_row_echelon_form_with_bit_order(&bit_order);
+
+        // Flipping an information bit flips the pivots of the rows it is in.
+        let mut is_pivot = vec![false; self.code.get_n_bits()];
+        pivots.iter().for_each(|pivot| is_pivot[*pivot] = true);
+        let mut flipped_pivots = vec![Vec::new(); self.code.get_n_bits()];
+        for (row, pivot) in reduced.checks_iter().zip(pivots.iter()) {
+            row.iter()
+                .filter(|bit| !is_pivot[**bit])
+                .for_each(|bit| flipped_pivots[*bit].push(*pivot));
+        }
+        let information_set: Vec<usize> = bit_order
+            .into_iter()
+            .filter(|bit| !is_pivot[*bit])
+            .collect();
+
+        let mut base = bp_result.get_hard_decision().to_vec();
+        pivots.iter().for_each(|pivot| base[*pivot] = GF2::B0);
+        for bit in information_set.iter() {
+            if base[*bit] == GF2::B1 {
+                flipped_pivots[*bit]
+                    .iter()
+                    .for_each(|pivot| base[*pivot] = base[*pivot] + GF2::B1);
+            }
+        }
+
+        let mut best_distance = get_distance_between(&base, received);
+        let mut best = base.clone();
+        for n_flips in 1..=self.order.min(information_set.len()) {
+            for flips in information_set.iter().combinations(n_flips) {
+                let mut candidate = base.clone();
+                for bit in flips {
+                    candidate[*bit] = candidate[*bit] + GF2::B1;
+                    flipped_pivots[*bit]
+                        .iter()
+                        .for_each(|pivot| candidate[*pivot] = candidate[*pivot] + GF2::B1);
+                }
+                let distance = get_distance_between(&candidate, received);
+                if distance < best_distance {
+                    best_distance = distance;
+                    best = candidate;
+                }
+            }
+        }
+        best
+    }
+}
+
+fn get_distance_between(word: &[GF2], other: &[GF2]) -> usize {
+    word.iter()
+        .zip(other.iter())
+        .filter(|(value, other_value)| value != other_value)
+        .count()
+}
+
+impl<D> Decoder for OsdPostProcessor<D>
+where
+    D: SoftDecoder<Code = ParityCheckMatrix, Error = Vec<usize>, Result = BPResult>,
+{
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.decoder = self.decoder.for_shared_code(Arc::clone(&code));
+        self.code = code;
+        self
+    }
+
+    // The wrapped decoder holds the only other reference to the code.
+    fn take_code(&mut self) -> Self::Code {
+        self.code = Arc::new(ParityCheckMatrix::new());
+        self.decoder.take_code()
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the decision is the all zero codeword that was sent. The number
+    // of iterations and the stopping criterion are the ones of belief propagation, so the
+    // decoding was post-processed if it didn't converge.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let mut received = vec![GF2::B0; self.code.get_n_bits()];
+        error.iter().for_each(|bit| received[*bit] = GF2::B1);
+        let (decision, bp_result) = self.decode_hard(&received);
+        let n_residual_errors = decision.iter().filter(|value| **value == GF2::B1).count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations: bp_result.get_n_iterations(),
+            stopping_criterion: bp_result.get_stopping_criterion(),
+            n_residual_errors,
+        }
+    }
+
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        self.decoder.get_random_error_with_rng(rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BscDecoder, CodeGenerator, MinSumDecoder, RegularLDPCCodeGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn get_codewords_of(code: &ParityCheckMatrix) -> Vec<Vec<GF2>> {
+        let generator = code.get_generator_matrix();
+        (0..1 << generator.get_n_checks())
+            .map(|index: usize| {
+                let message: Vec<GF2> = (0..generator.get_n_checks())
+                    .map(|bit| {
+                        if index >> bit & 1 == 1 {
+                            GF2::B1
+                        } else {
+                            GF2::B0
+                        }
+                    })
+                    .collect();
+                generator.encode(&message)
+            })
+            .collect()
+    }
+
+    fn get_maximum_likelihood_distance(codewords: &[Vec<GF2>], received: &[GF2]) -> usize {
+        codewords
+            .iter()
+            .map(|codeword| get_distance_between(codeword, received))
+            .min()
+            .unwrap()
+    }
+
+    fn get_word(index: usize, n_bits: usize) -> Vec<GF2> {
+        (0..n_bits)
+            .map(|bit| {
+                if index >> bit & 1 == 1 {
+                    GF2::B1
+                } else {
+                    GF2::B0
+                }
+            })
+            .collect()
+    }
+
+    // Without iterations, all the posteriors have the same magnitude.
+    fn post_processor_without_iterations(
+        order: usize,
+        code: ParityCheckMatrix,
+    ) -> OsdPostProcessor<BscDecoder> {
+        let bp_decoder = BscDecoder::with_crossover_prob(0.1).with_max_iterations(0);
+        OsdPostProcessor::order(order)
+            .wrapping(bp_decoder)
+            .for_code(code)
+    }
+
+    // Every word is at distance at most 1 from a codeword of a Hamming code. If the closest
+    // codeword differs on an information bit, flipping it gives this codeword.
+    #[test]
+    fn order_1_is_maximum_likelihood_on_hamming_code() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let codewords = get_codewords_of(&code);
+        let decoder = post_processor_without_iterations(1, code.clone());
+        for index in 0..1 << 7 {
+            let received = get_word(index, 7);
+            let (decision, _) = decoder.decode_hard(&received);
+            assert!(code
+                .get_syndrome_of(&decision)
+                .iter()
+                .all(|v| *v == GF2::B0));
+            assert_eq!(
+                get_distance_between(&decision, &received),
+                get_maximum_likelihood_distance(&codewords, &received)
+            );
+        }
+    }
+
+    #[test]
+    fn order_0_is_not_maximum_likelihood_on_hamming_code() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let codewords = get_codewords_of(&code);
+        let decoder = post_processor_without_iterations(0, code.clone());
+        let n_suboptimal_decisions = (0..1 << 7)
+            .filter(|index| {
+                let received = get_word(*index, 7);
+                let (decision, _) = decoder.decode_hard(&received);
+                assert!(code
+                    .get_syndrome_of(&decision)
+                    .iter()
+                    .all(|v| *v == GF2::B0));
+                get_distance_between(&decision, &received)
+                    > get_maximum_likelihood_distance(&codewords, &received)
+            })
+            .count();
+        // The received words with a single error on one of the 4 information bits.
+        assert_eq!(n_suboptimal_decisions, 16 * 4);
+    }
+
+    #[test]
+    fn full_order_is_maximum_likelihood_after_belief_propagation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 2, 4).generate_with_rng(&mut rng);
+        let dimension = code.get_n_bits() - code.get_rank();
+        let codewords = get_codewords_of(&code);
+        let bp_decoder = MinSumDecoder::with_crossover_prob(0.2).with_max_iterations(5);
+        let decoder = OsdPostProcessor::order(dimension)
+            .wrapping(bp_decoder)
+            .for_code(code.clone());
+        let mut n_post_processed = 0;
+        for _ in 0..200 {
+            let received = get_word(rng.gen_range(0, 1 << 12), 12);
+            let (decision, bp_result) = decoder.decode_hard(&received);
+            if !bp_result.has_converged() {
+                n_post_processed += 1;
+                assert_eq!(
+                    get_distance_between(&decision, &received),
+                    get_maximum_likelihood_distance(&codewords, &received)
+                );
+            }
+        }
+        assert!(n_post_processed > 0);
+    }
+
+    // The post-processing only changes the decisions of belief propagation when it fails.
+    #[test]
+    fn post_processing_never_adds_failures() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 4, 6).generate_with_rng(&mut rng.clone());
+        let bp_decoder = BscDecoder::with_crossover_prob(0.08).for_code(code);
+        let mut decoder = OsdPostProcessor::order(1).wrapping(bp_decoder.clone());
+        assert_eq!(decoder.get_order(), 1);
+
+        let mut bp_decoder = bp_decoder;
+        let bp_result = bp_decoder.simulate_n_iterations_with_rng(500, &mut rng.clone());
+        let osd_result = decoder.simulate_n_iterations_with_rng(500, &mut rng.clone());
+        assert!(osd_result.get_n_failures() < bp_result.get_n_failures());
+    }
+
+    #[test]
+    fn code_can_be_taken_back() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut decoder = post_processor_without_iterations(0, code.clone());
+        assert_eq!(decoder.get_n_bits(), 7);
+        assert_eq!(decoder.take_code(), code);
+        assert_eq!(decoder.get_n_bits(), 0);
+    }
+}
@@ -0,0 +1,143 @@
+//! Peeling of erasures for the erasure decoder.
+//!
+//! A check with a single erased bit determines the value of this bit. Such bits are resolved
+//! one at a time until no check has a single erased bit left. The remaining erased bits form a
+//! stopping set of the code.
+
+use crate::ParityCheckMatrix;
+
+#[derive(Debug, Clone)]
+pub(super) struct Peeler {
+    check_bits: Vec<Vec<usize>>,
+    bit_checks: Vec<Vec<usize>>,
+    // Buffers reused between erasures.
+    is_erased: Vec<bool>,
+    n_erased_bits_in_checks: Vec<usize>,
+}
+
+impl Peeler {
+    pub(super) fn from(code: &ParityCheckMatrix) -> Self {
+        let check_bits: Vec<Vec<usize>> = code.checks_iter().map(|check| check.to_vec()).collect();
+        let mut bit_checks = vec![Vec::new(); code.get_n_bits()];
+        for (check, bits) in check_bits.iter().enumerate() {
+            bits.iter().for_each(|bit| bit_checks[*bit].push(check));
+        }
+        Self {
+            is_erased: vec![false; code.get_n_bits()],
+            n_erased_bits_in_checks: vec![0; check_bits.len()],
+            check_bits,
+            bit_checks,
+        }
+    }
+
+    // Returns the erased bits that can't be resolved by peeling, sorted.
+    //
+    // Panics if some bits are out of bounds.
+    pub(super) fn get_unresolved_bits_of(&mut self, erased_bits: &[usize]) -> Vec<usize> {
+        for bit in erased_bits {
+            match self.is_erased.get_mut(*bit) {
+                Some(is_erased) if !*is_erased => *is_erased = true,
+                Some(_) => continue,
+                None => panic!("some bits are out of bounds"),
+            }
+            for check in self.bit_checks[*bit].iter() {
+                self.n_erased_bits_in_checks[*check] += 1;
+            }
+        }
+        let mut checks_to_peel: Vec<usize> = (0..self.check_bits.len())
+            .filter(|check| self.n_erased_bits_in_checks[*check] == 1)
+            .collect();
+        while let Some(check) = checks_to_peel.pop() {
+            if self.n_erased_bits_in_checks[check] == 1 {
+                let bit = self.get_erased_bit_of(check);
+                self.resolve(bit, &mut checks_to_peel);
+            }
+        }
+        self.take_unresolved_bits_among(erased_bits)
+    }
+
+    fn get_erased_bit_of(&self, check: usize) -> usize {
+        *self.check_bits[check]
+            .iter()
+            .find(|bit| self.is_erased[**bit])
+            .expect("the check has an erased bit")
+    }
+
+    fn resolve(&mut self, bit: usize, checks_to_peel: &mut Vec<usize>) {
+        self.is_erased[bit] = false;
+        for check in self.bit_checks[bit].iter() {
+            self.n_erased_bits_in_checks[*check] -= 1;
+            if self.n_erased_bits_in_checks[*check] == 1 {
+                checks_to_peel.push(*check);
+            }
+        }
+    }
+
+    // Also clears the buffers for the next erasure.
+    fn take_unresolved_bits_among(&mut self, erased_bits: &[usize]) -> Vec<usize> {
+        let mut unresolved_bits = Vec::new();
+        for bit in erased_bits {
+            if self.is_erased[*bit] {
+                self.is_erased[*bit] = false;
+                unresolved_bits.push(*bit);
+                for check in self.bit_checks[*bit].iter() {
+                    self.n_erased_bits_in_checks[*check] -= 1;
+                }
+            }
+        }
+        unresolved_bits.sort_unstable();
+        unresolved_bits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    #[test]
+    fn chain_of_checks_is_peeled() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let mut peeler = Peeler::from(&code);
+
+        assert!(peeler.get_unresolved_bits_of(&[0, 1, 2]).is_empty());
+        assert_eq!(
+            peeler.get_unresolved_bits_of(&[3, 1, 0, 2]),
+            vec![0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn stopping_sets_are_not_peeled() {
+        let mut peeler = Peeler::from(&hamming_code());
+
+        assert_eq!(peeler.get_unresolved_bits_of(&[0, 1, 2]), vec![0, 1, 2]);
+        assert!(peeler.get_unresolved_bits_of(&[4, 5, 0]).is_empty());
+        assert_eq!(
+            peeler.get_unresolved_bits_of(&[0, 1, 2, 3]),
+            vec![0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn buffers_are_cleared_between_erasures() {
+        let mut peeler = Peeler::from(&hamming_code());
+        peeler.get_unresolved_bits_of(&[0, 1, 2, 2]);
+
+        assert!(peeler.is_erased.iter().all(|is_erased| !is_erased));
+        assert!(peeler.n_erased_bits_in_checks.iter().all(|n| *n == 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_bits_panic() {
+        Peeler::from(&hamming_code()).get_unresolved_bits_of(&[7]);
+    }
+}
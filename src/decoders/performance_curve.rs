@@ -0,0 +1,156 @@
+//! The performance of a code as a function of the noise probability.
+
+use super::{ResultsTable, SimulationResult};
+
+/// The simulation results of a code for several noise probabilities, sorted by probability.
+///
+/// # Example
+///
+/// ```
+/// use believer::{PerformanceCurve, SimulationResult};
+/// let curve = PerformanceCurve::with_points(vec![
+///     (0.2, SimulationResult::with_n_successes_and_failures(60, 40)),
+///     (0.1, SimulationResult::with_n_successes_and_failures(90, 10)),
+/// ]);
+///
+/// assert_eq!(curve.get_probs(), vec![0.1, 0.2]);
+/// assert!((curve.get_crossing_prob(0.25).unwrap() - 0.15).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceCurve {
+    points: Vec<(f64, SimulationResult)>,
+}
+
+impl PerformanceCurve {
+    /// Creates a curve from pairs of probability and simulation result.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0.0 and 1.0.
+    pub fn with_points(mut points: Vec<(f64, SimulationResult)>) -> Self {
+        if points.iter().any(|(prob, _)| !(0.0..=1.0).contains(prob)) {
+            panic!("invalid probability");
+        }
+        points.sort_by(|(prob, _), (other_prob, _)| prob.partial_cmp(other_prob).unwrap());
+        Self { points }
+    }
+
+    /// Returns the pairs of probability and simulation result of `self` sorted by probability.
+    pub fn get_points(&self) -> &[(f64, SimulationResult)] {
+        &self.points
+    }
+
+    /// Returns the probabilities of `self` in increasing order.
+    pub fn get_probs(&self) -> Vec<f64> {
+        self.points.iter().map(|(prob, _)| *prob).collect()
+    }
+
+    /// Returns the failure rates of `self` sorted by probability.
+    pub fn get_failure_rates(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .map(|(_, result)| result.get_failure_rate())
+            .collect()
+    }
+
+    /// Returns the number of points of `self`.
+    pub fn get_n_points(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Finds the probability at which the failure rate first reaches `threshold`.
+    ///
+    /// The failure rate is interpolated linearly between the two points around the crossing.
+    /// Points without simulated errors are ignored. Returns `None` if the failure rate is
+    /// already above `threshold` at the first point or stays below it.
+    pub fn get_crossing_prob(&self, threshold: f64) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .filter(|(_, result)| result.get_n_iterations() > 0)
+            .map(|(prob, result)| (*prob, result.get_failure_rate()))
+            .collect();
+        if points.first()?.1 >= threshold {
+            return None;
+        }
+        points
+            .windows(2)
+            .find(|pair| pair[1].1 >= threshold)
+            .map(|pair| {
+                let ((prob, rate), (next_prob, next_rate)) = (pair[0], pair[1]);
+                prob + (threshold - rate) / (next_rate - rate) * (next_prob - prob)
+            })
+    }
+
+    /// Returns a table with one row per point of `self`, all with the given `label`.
+    ///
+    /// The label can be the name of the code to put the curves of many codes in a single
+    /// table.
+    pub fn to_results_table(&self, label: &str) -> ResultsTable {
+        let mut table = ResultsTable::new();
+        for (prob, result) in self.points.iter() {
+            table.add_row(label, *prob, result);
+        }
+        table
+    }
+
+    /// Returns `self` as CSV with the columns of a `ResultsTable` and empty labels.
+    pub fn to_csv(&self) -> String {
+        self.to_results_table("").to_csv()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn curve() -> PerformanceCurve {
+        PerformanceCurve::with_points(vec![
+            (0.3, SimulationResult::with_n_successes_and_failures(20, 80)),
+            (0.1, SimulationResult::with_n_successes_and_failures(100, 0)),
+            (0.4, SimulationResult::new()),
+            (0.2, SimulationResult::with_n_successes_and_failures(80, 20)),
+        ])
+    }
+
+    #[test]
+    fn points_are_sorted_by_probability() {
+        assert_eq!(curve().get_probs(), vec![0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(curve().get_n_points(), 4);
+    }
+
+    #[test]
+    fn crossing_is_interpolated_between_points() {
+        let curve = curve();
+        assert!((curve.get_crossing_prob(0.1).unwrap() - 0.15).abs() < 1e-12);
+        assert!((curve.get_crossing_prob(0.5).unwrap() - 0.25).abs() < 1e-12);
+        assert_eq!(curve.get_crossing_prob(0.2), Some(0.2));
+        assert_eq!(curve.get_crossing_prob(0.9), None);
+        assert_eq!(curve.get_crossing_prob(0.0), None);
+        assert_eq!(
+            PerformanceCurve::with_points(Vec::new()).get_crossing_prob(0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn csv_has_a_row_per_point() {
+        let csv = curve().to_results_table("hamming").to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("hamming,0.1,100,0,0,0,"));
+        assert!(lines[4].starts_with("hamming,0.4,0,0,NaN,0,1"));
+        assert!(curve()
+            .to_csv()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with(",0.1,100,"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_probabilities_panic() {
+        PerformanceCurve::with_points(vec![(1.5, SimulationResult::new())]);
+    }
+}
@@ -0,0 +1,149 @@
+use super::n_iterations_simulator::BATCH_SIZE;
+use super::{Decoder, PrecisionStoppingCondition, SimulationResult};
+use crate::SeedSequence;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+// The errors are sampled by `sample_error` from the decoder and a random number generator.
+// The precision is checked after each batch of errors.
+pub(super) struct PrecisionSimulator<'a, D, S> {
+    decoder: &'a mut D,
+    sample_error: S,
+    relative_error: f64,
+    max_iterations: usize,
+    result: SimulationResult,
+}
+
+impl<'a, D, S> PrecisionSimulator<'a, D, S>
+where
+    D: Decoder,
+    S: Fn(&D, &mut ChaCha8Rng) -> D::Error,
+{
+    pub(super) fn from(decoder: &'a mut D, sample_error: S) -> Self {
+        Self {
+            decoder,
+            sample_error,
+            relative_error: 0.0,
+            max_iterations: 0,
+            result: SimulationResult::new(),
+        }
+    }
+
+    pub(super) fn simulate_until_precision_with_rng<R: Rng>(
+        mut self,
+        relative_error: f64,
+        max_iterations: usize,
+        rng: &mut R,
+    ) -> Self {
+        self.relative_error = relative_error;
+        self.max_iterations = max_iterations;
+        self.run_the_simulation(rng);
+        self
+    }
+
+    fn run_the_simulation<R: Rng>(&mut self, rng: &mut R) {
+        let mut result = SimulationResult::new();
+        let n_bits = self.decoder.get_n_bits();
+        let mut errors = Vec::with_capacity(BATCH_SIZE);
+        let seeds = SeedSequence::from_rng(rng);
+        let mut n_iterations = 0;
+        while !self.has_reached_target_precision(&result) && n_iterations < self.max_iterations {
+            let batch_size = BATCH_SIZE.min(self.max_iterations - n_iterations);
+            errors.clear();
+            for index in n_iterations..n_iterations + batch_size {
+                let mut rng = seeds.get_rng(index as u64);
+                errors.push((self.sample_error)(self.decoder, &mut rng));
+            }
+            self.decoder
+                .decode_batch(&errors)
+                .into_iter()
+                .for_each(|decoding_result| {
+                    result.add_decoding_result_on_n_bits(decoding_result, n_bits)
+                });
+            n_iterations += batch_size;
+        }
+        let condition = if self.has_reached_target_precision(&result) {
+            PrecisionStoppingCondition::TargetPrecision
+        } else {
+            PrecisionStoppingCondition::MaxIterations
+        };
+        self.result = result.stopped_by(condition);
+    }
+
+    // Without failures, the relative error is infinite and the simulation goes on.
+    fn has_reached_target_precision(&self, result: &SimulationResult) -> bool {
+        result.get_relative_standard_error() <= self.relative_error
+    }
+
+    pub(super) fn get_result(self) -> SimulationResult {
+        self.result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{sample_from_decoder, ErasureDecoder};
+    use super::*;
+    use crate::ParityCheckMatrix;
+    use rand::SeedableRng;
+
+    #[test]
+    fn simulation_stops_once_the_target_precision_is_reached() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+
+        let result = PrecisionSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_until_precision_with_rng(0.1, 100_000, &mut rng)
+            .get_result();
+
+        assert!(result.get_relative_standard_error() <= 0.1);
+        assert!(result.get_n_iterations() < 100_000);
+        assert_eq!(result.get_n_iterations() as usize % BATCH_SIZE, 0);
+        assert_eq!(
+            result.get_precision_stopping_condition(),
+            Some(PrecisionStoppingCondition::TargetPrecision)
+        );
+    }
+
+    #[test]
+    fn simulation_without_failures_goes_on_until_the_cap() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.0).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+
+        let result = PrecisionSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_until_precision_with_rng(0.1, 1000, &mut rng)
+            .get_result();
+
+        assert_eq!(result.get_n_iterations(), 1000);
+        assert_eq!(result.get_n_failures(), 0);
+        assert_eq!(
+            result.get_precision_stopping_condition(),
+            Some(PrecisionStoppingCondition::MaxIterations)
+        );
+    }
+
+    #[test]
+    fn batches_give_the_same_result_as_decoding_one_error_at_a_time() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let mut decoder = ErasureDecoder::with_prob(0.4).for_code(code);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let n_iterations = BATCH_SIZE + 3;
+
+        let result = PrecisionSimulator::from(&mut decoder, sample_from_decoder)
+            .simulate_until_precision_with_rng(0.0, n_iterations, &mut rng.clone())
+            .get_result();
+
+        let mut expected = SimulationResult::new();
+        let seeds = SeedSequence::from_rng(&mut rng);
+        for mut rng in seeds.get_n_rngs(n_iterations) {
+            let decoding_result = decoder.decode_random_error_with_rng(&mut rng);
+            expected.add_decoding_result_on_n_bits(decoding_result, 3);
+        }
+        assert_eq!(
+            result,
+            expected.stopped_by(PrecisionStoppingCondition::MaxIterations)
+        );
+    }
+}
@@ -0,0 +1,762 @@
+//! A min-sum belief propagation decoder with fixed-point messages.
+
+use super::message_passing::{
+    get_channel_llrs_of, get_channel_llrs_of_given, get_channel_llrs_of_received, TannerGraph,
+};
+use super::{into_owned, Bsc, Decoder, NoiseModel, SoftDecoder};
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use crate::{BPResult, BscResult, SoftDecodingResult, StoppingCriterion};
+use rand::Rng;
+use std::sync::Arc;
+
+// The normalization is rounded to a multiple of 2^-NORMALIZATION_BITS. A magnitude is
+// normalized by multiplying it by the resulting integer and shifting it by this many bits.
+const NORMALIZATION_BITS: u32 = 8;
+
+/// The fixed-point format of the messages of a `QuantizedMinSumDecoder`.
+///
+/// A message is a signed integer of `total_bits` bits, including the sign bit, whose last
+/// `fractional_bits` bits are the fractional part. That is, the integer `m` represents the log
+/// likelihood ratio `m / 2^fractional_bits`. The messages are symmetric and saturate at
+/// `2^(total_bits - 1) - 1` in magnitude.
+///
+/// The messages are stored in an `i8` and the posteriors in an `i16`, so a valid configuration
+/// has between 2 and 8 total bits and less fractional bits than total bits.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let quantization = QuantizationConfig::with_total_and_fractional_bits(5, 2);
+///
+/// assert_eq!(quantization.get_max_message(), 15);
+/// assert_eq!(quantization.quantize(1.3), 5);
+/// assert_eq!(quantization.quantize(-10.0), -15);
+/// assert_eq!(quantization.dequantize(5), 1.25);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantizationConfig {
+    total_bits: u32,
+    fractional_bits: u32,
+}
+
+impl QuantizationConfig {
+    /// Creates a fixed-point format with `total_bits` bits of which `fractional_bits` are
+    /// fractional.
+    ///
+    /// The configuration is checked by the decoder using it.
+    pub fn with_total_and_fractional_bits(total_bits: u32, fractional_bits: u32) -> Self {
+        Self {
+            total_bits,
+            fractional_bits,
+        }
+    }
+
+    /// Returns the number of bits of the messages including the sign bit.
+    pub fn get_total_bits(&self) -> u32 {
+        self.total_bits
+    }
+
+    /// Returns the number of fractional bits of the messages.
+    pub fn get_fractional_bits(&self) -> u32 {
+        self.fractional_bits
+    }
+
+    /// Checks if the messages fit in an `i8` with at least one bit of magnitude and if the
+    /// fractional bits fit in the magnitude.
+    pub fn is_valid(&self) -> bool {
+        (2..=8).contains(&self.total_bits) && self.fractional_bits < self.total_bits
+    }
+
+    /// Returns the largest magnitude of a message.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `self` is not valid.
+    pub fn get_max_message(&self) -> i8 {
+        if !self.is_valid() {
+            panic!("invalid quantization");
+        }
+        ((1_i16 << (self.total_bits - 1)) - 1) as i8
+    }
+
+    /// Rounds a log likelihood ratio to the nearest message, saturating at the largest
+    /// magnitude. Halfway cases are rounded away from zero.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `self` is not valid.
+    pub fn quantize(&self, llr: f64) -> i8 {
+        let max_message = f64::from(self.get_max_message());
+        (llr * self.get_scale())
+            .round()
+            .clamp(-max_message, max_message) as i8
+    }
+
+    /// Returns the log likelihood ratio represented by a message or a posterior.
+    pub fn dequantize(&self, value: i16) -> f64 {
+        f64::from(value) / self.get_scale()
+    }
+
+    fn get_scale(&self) -> f64 {
+        f64::from(1_u32 << self.fractional_bits)
+    }
+}
+
+/// Decoder for the binary symmetric channel using min-sum belief propagation with fixed-point
+/// messages.
+///
+/// This is the same decoder as the `MinSumDecoder` with the flooding schedule except that all
+/// the computations are done on integers to reproduce a hardware decoder bit for bit. The
+/// channel log likelihood ratios are quantized with a `QuantizationConfig`, the messages are
+/// stored as `i8` and the posteriors as `i16`, and every addition is saturating.
+///
+/// The offset is quantized like a message and subtracted from the minimum magnitude with a
+/// floor at 0. Then, the normalization is rounded to a multiple of 1/256 and the product of
+/// the magnitude by the normalization is rounded down.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+/// let quantization = QuantizationConfig::with_total_and_fractional_bits(6, 2);
+/// let mut decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1)
+///     .with_quantization(quantization)
+///     .for_code(code);
+///
+/// assert_eq!(decoder.decode(&vec![1, 3]).get_outcome(), BscResult::Success);
+/// assert_eq!(decoder.decode(&vec![0, 1, 2]).get_outcome(), BscResult::Failure);
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuantizedMinSumDecoder {
+    code: Arc<ParityCheckMatrix>,
+    crossover_prob: f64,
+    max_iterations: usize,
+    quantization: QuantizationConfig,
+    normalization: f64,
+    offset: f64,
+    graph: TannerGraph,
+}
+
+impl QuantizedMinSumDecoder {
+    /// Creates a quantized min-sum decoder for the binary symmetric channel that flips each
+    /// bit with probability `crossover_prob`.
+    ///
+    /// By default, the decoder does at most 10 iterations and the messages have 6 bits of
+    /// which 2 are fractional.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `crossover_prob` is not between 0.0 and 1.0.
+    pub fn with_crossover_prob(crossover_prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&crossover_prob) {
+            panic!("invalid probability");
+        }
+        let code = Arc::new(ParityCheckMatrix::new());
+        Self {
+            graph: TannerGraph::from(&code),
+            code,
+            crossover_prob,
+            max_iterations: 10,
+            quantization: QuantizationConfig::with_total_and_fractional_bits(6, 2),
+            normalization: 1.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Sets the maximum number of iterations of belief propagation.
+    ///
+    /// The decoder stops before as soon as the hard decision has a zero syndrome. With 0
+    /// iterations, the hard decision is the channel output.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the fixed-point format of the messages.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `quantization` is not valid, that is if the messages don't fit in an `i8`
+    /// or if there are not less fractional bits than total bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let quantization = QuantizationConfig::with_total_and_fractional_bits(8, 3);
+    /// let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1)
+    ///     .with_quantization(quantization);
+    ///
+    /// assert_eq!(decoder.get_quantization(), quantization);
+    /// ```
+    pub fn with_quantization(mut self, quantization: QuantizationConfig) -> Self {
+        if !quantization.is_valid() {
+            panic!("invalid quantization");
+        }
+        self.quantization = quantization;
+        self
+    }
+
+    /// Sets the factor by which the messages from the checks to the bits are multiplied after
+    /// removing the offset. It is rounded to a multiple of 1/256.
+    ///
+    /// By default, the normalization is 1.0 which gives plain min-sum.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `normalization` is not in the range (0.0, 1.0].
+    pub fn with_normalization(mut self, normalization: f64) -> Self {
+        if normalization <= 0.0 || normalization > 1.0 {
+            panic!("normalization is not in (0, 1]");
+        }
+        self.normalization = normalization;
+        self
+    }
+
+    /// Sets the offset subtracted from the magnitudes of the messages from the checks to the
+    /// bits before the normalization. It is quantized like a message and the magnitudes are
+    /// floored at 0.
+    ///
+    /// By default, the offset is 0.0 which gives plain min-sum.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `offset` is negative or not finite.
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        if !(0.0..f64::INFINITY).contains(&offset) {
+            panic!("offset is not a non negative number");
+        }
+        self.offset = offset;
+        self
+    }
+
+    /// Returns the fixed-point format of the messages.
+    pub fn get_quantization(&self) -> QuantizationConfig {
+        self.quantization
+    }
+
+    /// Decodes a `received` word and returns the dequantized posterior log likelihood ratios
+    /// together with the hard decision.
+    ///
+    /// Unlike `decode`, this doesn't assume that the zero codeword was sent.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `received` is not the number of bits of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_soft(&[GF2::B0, GF2::B1, GF2::B0]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert_eq!(result.get_posteriors(), &[0.0, 2.25, 0.0]);
+    /// ```
+    pub fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        if received.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_received(received, self.crossover_prob);
+        self.decode_llrs(&channel_llrs, None)
+    }
+
+    /// Decodes the channel log likelihood ratios `llrs` of a received word, such as the output
+    /// of a demodulator, and returns the posterior log likelihood ratios together with the
+    /// hard decision.
+    ///
+    /// The crossover probability of the decoder isn't used. A positive log likelihood ratio
+    /// means that a bit is more likely to be 0. Infinite values are clamped like the messages.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `llrs` is not the number of bits of the code or if some of them
+    /// are NaN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.decode_from_llrs(&[1.5, -0.5, f64::INFINITY]);
+    ///
+    /// assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    /// assert!(result.has_converged());
+    /// ```
+    pub fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        if llrs.len() != self.code.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+        let channel_llrs = get_channel_llrs_of_given(llrs);
+        self.decode_llrs(&channel_llrs, None)
+    }
+
+    /// Estimates an error with the given `syndrome`.
+    ///
+    /// The decoder starts from the quantized all zero word biased by the crossover probability
+    /// and the checks with a non zero syndrome enforce an odd parity. Returns the hard decision
+    /// if its syndrome is `syndrome` before the maximum number of iterations or `None`
+    /// otherwise.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `syndrome` is not the number of checks of the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1).for_code(code);
+    ///
+    /// let error = decoder.decode_syndrome(&[GF2::B1, GF2::B1]);
+    /// assert_eq!(error, Some(vec![GF2::B0, GF2::B1, GF2::B0]));
+    /// ```
+    pub fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        if syndrome.len() != self.code.get_n_checks() {
+            panic!("syndrome doesn't have the right length");
+        }
+        let prior_llrs = get_channel_llrs_of(&[], self.code.get_n_bits(), self.crossover_prob);
+        let result = self.decode_llrs(&prior_llrs, Some(syndrome));
+        if result.has_converged() {
+            Some(result.decision)
+        } else {
+            None
+        }
+    }
+
+    // Without a syndrome, the decoder stops on a codeword.
+    fn decode_llrs(&self, channel_llrs: &[f64], syndrome: Option<&[GF2]>) -> SoftDecodingResult {
+        let channel_messages: Vec<i8> = channel_llrs
+            .iter()
+            .map(|llr| self.quantization.quantize(*llr))
+            .collect();
+        let (posteriors, n_iterations, stopping_criterion) =
+            self.iterate(&channel_messages, syndrome);
+        SoftDecodingResult {
+            decision: get_hard_decision_of(&posteriors),
+            posteriors: posteriors
+                .into_iter()
+                .map(|posterior| self.quantization.dequantize(posterior))
+                .collect(),
+            n_iterations,
+            stopping_criterion,
+        }
+    }
+
+    // Returns the final posteriors with the number of iterations and the stopping criterion.
+    // The messages of the checks with a non zero syndrome are flipped.
+    fn iterate(
+        &self,
+        channel_messages: &[i8],
+        syndrome: Option<&[GF2]>,
+    ) -> (Vec<i16>, usize, StoppingCriterion) {
+        let rule = self.get_rule();
+        let edge_bits = self.graph.get_edge_bits();
+        let mut bit_to_check: Vec<i8> =
+            edge_bits.iter().map(|bit| channel_messages[*bit]).collect();
+        let mut check_to_bit = vec![0; self.graph.get_n_edges()];
+        let mut posteriors: Vec<i16> = channel_messages.iter().map(|m| i16::from(*m)).collect();
+        let mut n_iterations = 0;
+        let stopping_criterion = loop {
+            let decision = get_hard_decision_of(&posteriors);
+            let is_solved = match syndrome {
+                Some(syndrome) => self.code.get_syndrome_of(&decision) == syndrome,
+                None => self.code.has_codeword(&decision),
+            };
+            if is_solved {
+                break StoppingCriterion::SatisfiedSyndrome;
+            }
+            if n_iterations == self.max_iterations {
+                break StoppingCriterion::MaxIterations;
+            }
+            for (check, edges) in self.graph.check_edges_iter().enumerate() {
+                let messages = &mut check_to_bit[edges.clone()];
+                rule.update_check_to_bit(&bit_to_check[edges], messages);
+                if syndrome.is_some_and(|syndrome| syndrome[check] == GF2::B1) {
+                    messages.iter_mut().for_each(|message| *message = -*message);
+                }
+            }
+            for ((posterior, channel), edges) in posteriors
+                .iter_mut()
+                .zip(channel_messages.iter())
+                .zip(self.graph.get_bit_edges().iter())
+            {
+                *posterior = edges.iter().fold(i16::from(*channel), |sum, edge| {
+                    sum.saturating_add(i16::from(check_to_bit[*edge]))
+                });
+            }
+            for (edge, bit) in edge_bits.iter().enumerate() {
+                let message = posteriors[*bit].saturating_sub(i16::from(check_to_bit[edge]));
+                bit_to_check[edge] = rule.saturate(message);
+            }
+            n_iterations += 1;
+        };
+        (posteriors, n_iterations, stopping_criterion)
+    }
+
+    fn get_rule(&self) -> QuantizedMinSum {
+        QuantizedMinSum {
+            max_message: self.quantization.get_max_message(),
+            offset: self.quantization.quantize(self.offset),
+            normalization: (self.normalization * f64::from(1_u32 << NORMALIZATION_BITS)).round()
+                as i16,
+        }
+    }
+}
+
+// The offset is in the unit of the messages and the normalization in units of
+// 2^-NORMALIZATION_BITS. Since a magnitude is at most 127 and the normalization at most 256,
+// their product fits in an i16.
+#[derive(Debug, Clone, Copy)]
+struct QuantizedMinSum {
+    max_message: i8,
+    offset: i8,
+    normalization: i16,
+}
+
+impl QuantizedMinSum {
+    fn get_corrected(&self, magnitude: i8) -> i8 {
+        let magnitude = i16::from(magnitude.saturating_sub(self.offset).max(0));
+        ((magnitude * self.normalization) >> NORMALIZATION_BITS) as i8
+    }
+
+    fn saturate(&self, value: i16) -> i8 {
+        let max_message = i16::from(self.max_message);
+        value.clamp(-max_message, max_message) as i8
+    }
+
+    // Same as the floating point rule, using the two smallest magnitudes. They start at the
+    // largest message, so a bit without other bits in the check gets a saturated message.
+    fn update_check_to_bit(&self, bit_to_check: &[i8], check_to_bit: &mut [i8]) {
+        let mut smallest = self.max_message;
+        let mut second_smallest = self.max_message;
+        let mut smallest_index = 0;
+        let mut is_negative = false;
+        for (index, message) in bit_to_check.iter().enumerate() {
+            is_negative ^= *message < 0;
+            let magnitude = message.abs();
+            if magnitude < smallest {
+                second_smallest = smallest;
+                smallest = magnitude;
+                smallest_index = index;
+            } else if magnitude < second_smallest {
+                second_smallest = magnitude;
+            }
+        }
+        for (index, message) in bit_to_check.iter().enumerate() {
+            let magnitude = self.get_corrected(if index == smallest_index {
+                second_smallest
+            } else {
+                smallest
+            });
+            check_to_bit[index] = if is_negative ^ (*message < 0) {
+                -magnitude
+            } else {
+                magnitude
+            };
+        }
+    }
+}
+
+fn get_hard_decision_of(posteriors: &[i16]) -> Vec<GF2> {
+    posteriors
+        .iter()
+        .map(|posterior| if *posterior < 0 { GF2::B1 } else { GF2::B0 })
+        .collect()
+}
+
+impl SoftDecoder for QuantizedMinSumDecoder {
+    fn decode_soft(&self, received: &[GF2]) -> SoftDecodingResult {
+        QuantizedMinSumDecoder::decode_soft(self, received)
+    }
+
+    fn decode_from_llrs(&self, llrs: &[f64]) -> SoftDecodingResult {
+        QuantizedMinSumDecoder::decode_from_llrs(self, llrs)
+    }
+
+    fn decode_syndrome(&self, syndrome: &[GF2]) -> Option<Vec<GF2>> {
+        QuantizedMinSumDecoder::decode_syndrome(self, syndrome)
+    }
+}
+
+impl Decoder for QuantizedMinSumDecoder {
+    type Error = Vec<usize>; // Positions of flipped bits.
+    type Result = BPResult;
+    type Code = ParityCheckMatrix;
+
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
+        self.graph = TannerGraph::from(&code);
+        self.code = code;
+        self
+    }
+
+    fn take_code(&mut self) -> Self::Code {
+        let code = std::mem::take(&mut self.code);
+        self.graph = TannerGraph::from(&self.code);
+        into_owned(code)
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.code.get_n_bits()
+    }
+
+    // The decoding succeeds if the hard decision after the last iteration is the all zero
+    // codeword that was sent.
+    fn decode(&mut self, error: &Self::Error) -> Self::Result {
+        let channel_llrs = get_channel_llrs_of(error, self.code.get_n_bits(), self.crossover_prob);
+        let result = self.decode_llrs(&channel_llrs, None);
+        let n_residual_errors = result
+            .decision
+            .iter()
+            .filter(|value| **value == GF2::B1)
+            .count();
+        let outcome = if n_residual_errors == 0 {
+            BscResult::Success
+        } else {
+            BscResult::Failure
+        };
+        BPResult {
+            outcome,
+            n_iterations: result.n_iterations,
+            stopping_criterion: result.stopping_criterion,
+            n_residual_errors,
+        }
+    }
+
+    // Flip random bits with given probability.
+    fn get_random_error_with_rng<R: Rng>(&self, rng: &mut R) -> Self::Error {
+        Bsc::with_crossover_prob(self.crossover_prob)
+            .sample_error_with_rng(self.code.get_n_bits(), rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn product_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(9).with_checks(vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0, 3, 6],
+            vec![1, 4, 7],
+            vec![2, 5, 8],
+        ])
+    }
+
+    fn rule() -> QuantizedMinSum {
+        QuantizedMinSum {
+            max_message: 31,
+            offset: 0,
+            normalization: 256,
+        }
+    }
+
+    #[test]
+    fn llrs_are_rounded_and_saturated() {
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(4, 1);
+        assert_eq!(quantization.get_max_message(), 7);
+        assert_eq!(quantization.quantize(1.2), 2);
+        assert_eq!(quantization.quantize(1.25), 3);
+        assert_eq!(quantization.quantize(-1.25), -3);
+        assert_eq!(quantization.quantize(100.0), 7);
+        assert_eq!(quantization.quantize(-100.0), -7);
+
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(8, 0);
+        assert_eq!(quantization.get_max_message(), 127);
+        assert_eq!(quantization.quantize(f64::INFINITY), 127);
+    }
+
+    #[test]
+    fn given_llrs_are_quantized() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(0)
+            .for_code(code);
+        let result = decoder.decode_from_llrs(&[1.3, f64::NEG_INFINITY, 0.1]);
+        assert_eq!(result.get_posteriors(), &[1.25, -7.75, 0.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_llrs_panic() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1).for_code(code);
+        decoder.decode_from_llrs(&[1.0, f64::NAN, 1.0]);
+    }
+
+    #[test]
+    fn check_messages_use_the_signs_and_the_minimum_of_the_other_bits() {
+        let mut check_to_bit = vec![0; 4];
+        rule().update_check_to_bit(&[8, -4, 12, -2], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![2, -2, 2, -4]);
+    }
+
+    #[test]
+    fn check_messages_of_degree_one_checks_saturate() {
+        let mut check_to_bit = vec![0; 1];
+        rule().update_check_to_bit(&[-5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![31]);
+
+        let rule = QuantizedMinSum {
+            normalization: 192,
+            ..rule()
+        };
+        rule.update_check_to_bit(&[5], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![23]);
+    }
+
+    #[test]
+    fn normalized_magnitudes_are_rounded_down() {
+        let rule = QuantizedMinSum {
+            normalization: 192,
+            ..rule()
+        };
+        let mut check_to_bit = vec![0; 4];
+        rule.update_check_to_bit(&[8, -5, 12, -3], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![2, -2, 2, -3]);
+    }
+
+    #[test]
+    fn offset_is_removed_before_the_normalization() {
+        let rule = QuantizedMinSum {
+            offset: 3,
+            normalization: 128,
+            ..rule()
+        };
+        let mut check_to_bit = vec![0; 4];
+        rule.update_check_to_bit(&[8, -4, 12, -2], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![0, 0, 0, 0]);
+        rule.update_check_to_bit(&[9, -9, 12, -6], &mut check_to_bit);
+        assert_eq!(check_to_bit, vec![1, -1, 1, -3]);
+    }
+
+    #[test]
+    fn bit_messages_saturate() {
+        assert_eq!(rule().saturate(100), 31);
+        assert_eq!(rule().saturate(-100), -31);
+        assert_eq!(rule().saturate(-20), -20);
+    }
+
+    // With 4 bits of which 1 is fractional, the channel messages are 4 and -4. On the 3 bits
+    // repetition code with the first bit flipped, this is the same tie as for the floating
+    // point decoder. The offset of 0.5 is a message of 1.
+    #[test]
+    fn offset_changes_the_hard_decision_of_a_tie() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(4, 1);
+        let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1)
+            .with_quantization(quantization)
+            .with_max_iterations(1)
+            .for_code(code);
+        let received = [GF2::B1, GF2::B0, GF2::B0];
+        assert_eq!(
+            decoder.decode_soft(&received).get_posteriors(),
+            &[0.0, 2.0, 4.0]
+        );
+
+        let decoder = decoder.with_offset(0.5);
+        let result = decoder.decode_soft(&received);
+        assert_eq!(result.get_posteriors(), &[-0.5, 2.0, 3.5]);
+        assert_eq!(result.get_hard_decision(), &received);
+
+        let result = decoder.with_max_iterations(2).decode_soft(&received);
+        assert_eq!(result.get_posteriors(), &[1.0, 2.0, 2.0]);
+        assert_eq!(result.get_hard_decision(), &[GF2::B0; 3]);
+    }
+
+    // The frames are sampled with a fixed seed and the decisions are pinned to catch any change
+    // of the fixed-point arithmetic.
+    #[test]
+    fn syndromes_of_single_flips_give_back_the_flips() {
+        let code = product_code();
+        let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.05).for_code(code.clone());
+        for bit in 0..9 {
+            let mut error = vec![GF2::B0; 9];
+            error[bit] = GF2::B1;
+            let syndrome = code.get_syndrome_of(&error);
+            assert_eq!(decoder.decode_syndrome(&syndrome), Some(error));
+        }
+
+        // The sum of the row checks is the sum of the column checks for every word.
+        let mut syndrome = vec![GF2::B0; 6];
+        syndrome[0] = GF2::B1;
+        assert_eq!(decoder.decode_syndrome(&syndrome), None);
+    }
+
+    #[test]
+    fn hard_decisions_are_bit_exact() {
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(5, 2);
+        let decoder = QuantizedMinSumDecoder::with_crossover_prob(0.2)
+            .with_quantization(quantization)
+            .with_normalization(0.75)
+            .with_offset(0.25)
+            .for_code(product_code());
+        let frames = [
+            (vec![1, 8], vec![]),
+            (vec![0, 3, 6], vec![]),
+            (vec![1, 3, 7, 8], vec![1, 3, 7, 8]),
+            (vec![2], vec![]),
+            (vec![], vec![]),
+            (vec![0, 1, 4], vec![0, 1, 3, 4]),
+        ];
+
+        for (flipped_bits, decided_ones) in frames.iter() {
+            let mut received = vec![GF2::B0; 9];
+            flipped_bits.iter().for_each(|bit| received[*bit] = GF2::B1);
+            let result = decoder.decode_soft(&received);
+            let decision = result.get_hard_decision();
+            let ones: Vec<usize> = (0..9).filter(|bit| decision[*bit] == GF2::B1).collect();
+            assert_eq!(&ones, decided_ones);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let noise = Bsc::with_crossover_prob(0.2);
+        for (flipped_bits, _) in frames.iter() {
+            assert_eq!(&noise.sample_error_with_rng(9, &mut rng), flipped_bits);
+        }
+    }
+
+    #[test]
+    fn reproductibility_of_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = QuantizedMinSumDecoder::with_crossover_prob(0.1)
+            .with_normalization(0.75)
+            .for_code(product_code());
+
+        let result_0 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        let result_1 = decoder.simulate_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(result_0, result_1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn messages_larger_than_a_byte_panic() {
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(9, 2);
+        QuantizedMinSumDecoder::with_crossover_prob(0.1).with_quantization(quantization);
+    }
+
+    #[test]
+    #[should_panic]
+    fn messages_without_magnitude_bits_panic() {
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(1, 0);
+        QuantizedMinSumDecoder::with_crossover_prob(0.1).with_quantization(quantization);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_fractional_bits_panic() {
+        let quantization = QuantizationConfig::with_total_and_fractional_bits(6, 6);
+        QuantizedMinSumDecoder::with_crossover_prob(0.1).with_quantization(quantization);
+    }
+}
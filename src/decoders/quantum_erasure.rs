@@ -1,11 +1,12 @@
 //! NOTE: Need to check the math to be sure everything work.
 
-use super::{Decoder};
+use super::{into_owned, Decoder};
 use crate::ErasureResult;
 use crate::Ressources;
 use crate::GF4Stabilizers;
 use crate::ParityCheckMatrix;
 use rand::{Rng};
+use std::sync::Arc;
 
 /// Decoder for quantum erasure channel.
 ///
@@ -13,18 +14,16 @@ use rand::{Rng};
 ///
 /// ```
 /// # use believer::*;
-/// let x_checks = ParityCheckMatrix::new(vec![vec![0, 1], vec![1, 2]], 3);
-/// let z_checks = ParityCheckMatrix::new(vec![vec![0, 1], vec![1, 2]], 3);
+/// let x_checks = ParityCheckMatrix::repetition_code_with_n_bits(3);
+/// let z_checks = ParityCheckMatrix::repetition_code_with_n_bits(3);
 /// let erasure_prob = 0.25;
 /// let stabilizers = GF4Stabilizers::from_parity_check_matrices(x_checks, z_checks);
-/// let decoder = QuantumErasureDecoder::new(stabilizers, erasure_prob);
-/// decoder.decode(&decoder.random_error());
+/// let mut decoder = QuantumErasureDecoder::new(stabilizers, erasure_prob);
+/// let error = decoder.get_random_error();
+/// decoder.decode(&error);
 /// ```
-/// 
-
-
 pub struct QuantumErasureDecoder {
-    stabilizers: GF4Stabilizers,
+    stabilizers: Arc<GF4Stabilizers>,
     merged: ParityCheckMatrix,
     no_error_rank: usize,
     erasure_prob: f64,
@@ -43,7 +42,7 @@ impl QuantumErasureDecoder {
     ///
     /// Panics if `erasure_prob` is not between 0.0 and 1.0.
     pub fn new(stabilizers: GF4Stabilizers, erasure_prob: f64) -> Self {
-        if erasure_prob < 0.0 || erasure_prob > 1.0 {
+        if !(0.0..=1.0).contains(&erasure_prob) {
             panic!("invalid probability");
         }
         let merged = stabilizers.merge();
@@ -58,7 +57,7 @@ impl QuantumErasureDecoder {
         };
 
         Self {
-            stabilizers,
+            stabilizers: Arc::new(stabilizers),
             merged,
             no_error_rank,
             erasure_prob,
@@ -67,7 +66,7 @@ impl QuantumErasureDecoder {
     }
 
     pub fn new_merged(merged: ParityCheckMatrix, erasure_prob: f64) -> Self {
-        if erasure_prob < 0.0 || erasure_prob > 1.0 {
+        if !(0.0..=1.0).contains(&erasure_prob) {
             panic!("invalid probability");
         }
         let stabilizers = GF4Stabilizers::empty_with_n_bits(merged.get_n_bits()/2);
@@ -82,7 +81,7 @@ impl QuantumErasureDecoder {
         };
 
         Self {
-            stabilizers,
+            stabilizers: Arc::new(stabilizers),
             merged,
             no_error_rank,
             erasure_prob,
@@ -102,7 +101,7 @@ impl Decoder for QuantumErasureDecoder {
     type Result = ErasureResult;
     type Code = GF4Stabilizers;
 
-    fn for_code(mut self, code: Self::Code) -> Self {
+    fn for_shared_code(mut self, code: Arc<Self::Code>) -> Self {
         self.merged = code.merge();
         self.stabilizers = code;
         self
@@ -144,13 +143,17 @@ impl Decoder for QuantumErasureDecoder {
     }
 
     fn take_code(&mut self) -> Self::Code {
-        std::mem::replace(
+        into_owned(std::mem::replace(
             &mut self.stabilizers,
-            GF4Stabilizers::from_parity_check_matrices(
+            Arc::new(GF4Stabilizers::from_parity_check_matrices(
                 ParityCheckMatrix::new(),
                 ParityCheckMatrix::new(),
-            ),
-        )
+            )),
+        ))
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.stabilizers.n_qubits()
     }
 
 
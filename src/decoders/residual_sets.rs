@@ -0,0 +1,174 @@
+//! The most frequent residual sets of the failures of a decoder.
+
+use std::collections::HashMap;
+
+/// A counter of the sets of bits left by the failures of a decoder, such as the erased bits
+/// that an erasure decoder could not recover, using a bounded memory.
+///
+/// The counter keeps at most `capacity` sets. When a new set is added to a full counter, the
+/// set with the smallest count is evicted and the new set takes its count plus one. Thus, the
+/// counts are upper bounds on the true counts. They are exact if there were never more than
+/// `capacity` different sets, and the sets that are more frequent than the total number of
+/// sets divided by `capacity` are always kept. Ties are broken by comparing the sets, so the
+/// counts don't depend on the order of a hash map.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let mut counter = ResidualSetCounter::with_capacity(2);
+/// counter.add(&[0, 1]);
+/// counter.add(&[2, 3]);
+/// counter.add(&[0, 1]);
+/// counter.add(&[4, 5]);
+///
+/// assert_eq!(counter.get_n_added_sets(), 4);
+/// assert_eq!(counter.get_most_frequent(2), vec![(vec![0, 1], 2), (vec![4, 5], 2)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResidualSetCounter {
+    capacity: usize,
+    counts: HashMap<Vec<usize>, u64>,
+    n_added_sets: u64,
+}
+
+impl ResidualSetCounter {
+    /// Creates an empty counter keeping at most `capacity` sets.
+    ///
+    /// A capacity a few times larger than the number of sets of interest gives accurate
+    /// counts for them.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity == 0 {
+            panic!("invalid capacity");
+        }
+        Self {
+            capacity,
+            counts: HashMap::with_capacity(capacity),
+            n_added_sets: 0,
+        }
+    }
+
+    /// Counts one more occurrence of `set`.
+    ///
+    /// The bits of `set` should be sorted, as the unresolved bits of an
+    /// `ErasureDecodingResult`, since sets with the same bits in different orders are counted
+    /// separately.
+    pub fn add(&mut self, set: &[usize]) {
+        self.n_added_sets += 1;
+        if let Some(count) = self.counts.get_mut(set) {
+            *count += 1;
+            return;
+        }
+        let count = if self.counts.len() < self.capacity {
+            1
+        } else {
+            let (evicted, count) = self
+                .counts
+                .iter()
+                .min_by(|(set, count), (other_set, other_count)| {
+                    count.cmp(other_count).then_with(|| set.cmp(other_set))
+                })
+                .map(|(set, count)| (set.clone(), *count))
+                .unwrap();
+            self.counts.remove(&evicted);
+            count + 1
+        };
+        self.counts.insert(set.to_vec(), count);
+    }
+
+    /// Returns up to `n_sets` of the most frequent sets with their counts, by decreasing count
+    /// and then by increasing set.
+    pub fn get_most_frequent(&self, n_sets: usize) -> Vec<(Vec<usize>, u64)> {
+        let mut sets: Vec<(Vec<usize>, u64)> = self
+            .counts
+            .iter()
+            .map(|(set, count)| (set.clone(), *count))
+            .collect();
+        sets.sort_by(|(set, count), (other_set, other_count)| {
+            other_count.cmp(count).then_with(|| set.cmp(other_set))
+        });
+        sets.truncate(n_sets);
+        sets
+    }
+
+    /// Returns the number of sets added to `self`, including the evicted ones.
+    pub fn get_n_added_sets(&self) -> u64 {
+        self.n_added_sets
+    }
+
+    /// Returns the maximum number of sets kept by `self`.
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_are_exact_below_capacity() {
+        let mut counter = ResidualSetCounter::with_capacity(3);
+        for set in &[
+            vec![1, 2],
+            vec![0],
+            vec![1, 2],
+            vec![3, 4, 5],
+            vec![0],
+            vec![1, 2],
+        ] {
+            counter.add(set);
+        }
+        assert_eq!(
+            counter.get_most_frequent(5),
+            vec![(vec![1, 2], 3), (vec![0], 2), (vec![3, 4, 5], 1)]
+        );
+        assert_eq!(counter.get_most_frequent(1), vec![(vec![1, 2], 3)]);
+        assert_eq!(counter.get_n_added_sets(), 6);
+    }
+
+    // When [0] and [1] both have the smallest count, the smallest set is evicted.
+    #[test]
+    fn sets_with_the_smallest_count_are_evicted_first() {
+        let mut counter = ResidualSetCounter::with_capacity(3);
+        for set in &[vec![5], vec![5], vec![1], vec![0], vec![2]] {
+            counter.add(set);
+        }
+        assert_eq!(
+            counter.get_most_frequent(3),
+            vec![(vec![2], 2), (vec![5], 2), (vec![1], 1)]
+        );
+
+        counter.add(&[2]);
+        counter.add(&[3]);
+        assert_eq!(
+            counter.get_most_frequent(3),
+            vec![(vec![2], 3), (vec![3], 2), (vec![5], 2)]
+        );
+        assert_eq!(counter.get_n_added_sets(), 7);
+    }
+
+    #[test]
+    fn frequent_sets_are_kept_among_many_rare_sets() {
+        let mut counter = ResidualSetCounter::with_capacity(4);
+        for index in 0..100 {
+            counter.add(&[index]);
+            if index % 2 == 0 {
+                counter.add(&[1000]);
+            }
+        }
+        let most_frequent = counter.get_most_frequent(1);
+        assert_eq!(most_frequent[0].0, vec![1000]);
+        assert!(most_frequent[0].1 >= 50);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity_panics() {
+        ResidualSetCounter::with_capacity(0);
+    }
+}
@@ -0,0 +1,193 @@
+//! A table of simulation results that can be written as CSV.
+
+use super::SimulationResult;
+use std::io::{self, Write};
+
+/// The columns of a `ResultsTable`, in the order they are written.
+pub const RESULTS_TABLE_HEADER: [&str; 7] = [
+    "label",
+    "erasure_prob",
+    "n_iterations",
+    "failures",
+    "failure_rate",
+    "ci_low",
+    "ci_high",
+];
+
+/// A table with one row per simulation result, for instance one row for each probability of a
+/// sweep or for each code of a search.
+///
+/// Each row contains a label, the erasure probability of the simulation, the number of
+/// iterations and failures, the failure rate and its confidence interval.
+///
+/// # Example
+///
+/// ```
+/// use believer::{ResultsTable, SimulationResult};
+/// let mut table = ResultsTable::new();
+/// table.add_row("a, b", 0.25, &SimulationResult::with_n_successes_and_failures(3, 1));
+///
+/// let csv = table.to_csv();
+/// let mut lines = csv.lines();
+/// assert_eq!(
+///     lines.next(),
+///     Some("label,erasure_prob,n_iterations,failures,failure_rate,ci_low,ci_high")
+/// );
+/// assert!(lines.next().unwrap().starts_with(r#""a, b",0.25,4,1,0.25,"#));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultsTable {
+    confidence: f64,
+    rows: Vec<Row>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    label: String,
+    erasure_prob: f64,
+    n_iterations: u64,
+    n_failures: u64,
+    failure_rate: f64,
+    confidence_interval: (f64, f64),
+}
+
+impl Default for ResultsTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultsTable {
+    /// Creates an empty table with 95% confidence intervals.
+    pub fn new() -> Self {
+        Self {
+            confidence: 0.95,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Sets the `confidence` of the intervals of the rows added afterward.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `confidence` is not strictly between 0.0 and 1.0.
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        if confidence <= 0.0 || confidence >= 1.0 {
+            panic!("invalid confidence");
+        }
+        self.confidence = confidence;
+        self
+    }
+
+    /// Adds a row for the `result` of a simulation with the given `label` and `erasure_prob`.
+    ///
+    /// The confidence interval is the Wilson interval of the failure rate.
+    pub fn add_row(&mut self, label: &str, erasure_prob: f64, result: &SimulationResult) {
+        self.rows.push(Row {
+            label: label.to_string(),
+            erasure_prob,
+            n_iterations: result.get_n_iterations(),
+            n_failures: result.get_n_failures(),
+            failure_rate: result.get_failure_rate(),
+            confidence_interval: result.get_failure_rate_confidence_interval(self.confidence),
+        });
+    }
+
+    /// Returns the confidence of the intervals.
+    pub fn get_confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    /// Returns the number of rows of `self`.
+    pub fn get_n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Checks if `self` has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Writes `self` as CSV in `writer`.
+    ///
+    /// The first line is the header and the rows follow in the order they were added. Labels
+    /// containing a comma, a quote or a line break are quoted.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{}", RESULTS_TABLE_HEADER.join(","))?;
+        for row in self.rows.iter() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                quote(&row.label),
+                row.erasure_prob,
+                row.n_iterations,
+                row.n_failures,
+                row.failure_rate,
+                row.confidence_interval.0,
+                row.confidence_interval.1
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns `self` as CSV.
+    pub fn to_csv(&self) -> String {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv)
+            .expect("writing in memory can't fail");
+        String::from_utf8(csv).expect("csv is valid utf8")
+    }
+}
+
+// Quotes a field following RFC 4180 when it contains a special character.
+fn quote(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn csv_format_is_stable() {
+        let mut table = ResultsTable::new();
+        table.add_row(
+            "code 0",
+            0.25,
+            &SimulationResult::with_n_successes_and_failures(75, 25),
+        );
+        table.add_row("empty", 0.5, &SimulationResult::new());
+        let mut table = table.with_confidence(0.99);
+        table.add_row(
+            "none",
+            1.0,
+            &SimulationResult::with_n_successes_and_failures(10, 0),
+        );
+
+        let expected = concat!(
+            "label,erasure_prob,n_iterations,failures,failure_rate,ci_low,ci_high\n",
+            "code 0,0.25,100,25,0.25,0.1754521135713868,0.3430446355608273\n",
+            "empty,0.5,0,0,NaN,0,1\n",
+            "none,1,10,0,0,0,0.3988540938438715\n",
+        );
+        assert_eq!(table.to_csv(), expected);
+    }
+
+    #[test]
+    fn labels_with_special_characters_are_quoted() {
+        assert_eq!(quote("plain label"), "plain label");
+        assert_eq!(quote("a,b"), "\"a,b\"");
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_confidence_panics() {
+        ResultsTable::new().with_confidence(0.0);
+    }
+}
@@ -1,10 +1,151 @@
 use super::DecodingResult;
+use std::iter::Sum;
+use std::ops::Add;
 
-/// An interface for simulation result. 
+/// The reason why a simulation until a target precision stopped.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrecisionStoppingCondition {
+    /// The relative standard error of the failure rate is below the target.
+    TargetPrecision,
+    /// The maximum number of iterations was done before reaching the target.
+    MaxIterations,
+}
+
+/// A Beta(alpha, beta) prior on the failure rate of a simulation.
+///
+/// The default is the Jeffreys prior Beta(1/2, 1/2). It gives a positive failure rate to
+/// simulations without failures which decreases with the number of iterations.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BetaPrior {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Default for BetaPrior {
+    fn default() -> Self {
+        Self::jeffreys()
+    }
+}
+
+impl BetaPrior {
+    /// Creates a Beta(`alpha`, `beta`) prior. The prior mean is `alpha / (alpha + beta)`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `alpha` or `beta` are not strictly positive and finite.
+    pub fn with_alpha_and_beta(alpha: f64, beta: f64) -> Self {
+        let is_valid = |value: f64| value > 0.0 && value.is_finite();
+        if !is_valid(alpha) || !is_valid(beta) {
+            panic!("invalid prior");
+        }
+        Self { alpha, beta }
+    }
+
+    /// Creates the Jeffreys prior Beta(1/2, 1/2).
+    pub fn jeffreys() -> Self {
+        Self::with_alpha_and_beta(0.5, 0.5)
+    }
+
+    /// Creates the uniform prior Beta(1, 1).
+    pub fn uniform() -> Self {
+        Self::with_alpha_and_beta(1.0, 1.0)
+    }
+
+    /// Returns the alpha parameter of `self`, the prior number of failures.
+    pub fn get_alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the beta parameter of `self`, the prior number of successes.
+    pub fn get_beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+// The sums of the likelihood ratios of the decodings of an importance sampling simulation and
+// of their squares, over all the decodings and over the failures only.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct ImportanceWeights {
+    sum: f64,
+    sum_of_squares: f64,
+    failure_sum: f64,
+    failure_sum_of_squares: f64,
+}
+
+impl ImportanceWeights {
+    // The weights of `result`, which are all 1 without importance sampling.
+    fn of(result: &SimulationResult) -> Self {
+        if let Some(weights) = result.importance_weights {
+            return weights;
+        }
+        let n_iterations = result.get_n_iterations() as f64;
+        let n_failures = result.n_failures as f64;
+        Self {
+            sum: n_iterations,
+            sum_of_squares: n_iterations,
+            failure_sum: n_failures,
+            failure_sum_of_squares: n_failures,
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self {
+            sum: self.sum + other.sum,
+            sum_of_squares: self.sum_of_squares + other.sum_of_squares,
+            failure_sum: self.failure_sum + other.failure_sum,
+            failure_sum_of_squares: self.failure_sum_of_squares + other.failure_sum_of_squares,
+        }
+    }
+}
+
+/// An interface for simulation result. 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimulationResult {
     n_successes: u64,
     n_failures: u64,
+    // The total and the maximum number of iterations of an iterative decoder. They default to
+    // 0 when reading results saved without them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    n_decoding_iterations: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_n_decoding_iterations: u64,
+    // Only set by simulations until a target precision.
+    #[cfg_attr(feature = "serde", serde(default))]
+    precision_stopping_condition: Option<PrecisionStoppingCondition>,
+    // The number of decodings with each histogram value, such as the number of iterations.
+    // Its length is bounded by the largest value, that is the iteration cap of the decoder.
+    #[cfg_attr(feature = "serde", serde(default))]
+    histogram: Vec<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    n_unconverged: u64,
+    // The total number of bits that are wrong or still erased after decoding and the total
+    // number of bits of the decodings reporting them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    n_residual_errors: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    n_decoded_bits: u64,
+    // Only set by importance sampling simulations.
+    #[cfg_attr(feature = "serde", serde(default))]
+    importance_weights: Option<ImportanceWeights>,
+    // The total and the maximum number of iterations of the decodings that converged and the
+    // maximum number of iterations of the others. The total for the decodings that didn't
+    // converge is the remainder of the total number of iterations.
+    #[cfg_attr(feature = "serde", serde(default))]
+    n_converged_decoding_iterations: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_n_converged_decoding_iterations: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_n_unconverged_decoding_iterations: u64,
+}
+
+impl Default for SimulationResult {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SimulationResult {
@@ -12,22 +153,75 @@ impl SimulationResult {
 
     /// Creates a new empty `SimulationResult`.
     pub fn new() -> Self {
-        Self { n_successes: 0, n_failures: 0 }
+        Self::with_n_successes_and_failures(0, 0)
     }
 
     /// Creates a new `SimulationResult` from the number of successes and failures.
     pub fn with_n_successes_and_failures(n_successes: u64, n_failures: u64) -> Self {
-        Self { n_successes, n_failures }
+        Self {
+            n_successes,
+            n_failures,
+            n_decoding_iterations: 0,
+            max_n_decoding_iterations: 0,
+            precision_stopping_condition: None,
+            histogram: Vec::new(),
+            n_unconverged: 0,
+            n_residual_errors: 0,
+            n_decoded_bits: 0,
+            importance_weights: None,
+            n_converged_decoding_iterations: 0,
+            max_n_converged_decoding_iterations: 0,
+            max_n_unconverged_decoding_iterations: 0,
+        }
+    }
+
+    /// Creates an empty `SimulationResult`, the identity when combining results.
+    ///
+    /// This is the same as `new`. It is not the worse result, which has a failure.
+    pub fn empty() -> Self {
+        Self::new()
     }
 
     /// Creates the worse `SimulationResult`. That is, a simulation with failure rate 1.
     pub fn worse_result() -> Self {
-        Self { n_successes: 0, n_failures: 1 }
+        Self::with_n_successes_and_failures(0, 1)
     }
 
     // ***** Updaters *****
 
+    /// Adds the decoding `result` of an error on a code with `n_bits` bits.
+    ///
+    /// The number of bits is only used to compute the bit error rate of decoders reporting
+    /// their residual errors.
+    pub fn add_decoding_result_on_n_bits<D: DecodingResult>(&mut self, result: D, n_bits: usize) {
+        if let Some(n_residual_errors) = result.get_n_residual_errors() {
+            self.n_residual_errors += n_residual_errors as u64;
+            self.n_decoded_bits += n_bits as u64;
+        }
+        self.add_decoding_result(result);
+    }
+
     pub fn add_decoding_result<D: DecodingResult>(&mut self, result: D) {
+        let n_decoding_iterations = result.get_n_decoding_iterations() as u64;
+        self.n_decoding_iterations += n_decoding_iterations;
+        self.max_n_decoding_iterations = self.max_n_decoding_iterations.max(n_decoding_iterations);
+        if let Some(value) = result.get_histogram_value() {
+            if value >= self.histogram.len() {
+                self.histogram.resize(value + 1, 0);
+            }
+            self.histogram[value] += 1;
+        }
+        if result.has_converged() {
+            self.n_converged_decoding_iterations += n_decoding_iterations;
+            self.max_n_converged_decoding_iterations = self
+                .max_n_converged_decoding_iterations
+                .max(n_decoding_iterations);
+        } else {
+            self.n_unconverged += 1;
+            self.max_n_unconverged_decoding_iterations = self
+                .max_n_unconverged_decoding_iterations
+                .max(n_decoding_iterations);
+        }
         if result.is_success() {
             self.n_successes += 1;
         } else {
@@ -35,6 +229,30 @@ impl SimulationResult {
         }
     }
 
+    /// Adds the decoding `result` of an error sampled from a biased distribution where
+    /// `weight` is the likelihood ratio of the error between the true and the biased
+    /// distributions.
+    ///
+    /// Once a weighted result is added, the failure rate of `self` is the importance sampling
+    /// estimate. Mixing weighted and unweighted results counts the unweighted results with
+    /// weight 1.
+    pub fn add_weighted_decoding_result<D: DecodingResult>(&mut self, result: D, weight: f64) {
+        let mut weights = ImportanceWeights::of(self);
+        weights.sum += weight;
+        weights.sum_of_squares += weight * weight;
+        if result.is_failure() {
+            weights.failure_sum += weight;
+            weights.failure_sum_of_squares += weight * weight;
+        }
+        self.importance_weights = Some(weights);
+        self.add_decoding_result(result);
+    }
+
+    pub(super) fn stopped_by(mut self, condition: PrecisionStoppingCondition) -> Self {
+        self.precision_stopping_condition = Some(condition);
+        self
+    }
+
     // ***** Checkers *****
 
     pub fn has_not_at_least_one_success_and_one_failure(&self) -> bool {
@@ -46,15 +264,111 @@ impl SimulationResult {
         self.get_failure_rate() < other.get_failure_rate()
     }
 
+    /// Checks if `self` has better performance than `other` with the given `confidence`.
+    ///
+    /// That is, if the confidence interval of the failure rate of `self` is entirely below the
+    /// confidence interval of the failure rate of `other`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `confidence` is not strictly between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::SimulationResult;
+    /// let good = SimulationResult::with_n_successes_and_failures(980, 20);
+    /// let bad = SimulationResult::with_n_successes_and_failures(950, 50);
+    /// assert!(good.is_significantly_better_than(&bad, 0.95));
+    ///
+    /// let good = SimulationResult::with_n_successes_and_failures(49, 1);
+    /// let bad = SimulationResult::with_n_successes_and_failures(48, 2);
+    /// assert!(good.is_better_than(&bad));
+    /// assert!(!good.is_significantly_better_than(&bad, 0.95));
+    /// ```
+    pub fn is_significantly_better_than(&self, other: &Self, confidence: f64) -> bool {
+        let (_, upper) = self.get_failure_rate_confidence_interval(confidence);
+        let (lower, _) = other.get_failure_rate_confidence_interval(confidence);
+        upper < lower
+    }
+
     // ***** Getters *****
 
-    pub fn combine_with(&self, other: SimulationResult) -> Self {
+    /// Merges the statistics of `self` and `other` as if they came from a single simulation.
+    ///
+    /// This is how the partial results of parallel or sharded simulations are put together.
+    /// The counts are added, so combining is associative and commutative and the identity is
+    /// `SimulationResult::empty()`. A combined simulation until a target precision stopped by
+    /// reaching the maximum number of iterations if any of its parts did.
+    ///
+    /// Results can also be combined with `+` or summed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::SimulationResult;
+    /// let first = SimulationResult::with_n_successes_and_failures(9, 1);
+    /// let second = SimulationResult::with_n_successes_and_failures(5, 5);
+    ///
+    /// let combined = first.combine(&second);
+    /// assert_eq!(combined, SimulationResult::with_n_successes_and_failures(14, 6));
+    /// assert_eq!(combined.get_failure_rate(), 0.3);
+    /// assert_eq!(&first + &second, combined);
+    /// assert_eq!(vec![first, second].into_iter().sum::<SimulationResult>(), combined);
+    /// ```
+    pub fn combine(&self, other: &SimulationResult) -> Self {
+        let mut histogram = self.histogram.clone();
+        if other.histogram.len() > histogram.len() {
+            histogram.resize(other.histogram.len(), 0);
+        }
+        histogram
+            .iter_mut()
+            .zip(other.histogram.iter())
+            .for_each(|(count, other_count)| *count += other_count);
+        let precision_stopping_condition = match (
+            self.precision_stopping_condition,
+            other.precision_stopping_condition,
+        ) {
+            (Some(condition), Some(other_condition)) if condition != other_condition => {
+                Some(PrecisionStoppingCondition::MaxIterations)
+            }
+            (condition, other_condition) => condition.or(other_condition),
+        };
+        let importance_weights = match (self.importance_weights, other.importance_weights) {
+            (None, None) => None,
+            _ => Some(ImportanceWeights::of(self).add(&ImportanceWeights::of(other))),
+        };
         Self {
             n_successes: self.n_successes + other.n_successes,
             n_failures: self.n_failures + other.n_failures,
+            n_decoding_iterations: self.n_decoding_iterations + other.n_decoding_iterations,
+            max_n_decoding_iterations: self
+                .max_n_decoding_iterations
+                .max(other.max_n_decoding_iterations),
+            precision_stopping_condition,
+            histogram,
+            n_unconverged: self.n_unconverged + other.n_unconverged,
+            n_residual_errors: self.n_residual_errors + other.n_residual_errors,
+            n_decoded_bits: self.n_decoded_bits + other.n_decoded_bits,
+            importance_weights,
+            n_converged_decoding_iterations: self.n_converged_decoding_iterations
+                + other.n_converged_decoding_iterations,
+            max_n_converged_decoding_iterations: self
+                .max_n_converged_decoding_iterations
+                .max(other.max_n_converged_decoding_iterations),
+            max_n_unconverged_decoding_iterations: self
+                .max_n_unconverged_decoding_iterations
+                .max(other.max_n_unconverged_decoding_iterations),
         }
     }
 
+    /// Merges the statistics of `self` and `other` as if they came from a single simulation.
+    ///
+    /// This is the same as `combine`, but takes `other` by value.
+    pub fn combine_with(&self, other: SimulationResult) -> Self {
+        self.combine(&other)
+    }
+
     /// Get the effective failure rate of `self` for a given code `dimension`. 
     ///
     /// This is the equivalent failure rate per bit if `dimension` similar bits without error
@@ -86,10 +400,13 @@ impl SimulationResult {
     /// assert_eq!(result.get_effective_success_rate_for_code_dimension(2), 0.6);
     /// ```
     pub fn get_effective_success_rate_for_code_dimension(&self, dimension: u32) -> f64 {
-        (self.get_success_rate() as f64).powf(1.0 / dimension as f64)
+        self.get_success_rate().powf(1.0 / dimension as f64)
     }
 
     /// Get the failure rate of `self`.
+    ///
+    /// For an importance sampling simulation, this is the unbiased estimate of the failure
+    /// rate given by the average weight of the failures over all decodings.
     /// 
     /// # Example 
     /// 
@@ -99,7 +416,214 @@ impl SimulationResult {
     /// assert_eq!(result.get_failure_rate(), 0.64);
     /// ```
     pub fn get_failure_rate(&self) -> f64 {
-        self.n_failures as f64 / self.get_n_iterations() as f64
+        match self.importance_weights {
+            Some(weights) => weights.failure_sum / self.get_n_iterations() as f64,
+            None => self.n_failures as f64 / self.get_n_iterations() as f64,
+        }
+    }
+
+    /// Get the bit error rate of `self`. That is, the fraction of the decoded bits that are
+    /// wrong or still erased after decoding.
+    ///
+    /// Only the decodings of decoders reporting their residual errors are counted. This is 0 if
+    /// no decoded bits were recorded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+    /// let mut decoder = ErasureDecoder::with_prob(0.25).for_code(code);
+    /// let mut result = SimulationResult::new();
+    /// result.add_decoding_result_on_n_bits(decoder.decode(&vec![0, 1, 2, 3]), 4);
+    /// result.add_decoding_result_on_n_bits(decoder.decode(&vec![0, 1, 2]), 4);
+    ///
+    /// assert_eq!(result.get_failure_rate(), 0.5);
+    /// assert_eq!(result.get_bit_error_rate(), 0.5);
+    /// ```
+    pub fn get_bit_error_rate(&self) -> f64 {
+        if self.n_decoded_bits == 0 {
+            return 0.0;
+        }
+        self.n_residual_errors as f64 / self.n_decoded_bits as f64
+    }
+
+    /// Get the mean of the posterior distribution of the failure rate of `self` given a Beta
+    /// `prior`. That is, `(n_failures + alpha) / (n_iterations + alpha + beta)`.
+    ///
+    /// Unlike the failure rate, it is positive for simulations without failures and goes to 0
+    /// only as the number of iterations grows.
+    ///
+    /// The posterior is computed from the number of failures and successes, so it ignores the
+    /// weights of an importance sampling simulation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BetaPrior, SimulationResult};
+    /// let lucky = SimulationResult::with_n_successes_and_failures(10, 0);
+    /// let long = SimulationResult::with_n_successes_and_failures(99_999, 1);
+    /// assert!(lucky.is_better_than(&long));
+    ///
+    /// let prior = BetaPrior::default();
+    /// assert_eq!(lucky.get_posterior_mean_failure_rate(prior), 0.5 / 11.0);
+    /// let lucky_rate = lucky.get_posterior_mean_failure_rate(prior);
+    /// assert!(long.get_posterior_mean_failure_rate(prior) < lucky_rate);
+    /// ```
+    pub fn get_posterior_mean_failure_rate(&self, prior: BetaPrior) -> f64 {
+        (self.n_failures as f64 + prior.alpha)
+            / (self.get_n_iterations() as f64 + prior.alpha + prior.beta)
+    }
+
+    /// Get the upper bound of the failure rate of `self` with the given posterior probability
+    /// `credibility` under a Beta `prior`.
+    ///
+    /// That is, the failure rate is below this bound with probability `credibility` according
+    /// to the Beta(n_failures + alpha, n_successes + beta) posterior distribution.
+    /// Like the posterior mean, it ignores the weights of an importance sampling simulation.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `credibility` is not strictly between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BetaPrior, SimulationResult};
+    /// let result = SimulationResult::with_n_successes_and_failures(99, 1);
+    /// let bound = result.get_failure_rate_upper_credible_bound(BetaPrior::uniform(), 0.95);
+    ///
+    /// assert!(bound > result.get_failure_rate());
+    /// assert!((bound - 0.0461).abs() < 1e-4);
+    /// ```
+    pub fn get_failure_rate_upper_credible_bound(&self, prior: BetaPrior, credibility: f64) -> f64 {
+        if credibility <= 0.0 || credibility >= 1.0 {
+            panic!("invalid credibility");
+        }
+        get_beta_quantile(
+            credibility,
+            self.n_failures as f64 + prior.alpha,
+            self.n_successes as f64 + prior.beta,
+        )
+    }
+
+    /// Get the Wilson score interval of the failure rate of `self` with the given `confidence`.
+    ///
+    /// The normal approximation interval is not used since it collapses to a single point when
+    /// no failures or no successes are found and it is too narrow for rare failures, which is
+    /// the usual regime of a good code. The Wilson interval stays between 0 and 1 and has a
+    /// positive width in these cases. An empty result gives the interval from 0 to 1.
+    ///
+    /// For an importance sampling simulation, the interval is the normal approximation
+    /// interval from the standard error of the weighted estimate, which is only reliable if
+    /// the effective sample size is large.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `confidence` is not strictly between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::SimulationResult;
+    /// let result = SimulationResult::with_n_successes_and_failures(10, 0);
+    /// let (lower, upper) = result.get_failure_rate_confidence_interval(0.95);
+    ///
+    /// assert_eq!(lower, 0.0);
+    /// assert!((upper - 0.2775).abs() < 1e-4);
+    /// ```
+    pub fn get_failure_rate_confidence_interval(&self, confidence: f64) -> (f64, f64) {
+        if confidence <= 0.0 || confidence >= 1.0 {
+            panic!("invalid confidence");
+        }
+        let n_iterations = self.get_n_iterations() as f64;
+        if n_iterations == 0.0 {
+            return (0.0, 1.0);
+        }
+        let z = get_standard_normal_quantile(0.5 + confidence / 2.0);
+        let rate = self.get_failure_rate();
+        if let Some(standard_error) = self.get_weighted_standard_error() {
+            let half_width = z * standard_error;
+            return ((rate - half_width).max(0.0), (rate + half_width).min(1.0));
+        }
+        let z_squared = z * z;
+        let scale = 1.0 + z_squared / n_iterations;
+        let center = (rate + z_squared / (2.0 * n_iterations)) / scale;
+        let half_width = z
+            * (rate * (1.0 - rate) / n_iterations
+                + z_squared / (4.0 * n_iterations * n_iterations))
+                .sqrt()
+            / scale;
+        // The bounds are exact when no failures or no successes are found instead of being off
+        // by a rounding error.
+        let lower = if self.n_failures == 0 {
+            0.0
+        } else {
+            (center - half_width).max(0.0)
+        };
+        let upper = if self.n_successes == 0 {
+            1.0
+        } else {
+            (center + half_width).min(1.0)
+        };
+        (lower, upper)
+    }
+
+    /// Get the estimated relative standard error of the failure rate of `self`.
+    ///
+    /// This is the standard error of the failure rate divided by the failure rate. It is
+    /// infinite if no failures were found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::SimulationResult;
+    /// let result = SimulationResult::with_n_successes_and_failures(75, 25);
+    /// assert_eq!(result.get_relative_standard_error(), 0.17320508075688773);
+    ///
+    /// let result = SimulationResult::with_n_successes_and_failures(100, 0);
+    /// assert_eq!(result.get_relative_standard_error(), f64::INFINITY);
+    /// ```
+    pub fn get_relative_standard_error(&self) -> f64 {
+        if self.n_failures == 0 {
+            return f64::INFINITY;
+        }
+        if let Some(standard_error) = self.get_weighted_standard_error() {
+            return standard_error / self.get_failure_rate();
+        }
+        (self.n_successes as f64 / (self.get_n_iterations() * self.n_failures) as f64).sqrt()
+    }
+
+    // The standard error of the failure rate of an importance sampling simulation, estimated
+    // from the sample variance of the weights of the failures.
+    fn get_weighted_standard_error(&self) -> Option<f64> {
+        let weights = self.importance_weights?;
+        let n_iterations = self.get_n_iterations() as f64;
+        let rate = weights.failure_sum / n_iterations;
+        let variance = (weights.failure_sum_of_squares / n_iterations - rate * rate).max(0.0);
+        Some((variance / n_iterations).sqrt())
+    }
+
+    /// Get the effective sample size of `self`. That is, the number of unweighted decodings
+    /// giving an estimate as precise as the weighted decodings of an importance sampling
+    /// simulation, `(sum of weights)^2 / (sum of squared weights)`.
+    ///
+    /// This is the number of iterations for the other simulations. A small effective sample
+    /// size compared to the number of iterations means that a few decodings dominate the
+    /// estimate, so the bias of the sampling is too strong.
+    pub fn get_effective_sample_size(&self) -> f64 {
+        match self.importance_weights {
+            Some(weights) if weights.sum_of_squares > 0.0 => {
+                weights.sum * weights.sum / weights.sum_of_squares
+            }
+            Some(_) => 0.0,
+            None => self.get_n_iterations() as f64,
+        }
+    }
+
+    /// Checks if `self` comes from an importance sampling simulation.
+    pub fn is_weighted(&self) -> bool {
+        self.importance_weights.is_some()
     }
 
     /// Get the success rate of `self`.
@@ -112,6 +636,9 @@ impl SimulationResult {
     /// assert_eq!(result.get_success_rate(), 0.36);
     /// ```
     pub fn get_success_rate(&self) -> f64 {
+        if self.importance_weights.is_some() {
+            return 1.0 - self.get_failure_rate();
+        }
         self.n_successes as f64 / self.get_n_iterations() as f64
     }
 
@@ -119,11 +646,872 @@ impl SimulationResult {
         self.n_failures + self.n_successes
     }
 
+    /// Get the number of failed decodings of `self`.
     pub fn get_n_failures(&self) -> u64 {
         self.n_failures
     }
-    
+
+    /// Get the number of successful decodings of `self`.
     pub fn get_n_successes(&self) -> u64 {
         self.n_successes
     }
-}
\ No newline at end of file
+
+    /// Get the average number of iterations used by an iterative decoder over all decoded
+    /// errors. This is 0 for the other decoders.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.simulate_n_iterations(100);
+    ///
+    /// assert!(result.get_mean_n_decoding_iterations() <= 10.0);
+    /// ```
+    pub fn get_mean_n_decoding_iterations(&self) -> f64 {
+        self.n_decoding_iterations as f64 / self.get_n_iterations() as f64
+    }
+
+    /// Get the maximum number of iterations used by an iterative decoder to decode an error.
+    /// This is 0 for the other decoders.
+    pub fn get_max_n_decoding_iterations(&self) -> u64 {
+        self.max_n_decoding_iterations
+    }
+
+    /// Get the average number of iterations used by an iterative decoder over the decodings
+    /// that converged. This is 0 if there are no such decodings.
+    ///
+    /// This is the typical latency of the decoder, while the iterations of the decodings that
+    /// didn't converge are usually the iteration cap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BscDecoder::with_crossover_prob(0.1)
+    ///     .with_max_iterations(10)
+    ///     .for_code(code);
+    /// let result = decoder.simulate_n_iterations(100);
+    ///
+    /// assert!(
+    ///     result.get_mean_n_decoding_iterations_of_converged_decodings()
+    ///         <= result.get_max_n_decoding_iterations_of_converged_decodings() as f64
+    /// );
+    /// assert!(result.get_max_n_decoding_iterations_of_unconverged_decodings() <= 10);
+    /// ```
+    pub fn get_mean_n_decoding_iterations_of_converged_decodings(&self) -> f64 {
+        let n_converged = self.get_n_iterations() - self.n_unconverged;
+        if n_converged == 0 {
+            return 0.0;
+        }
+        self.n_converged_decoding_iterations as f64 / n_converged as f64
+    }
+
+    /// Get the maximum number of iterations used by an iterative decoder over the decodings
+    /// that converged. This is 0 if there are no such decodings.
+    pub fn get_max_n_decoding_iterations_of_converged_decodings(&self) -> u64 {
+        self.max_n_converged_decoding_iterations
+    }
+
+    /// Get the average number of iterations used by an iterative decoder over the decodings
+    /// that didn't converge. This is 0 if there are no such decodings.
+    pub fn get_mean_n_decoding_iterations_of_unconverged_decodings(&self) -> f64 {
+        if self.n_unconverged == 0 {
+            return 0.0;
+        }
+        (self.n_decoding_iterations - self.n_converged_decoding_iterations) as f64
+            / self.n_unconverged as f64
+    }
+
+    /// Get the maximum number of iterations used by an iterative decoder over the decodings
+    /// that didn't converge. This is 0 if there are no such decodings.
+    pub fn get_max_n_decoding_iterations_of_unconverged_decodings(&self) -> u64 {
+        self.max_n_unconverged_decoding_iterations
+    }
+
+    /// Get the histogram of the decodings of `self`. The value at index `i` is the number of
+    /// decodings that took `i` iterations for an iterative decoder.
+    ///
+    /// The histogram is empty for decoders that don't record a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+    /// let result = decoder.simulate_n_iterations(100);
+    ///
+    /// assert_eq!(result.get_iteration_histogram().iter().sum::<u64>(), 100);
+    /// ```
+    pub fn get_iteration_histogram(&self) -> &[u64] {
+        &self.histogram
+    }
+
+    /// Get the mean of the values in the histogram of `self`, or `None` if it is empty.
+    pub fn get_histogram_mean(&self) -> Option<f64> {
+        let n_values: u64 = self.histogram.iter().sum();
+        if n_values == 0 {
+            return None;
+        }
+        let sum: u64 = self
+            .histogram
+            .iter()
+            .enumerate()
+            .map(|(value, count)| value as u64 * count)
+            .sum();
+        Some(sum as f64 / n_values as f64)
+    }
+
+    /// Get the smallest value in the histogram of `self` such that at least the given
+    /// `fraction` of the values are at most this value, or `None` if the histogram is empty.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `fraction` is not between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(5);
+    /// let mut decoder = BscDecoder::with_crossover_prob(0.1)
+    ///     .with_max_iterations(10)
+    ///     .for_code(code);
+    /// let result = decoder.simulate_n_iterations(100);
+    ///
+    /// let median = result.get_histogram_percentile(0.5).unwrap();
+    /// assert!(median <= result.get_histogram_percentile(0.99).unwrap());
+    /// assert!(result.get_histogram_percentile(1.0).unwrap() <= 10);
+    /// ```
+    pub fn get_histogram_percentile(&self, fraction: f64) -> Option<usize> {
+        if !(0.0..=1.0).contains(&fraction) {
+            panic!("invalid fraction");
+        }
+        let n_values: u64 = self.histogram.iter().sum();
+        if n_values == 0 {
+            return None;
+        }
+        let threshold = (fraction * n_values as f64).ceil().max(1.0) as u64;
+        let mut n_smaller_values = 0;
+        self.histogram.iter().position(|count| {
+            n_smaller_values += count;
+            n_smaller_values >= threshold
+        })
+    }
+
+    /// Get the average number of bits that are wrong or still erased after a failed decoding.
+    /// This is 0 if there are no failures.
+    pub fn get_average_residual_errors(&self) -> f64 {
+        if self.n_failures == 0 {
+            return 0.0;
+        }
+        self.n_residual_errors as f64 / self.n_failures as f64
+    }
+
+    /// Get the number of decodings of `self` where an iterative decoder didn't converge.
+    pub fn get_n_unconverged_decodings(&self) -> u64 {
+        self.n_unconverged
+    }
+
+    /// Get the reason why a simulation until a target precision stopped. This is `None` for
+    /// the other simulations.
+    pub fn get_precision_stopping_condition(&self) -> Option<PrecisionStoppingCondition> {
+        self.precision_stopping_condition
+    }
+
+    // ***** Export *****
+
+    /// Returns `self` as a JSON object.
+    ///
+    /// This is available without the `serde` feature and gives the same JSON as `serde_json`,
+    /// so it can be read back with the `serde` feature. Like `serde_json`, infinite and NaN
+    /// weights are written as `null` since JSON has no such numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::SimulationResult;
+    /// let result = SimulationResult::with_n_successes_and_failures(9, 16);
+    /// assert!(result.to_json().starts_with(r#"{"n_successes":9,"n_failures":16,"#));
+    /// ```
+    pub fn to_json(&self) -> String {
+        let precision_stopping_condition = match self.precision_stopping_condition {
+            Some(PrecisionStoppingCondition::TargetPrecision) => "\"TargetPrecision\"",
+            Some(PrecisionStoppingCondition::MaxIterations) => "\"MaxIterations\"",
+            None => "null",
+        };
+        let histogram: Vec<String> = self.histogram.iter().map(|count| count.to_string()).collect();
+        let importance_weights = match self.importance_weights {
+            Some(weights) => format!(
+                concat!(
+                    r#"{{"sum":{},"sum_of_squares":{},"failure_sum":{},"#,
+                    r#""failure_sum_of_squares":{}}}"#
+                ),
+                get_json_of(weights.sum),
+                get_json_of(weights.sum_of_squares),
+                get_json_of(weights.failure_sum),
+                get_json_of(weights.failure_sum_of_squares)
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            concat!(
+                r#"{{"n_successes":{},"n_failures":{},"n_decoding_iterations":{},"#,
+                r#""max_n_decoding_iterations":{},"precision_stopping_condition":{},"#,
+                r#""histogram":[{}],"n_unconverged":{},"n_residual_errors":{},"#,
+                r#""n_decoded_bits":{},"importance_weights":{},"#,
+                r#""n_converged_decoding_iterations":{},"#,
+                r#""max_n_converged_decoding_iterations":{},"#,
+                r#""max_n_unconverged_decoding_iterations":{}}}"#
+            ),
+            self.n_successes,
+            self.n_failures,
+            self.n_decoding_iterations,
+            self.max_n_decoding_iterations,
+            precision_stopping_condition,
+            histogram.join(","),
+            self.n_unconverged,
+            self.n_residual_errors,
+            self.n_decoded_bits,
+            importance_weights,
+            self.n_converged_decoding_iterations,
+            self.max_n_converged_decoding_iterations,
+            self.max_n_unconverged_decoding_iterations,
+        )
+    }
+}
+
+// Floats are written like serde_json writes them. JSON has no infinite or NaN numbers, so these
+// are written as null.
+fn get_json_of(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:?}", value)
+    } else {
+        "null".to_string()
+    }
+}
+
+impl Add for SimulationResult {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.combine(&other)
+    }
+}
+
+impl<'a> Add<&'a SimulationResult> for &'a SimulationResult {
+    type Output = SimulationResult;
+
+    fn add(self, other: Self) -> SimulationResult {
+        self.combine(other)
+    }
+}
+
+impl Sum for SimulationResult {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::empty(), |sum, result| sum + result)
+    }
+}
+
+impl<'a> Sum<&'a SimulationResult> for SimulationResult {
+    fn sum<I: Iterator<Item = &'a SimulationResult>>(iter: I) -> Self {
+        iter.fold(Self::empty(), |sum, result| sum.combine(result))
+    }
+}
+
+// The quantile of the Beta(`a`, `b`) distribution at `prob` found by bisection on the
+// regularized incomplete beta function, which is increasing.
+fn get_beta_quantile(prob: f64, a: f64, b: f64) -> f64 {
+    let (mut lower, mut upper) = (0.0, 1.0);
+    for _ in 0..100 {
+        let middle = (lower + upper) / 2.0;
+        if get_regularized_incomplete_beta(middle, a, b) < prob {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    (lower + upper) / 2.0
+}
+
+// The cumulative distribution of Beta(`a`, `b`) at `x`, computed with the continued fraction
+// of Numerical Recipes on the side where it converges quickly.
+fn get_regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let log_front = get_log_gamma(a + b) - get_log_gamma(a) - get_log_gamma(b)
+        + a * x.ln()
+        + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        log_front.exp() * get_incomplete_beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - log_front.exp() * get_incomplete_beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+// Evaluates the continued fraction of the incomplete beta function with the modified method
+// of Lentz.
+fn get_incomplete_beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut fraction = d;
+    for m in 1..1000 {
+        let m = m as f64;
+        let even_step = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even_step * d);
+        c = clamp(1.0 + even_step / c);
+        fraction *= d * c;
+        let odd_step = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd_step * d);
+        c = clamp(1.0 + odd_step / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    fraction
+}
+
+// The logarithm of the gamma function using the Lanczos approximation with g = 7.
+fn get_log_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - get_log_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, coefficient)| {
+            sum + coefficient / (x + i as f64 + 1.0)
+        });
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// The inverse of the standard normal cumulative distribution at `prob` using the rational
+// approximation of Acklam, which has a relative error below 1.2e-9.
+fn get_standard_normal_quantile(prob: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if prob < 0.02425 {
+        tail((-2.0 * prob.ln()).sqrt())
+    } else if prob > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - prob).ln()).sqrt())
+    } else {
+        let q = prob - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        BPResult, BscDecoder, BscResult, Decoder, ErasureResult, ParityCheckMatrix,
+        StoppingCriterion,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn bp_result(outcome: BscResult, n_iterations: usize) -> BPResult {
+        BPResult {
+            outcome,
+            n_iterations,
+            stopping_criterion: StoppingCriterion::SatisfiedSyndrome,
+            n_residual_errors: 0,
+        }
+    }
+
+    fn unconverged_bp_result(n_iterations: usize) -> BPResult {
+        BPResult {
+            outcome: BscResult::Failure,
+            n_iterations,
+            stopping_criterion: StoppingCriterion::MaxIterations,
+            n_residual_errors: 0,
+        }
+    }
+
+    #[test]
+    fn iterations_are_recorded_in_the_histogram() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result(bp_result(BscResult::Success, 1));
+        result.add_decoding_result(bp_result(BscResult::Success, 1));
+        result.add_decoding_result(bp_result(BscResult::Failure, 3));
+        result.add_decoding_result(unconverged_bp_result(4));
+        assert_eq!(result.get_iteration_histogram(), &[0, 2, 0, 1, 1]);
+        assert_eq!(result.get_histogram_mean(), Some(2.25));
+        assert_eq!(result.get_n_unconverged_decodings(), 1);
+
+        let mut other = SimulationResult::new();
+        other.add_decoding_result(bp_result(BscResult::Success, 0));
+        other.add_decoding_result(unconverged_bp_result(6));
+        let combined = result.combine_with(other);
+        assert_eq!(combined.get_iteration_histogram(), &[1, 2, 0, 1, 1, 0, 1]);
+        assert_eq!(combined.get_n_unconverged_decodings(), 2);
+    }
+
+    #[test]
+    fn residual_errors_give_the_bit_error_rate() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result_on_n_bits(bp_result(BscResult::Success, 1), 10);
+        let mut failure = unconverged_bp_result(4);
+        failure.n_residual_errors = 3;
+        result.add_decoding_result_on_n_bits(failure, 10);
+        result.add_decoding_result_on_n_bits(ErasureResult::Failure, 10);
+        assert_eq!(result.get_bit_error_rate(), 0.15);
+        assert_eq!(result.get_average_residual_errors(), 1.5);
+
+        let mut other = SimulationResult::new();
+        other.add_decoding_result_on_n_bits(bp_result(BscResult::Failure, 2), 10);
+        let combined = result.combine_with(other);
+        assert_eq!(combined.get_bit_error_rate(), 0.1);
+        assert_eq!(combined.get_average_residual_errors(), 1.0);
+        assert_eq!(SimulationResult::new().get_average_residual_errors(), 0.0);
+        assert_eq!(SimulationResult::new().get_bit_error_rate(), 0.0);
+    }
+
+    #[test]
+    fn json_format_is_stable() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result_on_n_bits(bp_result(BscResult::Success, 2), 5);
+        result.add_decoding_result_on_n_bits(unconverged_bp_result(1), 5);
+        let result = result.stopped_by(PrecisionStoppingCondition::MaxIterations);
+        assert_eq!(
+            result.to_json(),
+            concat!(
+                r#"{"n_successes":1,"n_failures":1,"n_decoding_iterations":3,"#,
+                r#""max_n_decoding_iterations":2,"precision_stopping_condition":"MaxIterations","#,
+                r#""histogram":[0,1,1],"n_unconverged":1,"n_residual_errors":0,"#,
+                r#""n_decoded_bits":10,"importance_weights":null,"#,
+                r#""n_converged_decoding_iterations":2,"max_n_converged_decoding_iterations":2,"#,
+                r#""max_n_unconverged_decoding_iterations":1}"#
+            )
+        );
+    }
+
+    #[test]
+    fn non_finite_weights_are_written_as_null() {
+        let mut result = SimulationResult::new();
+        result.add_weighted_decoding_result(BscResult::Failure, 1e200);
+        assert!(result.to_json().contains(concat!(
+            r#""importance_weights":{"sum":1e200,"sum_of_squares":null,"#,
+            r#""failure_sum":1e200,"failure_sum_of_squares":null}"#
+        )));
+    }
+
+    fn random_result_with_rng(rng: &mut ChaCha8Rng) -> SimulationResult {
+        let mut result = SimulationResult::with_n_successes_and_failures(
+            rng.gen_range(0, 1000),
+            rng.gen_range(0, 1000),
+        );
+        for _ in 0..rng.gen_range(0, 5) {
+            let mut decoding_result = unconverged_bp_result(rng.gen_range(0, 20));
+            decoding_result.n_residual_errors = rng.gen_range(0, 10);
+            result.add_decoding_result_on_n_bits(decoding_result, 10);
+        }
+        match rng.gen_range(0, 3) {
+            0 => result.stopped_by(PrecisionStoppingCondition::TargetPrecision),
+            1 => result.stopped_by(PrecisionStoppingCondition::MaxIterations),
+            _ => result,
+        }
+    }
+
+    #[test]
+    fn posterior_mean_doesnt_favor_few_trials_without_failures() {
+        let few_trials = SimulationResult::with_n_successes_and_failures(20, 0);
+        let many_trials = SimulationResult::with_n_successes_and_failures(999_999, 1);
+        assert!(few_trials.is_better_than(&many_trials));
+
+        for prior in &[BetaPrior::jeffreys(), BetaPrior::uniform()] {
+            assert!(
+                many_trials.get_posterior_mean_failure_rate(*prior)
+                    < few_trials.get_posterior_mean_failure_rate(*prior)
+            );
+            assert!(
+                many_trials.get_failure_rate_upper_credible_bound(*prior, 0.95)
+                    < few_trials.get_failure_rate_upper_credible_bound(*prior, 0.95)
+            );
+        }
+    }
+
+    #[test]
+    fn upper_credible_bounds_are_beta_quantiles() {
+        // With a uniform prior and no failures, the posterior is Beta(1, n + 1) whose quantile
+        // at p is 1 - (1 - p)^(1 / (n + 1)).
+        let result = SimulationResult::with_n_successes_and_failures(9, 0);
+        let bound = result.get_failure_rate_upper_credible_bound(BetaPrior::uniform(), 0.9);
+        assert!((bound - (1.0 - 0.1f64.powf(0.1))).abs() < 1e-12);
+
+        // The uniform posterior with 1 failure and 99 successes is Beta(2, 100).
+        let result = SimulationResult::with_n_successes_and_failures(99, 1);
+        let bound = result.get_failure_rate_upper_credible_bound(BetaPrior::uniform(), 0.95);
+        assert!((bound - 0.04610734992488912).abs() < 1e-12);
+
+        // The Jeffreys posterior with 5 failures and 5 successes is symmetric.
+        let result = SimulationResult::with_n_successes_and_failures(5, 5);
+        let bound = result.get_failure_rate_upper_credible_bound(BetaPrior::jeffreys(), 0.5);
+        assert!((bound - 0.5).abs() < 1e-12);
+
+        assert!((get_log_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((get_log_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn non_positive_priors_panic() {
+        BetaPrior::with_alpha_and_beta(0.0, 1.0);
+    }
+
+    #[test]
+    fn weighted_results_estimate_the_failure_rate_with_the_weights() {
+        let mut result = SimulationResult::new();
+        result.add_weighted_decoding_result(BscResult::Failure, 0.5);
+        result.add_weighted_decoding_result(BscResult::Success, 2.0);
+        result.add_weighted_decoding_result(BscResult::Failure, 0.1);
+        result.add_weighted_decoding_result(BscResult::Success, 1.4);
+        assert!(result.is_weighted());
+        assert_eq!(result.get_n_failures(), 2);
+        assert!((result.get_failure_rate() - 0.15).abs() < 1e-12);
+        assert!((result.get_success_rate() - 0.85).abs() < 1e-12);
+        assert!((result.get_effective_sample_size() - 16.0 / 6.22).abs() < 1e-12);
+
+        let variance: f64 = (0.26 / 4.0 - 0.15 * 0.15) / 4.0;
+        let relative_error = variance.sqrt() / 0.15;
+        assert!((result.get_relative_standard_error() - relative_error).abs() < 1e-12);
+        let (lower, upper) = result.get_failure_rate_confidence_interval(0.95);
+        assert_eq!(lower, 0.0);
+        assert!((upper - 0.15 - 1.959964 * variance.sqrt()).abs() < 1e-6);
+
+        // Unweighted results count with weight 1.
+        let unweighted = SimulationResult::with_n_successes_and_failures(3, 1);
+        let combined = result.combine(&unweighted);
+        assert!((combined.get_failure_rate() - 1.6 / 8.0).abs() < 1e-12);
+        assert_eq!(combined, unweighted.combine(&result));
+        assert!(!unweighted.is_weighted());
+        assert_eq!(unweighted.get_effective_sample_size(), 4.0);
+    }
+
+    #[test]
+    fn combining_is_associative_and_commutative() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        for _ in 0..100 {
+            let first = random_result_with_rng(&mut rng);
+            let second = random_result_with_rng(&mut rng);
+            let third = random_result_with_rng(&mut rng);
+
+            assert_eq!(first.combine(&second), second.combine(&first));
+            assert_eq!(
+                first.combine(&second).combine(&third),
+                first.combine(&second.combine(&third))
+            );
+            assert_eq!(first.combine(&SimulationResult::empty()), first);
+            assert_eq!(SimulationResult::empty().combine(&first), first);
+        }
+    }
+
+    #[test]
+    fn sums_combine_all_the_results() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let results: Vec<SimulationResult> =
+            (0..10).map(|_| random_result_with_rng(&mut rng)).collect();
+
+        let expected = results
+            .iter()
+            .fold(SimulationResult::empty(), |sum, result| sum.combine(result));
+        assert_eq!(results.iter().sum::<SimulationResult>(), expected);
+        assert_eq!(results.into_iter().sum::<SimulationResult>(), expected);
+        assert_eq!(
+            Vec::<SimulationResult>::new().into_iter().sum::<SimulationResult>(),
+            SimulationResult::empty()
+        );
+        assert_ne!(SimulationResult::empty(), SimulationResult::worse_result());
+    }
+
+    #[test]
+    fn percentiles_are_read_from_the_histogram() {
+        let mut result = SimulationResult::new();
+        for n_iterations in 1..=10 {
+            result.add_decoding_result(bp_result(BscResult::Success, n_iterations));
+        }
+        assert_eq!(result.get_histogram_percentile(0.0), Some(1));
+        assert_eq!(result.get_histogram_percentile(0.5), Some(5));
+        assert_eq!(result.get_histogram_percentile(0.55), Some(6));
+        assert_eq!(result.get_histogram_percentile(1.0), Some(10));
+        assert_eq!(SimulationResult::new().get_histogram_percentile(0.5), None);
+        assert_eq!(SimulationResult::new().get_histogram_mean(), None);
+    }
+
+    #[test]
+    fn iterations_are_accumulated_and_combined() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result(bp_result(BscResult::Success, 2));
+        result.add_decoding_result(bp_result(BscResult::Failure, 5));
+        assert_eq!(result.get_mean_n_decoding_iterations(), 3.5);
+        assert_eq!(result.get_max_n_decoding_iterations(), 5);
+
+        let mut other = SimulationResult::new();
+        other.add_decoding_result(bp_result(BscResult::Success, 8));
+        let combined = result.combine_with(other);
+        assert_eq!(combined.get_n_iterations(), 3);
+        assert_eq!(combined.get_mean_n_decoding_iterations(), 5.0);
+        assert_eq!(combined.get_max_n_decoding_iterations(), 8);
+    }
+
+    #[test]
+    fn iterations_are_split_between_converged_and_unconverged_decodings() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result(bp_result(BscResult::Success, 2));
+        result.add_decoding_result(bp_result(BscResult::Failure, 4));
+        result.add_decoding_result(unconverged_bp_result(10));
+        assert_eq!(result.get_mean_n_decoding_iterations_of_converged_decodings(), 3.0);
+        assert_eq!(result.get_max_n_decoding_iterations_of_converged_decodings(), 4);
+        assert_eq!(result.get_mean_n_decoding_iterations_of_unconverged_decodings(), 10.0);
+        assert_eq!(result.get_max_n_decoding_iterations_of_unconverged_decodings(), 10);
+
+        let mut other = SimulationResult::new();
+        other.add_decoding_result(bp_result(BscResult::Success, 6));
+        other.add_decoding_result(unconverged_bp_result(8));
+        let combined = result.combine(&other);
+        assert_eq!(combined.get_mean_n_decoding_iterations_of_converged_decodings(), 4.0);
+        assert_eq!(combined.get_max_n_decoding_iterations_of_converged_decodings(), 6);
+        assert_eq!(combined.get_mean_n_decoding_iterations_of_unconverged_decodings(), 9.0);
+        assert_eq!(combined.get_max_n_decoding_iterations_of_unconverged_decodings(), 10);
+        assert_eq!(combined.get_mean_n_decoding_iterations(), 6.0);
+        assert_eq!(combined, other.combine(&result));
+
+        let empty = SimulationResult::new();
+        assert_eq!(empty.get_mean_n_decoding_iterations_of_converged_decodings(), 0.0);
+        assert_eq!(empty.get_mean_n_decoding_iterations_of_unconverged_decodings(), 0.0);
+    }
+
+    // The decoder corrects an error on the last bit of the Hamming code with one iteration and
+    // an error on the first bit with two iterations, but can't converge on two errors with a
+    // single iteration.
+    #[test]
+    fn simulated_iterations_are_split_between_converged_and_unconverged_decodings() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut decoder = BscDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(10)
+            .for_code(code.clone());
+        let mut capped_decoder = BscDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(1)
+            .for_code(code);
+
+        let mut result = SimulationResult::new();
+        for _ in 0..3 {
+            result.add_decoding_result(decoder.decode(&vec![6]));
+        }
+        result.add_decoding_result(decoder.decode(&vec![0]));
+        result.add_decoding_result(capped_decoder.decode(&vec![0, 2]));
+        assert_eq!(result.get_n_unconverged_decodings(), 1);
+        assert_eq!(result.get_mean_n_decoding_iterations_of_converged_decodings(), 1.25);
+        assert_eq!(result.get_max_n_decoding_iterations_of_converged_decodings(), 2);
+        assert_eq!(result.get_mean_n_decoding_iterations_of_unconverged_decodings(), 1.0);
+        assert_eq!(result.get_max_n_decoding_iterations_of_unconverged_decodings(), 1);
+        assert_eq!(result.get_mean_n_decoding_iterations(), 1.2);
+    }
+
+    #[test]
+    fn non_iterative_decoders_have_no_iterations() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result(ErasureResult::Success);
+        result.add_decoding_result(ErasureResult::Failure);
+        assert_eq!(result.get_mean_n_decoding_iterations(), 0.0);
+        assert_eq!(result.get_max_n_decoding_iterations(), 0);
+        assert!(result.get_iteration_histogram().is_empty());
+        assert_eq!(result.get_n_unconverged_decodings(), 0);
+        assert_eq!(result, SimulationResult::with_n_successes_and_failures(1, 1));
+    }
+
+    fn assert_interval_is_close(interval: (f64, f64), expected: (f64, f64)) {
+        assert!((interval.0 - expected.0).abs() < 1e-4, "{:?}", interval);
+        assert!((interval.1 - expected.1).abs() < 1e-4, "{:?}", interval);
+    }
+
+    #[test]
+    fn confidence_intervals_are_wilson_intervals() {
+        let result = SimulationResult::with_n_successes_and_failures(5, 5);
+        assert_interval_is_close(
+            result.get_failure_rate_confidence_interval(0.95),
+            (0.2366, 0.7634),
+        );
+
+        let result = SimulationResult::with_n_successes_and_failures(49, 1);
+        assert_interval_is_close(
+            result.get_failure_rate_confidence_interval(0.95),
+            (0.0035, 0.1050),
+        );
+        assert_interval_is_close(
+            result.get_failure_rate_confidence_interval(0.99),
+            (0.0024, 0.1501),
+        );
+    }
+
+    #[test]
+    fn intervals_without_failures_or_successes_are_bounded() {
+        let result = SimulationResult::with_n_successes_and_failures(10, 0);
+        assert_interval_is_close(
+            result.get_failure_rate_confidence_interval(0.95),
+            (0.0, 0.2775),
+        );
+
+        let result = SimulationResult::with_n_successes_and_failures(0, 10);
+        assert_interval_is_close(
+            result.get_failure_rate_confidence_interval(0.95),
+            (0.7225, 1.0),
+        );
+
+        let result = SimulationResult::new();
+        assert_eq!(result.get_failure_rate_confidence_interval(0.95), (0.0, 1.0));
+    }
+
+    #[test]
+    fn more_events_give_narrower_intervals() {
+        let small = SimulationResult::with_n_successes_and_failures(49, 1);
+        let large = SimulationResult::with_n_successes_and_failures(49_000, 1_000);
+        assert_eq!(small.get_failure_rate(), large.get_failure_rate());
+
+        let (small_lower, small_upper) = small.get_failure_rate_confidence_interval(0.95);
+        let (large_lower, large_upper) = large.get_failure_rate_confidence_interval(0.95);
+        assert!(small_lower < large_lower && large_upper < small_upper);
+    }
+
+    #[test]
+    fn overlapping_intervals_are_not_significantly_better() {
+        let good = SimulationResult::with_n_successes_and_failures(9_900, 100);
+        let bad = SimulationResult::with_n_successes_and_failures(9_800, 200);
+        assert!(good.is_significantly_better_than(&bad, 0.99));
+        assert!(!bad.is_significantly_better_than(&good, 0.99));
+        assert!(!good.is_significantly_better_than(&good, 0.5));
+    }
+
+    #[test]
+    fn standard_normal_quantiles_match_the_tables() {
+        assert!(get_standard_normal_quantile(0.5).abs() < 1e-9);
+        assert!((get_standard_normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((get_standard_normal_quantile(0.995) - 2.575829).abs() < 1e-6);
+        assert!((get_standard_normal_quantile(0.01) + 2.326348).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_confidences_panic() {
+        let result = SimulationResult::with_n_successes_and_failures(1, 1);
+        result.get_failure_rate_confidence_interval(1.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let result = SimulationResult::with_n_successes_and_failures(9, 16);
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"n_successes":9,"n_failures":16,"n_decoding_iterations":0,"#,
+                r#""max_n_decoding_iterations":0,"precision_stopping_condition":null,"#,
+                r#""histogram":[],"n_unconverged":0,"n_residual_errors":0,"n_decoded_bits":0,"#,
+                r#""importance_weights":null,"n_converged_decoding_iterations":0,"#,
+                r#""max_n_converged_decoding_iterations":0,"#,
+                r#""max_n_unconverged_decoding_iterations":0}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<SimulationResult>(&json).unwrap(), result);
+        assert_eq!(result.to_json(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn exported_json_is_the_serde_json() {
+        let mut result = SimulationResult::new();
+        result.add_decoding_result_on_n_bits(bp_result(BscResult::Success, 2), 5);
+        result.add_decoding_result_on_n_bits(unconverged_bp_result(3), 5);
+        let result = result.stopped_by(PrecisionStoppingCondition::TargetPrecision);
+        assert_eq!(result.to_json(), serde_json::to_string(&result).unwrap());
+
+        let mut result = SimulationResult::new();
+        result.add_weighted_decoding_result(BscResult::Failure, 1e-7);
+        result.add_weighted_decoding_result(BscResult::Success, 0.3);
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(result.to_json(), json);
+        assert_eq!(serde_json::from_str::<SimulationResult>(&json).unwrap(), result);
+
+        let mut result = SimulationResult::new();
+        result.add_weighted_decoding_result(BscResult::Failure, f64::INFINITY);
+        assert_eq!(result.to_json(), serde_json::to_string(&result).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_without_iterations_can_be_read() {
+        let json = r#"{"n_successes":9,"n_failures":16}"#;
+        assert_eq!(
+            serde_json::from_str::<SimulationResult>(json).unwrap(),
+            SimulationResult::with_n_successes_and_failures(9, 16)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bincode_round_trip() {
+        let mut result = SimulationResult::with_n_successes_and_failures(9, 16);
+        result.add_decoding_result(bp_result(BscResult::Success, 4));
+        let bytes = bincode::serialize(&result).unwrap();
+        assert_eq!(bincode::deserialize::<SimulationResult>(&bytes).unwrap(), result);
+    }
+}
@@ -0,0 +1,236 @@
+//! Simulation of a decoder with errors sampled from a noise model.
+
+use super::duration_simulator::DurationSimulator;
+use super::n_events_simulator::NEventsSimulator;
+use super::n_iterations_simulator::{
+    simulate_n_iterations_in_parallel_with_rng, NIterationsSimulator,
+};
+use super::precision_simulator::PrecisionSimulator;
+use super::{Decoder, NoiseModel, SimulationResult};
+use rand::{thread_rng, Rng};
+use std::time::Duration;
+
+/// A decoder together with the noise model sampling the errors to decode.
+///
+/// The simulations are the same as the simulations of the decoder, except that the errors are
+/// sampled by the noise model on the bits of the code of the decoder. Thus, a new channel only
+/// needs a new noise model.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// let decoder = BscDecoder::with_crossover_prob(0.05).for_code(code);
+/// let mut simulator = decoder.with_noise(Bsc::with_crossover_prob(0.1));
+///
+/// let result = simulator.simulate_n_iterations(100);
+/// assert_eq!(result.get_n_iterations(), 100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulator<D, N> {
+    decoder: D,
+    noise: N,
+}
+
+impl<D, N> Simulator<D, N>
+where
+    D: Decoder,
+    N: NoiseModel<Error = D::Error>,
+{
+    pub(super) fn from(decoder: D, noise: N) -> Self {
+        Self { decoder, noise }
+    }
+
+    /// Returns the decoder of `self`.
+    pub fn get_decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns the noise model of `self`.
+    pub fn get_noise(&self) -> &N {
+        &self.noise
+    }
+
+    /// Returns the decoder of `self` and drops the noise model.
+    pub fn into_decoder(self) -> D {
+        self.decoder
+    }
+
+    /// Samples an error from the noise model with random number generator `rng` and decodes it.
+    pub fn decode_random_error_with_rng<R: Rng>(&mut self, rng: &mut R) -> D::Result {
+        let error = self
+            .noise
+            .sample_error_with_rng(self.decoder.get_n_bits(), rng);
+        self.decoder.decode(&error)
+    }
+
+    /// Simulates decoding `n_iterations` random errors from the noise model with random number
+    /// generator `rng`.
+    pub fn simulate_n_iterations_with_rng<R: Rng>(
+        &mut self,
+        n_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult {
+        let noise = &self.noise;
+        NIterationsSimulator::from(&mut self.decoder, |decoder: &D, rng: &mut _| {
+            noise.sample_error_with_rng(decoder.get_n_bits(), rng)
+        })
+        .simulate_n_iterations_with_rng(n_iterations, rng)
+        .get_result()
+    }
+
+    /// Simulates decoding `n_iterations` random errors from the noise model with the thread
+    /// random number generator.
+    pub fn simulate_n_iterations(&mut self, n_iterations: usize) -> SimulationResult {
+        self.simulate_n_iterations_with_rng(n_iterations, &mut thread_rng())
+    }
+
+    /// Simulates decoding `n_iterations` random errors from the noise model using clones of the
+    /// decoder on all threads with random number generator `rng`.
+    ///
+    /// The result only depends on `rng` and not on the number of threads.
+    pub fn simulate_n_iterations_in_parallel_with_rng<R: Rng>(
+        &self,
+        n_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult
+    where
+        D: Clone,
+    {
+        let noise = &self.noise;
+        simulate_n_iterations_in_parallel_with_rng(
+            &self.decoder,
+            |decoder: &D, rng: &mut _| noise.sample_error_with_rng(decoder.get_n_bits(), rng),
+            n_iterations,
+            rng,
+        )
+    }
+
+    /// Simulates decoding `n_iterations` random errors from the noise model using clones of the
+    /// decoder on all threads with the thread random number generator.
+    pub fn simulate_n_iterations_in_parallel(&self, n_iterations: usize) -> SimulationResult
+    where
+        D: Clone,
+    {
+        self.simulate_n_iterations_in_parallel_with_rng(n_iterations, &mut thread_rng())
+    }
+
+    /// Simulates decoding random errors from the noise model until `n_events` successes and
+    /// `n_events` failures are found with random number generator `rng`.
+    pub fn simulate_until_n_events_are_found_with_rng<R: Rng>(
+        &mut self,
+        n_events: usize,
+        rng: &mut R,
+    ) -> SimulationResult {
+        let noise = &self.noise;
+        NEventsSimulator::from(&mut self.decoder, |decoder: &D, rng: &mut _| {
+            noise.sample_error_with_rng(decoder.get_n_bits(), rng)
+        })
+        .simulate_until_n_events_are_found_with_rng(n_events, rng)
+        .get_result()
+    }
+
+    /// Simulates decoding random errors from the noise model until `n_events` successes and
+    /// `n_events` failures are found with the thread random number generator.
+    pub fn simulate_until_n_events_are_found(&mut self, n_events: usize) -> SimulationResult {
+        self.simulate_until_n_events_are_found_with_rng(n_events, &mut thread_rng())
+    }
+
+    /// Simulates decoding random errors from the noise model until the relative standard error
+    /// of the failure rate is at most `relative_error` or `max_iterations` errors are decoded
+    /// with random number generator `rng`.
+    pub fn simulate_until_precision_with_rng<R: Rng>(
+        &mut self,
+        relative_error: f64,
+        max_iterations: usize,
+        rng: &mut R,
+    ) -> SimulationResult {
+        let noise = &self.noise;
+        PrecisionSimulator::from(&mut self.decoder, |decoder: &D, rng: &mut _| {
+            noise.sample_error_with_rng(decoder.get_n_bits(), rng)
+        })
+        .simulate_until_precision_with_rng(relative_error, max_iterations, rng)
+        .get_result()
+    }
+
+    /// Simulates decoding random errors from the noise model until the relative standard error
+    /// of the failure rate is at most `relative_error` or `max_iterations` errors are decoded
+    /// with the thread random number generator.
+    pub fn simulate_until_precision(
+        &mut self,
+        relative_error: f64,
+        max_iterations: usize,
+    ) -> SimulationResult {
+        self.simulate_until_precision_with_rng(relative_error, max_iterations, &mut thread_rng())
+    }
+
+    /// Simulates decoding random errors from the noise model for the given time `budget` with
+    /// random number generator `rng`.
+    pub fn simulate_for_duration_with_rng<R: Rng>(
+        &mut self,
+        budget: Duration,
+        rng: &mut R,
+    ) -> SimulationResult {
+        let noise = &self.noise;
+        DurationSimulator::from(&mut self.decoder, |decoder: &D, rng: &mut _| {
+            noise.sample_error_with_rng(decoder.get_n_bits(), rng)
+        })
+        .simulate_for_duration_with_rng(budget, rng)
+        .get_result()
+    }
+
+    /// Simulates decoding random errors from the noise model for the given time `budget` with
+    /// the thread random number generator.
+    pub fn simulate_for_duration(&mut self, budget: Duration) -> SimulationResult {
+        self.simulate_for_duration_with_rng(budget, &mut thread_rng())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DecodingResult, ErasureDecoder, ParityCheckMatrix, PerBitErasure, UniformErasure};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    #[test]
+    fn noise_model_gives_the_same_simulation_as_the_decoder() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let mut decoder = ErasureDecoder::with_prob(0.3).for_code(hamming_code());
+        let mut simulator = ErasureDecoder::with_prob(0.9)
+            .for_code(hamming_code())
+            .with_noise(UniformErasure::with_prob(0.3));
+
+        assert_eq!(
+            simulator.simulate_n_iterations_with_rng(300, &mut rng.clone()),
+            decoder.simulate_n_iterations_with_rng(300, &mut rng.clone())
+        );
+        assert_eq!(
+            simulator.simulate_until_n_events_are_found_with_rng(10, &mut rng.clone()),
+            decoder.simulate_until_n_events_are_found_with_rng(10, &mut rng.clone())
+        );
+    }
+
+    #[test]
+    fn errors_are_sampled_from_the_noise_model() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let noise = PerBitErasure::with_probs(vec![0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+        let mut simulator = ErasureDecoder::with_prob(0.0)
+            .for_code(hamming_code())
+            .with_noise(noise);
+
+        assert!(simulator.decode_random_error_with_rng(&mut rng).is_failure());
+        let result = simulator.simulate_n_iterations_with_rng(20, &mut rng);
+        assert_eq!(result.get_n_failures(), 20);
+        assert_eq!(simulator.into_decoder().take_code(), hamming_code());
+    }
+}
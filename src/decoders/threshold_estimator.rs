@@ -0,0 +1,337 @@
+//! Estimation of the erasure probability at which a code reaches a target failure rate.
+
+use super::{Decoder, ErasureDecoder, ErasureStrategy, PerformanceCurve};
+use crate::{CodeGenerator, ParityCheckMatrix, SeedSequence};
+use rand::{thread_rng, Rng};
+
+/// The erasure probability at which a code reaches a target failure rate, together with the
+/// simulations used to find it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdEstimate {
+    threshold: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+    curve: PerformanceCurve,
+}
+
+impl ThresholdEstimate {
+    /// Returns the middle of the final bracket of the bisection.
+    pub fn get_threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the band of erasure probabilities that contains the threshold according to the
+    /// confidence intervals of the simulated points.
+    ///
+    /// The lower end is the largest simulated probability whose confidence interval is below
+    /// the target failure rate and the upper end is the smallest one whose confidence interval
+    /// is above it. The ends of the initial bracket are used if there are no such points.
+    pub fn get_uncertainty_band(&self) -> (f64, f64) {
+        (self.lower_bound, self.upper_bound)
+    }
+
+    /// Returns the simulation results of the bisection points sorted by probability.
+    pub fn get_curve(&self) -> &PerformanceCurve {
+        &self.curve
+    }
+}
+
+/// A bisection on the erasure probability to find where an erasure decoder reaches a target
+/// failure rate.
+///
+/// Each step simulates the middle of the current bracket with
+/// `simulate_until_n_events_are_found` and keeps the half of the bracket where the failure
+/// rate crosses the target, until the width of the bracket is below the tolerance. The failure
+/// rate is assumed to increase with the erasure probability. The points use independent random
+/// number generators of a `SeedSequence` drawn from the given generator, so the estimate only
+/// depends on it.
+///
+/// A point is simulated until some failures and successes are found, which takes a long time
+/// if the target failure rate is tiny or close to 1.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// # use rand::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// // The repetition code fails when all its bits are erased.
+/// let code = ParityCheckMatrix::repetition_code_with_n_bits(2);
+/// let estimator = ThresholdEstimator::with_target_failure_rate(0.25)
+///     .with_n_events_per_point(200)
+///     .with_tolerance(0.01);
+///
+/// let mut rng = ChaCha8Rng::seed_from_u64(123);
+/// let estimate = estimator.estimate_for_code_with_rng(&code, &mut rng);
+///
+/// assert!((estimate.get_threshold() - 0.5).abs() < 0.05);
+/// assert_eq!(estimate.get_curve().get_n_points(), 7);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThresholdEstimator {
+    target_failure_rate: f64,
+    low: f64,
+    high: f64,
+    n_events_per_point: usize,
+    tolerance: f64,
+    confidence: f64,
+    strategy: ErasureStrategy,
+}
+
+impl ThresholdEstimator {
+    /// Creates an estimator of the erasure probability at which the failure rate is
+    /// `target_failure_rate`.
+    ///
+    /// If not specified, the bracket is from 0.0 to 1.0, the points are simulated until 100
+    /// failures and successes are found, the tolerance is 0.01, the confidence of the
+    /// intervals is 0.95 and the erasures are decoded with maximum likelihood.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `target_failure_rate` is not strictly between 0.0 and 1.0.
+    pub fn with_target_failure_rate(target_failure_rate: f64) -> Self {
+        if target_failure_rate <= 0.0 || target_failure_rate >= 1.0 {
+            panic!("invalid rate");
+        }
+        Self {
+            target_failure_rate,
+            low: 0.0,
+            high: 1.0,
+            n_events_per_point: 100,
+            tolerance: 0.01,
+            confidence: 0.95,
+            strategy: ErasureStrategy::MaximumLikelihood,
+        }
+    }
+
+    /// Sets the bracket of erasure probabilities from `low` to `high` containing the threshold.
+    ///
+    /// The ends of the bracket are not simulated. If the threshold is outside the bracket, the
+    /// estimate converges to the closest end.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `low` is not smaller than `high` or if they are not between 0.0 and 1.0.
+    pub fn with_bracket(mut self, low: f64, high: f64) -> Self {
+        if low < 0.0 || high > 1.0 || low >= high {
+            panic!("invalid probability");
+        }
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    /// Sets the number of failures and successes to find at each point.
+    pub fn with_n_events_per_point(mut self, n_events: usize) -> Self {
+        self.n_events_per_point = n_events;
+        self
+    }
+
+    /// Sets the width of the bracket below which the bisection stops.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `tolerance` is not positive.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        if tolerance <= 0.0 {
+            panic!("invalid tolerance");
+        }
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the confidence of the intervals of the failure rates giving the uncertainty band.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `confidence` is not strictly between 0.0 and 1.0.
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        if confidence <= 0.0 || confidence >= 1.0 {
+            panic!("invalid confidence");
+        }
+        self.confidence = confidence;
+        self
+    }
+
+    /// Sets the `strategy` used to recover the erased bits.
+    pub fn with_strategy(mut self, strategy: ErasureStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Estimates the threshold of `code` with random number generator `rng`.
+    pub fn estimate_for_code_with_rng<R: Rng>(
+        &self,
+        code: &ParityCheckMatrix,
+        rng: &mut R,
+    ) -> ThresholdEstimate {
+        let decoder = ErasureDecoder::with_prob(self.low)
+            .with_strategy(self.strategy)
+            .for_code(code.clone());
+        let seeds = SeedSequence::from_rng(rng);
+        let (mut low, mut high) = (self.low, self.high);
+        let (mut lower_bound, mut upper_bound) = (low, high);
+        let mut points = Vec::new();
+        while high - low >= self.tolerance {
+            let prob = (low + high) / 2.0;
+            let mut rng = seeds.get_rng(points.len() as u64);
+            let result = decoder
+                .with_uniform_prob(prob)
+                .simulate_until_n_events_are_found_with_rng(self.n_events_per_point, &mut rng);
+            if result.get_failure_rate() < self.target_failure_rate {
+                low = prob;
+            } else {
+                high = prob;
+            }
+            // The bisection only simulates points inside the current band, so these are the
+            // closest points to the threshold.
+            let (lower, upper) = result.get_failure_rate_confidence_interval(self.confidence);
+            if upper < self.target_failure_rate {
+                lower_bound = prob;
+            } else if lower > self.target_failure_rate {
+                upper_bound = prob;
+            }
+            points.push((prob, result));
+        }
+        ThresholdEstimate {
+            threshold: (low + high) / 2.0,
+            lower_bound,
+            upper_bound,
+            curve: PerformanceCurve::with_points(points),
+        }
+    }
+
+    /// Estimates the threshold of `code` with the thread random number generator.
+    pub fn estimate_for_code(&self, code: &ParityCheckMatrix) -> ThresholdEstimate {
+        self.estimate_for_code_with_rng(code, &mut thread_rng())
+    }
+
+    /// Estimates the threshold of a code sampled from `generator` with random number generator
+    /// `rng`, which is used for both the code and the simulations.
+    pub fn estimate_for_generator_with_rng<G, R>(
+        &self,
+        generator: &G,
+        rng: &mut R,
+    ) -> ThresholdEstimate
+    where
+        G: CodeGenerator,
+        R: Rng,
+    {
+        let code = generator.generate_with_rng(rng);
+        self.estimate_for_code_with_rng(&code, rng)
+    }
+
+    /// Estimates the threshold of a code sampled from `generator` with the thread random number
+    /// generator.
+    pub fn estimate_for_generator<G: CodeGenerator>(&self, generator: &G) -> ThresholdEstimate {
+        self.estimate_for_generator_with_rng(generator, &mut thread_rng())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DecodingResult, RegularLDPCCodeGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // A repetition code of n bits fails with probability p^n, so the threshold of the target
+    // rate r is r^(1 / n).
+    #[test]
+    fn threshold_of_repetition_code_is_in_the_band() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let expected = 0.1_f64.powf(1.0 / 3.0);
+        let estimator = ThresholdEstimator::with_target_failure_rate(0.1)
+            .with_n_events_per_point(200)
+            .with_tolerance(0.001);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let estimate = estimator.estimate_for_code_with_rng(&code, &mut rng);
+
+        let (lower, upper) = estimate.get_uncertainty_band();
+        assert!(lower <= estimate.get_threshold() && estimate.get_threshold() <= upper);
+        assert!(lower < expected && expected < upper);
+        assert!(upper - lower < 0.1);
+        assert!((estimate.get_threshold() - expected).abs() < 0.02);
+        assert_eq!(estimate.get_curve().get_n_points(), 10);
+    }
+
+    #[test]
+    fn estimates_are_reproducible() {
+        let generator = RegularLDPCCodeGenerator::new(3, 6, 2, 4);
+        let estimator = ThresholdEstimator::with_target_failure_rate(0.5)
+            .with_bracket(0.1, 0.9)
+            .with_n_events_per_point(20);
+        let first = estimator
+            .estimate_for_generator_with_rng(&generator, &mut ChaCha8Rng::seed_from_u64(123));
+        let second = estimator
+            .estimate_for_generator_with_rng(&generator, &mut ChaCha8Rng::seed_from_u64(123));
+        assert_eq!(first, second);
+        assert!(first
+            .get_curve()
+            .get_probs()
+            .iter()
+            .all(|prob| 0.1 < *prob && *prob < 0.9));
+    }
+
+    // The exact failure rate sums the probabilities of the erasures that the decoder doesn't
+    // recover and its threshold is found by a bisection on the exact rate.
+    fn get_exact_threshold_of(decoder: &mut ErasureDecoder, target_failure_rate: f64) -> f64 {
+        let n_bits = decoder.get_n_bits();
+        let unrecovered_weights: Vec<usize> = (0..1_usize << n_bits)
+            .map(|index| (0..n_bits).filter(|bit| index >> bit & 1 == 1).collect())
+            .filter(|erasure: &Vec<usize>| decoder.decode(erasure).is_failure())
+            .map(|erasure| erasure.len())
+            .collect();
+        let get_failure_rate_at = |prob: f64| -> f64 {
+            unrecovered_weights
+                .iter()
+                .map(|weight| {
+                    prob.powi(*weight as i32) * (1.0 - prob).powi((n_bits - weight) as i32)
+                })
+                .sum()
+        };
+        let (mut low, mut high) = (0.0, 1.0);
+        while high - low > 1e-9 {
+            let prob = (low + high) / 2.0;
+            if get_failure_rate_at(prob) < target_failure_rate {
+                low = prob;
+            } else {
+                high = prob;
+            }
+        }
+        low
+    }
+
+    #[test]
+    fn exact_thresholds_of_both_strategies_are_in_the_bands() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        for strategy in [ErasureStrategy::MaximumLikelihood, ErasureStrategy::Peeling].iter() {
+            let mut decoder = ErasureDecoder::with_prob(0.1)
+                .with_strategy(*strategy)
+                .for_code(code.clone());
+            let expected = get_exact_threshold_of(&mut decoder, 0.2);
+            let estimate = ThresholdEstimator::with_target_failure_rate(0.2)
+                .with_n_events_per_point(200)
+                .with_tolerance(0.005)
+                .with_strategy(*strategy)
+                .estimate_for_code_with_rng(&code, &mut rng);
+
+            let (lower, upper) = estimate.get_uncertainty_band();
+            assert!(lower < expected && expected < upper);
+            assert!((estimate.get_threshold() - expected).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_bracket_panics() {
+        ThresholdEstimator::with_target_failure_rate(0.5).with_bracket(0.4, 0.4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn target_failure_rate_of_one_panics() {
+        ThresholdEstimator::with_target_failure_rate(1.0);
+    }
+}
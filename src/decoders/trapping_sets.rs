@@ -0,0 +1,293 @@
+//! A search for the small trapping sets of belief propagation decoders.
+
+use super::SoftDecoder;
+use crate::ParityCheckMatrix;
+use crate::GF2;
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A set of bits on which a belief propagation decoder got stuck.
+///
+/// An `(a, b)` trapping set has `a` bits and `b` checks connected to an odd number of them,
+/// which are the unsatisfied checks of the hard decision. The small trapping sets with few
+/// unsatisfied checks cause the error floor of belief propagation decoders.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrappingSet {
+    bits: Vec<usize>,
+    n_unsatisfied_checks: usize,
+}
+
+impl TrappingSet {
+    /// Returns the sorted bits of the set.
+    pub fn get_bits(&self) -> &[usize] {
+        &self.bits
+    }
+
+    /// Returns the number of bits of the set.
+    pub fn get_size(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns the number of checks connected to an odd number of bits of the set.
+    pub fn get_n_unsatisfied_checks(&self) -> usize {
+        self.n_unsatisfied_checks
+    }
+
+    /// Returns the `(a, b)` class of the set where `a` is the number of bits and `b` the
+    /// number of unsatisfied checks.
+    pub fn get_class(&self) -> (usize, usize) {
+        (self.get_size(), self.n_unsatisfied_checks)
+    }
+}
+
+/// A search for the trapping sets of a belief propagation decoder for the binary symmetric
+/// channel.
+///
+/// The decoder is given small error patterns grown along the Tanner graph of the code. If the
+/// code has 4-cycles, the patterns of more than one bit start from the two bits of a random
+/// 4-cycle, since the trapping sets are made of short cycles. Otherwise, they start from a
+/// random bit. Each pattern is extended with random bits sharing a check with the previous
+/// ones, up to a random size. When the decoder doesn't converge, the bits of its hard decision
+/// form a trapping set which is recorded if its class is small enough.
+///
+/// This finds the trapping sets that dominate the error floor without simulating the very
+/// small error rates where they appear.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// # use rand::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// let decoder = BscDecoder::with_crossover_prob(0.1)
+///     .with_max_iterations(5)
+///     .for_code(code);
+/// let finder = TrappingSetFinder::with_max_class(4, 2).using(decoder);
+///
+/// let mut rng = ChaCha8Rng::seed_from_u64(123);
+/// for (set, multiplicity) in finder.find_with_rng(100, &mut rng) {
+///     assert!(set.get_size() <= 4);
+///     assert!(set.get_n_unsatisfied_checks() <= 2);
+///     assert!(multiplicity > 0);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrappingSetFinder<D> {
+    decoder: D,
+    max_size: usize,
+    max_n_unsatisfied_checks: usize,
+    code: Arc<ParityCheckMatrix>,
+    cycles: Vec<(usize, usize, usize, usize)>,
+}
+
+impl TrappingSetFinder<()> {
+    /// Creates a search recording the `(a, b)` trapping sets with at most `max_size` bits and
+    /// at most `max_n_unsatisfied_checks` unsatisfied checks.
+    ///
+    /// The error patterns given to the decoder have at most `max_size` bits.
+    pub fn with_max_class(max_size: usize, max_n_unsatisfied_checks: usize) -> Self {
+        Self {
+            decoder: (),
+            max_size,
+            max_n_unsatisfied_checks,
+            code: Arc::new(ParityCheckMatrix::new()),
+            cycles: Vec::new(),
+        }
+    }
+
+    /// Searches the trapping sets of `decoder` on its code.
+    pub fn using<D>(self, mut decoder: D) -> TrappingSetFinder<D>
+    where
+        D: SoftDecoder<Code = ParityCheckMatrix>,
+    {
+        let code = Arc::new(decoder.take_code());
+        let cycles = code.get_4_cycles();
+        TrappingSetFinder {
+            decoder: decoder.for_shared_code(Arc::clone(&code)),
+            max_size: self.max_size,
+            max_n_unsatisfied_checks: self.max_n_unsatisfied_checks,
+            code,
+            cycles,
+        }
+    }
+}
+
+impl<D> TrappingSetFinder<D>
+where
+    D: SoftDecoder<Code = ParityCheckMatrix>,
+{
+    /// Decodes `n_seeds` random error patterns and returns the distinct trapping sets found
+    /// together with the number of patterns that ended in each of them.
+    ///
+    /// The sets are sorted by class and then by bits.
+    pub fn find_with_rng<R: Rng>(&self, n_seeds: usize, rng: &mut R) -> Vec<(TrappingSet, u64)> {
+        let mut multiplicities: HashMap<TrappingSet, u64> = HashMap::new();
+        if self.max_size == 0 || self.code.get_n_bits() == 0 {
+            return Vec::new();
+        }
+        for _ in 0..n_seeds {
+            let seed = self.get_random_seed_with_rng(rng);
+            if let Some(set) = self.get_trapping_set_reached_from(&seed) {
+                *multiplicities.entry(set).or_insert(0) += 1;
+            }
+        }
+        let mut sets: Vec<(TrappingSet, u64)> = multiplicities.into_iter().collect();
+        sets.sort_by(|(set, _), (other, _)| {
+            set.get_class()
+                .cmp(&other.get_class())
+                .then_with(|| set.bits.cmp(&other.bits))
+        });
+        sets
+    }
+
+    /// Decodes `n_seeds` random error patterns and returns the distinct trapping sets found
+    /// together with the number of patterns that ended in each of them using the thread rng.
+    pub fn find(&self, n_seeds: usize) -> Vec<(TrappingSet, u64)> {
+        self.find_with_rng(n_seeds, &mut thread_rng())
+    }
+
+    fn get_random_seed_with_rng<R: Rng>(&self, rng: &mut R) -> Vec<usize> {
+        let size = rng.gen_range(1, self.max_size + 1);
+        let mut seed = if size == 1 || self.cycles.is_empty() {
+            vec![rng.gen_range(0, self.code.get_n_bits())]
+        } else {
+            let (_, _, bit_0, bit_1) = self.cycles[rng.gen_range(0, self.cycles.len())];
+            vec![bit_0, bit_1]
+        };
+        while seed.len() < size {
+            let neighbors = self.get_neighbors_of(&seed);
+            if neighbors.is_empty() {
+                break;
+            }
+            seed.push(neighbors[rng.gen_range(0, neighbors.len())]);
+        }
+        seed
+    }
+
+    // The bits sharing a check with a bit of `bits` that are not in `bits`.
+    fn get_neighbors_of(&self, bits: &[usize]) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = bits
+            .iter()
+            .flat_map(|bit| self.code.get_checks_adjacent_to_bit(*bit).unwrap_or(&[]))
+            .flat_map(|check| self.code.get_check(*check).unwrap().to_vec())
+            .filter(|bit| !bits.contains(bit))
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    fn get_trapping_set_reached_from(&self, error: &[usize]) -> Option<TrappingSet> {
+        let mut received = vec![GF2::B0; self.code.get_n_bits()];
+        error.iter().for_each(|bit| received[*bit] = GF2::B1);
+        let result = self.decoder.decode_soft(&received);
+        if result.has_converged() {
+            return None;
+        }
+        let decision = result.get_hard_decision();
+        let set = TrappingSet {
+            bits: (0..decision.len())
+                .filter(|bit| decision[*bit] == GF2::B1)
+                .collect(),
+            n_unsatisfied_checks: self
+                .code
+                .get_syndrome_of(decision)
+                .into_iter()
+                .filter(|value| *value == GF2::B1)
+                .count(),
+        };
+        if set.get_size() <= self.max_size
+            && set.get_n_unsatisfied_checks() <= self.max_n_unsatisfied_checks
+        {
+            Some(set)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BscDecoder, CodeGenerator, Decoder, RegularLDPCCodeGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    // Without iterations, the hard decision is the error pattern.
+    #[test]
+    fn error_patterns_are_trapping_sets_without_iterations() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let decoder = BscDecoder::with_crossover_prob(0.1)
+            .with_max_iterations(0)
+            .for_code(code.clone());
+        let finder = TrappingSetFinder::with_max_class(1, 3).using(decoder);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let sets = finder.find_with_rng(1000, &mut rng);
+
+        assert_eq!(sets.len(), 7);
+        assert_eq!(sets.iter().map(|(_, count)| count).sum::<u64>(), 1000);
+        for (set, _) in sets.iter() {
+            let bit = set.get_bits()[0];
+            assert_eq!(set.get_class(), (1, code.get_bit_degree(bit).unwrap()));
+        }
+        assert_eq!(sets[0].0.get_class(), (1, 1));
+        assert_eq!(sets[6].0.get_bits(), &[0]);
+    }
+
+    #[test]
+    fn sets_are_within_the_class_and_unsatisfied_checks_are_odd_checks() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let code = RegularLDPCCodeGenerator::new(3, 6, 2, 4).generate_with_rng(&mut rng);
+        let decoder = BscDecoder::with_crossover_prob(0.05)
+            .with_max_iterations(10)
+            .for_code(code.clone());
+        let finder = TrappingSetFinder::with_max_class(5, 3).using(decoder);
+        let sets = finder.find_with_rng(500, &mut rng);
+
+        assert!(!sets.is_empty());
+        for (set, _) in sets.iter() {
+            assert!((1..=5).contains(&set.get_size()));
+            let n_odd_checks = code
+                .checks_iter()
+                .filter(|check| {
+                    check
+                        .iter()
+                        .filter(|bit| set.get_bits().contains(bit))
+                        .count()
+                        % 2
+                        == 1
+                })
+                .count();
+            assert_eq!(set.get_n_unsatisfied_checks(), n_odd_checks);
+            assert!((1..=3).contains(&n_odd_checks));
+        }
+        assert!(sets
+            .windows(2)
+            .all(|pair| pair[0].0.get_class() <= pair[1].0.get_class()));
+    }
+
+    #[test]
+    fn search_is_reproducible_and_keeps_the_code() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code.clone());
+        let mut finder = TrappingSetFinder::with_max_class(3, 3).using(decoder);
+        let first = finder.find_with_rng(200, &mut ChaCha8Rng::seed_from_u64(123));
+        let second = finder.find_with_rng(200, &mut ChaCha8Rng::seed_from_u64(123));
+        assert_eq!(first, second);
+        assert_eq!(finder.decoder.take_code(), code);
+    }
+
+    #[test]
+    fn empty_class_finds_nothing() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let decoder = BscDecoder::with_crossover_prob(0.1).for_code(code);
+        let finder = TrappingSetFinder::with_max_class(0, 3).using(decoder);
+        assert!(finder.find(10).is_empty());
+    }
+}
@@ -1,14 +1,120 @@
 use super::CodeGenerator;
-use crate::{Decoder, ErasureDecoder, ParityCheckMatrix, SimulationResult};
-use rand::distributions::Standard;
-use rand::{Rng, SeedableRng, thread_rng};
+use crate::{
+    BetaPrior, BitFlippingDecoder, BurstErasure, Decoder, ErasureDecoder, PairedErasure,
+    ParityCheckMatrix, ResultsTable, SeedSequence, SimulationResult,
+};
+use rand::{Rng, thread_rng};
 use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 type CodeAndResult = (Option<ParityCheckMatrix>, SimulationResult);
 
+type ProgressCallback<'a> = Box<dyn Fn(ProgressEvent) + Sync + 'a>;
+
+/// The progress of a code search reported after each simulated code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressEvent {
+    n_completed_codes: usize,
+    n_codes: usize,
+    best_failure_rate: Option<f64>,
+    elapsed: Duration,
+}
+
+impl ProgressEvent {
+    /// Returns the number of codes that were simulated or rejected so far.
+    pub fn get_n_completed_codes(&self) -> usize {
+        self.n_completed_codes
+    }
+
+    /// Returns the total number of codes to try.
+    pub fn get_n_codes(&self) -> usize {
+        self.n_codes
+    }
+
+    /// Returns the best failure rate among the simulated codes so far, or `None` if all the
+    /// completed codes were rejected.
+    pub fn get_best_failure_rate(&self) -> Option<f64> {
+        self.best_failure_rate
+    }
+
+    /// Returns the time elapsed since the beginning of the search.
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// How a code search compares the simulation results of two codes.
+///
+/// The failure rate is a poor estimate when few failures are found. For instance, a code
+/// without failures in a short simulation beats a code with a single failure in a very long
+/// simulation even if the second code is much more likely to be the best. The Bayesian rankings
+/// avoid this by giving a positive failure rate to simulations without failures.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CodeRanking {
+    /// Ranks codes by their failure rate. This is the default.
+    #[default]
+    FailureRate,
+    /// Ranks codes by the posterior mean of their failure rate with the given prior.
+    PosteriorMean(BetaPrior),
+    /// Ranks codes by the upper bound of their failure rate with the given prior and posterior
+    /// probability. This favors the codes whose failure rate is known to be small.
+    UpperCredibleBound(BetaPrior, f64),
+}
+
+impl CodeRanking {
+    // The score of the `result` of a code. Lower is better. Rejected codes always lose with
+    // the Bayesian rankings since their worse result has a posterior failure rate below 1.
+    fn get_score_of(&self, (code, result): &CodeAndResult) -> f64 {
+        match self {
+            CodeRanking::FailureRate => result.get_failure_rate(),
+            _ if code.is_none() => f64::INFINITY,
+            CodeRanking::PosteriorMean(prior) => result.get_posterior_mean_failure_rate(*prior),
+            CodeRanking::UpperCredibleBound(prior, credibility) => {
+                result.get_failure_rate_upper_credible_bound(*prior, *credibility)
+            }
+        }
+    }
+}
+
 /// An interface to find the best code generated by some code generator among a given number of
-/// code.
+/// code using a given decoder to simulate each code.
+/// 
+/// The decoder is used as a template. Each generated code is simulated by a clone of the
+/// decoder for this code.
+/// 
+/// # Example 
+/// 
+/// ```
+/// use believer::{BestCodeFinder, BscDecoder};
+/// use believer::RegularLDPCCodeGenerator;
+/// 
+/// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+/// let decoder = BscDecoder::with_crossover_prob(0.05);
+/// let code_finder = BestCodeFinder::from_code_generator_and_decoder(&generator, decoder)
+///     .among_n_codes(10);
+/// let (code, result) = code_finder.find_best_code_simulating_n_iterations(100);
+/// ```
+pub struct BestCodeFinder<'a, G, D>
+where
+    G: CodeGenerator,
+    D: Decoder<Code = ParityCheckMatrix> + Clone,
+{
+    code_generator: &'a G,
+    decoder: D,
+    n_codes_to_try: usize,
+    rejects_codes_with_unprotected_bits: bool,
+    prefilter: Option<BitFlippingPrefilter>,
+    time_budget: Option<Duration>,
+    progress: Option<ProgressCallback<'a>>,
+    ranking: CodeRanking,
+    thread_pool: Option<&'a ThreadPool>,
+}
+
+/// An interface to find the best code generated by some code generator among a given number of
+/// code using an erasure decoder.
 /// 
 /// # Example 
 /// 
@@ -22,13 +128,9 @@ type CodeAndResult = (Option<ParityCheckMatrix>, SimulationResult);
 ///     .among_n_codes(10);
 /// let (code, result) = code_finder.find_best_code_simulating_n_iterations(1000);
 /// ```
-pub struct BestCodeFinderUsingErasure<'a, G: CodeGenerator> {
-    code_generator: &'a G,
-    erasure_prob: f64,
-    n_codes_to_try: usize,
-}
+pub type BestCodeFinderUsingErasure<'a, G> = BestCodeFinder<'a, G, ErasureDecoder>;
 
-impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
+impl<'a, G: CodeGenerator> BestCodeFinder<'a, G, ErasureDecoder> {
     // ***** Construction *****
 
     /// Creates a new `BestCodeFinderUsingErasure` from a given `code_generator`.
@@ -43,10 +145,201 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
     /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator);
     /// ```
     pub fn from_code_generator(code_generator: &'a G) -> Self {
+        Self::from_code_generator_and_decoder(code_generator, ErasureDecoder::with_prob(0.5))
+    }
+
+    /// Set the erasure `prob` to use when simulating code performance. 
+    /// 
+    /// If not specified, default to 0.5. Only the noise of the decoder changes, so a strategy
+    /// such as peeling is kept.
+    /// 
+    /// # Example 
+    /// 
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// 
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure
+    ///     ::from_code_generator(&generator)
+    ///     .with_erasure_prob(0.2);
+    /// ```
+    pub fn with_erasure_prob(mut self, prob: f64) -> Self {
+        if !(0.0..=1.0).contains(&prob) {
+            panic!("prob is not between 0 and 1")
+        }
+        self.decoder = self.decoder.with_noise_of(ErasureDecoder::with_prob(prob));
+        self
+    }
+
+    /// Set the erasure probability of each bit to use when simulating code performance. The
+    /// bit `i` of each code is erased with probability `probs[i]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some probabilities are not between 0 and 1. Finding a code panics if the
+    /// number of probabilities is not the number of bits of the generated codes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let mut probs = vec![0.1; generator.n_bits()];
+    /// probs[0] = 0.5;
+    /// let code_finder = BestCodeFinderUsingErasure
+    ///     ::from_code_generator(&generator)
+    ///     .with_erasure_probs(probs);
+    /// ```
+    pub fn with_erasure_probs(mut self, probs: Vec<f64>) -> Self {
+        if probs.iter().any(|prob| !(0.0..=1.0).contains(prob)) {
+            panic!("prob is not between 0 and 1")
+        }
+        self.decoder = self.decoder.with_noise_of(ErasureDecoder::with_probs(probs));
+        self
+    }
+
+    /// Set the bursts of erasures to use when simulating code performance.
+    ///
+    /// This finds the codes that best recover from correlated erasures, such as the ones of a
+    /// storage medium.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BestCodeFinderUsingErasure, BurstErasure};
+    /// use believer::RegularLDPCCodeGenerator;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.05, 3.0);
+    /// let code_finder = BestCodeFinderUsingErasure
+    ///     ::from_code_generator(&generator)
+    ///     .with_burst_erasure(noise)
+    ///     .among_n_codes(10);
+    /// let (code, result) = code_finder.find_best_code_simulating_n_iterations(1000);
+    /// ```
+    pub fn with_burst_erasure(mut self, noise: BurstErasure) -> Self {
+        self.decoder = self.decoder.with_noise_of(ErasureDecoder::with_burst_erasure(noise));
+        self
+    }
+
+    /// Set the pairs of bits erased together to use when simulating code performance.
+    ///
+    /// This finds the codes whose small stopping sets avoid the pairs.
+    ///
+    /// # Panic
+    ///
+    /// Finding a code panics if the number of bits of `noise` is not the number of bits of the
+    /// generated codes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BestCodeFinderUsingErasure, PairedErasure};
+    /// use believer::RegularLDPCCodeGenerator;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let noise = PairedErasure::with_adjacent_pairs(generator.n_bits(), 0.2);
+    /// let code_finder = BestCodeFinderUsingErasure
+    ///     ::from_code_generator(&generator)
+    ///     .with_paired_erasure(noise)
+    ///     .among_n_codes(10);
+    /// let (code, result) = code_finder.find_best_code_simulating_n_iterations(1000);
+    /// ```
+    pub fn with_paired_erasure(mut self, noise: PairedErasure) -> Self {
+        self.decoder = self.decoder.with_noise_of(ErasureDecoder::with_paired_erasure(noise));
+        self
+    }
+
+    /// Returns the best code and its performance together with a table of the results of all
+    /// the simulated codes obtained using the given random number generator `rng`.
+    ///
+    /// To evaluate the performance of each code, `n_iterations` random error decoding are done.
+    /// The rows of the table are in the order the codes were generated and the code `i` is
+    /// labeled `code i`. The rejected codes are not simulated, so they have no row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use rand::thread_rng;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .with_erasure_prob(0.5)
+    ///     .among_n_codes(10);
+    /// let ((code, result), table) = code_finder
+    ///     .find_best_code_and_table_simulating_n_iterations_with_rng(1000, &mut thread_rng());
+    ///
+    /// assert_eq!(table.get_n_rows(), 10);
+    /// println!("{}", table.to_csv());
+    /// ```
+    pub fn find_best_code_and_table_simulating_n_iterations_with_rng<R: Rng>(
+        &self,
+        n_iterations: usize,
+        rng: &mut R,
+    ) -> (CodeAndResult, ResultsTable) {
+        let search = BestCodeSearch::from(self, |decoder: &mut ErasureDecoder, rng: &mut _| {
+            decoder.simulate_n_iterations_with_rng(n_iterations, rng)
+        });
+        let code_and_result = search.find_with_rng(rng);
+        let mut table = ResultsTable::new();
+        for (code_index, result) in search.into_results() {
+            let label = format!("code {}", code_index);
+            table.add_row(&label, self.decoder.get_erasure_prob(), &result);
+        }
+        (code_and_result, table)
+    }
+
+    /// Returns the best code and its performance together with a table of the results of all
+    /// the simulated codes obtained using the thread rng.
+    ///
+    /// To evaluate the performance of each code, `n_iterations` random error decoding are done.
+    pub fn find_best_code_and_table_simulating_n_iterations(
+        &self,
+        n_iterations: usize,
+    ) -> (CodeAndResult, ResultsTable) {
+        self.find_best_code_and_table_simulating_n_iterations_with_rng(
+            n_iterations,
+            &mut thread_rng(),
+        )
+    }
+}
+
+impl<'a, G, D> BestCodeFinder<'a, G, D>
+where
+    G: CodeGenerator,
+    D: Decoder<Code = ParityCheckMatrix> + Clone,
+{
+    // ***** Construction *****
+
+    /// Creates a new `BestCodeFinder` from a given `code_generator` simulating the codes with
+    /// clones of `decoder`.
+    /// 
+    /// # Example 
+    /// 
+    /// ```
+    /// use believer::{BestCodeFinder, BscDecoder};
+    /// use believer::RegularLDPCCodeGenerator;
+    /// 
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let decoder = BscDecoder::with_crossover_prob(0.05);
+    /// let code_finder = BestCodeFinder::from_code_generator_and_decoder(&generator, decoder);
+    /// ```
+    pub fn from_code_generator_and_decoder(code_generator: &'a G, decoder: D) -> Self {
         Self {
             code_generator,
-            erasure_prob: 0.5,
+            decoder,
             n_codes_to_try: 0,
+            rejects_codes_with_unprotected_bits: false,
+            prefilter: None,
+            time_budget: None,
+            progress: None,
+            ranking: CodeRanking::default(),
+            thread_pool: None,
         }
     }
 
@@ -70,9 +363,10 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
         self
     }
 
-    /// Set the erasure `prob` to use when simulating code performance. 
+    /// Skips the generated codes with some bits that are not connected to any check instead of
+    /// simulating them.
     /// 
-    /// If not specified, default to 0.5.
+    /// If not specified, all codes are simulated.
     /// 
     /// # Example 
     /// 
@@ -83,16 +377,199 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
     /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
     /// let code_finder = BestCodeFinderUsingErasure
     ///     ::from_code_generator(&generator)
-    ///     .with_erasure_prob(0.2);
+    ///     .rejecting_codes_with_unprotected_bits();
     /// ```
-    pub fn with_erasure_prob(mut self, prob: f64) -> Self {
-        if prob < 0.0 || prob > 1.0 {
-            panic!("prob is not between 0 and 1")
+    pub fn rejecting_codes_with_unprotected_bits(mut self) -> Self {
+        self.rejects_codes_with_unprotected_bits = true;
+        self
+    }
+
+    /// Simulates each code with a bit flipping `decoder` for `n_iterations` before simulating
+    /// it with the decoder of `self`, and skips the codes with a failure rate above
+    /// `max_failure_rate`.
+    ///
+    /// Bit flipping is much faster than belief propagation, so this quickly discards the bad
+    /// codes of a search. The prefilter uses the random number generator of the code, so
+    /// seeded searches stay reproducible. If not specified, all codes are simulated.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `max_failure_rate` is not between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BestCodeFinder, BitFlippingDecoder, BscDecoder};
+    /// use believer::RegularLDPCCodeGenerator;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 6, 20, 6);
+    /// let decoder = BscDecoder::with_crossover_prob(0.02);
+    /// let code_finder = BestCodeFinder::from_code_generator_and_decoder(&generator, decoder)
+    ///     .among_n_codes(10)
+    ///     .prefiltering_with_bit_flipping(
+    ///         BitFlippingDecoder::with_crossover_prob(0.02),
+    ///         200,
+    ///         0.5,
+    ///     );
+    /// let (code, result) = code_finder.find_best_code_simulating_n_iterations(100);
+    /// ```
+    pub fn prefiltering_with_bit_flipping(
+        mut self,
+        decoder: BitFlippingDecoder,
+        n_iterations: usize,
+        max_failure_rate: f64,
+    ) -> Self {
+        if !(0.0..=1.0).contains(&max_failure_rate) {
+            panic!("invalid probability");
         }
-        self.erasure_prob = prob;
+        self.prefilter = Some(BitFlippingPrefilter {
+            decoder,
+            n_iterations,
+            max_failure_rate,
+        });
         self
     }
 
+    /// Set the total time `budget` to use when finding a code within a time budget.
+    ///
+    /// The budget is split across the codes to try. Since codes are simulated in parallel,
+    /// each code gets a share of the budget times the number of threads, so the whole search
+    /// takes about `budget` on any machine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use std::time::Duration;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure
+    ///     ::from_code_generator(&generator)
+    ///     .among_n_codes(10)
+    ///     .with_time_budget(Duration::from_millis(50));
+    /// ```
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Calls `progress` each time a code is simulated or rejected during a search.
+    ///
+    /// Since codes are simulated in parallel, `progress` is called from many threads, but the
+    /// calls never overlap. If not specified, nothing is reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let n_reports = AtomicUsize::new(0);
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .among_n_codes(10)
+    ///     .with_progress(|event| {
+    ///         n_reports.fetch_add(1, Ordering::SeqCst);
+    ///         println!("{}/{} codes", event.get_n_completed_codes(), event.get_n_codes());
+    ///     });
+    /// code_finder.find_best_code_simulating_n_iterations(100);
+    ///
+    /// assert_eq!(n_reports.load(Ordering::SeqCst), 10);
+    /// ```
+    pub fn with_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(ProgressEvent) + Sync + 'a,
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Set the `ranking` used to compare the simulated codes.
+    ///
+    /// If not specified, codes are ranked by failure rate.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the credibility of an upper credible bound is not strictly between 0.0 and
+    /// 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{BestCodeFinderUsingErasure, BetaPrior, CodeRanking};
+    /// use believer::RegularLDPCCodeGenerator;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .with_erasure_prob(0.1)
+    ///     .among_n_codes(10)
+    ///     .ranking_codes_by(CodeRanking::PosteriorMean(BetaPrior::jeffreys()));
+    /// let (code, result) = code_finder.find_best_code_simulating_n_iterations(100);
+    /// ```
+    pub fn ranking_codes_by(mut self, ranking: CodeRanking) -> Self {
+        if let CodeRanking::UpperCredibleBound(_, credibility) = ranking {
+            if credibility <= 0.0 || credibility >= 1.0 {
+                panic!("invalid credibility");
+            }
+        }
+        self.ranking = ranking;
+        self
+    }
+
+    /// Simulates the codes on the threads of `pool` instead of the global rayon pool.
+    ///
+    /// This limits the number of threads used by a search or keeps it from competing with
+    /// other work of the global pool. Each code has its own seed, so the results don't depend
+    /// on the number of threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .among_n_codes(10);
+    /// let rng = ChaCha8Rng::seed_from_u64(123);
+    /// let (code, result) = code_finder
+    ///     .find_best_code_simulating_n_iterations_with_rng(100, &mut rng.clone());
+    ///
+    /// let code_finder = code_finder.with_thread_pool(&pool);
+    /// assert_eq!(
+    ///     code_finder.find_best_code_simulating_n_iterations_with_rng(100, &mut rng.clone()),
+    ///     (code, result)
+    /// );
+    /// ```
+    pub fn with_thread_pool(mut self, pool: &'a ThreadPool) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
+    fn rejects(&self, code: &ParityCheckMatrix) -> bool {
+        self.rejects_codes_with_unprotected_bits && code.has_unprotected_bits()
+    }
+
+    fn prefilter_rejects_with_rng<R: Rng>(
+        &self,
+        code: &Arc<ParityCheckMatrix>,
+        rng: &mut R,
+    ) -> bool {
+        self.prefilter.as_ref().is_some_and(|prefilter| {
+            let result = prefilter
+                .decoder
+                .clone()
+                .for_shared_code(Arc::clone(code))
+                .simulate_n_iterations_with_rng(prefilter.n_iterations, rng);
+            result.get_failure_rate() > prefilter.max_failure_rate
+        })
+    }
+
     /// Returns the best code and its performance obtained using the given random number generator 
     /// `rng`. 
     /// 
@@ -121,9 +598,10 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
         n_iterations: usize,
         rng: &mut R,
     ) -> CodeAndResult {
-        NIterationsBestCodeFinderUsingErasure::from(self)
-            .with_n_iterations(n_iterations)
-            .find_with_rng(rng)
+        BestCodeSearch::from(self, |decoder: &mut D, rng: &mut ChaCha8Rng| {
+            decoder.simulate_n_iterations_with_rng(n_iterations, rng)
+        })
+        .find_with_rng(rng)
     }
 
     /// Returns the best code and its performance obtained using the thread rng.
@@ -180,9 +658,10 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
         n_events: usize,
         rng: &mut R,
     ) -> CodeAndResult {
-        NEventsBestCodeFinderUsingErasure::from(self)
-            .with_n_events(n_events)
-            .find_with_rng(rng)
+        BestCodeSearch::from(self, |decoder: &mut D, rng: &mut ChaCha8Rng| {
+            decoder.simulate_until_n_events_are_found_with_rng(n_events, rng)
+        })
+        .find_with_rng(rng)
     }
 
     /// Returns the best code and its performance obtained using the thread rng.
@@ -210,125 +689,239 @@ impl<'a, G: CodeGenerator> BestCodeFinderUsingErasure<'a, G> {
     pub fn find_best_code_simulating_n_events(&self, n_events: usize) -> CodeAndResult {
         self.find_best_code_simulating_n_events_with_rng(n_events, &mut thread_rng())
     }
-}
-
-// The next 2 structs are basically the same things. They should be refactored.
-
-struct NIterationsBestCodeFinderUsingErasure<'a, G: CodeGenerator> {
-    code_finder: &'a BestCodeFinderUsingErasure<'a, G>,
-    n_iterations: usize,
-    random_seeds: Vec<u64>,
-}
-
-impl<'a, G: CodeGenerator> NIterationsBestCodeFinderUsingErasure<'a, G> {
-    fn from(code_finder: &'a BestCodeFinderUsingErasure<'a, G>) -> Self {
-        Self {
-            code_finder,
-            n_iterations: 0,
-            random_seeds: Vec::new(),
-        }
-    }
 
-    fn with_n_iterations(mut self, n_iterations: usize) -> Self {
-        self.n_iterations = n_iterations;
-        self
+    /// Returns the best code and its performance obtained using the given random number generator
+    /// `rng`.
+    ///
+    /// Each code is simulated for its share of the time budget of `self`.
+    ///
+    /// It returns a pair of values. The first value is some code if at least one of the rate
+    /// bellow 1.0. If no code obtained better failure rate, none is return. The second element is
+    /// the associated performance.
+    ///
+    /// # Panic
+    ///
+    /// Panics if no time budget was set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use rand::thread_rng;
+    /// use std::time::Duration;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .with_erasure_prob(0.5)
+    ///     .among_n_codes(10)
+    ///     .with_time_budget(Duration::from_millis(50));
+    /// let (code, result) = code_finder
+    ///     .find_best_code_within_time_budget_with_rng(&mut thread_rng());
+    /// ```
+    pub fn find_best_code_within_time_budget_with_rng<R: Rng>(&self, rng: &mut R) -> CodeAndResult {
+        let budget = self.get_time_budget_per_code();
+        BestCodeSearch::from(self, |decoder: &mut D, rng: &mut ChaCha8Rng| {
+            decoder.simulate_for_duration_with_rng(budget, rng)
+        })
+        .find_with_rng(rng)
     }
 
-    fn find_with_rng<R: Rng>(mut self, rng: &mut R) -> CodeAndResult {
-        self.initialize_random_seeds_with_rng(rng);
-        (0..self.code_finder.n_codes_to_try)
-            .into_par_iter()
-            .map(|code_index| {
-                let mut rng = self.get_rng_for(code_index);
-                self.simulate_one_code_with_rng(&mut rng)
-            })
-            .reduce(
-                || (None, SimulationResult::worse_result()),
-                |accumulator, code_and_result| Self::get_best_between(accumulator, code_and_result),
-            )
+    /// Returns the best code and its performance obtained using the thread rng.
+    ///
+    /// Each code is simulated for its share of the time budget of `self`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if no time budget was set.
+    pub fn find_best_code_within_time_budget(&self) -> CodeAndResult {
+        self.find_best_code_within_time_budget_with_rng(&mut thread_rng())
     }
 
-    fn initialize_random_seeds_with_rng<R: Rng>(&mut self, rng: &mut R) {
-        self.random_seeds = rng
-            .sample_iter(Standard)
-            .take(self.code_finder.n_codes_to_try)
-            .collect()
+    fn get_time_budget_per_code(&self) -> Duration {
+        let budget = self.time_budget.expect("no time budget");
+        if self.n_codes_to_try == 0 {
+            return budget;
+        }
+        let n_threads = self
+            .thread_pool
+            .map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads());
+        let n_parallel_codes = n_threads.min(self.n_codes_to_try);
+        budget * n_parallel_codes as u32 / self.n_codes_to_try as u32
     }
 
-    fn get_rng_for(&self, index: usize) -> ChaCha8Rng {
-        ChaCha8Rng::seed_from_u64(self.random_seeds[index])
+    /// Returns the best code and its performance obtained using the given random number generator
+    /// `rng`.
+    ///
+    /// Each code is simulated until the relative standard error of its failure rate is at most
+    /// `relative_error` or `max_iterations` random errors are decoded. Thus, bad codes stop
+    /// early while good codes are simulated long enough to see failures.
+    ///
+    /// It returns a pair of values. The first value is some code if at least one of the rate
+    /// bellow 1.0. If no code obtained better failure rate, none is return. The second element is
+    /// the associated performance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::BestCodeFinderUsingErasure;
+    /// use believer::RegularLDPCCodeGenerator;
+    /// use rand::thread_rng;
+    ///
+    /// let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+    /// let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+    ///     .with_erasure_prob(0.5)
+    ///     .among_n_codes(10);
+    /// let (code, result) = code_finder
+    ///     .find_best_code_with_target_precision_with_rng(0.1, 10_000, &mut thread_rng());
+    /// ```
+    pub fn find_best_code_with_target_precision_with_rng<R: Rng>(
+        &self,
+        relative_error: f64,
+        max_iterations: usize,
+        rng: &mut R,
+    ) -> CodeAndResult {
+        BestCodeSearch::from(self, |decoder: &mut D, rng: &mut ChaCha8Rng| {
+            decoder.simulate_until_precision_with_rng(relative_error, max_iterations, rng)
+        })
+        .find_with_rng(rng)
     }
 
-    fn simulate_one_code_with_rng<R: Rng>(&self, rng: &mut R) -> CodeAndResult {
-        let code = self.code_finder.code_generator.generate_with_rng(rng);
-        let mut decoder = ErasureDecoder::with_prob(self.code_finder.erasure_prob).for_code(code);
-        let result = decoder.simulate_n_iterations_with_rng(self.n_iterations, rng);
-        (Some(decoder.take_code()), result)
+    /// Returns the best code and its performance obtained using the thread rng.
+    ///
+    /// Each code is simulated until the relative standard error of its failure rate is at most
+    /// `relative_error` or `max_iterations` random errors are decoded.
+    pub fn find_best_code_with_target_precision(
+        &self,
+        relative_error: f64,
+        max_iterations: usize,
+    ) -> CodeAndResult {
+        self.find_best_code_with_target_precision_with_rng(
+            relative_error,
+            max_iterations,
+            &mut thread_rng(),
+        )
     }
+}
 
-    fn get_best_between(first: CodeAndResult, second: CodeAndResult) -> CodeAndResult {
-        if first.1.is_better_than(&second.1) {
-            first
-        } else {
-            second
-        }
-    }
+// The codes with a failure rate above `max_failure_rate` for the bit flipping decoder are not
+// simulated with the decoder of the finder.
+struct BitFlippingPrefilter {
+    decoder: BitFlippingDecoder,
+    n_iterations: usize,
+    max_failure_rate: f64,
 }
 
-struct NEventsBestCodeFinderUsingErasure<'a, G: CodeGenerator> {
-    code_finder: &'a BestCodeFinderUsingErasure<'a, G>,
-    n_events: usize,
-    random_seeds: Vec<u64>,
+// Simulates each generated code with `simulate` and keeps the best one.
+struct BestCodeSearch<'a, G, D, S>
+where
+    G: CodeGenerator,
+    D: Decoder<Code = ParityCheckMatrix> + Clone,
+    S: Fn(&mut D, &mut ChaCha8Rng) -> SimulationResult + Sync,
+{
+    code_finder: &'a BestCodeFinder<'a, G, D>,
+    simulate: S,
+    start: Instant,
+    // The number of completed codes and the best failure rate so far.
+    progress: Mutex<(usize, Option<f64>)>,
+    // The result of each simulated code with its index.
+    results: Mutex<Vec<(usize, SimulationResult)>>,
 }
 
-impl<'a, G: CodeGenerator> NEventsBestCodeFinderUsingErasure<'a, G> {
-    fn from(code_finder: &'a BestCodeFinderUsingErasure<'a, G>) -> Self {
+impl<'a, G, D, S> BestCodeSearch<'a, G, D, S>
+where
+    G: CodeGenerator,
+    D: Decoder<Code = ParityCheckMatrix> + Clone,
+    S: Fn(&mut D, &mut ChaCha8Rng) -> SimulationResult + Sync,
+{
+    fn from(code_finder: &'a BestCodeFinder<'a, G, D>, simulate: S) -> Self {
         Self {
             code_finder,
-            n_events: 0,
-            random_seeds: Vec::new(),
+            simulate,
+            start: Instant::now(),
+            progress: Mutex::new((0, None)),
+            results: Mutex::new(Vec::new()),
         }
     }
 
-    fn with_n_events(mut self, n_events: usize) -> Self {
-        self.n_events = n_events;
-        self
+    // Each code is simulated with the generator of its index, so the result doesn't depend on
+    // the number of threads.
+    fn find_with_rng<R: Rng>(&self, rng: &mut R) -> CodeAndResult {
+        let seeds = SeedSequence::from_rng(rng);
+        match self.code_finder.thread_pool {
+            Some(pool) => pool.install(|| self.find_in_parallel(&seeds)),
+            None => self.find_in_parallel(&seeds),
+        }
     }
 
-    fn find_with_rng<R: Rng>(mut self, rng: &mut R) -> CodeAndResult {
-        self.initialize_random_seeds_with_rng(rng);
+    fn find_in_parallel(&self, seeds: &SeedSequence) -> CodeAndResult {
         (0..self.code_finder.n_codes_to_try)
             .into_par_iter()
             .map(|code_index| {
-                let mut rng = self.get_rng_for(code_index);
-                self.simulate_one_code_with_rng(&mut rng)
+                let mut rng = seeds.get_rng(code_index as u64);
+                let code_and_result = self.simulate_one_code_with_rng(&mut rng);
+                self.report_progress_of(&code_and_result);
+                if code_and_result.0.is_some() {
+                    let result = code_and_result.1.clone();
+                    self.results.lock().unwrap().push((code_index, result));
+                }
+                code_and_result
             })
             .reduce(
                 || (None, SimulationResult::worse_result()),
-                |accumulator, code_and_result| Self::get_best_between(accumulator, code_and_result),
+                |accumulator, code_and_result| {
+                    self.get_best_between(accumulator, code_and_result)
+                },
             )
     }
 
-    fn initialize_random_seeds_with_rng<R: Rng>(&mut self, rng: &mut R) {
-        self.random_seeds = rng
-            .sample_iter(Standard)
-            .take(self.code_finder.n_codes_to_try)
-            .collect()
+    // The results of the simulated codes sorted by index.
+    fn into_results(self) -> Vec<(usize, SimulationResult)> {
+        let mut results = self.results.into_inner().unwrap();
+        results.sort_by_key(|(code_index, _)| *code_index);
+        results
     }
 
-    fn get_rng_for(&self, index: usize) -> ChaCha8Rng {
-        ChaCha8Rng::seed_from_u64(self.random_seeds[index])
+    fn report_progress_of(&self, (code, result): &CodeAndResult) {
+        if let Some(progress) = &self.code_finder.progress {
+            let mut state = self.progress.lock().unwrap();
+            state.0 += 1;
+            if code.is_some() {
+                let rate = result.get_failure_rate();
+                state.1 = Some(state.1.map_or(rate, |best| best.min(rate)));
+            }
+            progress(ProgressEvent {
+                n_completed_codes: state.0,
+                n_codes: self.code_finder.n_codes_to_try,
+                best_failure_rate: state.1,
+                elapsed: self.start.elapsed(),
+            });
+        }
     }
 
-    fn simulate_one_code_with_rng<R: Rng>(&self, rng: &mut R) -> CodeAndResult {
+    fn simulate_one_code_with_rng(&self, rng: &mut ChaCha8Rng) -> CodeAndResult {
         let code = self.code_finder.code_generator.generate_with_rng(rng);
-        let mut decoder = ErasureDecoder::with_prob(self.code_finder.erasure_prob).for_code(code);
-        let result = decoder.simulate_until_n_events_are_found_with_rng(self.n_events, rng);
-        (Some(decoder.take_code()), result)
+        if self.code_finder.rejects(&code) {
+            return (None, SimulationResult::worse_result());
+        }
+        let code = Arc::new(code);
+        if self.code_finder.prefilter_rejects_with_rng(&code, rng) {
+            return (None, SimulationResult::worse_result());
+        }
+        let mut decoder = self
+            .code_finder
+            .decoder
+            .clone()
+            .for_shared_code(Arc::clone(&code));
+        let result = (self.simulate)(&mut decoder, rng);
+        drop(decoder);
+        // The decoder is dropped, so the code is not shared anymore.
+        (Arc::try_unwrap(code).ok(), result)
     }
 
-    fn get_best_between(first: CodeAndResult, second: CodeAndResult) -> CodeAndResult {
-        if first.1.is_better_than(&second.1) {
+    fn get_best_between(&self, first: CodeAndResult, second: CodeAndResult) -> CodeAndResult {
+        let ranking = &self.code_finder.ranking;
+        if ranking.get_score_of(&first) < ranking.get_score_of(&second) {
             first
         } else {
             second
@@ -340,8 +933,10 @@ impl<'a, G: CodeGenerator> NEventsBestCodeFinderUsingErasure<'a, G> {
 mod test {
     use super::super::RegularLDPCCodeGenerator;
     use super::*;
+    use crate::{BscDecoder, DecodingResult, ErasureStrategy};
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
+    use std::sync::OnceLock;
 
     #[test]
     fn reproductibility_for_finding_best_ldpc_code_simulating_n_iterations() {
@@ -360,6 +955,91 @@ mod test {
         assert_eq!(code_and_result_0, code_and_result_1);
     }
 
+    #[test]
+    fn burst_erasures_are_used_for_the_simulations_and_the_table() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let noise = BurstErasure::with_burst_start_prob_and_mean_length(0.05, 3.0);
+        let burst_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_burst_erasure(noise)
+            .among_n_codes(5);
+        let uniform_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(noise.get_erasure_prob())
+            .among_n_codes(5);
+
+        let (bursts, table) = burst_finder
+            .find_best_code_and_table_simulating_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(
+            burst_finder.find_best_code_simulating_n_iterations_with_rng(200, &mut rng.clone()),
+            bursts
+        );
+        assert_ne!(
+            uniform_finder.find_best_code_simulating_n_iterations_with_rng(200, &mut rng.clone()),
+            bursts
+        );
+        assert_eq!(table.get_n_rows(), 5);
+        assert!(table.to_csv().contains(&noise.get_erasure_prob().to_string()));
+    }
+
+    #[test]
+    fn paired_erasures_are_used_for_the_simulations() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let noise = PairedErasure::with_adjacent_pairs(generator.n_bits(), 0.2);
+        let paired_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_paired_erasure(noise.clone())
+            .among_n_codes(5);
+        let uniform_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(noise.get_erasure_prob())
+            .among_n_codes(5);
+
+        let pairs =
+            paired_finder.find_best_code_simulating_n_iterations_with_rng(200, &mut rng.clone());
+        assert_eq!(
+            paired_finder.find_best_code_simulating_n_iterations_with_rng(200, &mut rng.clone()),
+            pairs
+        );
+        assert_ne!(
+            uniform_finder.find_best_code_simulating_n_iterations_with_rng(200, &mut rng.clone()),
+            pairs
+        );
+    }
+
+    #[test]
+    fn searches_on_a_single_thread_give_the_same_results_as_on_four_threads() {
+        // The pools are never dropped, since tearing down a multi-threaded pool while other
+        // tests run can abort the test binary.
+        static SINGLE_THREAD: OnceLock<ThreadPool> = OnceLock::new();
+        static FOUR_THREADS: OnceLock<ThreadPool> = OnceLock::new();
+        let pool_with = |lock: &'static OnceLock<ThreadPool>, n_threads| {
+            lock.get_or_init(|| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(n_threads)
+                    .build()
+                    .unwrap()
+            })
+        };
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let find_with = |pool: &ThreadPool| {
+            let n_threads = pool.current_num_threads();
+            let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+                .with_erasure_prob(0.25)
+                .among_n_codes(12)
+                .with_thread_pool(pool)
+                .with_progress(move |_| assert_eq!(rayon::current_num_threads(), n_threads));
+            let (_, table) = code_finder
+                .find_best_code_and_table_simulating_n_iterations_with_rng(100, &mut rng.clone());
+            let code_and_result =
+                code_finder.find_best_code_simulating_n_events_with_rng(20, &mut rng.clone());
+            (table.to_csv(), code_and_result)
+        };
+        assert_eq!(
+            find_with(pool_with(&SINGLE_THREAD, 1)),
+            find_with(pool_with(&FOUR_THREADS, 4))
+        );
+    }
+
     #[test]
     fn reproductibility_for_finding_best_ldpc_code_simulating_n_events() {
         let rng = ChaCha8Rng::seed_from_u64(123);
@@ -376,4 +1056,269 @@ mod test {
 
         assert_eq!(code_and_result_0, code_and_result_1);
     }
+
+    #[test]
+    fn reproductibility_for_finding_best_ldpc_code_with_bsc_decoder() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let decoder = BscDecoder::with_crossover_prob(0.05);
+
+        let code_finder = BestCodeFinder::from_code_generator_and_decoder(&generator, decoder)
+            .among_n_codes(5);
+
+        let code_and_result_0 =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        let code_and_result_1 =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        assert!(code_and_result_0.0.is_some());
+        assert_eq!(code_and_result_0, code_and_result_1);
+    }
+
+    #[test]
+    fn table_has_a_row_for_each_simulated_code() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(0.25)
+            .among_n_codes(6);
+
+        let (code_and_result, table) = code_finder
+            .find_best_code_and_table_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        assert_eq!(
+            code_and_result,
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone())
+        );
+
+        let csv = table.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        for (code_index, line) in lines[1..].iter().enumerate() {
+            assert!(line.starts_with(&format!("code {},0.25,50,", code_index)));
+        }
+        let best_failures = format!(",50,{},", code_and_result.1.get_n_failures());
+        assert!(lines[1..].iter().any(|line| line.contains(&best_failures)));
+    }
+
+    #[test]
+    fn best_code_with_target_precision_reaches_the_precision() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(0.25)
+            .among_n_codes(5);
+
+        let (code, result) = code_finder
+            .find_best_code_with_target_precision_with_rng(0.1, 100_000, &mut rng.clone());
+        assert!(code.is_some());
+        assert!(result.get_relative_standard_error() <= 0.1);
+        assert_eq!(
+            (code, result),
+            code_finder.find_best_code_with_target_precision_with_rng(
+                0.1,
+                100_000,
+                &mut rng.clone()
+            )
+        );
+    }
+
+    #[test]
+    fn bayesian_rankings_dont_favor_short_simulations_without_failures() {
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let lucky = (
+            Some(generator.generate_with_rng(&mut rng)),
+            SimulationResult::with_n_successes_and_failures(20, 0),
+        );
+        let long = (
+            Some(generator.generate_with_rng(&mut rng)),
+            SimulationResult::with_n_successes_and_failures(999_999, 1),
+        );
+        let rejected = (None, SimulationResult::worse_result());
+        let prior = BetaPrior::jeffreys();
+
+        for (ranking, expected) in [
+            (CodeRanking::FailureRate, &lucky),
+            (CodeRanking::PosteriorMean(prior), &long),
+            (CodeRanking::UpperCredibleBound(prior, 0.95), &long),
+        ] {
+            let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+                .ranking_codes_by(ranking);
+            let search = BestCodeSearch::from(&code_finder, |decoder: &mut ErasureDecoder, rng| {
+                decoder.simulate_n_iterations_with_rng(10, rng)
+            });
+            for (first, second) in [(&lucky, &long), (&long, &lucky)] {
+                let best = search.get_best_between(first.clone(), second.clone());
+                assert_eq!(&best, expected);
+            }
+            let best = search.get_best_between(rejected.clone(), long.clone());
+            assert_eq!(best, long);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_credibility_panics() {
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .ranking_codes_by(CodeRanking::UpperCredibleBound(BetaPrior::uniform(), 1.0));
+    }
+
+    #[test]
+    fn time_budget_is_split_across_codes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let budget = Duration::from_millis(40);
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(0.25)
+            .among_n_codes(4 * rayon::current_num_threads())
+            .with_time_budget(budget);
+
+        assert_eq!(code_finder.get_time_budget_per_code(), budget / 4);
+        let (code, result) = code_finder.find_best_code_within_time_budget_with_rng(&mut rng);
+        assert!(code.is_some());
+        assert!(result.get_n_iterations() > 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn finding_a_code_without_time_budget_panics() {
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .among_n_codes(2)
+            .find_best_code_within_time_budget();
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_code() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let events = Mutex::new(Vec::new());
+
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(0.25)
+            .among_n_codes(8)
+            .with_progress(|event| events.lock().unwrap().push(event));
+        let (_, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        drop(code_finder);
+
+        let events = events.into_inner().unwrap();
+        let n_completed: Vec<usize> = events.iter().map(|e| e.get_n_completed_codes()).collect();
+        assert_eq!(n_completed, (1..=8).collect::<Vec<usize>>());
+        assert!(events.iter().all(|event| event.get_n_codes() == 8));
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[1].get_best_failure_rate() <= pair[0].get_best_failure_rate()));
+        assert!(events.windows(2).all(|pair| pair[0].get_elapsed() <= pair[1].get_elapsed()));
+        assert_eq!(events[7].get_best_failure_rate(), Some(result.get_failure_rate()));
+    }
+
+    struct CodeWithUnprotectedBitGenerator;
+
+    impl CodeGenerator for CodeWithUnprotectedBitGenerator {
+        fn generate_with_rng<R: Rng>(&self, _rng: &mut R) -> ParityCheckMatrix {
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]])
+        }
+    }
+
+    #[test]
+    fn codes_with_unprotected_bits_are_rejected_only_if_asked() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = CodeWithUnprotectedBitGenerator;
+
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_prob(0.1)
+            .among_n_codes(5);
+        let (code, _) = code_finder.find_best_code_simulating_n_iterations_with_rng(100, &mut rng);
+        assert!(code.is_some());
+
+        let code_finder = code_finder.rejecting_codes_with_unprotected_bits();
+        let (code, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(100, &mut rng);
+        assert!(code.is_none());
+        assert_eq!(result, SimulationResult::worse_result());
+
+        let (code, _) = code_finder.find_best_code_simulating_n_events_with_rng(10, &mut rng);
+        assert!(code.is_none());
+    }
+
+    #[test]
+    fn codes_failing_the_bit_flipping_prefilter_are_rejected() {
+        let rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 6, 20, 6);
+        let decoder = BscDecoder::with_crossover_prob(0.02);
+        let bit_flipping = BitFlippingDecoder::with_crossover_prob(0.02);
+
+        let code_finder = BestCodeFinder::from_code_generator_and_decoder(&generator, decoder)
+            .among_n_codes(5)
+            .prefiltering_with_bit_flipping(bit_flipping.clone(), 100, 1.0);
+        let (code, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        assert!(code.is_some());
+        assert_eq!(
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone()),
+            (code, result)
+        );
+
+        let code_finder = code_finder.prefiltering_with_bit_flipping(bit_flipping, 100, 0.0);
+        let (code, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(50, &mut rng.clone());
+        assert!(code.is_none());
+        assert_eq!(result, SimulationResult::worse_result());
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_prefilter_failure_rate_panics() {
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        BestCodeFinderUsingErasure::from_code_generator(&generator).prefiltering_with_bit_flipping(
+            BitFlippingDecoder::with_crossover_prob(0.1),
+            10,
+            1.5,
+        );
+    }
+
+    #[test]
+    fn codes_are_simulated_with_per_bit_erasure_probabilities() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = CodeWithUnprotectedBitGenerator;
+
+        let code_finder = BestCodeFinderUsingErasure::from_code_generator(&generator)
+            .with_erasure_probs(vec![0.5, 0.5, 0.5, 0.0])
+            .among_n_codes(2);
+        let (code, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(100, &mut rng);
+        assert!(code.is_some());
+        assert!(result.get_failure_rate() < 1.0);
+
+        let code_finder = code_finder.with_erasure_probs(vec![0.0, 0.0, 0.0, 1.0]);
+        let (_, result) =
+            code_finder.find_best_code_simulating_n_iterations_with_rng(100, &mut rng);
+        assert_eq!(result.get_failure_rate(), 1.0);
+    }
+
+    #[test]
+    fn erasure_noises_keep_the_strategy_of_the_decoder() {
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 2, 4);
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let decoder = ErasureDecoder::with_prob(0.5).with_strategy(ErasureStrategy::Peeling);
+        let code_finder =
+            || BestCodeFinder::from_code_generator_and_decoder(&generator, decoder.clone());
+        let bursts = BurstErasure::with_burst_start_prob_and_mean_length(0.1, 2.0);
+        let code_finders = vec![
+            code_finder().with_erasure_prob(0.2),
+            code_finder().with_erasure_probs(vec![0.2; 7]),
+            code_finder().with_burst_erasure(bursts),
+            code_finder().with_paired_erasure(PairedErasure::with_adjacent_pairs(7, 0.2)),
+        ];
+        for code_finder in code_finders {
+            let mut decoder = code_finder.decoder.for_code(code.clone());
+            assert!(!decoder.decode(&vec![0, 1, 2]).is_success());
+        }
+    }
 }
@@ -2,7 +2,9 @@ use crate::ParityCheckMatrix;
 use rand::{thread_rng, Rng};
 
 pub mod best_code_finder;
-pub use best_code_finder::BestCodeFinderUsingErasure;
+pub use best_code_finder::{
+    BestCodeFinder, BestCodeFinderUsingErasure, CodeRanking, ProgressEvent,
+};
 
 pub mod random_checks;
 
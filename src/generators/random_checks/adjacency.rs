@@ -70,7 +70,7 @@ impl Adjacency {
         }
     }
 
-    fn initialize_adjacent_bits_getter(&self, source_bit: usize) -> AdjacentBitsGetter {
+    fn initialize_adjacent_bits_getter(&self, source_bit: usize) -> AdjacentBitsGetter<'_> {
         AdjacentBitsGetter {
             source_bit,
             adjacent_bits: BTreeMap::new(),
@@ -74,6 +74,12 @@ pub struct Generator<R: Rng = ThreadRng> {
     random_number_generator: R,
 }
 
+impl Default for Generator<ThreadRng> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Generator<ThreadRng> {
     /// Creates a generator for empty code.
     pub fn new() -> Self {
@@ -394,7 +400,7 @@ impl<R: Rng> Generator<R> {
             .add_random_bit_to_check(check);
     }
 
-    fn get_random_bit_generator_for_check(&mut self, check: &[usize]) -> RandomBitGenerator<R> {
+    fn get_random_bit_generator_for_check(&mut self, check: &[usize]) -> RandomBitGenerator<'_, R> {
         let availables = self.get_available_bits_for_check(check);
         let distribution = self.get_distribution_over(&availables);
         RandomBitGenerator {
@@ -592,7 +598,7 @@ mod test {
 
         // A degree 1 check will not create a 4-cycle.
         let third_check = generator.set_target_check_degree(1).get_random_check();
-        assert_eq!(third_check.is_some(), true);
+        assert!(third_check.is_some());
     }
 
     #[test]
@@ -618,7 +624,7 @@ mod test {
 
         // Possible checks are [0, 4] or [3, 4]
         let fourth_check = generator.set_over_bits(vec![0, 3, 4]).get_random_check();
-        assert_eq!(fourth_check.clone().unwrap().contains(&4), true);
+        assert!(fourth_check.clone().unwrap().contains(&4));
         assert_eq!(fourth_check.unwrap().len(), 2);
     }
 
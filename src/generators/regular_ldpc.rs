@@ -30,11 +30,11 @@ impl CodeGenerator for RegularLDPCCodeGenerator {
 
 impl RegularLDPCCodeGenerator {
     pub fn n_bits(&self) -> usize {
-        (self.scale * self.check_degree) as usize
+        self.scale * self.check_degree
     }
 
     pub fn n_checks(&self) -> usize {
-        (self.scale * self.bit_degree) as usize
+        self.scale * self.bit_degree
     }
 
     pub fn new(bit_degree: usize, check_degree: usize, scale: usize, minimal_girth: usize) -> Self {
@@ -19,6 +19,7 @@ use std::ops::{Add, Mul};
 
 #[repr(u8)]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GF2 {
     B0,
     B1,
@@ -2,6 +2,7 @@
 use crate::ParityCheckMatrix;
 use crate::Pauli;
 
+#[derive(Clone)]
 pub struct GF4Stabilizers {
     x_checks: ParityCheckMatrix,
     z_checks: ParityCheckMatrix,
@@ -12,8 +12,8 @@ pub use gf2::*;
 pub mod gf4_stabilizers;
 pub use gf4_stabilizers::*;
 
-// pub mod generators;
-// pub use generators::*;
+pub mod generators;
+pub use generators::*;
 
 pub mod parity_check_matrix;
 pub use parity_check_matrix::*;
@@ -21,4 +21,7 @@ pub use parity_check_matrix::*;
 pub mod paulis;
 pub use paulis::*;
 
+pub mod seed_sequence;
+pub use seed_sequence::*;
+
 // mod sparse_matrix;
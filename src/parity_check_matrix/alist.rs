@@ -0,0 +1,226 @@
+//! Reading and writing parity check matrices in the alist format.
+//!
+//! The alist layout is
+//!
+//! ```text
+//! n_bits n_checks
+//! max_bit_degree max_check_degree
+//! bit degrees (n_bits values)
+//! check degrees (n_checks values)
+//! checks of each bit (n_bits lines, 1-based)
+//! bits of each check (n_checks lines, 1-based)
+//! ```
+//!
+//! Lists of indices may be padded with zeros up to the maximal degree.
+
+use super::{Check, ParityCheckMatrix};
+use std::io::{self, Read, Write};
+
+pub(super) struct AlistReader {
+    tokens: std::vec::IntoIter<usize>,
+}
+
+impl AlistReader {
+    pub(super) fn from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let tokens = content
+            .split_whitespace()
+            .map(|token| {
+                token
+                    .parse::<usize>()
+                    .map_err(|_| invalid_data("non integer value"))
+            })
+            .collect::<io::Result<Vec<usize>>>()?;
+        Ok(Self {
+            tokens: tokens.into_iter(),
+        })
+    }
+
+    pub(super) fn read_matrix(mut self) -> io::Result<ParityCheckMatrix> {
+        let n_bits = self.next_token()?;
+        let n_checks = self.next_token()?;
+        let _max_bit_degree = self.next_token()?;
+        let _max_check_degree = self.next_token()?;
+        let bit_degrees = self.next_tokens(n_bits)?;
+        let check_degrees = self.next_tokens(n_checks)?;
+
+        // Zeros are only padding since indices are 1-based.
+        self.tokens = self
+            .tokens
+            .filter(|index| *index != 0)
+            .collect::<Vec<usize>>()
+            .into_iter();
+
+        let bits = self.read_adjacency(&bit_degrees, n_checks)?;
+        let checks = self.read_adjacency(&check_degrees, n_bits)?;
+
+        let matrix = ParityCheckMatrix::with_n_bits(n_bits).with_checks(checks);
+        if matrix.get_transposed_matrix()
+            != ParityCheckMatrix::with_n_bits(n_checks).with_checks(bits)
+        {
+            return Err(invalid_data("bit and check lists are inconsistent"));
+        }
+        Ok(matrix)
+    }
+
+    fn read_adjacency(&mut self, degrees: &[usize], n_neighbors: usize) -> io::Result<Vec<Check>> {
+        degrees
+            .iter()
+            .map(|degree| {
+                self.next_tokens(*degree)?
+                    .into_iter()
+                    .map(|index| {
+                        if index > n_neighbors {
+                            Err(invalid_data("index out of bounds"))
+                        } else {
+                            Ok(index - 1)
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn next_token(&mut self) -> io::Result<usize> {
+        self.tokens
+            .next()
+            .ok_or_else(|| invalid_data("unexpected end of file"))
+    }
+
+    fn next_tokens(&mut self, n_tokens: usize) -> io::Result<Vec<usize>> {
+        (0..n_tokens).map(|_| self.next_token()).collect()
+    }
+}
+
+pub(super) struct AlistWriter<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> AlistWriter<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    pub(super) fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let bit_degrees = self.matrix.get_bit_degrees();
+        let check_degrees = self.matrix.get_check_degrees();
+        let max_bit_degree = bit_degrees.iter().max().cloned().unwrap_or(0);
+        let max_check_degree = check_degrees.iter().max().cloned().unwrap_or(0);
+
+        writeln!(
+            writer,
+            "{} {}",
+            self.matrix.get_n_bits(),
+            self.matrix.get_n_checks()
+        )?;
+        writeln!(writer, "{} {}", max_bit_degree, max_check_degree)?;
+        write_line(&mut writer, &bit_degrees)?;
+        write_line(&mut writer, &check_degrees)?;
+        for bit in self.matrix.get_transposed_matrix().checks_iter() {
+            write_padded_indices(&mut writer, bit.as_ref(), max_bit_degree)?;
+        }
+        for check in self.matrix.checks_iter() {
+            write_padded_indices(&mut writer, check.as_ref(), max_check_degree)?;
+        }
+        writer.flush()
+    }
+}
+
+fn write_padded_indices<W: Write>(
+    writer: &mut W,
+    indices: &[usize],
+    width: usize,
+) -> io::Result<()> {
+    let padded: Vec<usize> = indices
+        .iter()
+        .map(|index| index + 1)
+        .chain(std::iter::repeat(0))
+        .take(width)
+        .collect();
+    write_line(writer, &padded)
+}
+
+fn write_line<W: Write>(writer: &mut W, values: &[usize]) -> io::Result<()> {
+    let line: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    writeln!(writer, "{}", line.join(" "))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    #[test]
+    fn write_hamming_code() {
+        let mut output = Vec::new();
+        AlistWriter::from(&hamming_code())
+            .write_to(&mut output)
+            .unwrap();
+        let expected = "7 3\n\
+                        3 4\n\
+                        3 2 2 2 1 1 1\n\
+                        4 4 4\n\
+                        1 2 3\n\
+                        1 2 0\n\
+                        1 3 0\n\
+                        2 3 0\n\
+                        1 0 0\n\
+                        2 0 0\n\
+                        3 0 0\n\
+                        1 2 3 5\n\
+                        1 2 4 6\n\
+                        1 3 4 7\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn read_without_padding() {
+        let input = "7 3\n3 4\n3 2 2 2 1 1 1\n4 4 4\n\
+                     1 2 3\n1 2\n1 3\n2 3\n1\n2\n3\n\
+                     1 2 3 5\n1 2 4 6\n1 3 4 7\n";
+        let matrix = AlistReader::from(input.as_bytes())
+            .unwrap()
+            .read_matrix()
+            .unwrap();
+        assert_eq!(matrix, hamming_code());
+    }
+
+    #[test]
+    fn round_trip() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(6).with_checks(vec![vec![0, 1], vec![], vec![1, 2, 4]]);
+        let mut output = Vec::new();
+        AlistWriter::from(&matrix).write_to(&mut output).unwrap();
+        let read = AlistReader::from(output.as_slice())
+            .unwrap()
+            .read_matrix()
+            .unwrap();
+        assert_eq!(read, matrix);
+    }
+
+    #[test]
+    fn inconsistent_lists_are_rejected() {
+        let input = "3 1\n1 2\n1 1 0\n2\n1\n1\n1 3\n";
+        let result = AlistReader::from(input.as_bytes()).unwrap().read_matrix();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let input = "7 3\n3 4\n3 2 2";
+        let result = AlistReader::from(input.as_bytes()).unwrap().read_matrix();
+        assert!(result.is_err());
+    }
+}
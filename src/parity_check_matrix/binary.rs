@@ -0,0 +1,228 @@
+//! Reading and writing parity check matrices in a compact binary format.
+//!
+//! All values are little endian `u32`s and the layout is
+//!
+//! ```text
+//! magic (the 4 bytes "BLVR")
+//! version
+//! n_checks n_bits n_edges
+//! check degrees (n_checks values)
+//! bits of each check (n_edges values, 0-based)
+//! ```
+//!
+//! A reader never consumes bytes after the end of a matrix, so several matrices can be written
+//! to the same stream one after the other.
+
+use super::ParityCheckMatrix;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"BLVR";
+const VERSION: u32 = 1;
+
+pub(super) struct BinaryReader<R> {
+    reader: R,
+}
+
+impl<R: Read> BinaryReader<R> {
+    pub(super) fn from(reader: R) -> Self {
+        Self { reader }
+    }
+
+    pub(super) fn read_matrix(mut self) -> io::Result<ParityCheckMatrix> {
+        self.read_header()?;
+        let n_checks = self.next_value()?;
+        let n_bits = self.next_value()?;
+        let n_edges = self.next_value()?;
+        let check_degrees = self.next_values(n_checks)?;
+        if check_degrees.iter().sum::<usize>() != n_edges {
+            return Err(invalid_data(
+                "check degrees don't sum to the number of edges",
+            ));
+        }
+        let checks = check_degrees
+            .iter()
+            .map(|degree| self.read_check(*degree, n_bits))
+            .collect::<io::Result<Vec<_>>>()?;
+        let matrix = ParityCheckMatrix::with_n_bits(n_bits).with_checks(checks);
+        if matrix.get_n_edges() != n_edges {
+            return Err(invalid_data("some checks contain the same bit twice"));
+        }
+        Ok(matrix)
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        let mut magic = [0; 4];
+        self.reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("invalid magic number"));
+        }
+        match self.next_value()? {
+            version if version == VERSION as usize => Ok(()),
+            _ => Err(invalid_data("unsupported version")),
+        }
+    }
+
+    fn read_check(&mut self, degree: usize, n_bits: usize) -> io::Result<Vec<usize>> {
+        let bits = self.next_values(degree)?;
+        if bits.iter().any(|bit| *bit >= n_bits) {
+            return Err(invalid_data("index out of bounds"));
+        }
+        Ok(bits)
+    }
+
+    fn next_value(&mut self) -> io::Result<usize> {
+        let mut bytes = [0; 4];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    // Values are read one by one so that a corrupted length doesn't allocate a huge buffer.
+    fn next_values(&mut self, n_values: usize) -> io::Result<Vec<usize>> {
+        (0..n_values).map(|_| self.next_value()).collect()
+    }
+}
+
+pub(super) struct BinaryWriter<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> BinaryWriter<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    pub(super) fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_value(&mut writer, VERSION as usize)?;
+        write_value(&mut writer, self.matrix.get_n_checks())?;
+        write_value(&mut writer, self.matrix.get_n_bits())?;
+        write_value(&mut writer, self.matrix.get_n_edges())?;
+        for degree in self.matrix.get_check_degrees() {
+            write_value(&mut writer, degree)?;
+        }
+        for (_, bit) in self.matrix.edges_iter() {
+            write_value(&mut writer, bit)?;
+        }
+        writer.flush()
+    }
+}
+
+fn write_value<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "matrix is too large for the binary format",
+        )
+    })?;
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matrix() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(6).with_checks(vec![vec![0, 1, 4], vec![], vec![1, 2]])
+    }
+
+    fn write(matrix: &ParityCheckMatrix) -> Vec<u8> {
+        let mut output = Vec::new();
+        BinaryWriter::from(matrix).write_to(&mut output).unwrap();
+        output
+    }
+
+    fn values(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn write_small_matrix() {
+        let expected = [
+            MAGIC.to_vec(),
+            values(&[1, 3, 6, 5, 3, 0, 2, 0, 1, 4, 1, 2]),
+        ]
+        .concat();
+        assert_eq!(write(&matrix()), expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        for matrix in [
+            matrix(),
+            ParityCheckMatrix::new(),
+            ParityCheckMatrix::with_n_bits(3),
+            ParityCheckMatrix::hamming_code_with_n_checks(4),
+        ] {
+            let output = write(&matrix);
+            let read = BinaryReader::from(output.as_slice()).read_matrix().unwrap();
+            assert_eq!(read, matrix);
+        }
+    }
+
+    #[test]
+    fn consecutive_matrices_can_be_read_back() {
+        let hamming_code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let output = [write(&matrix()), write(&hamming_code)].concat();
+        let mut input = output.as_slice();
+
+        assert_eq!(
+            BinaryReader::from(&mut input).read_matrix().unwrap(),
+            matrix()
+        );
+        assert_eq!(
+            BinaryReader::from(&mut input).read_matrix().unwrap(),
+            hamming_code
+        );
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let output = write(&matrix());
+        for length in 0..output.len() {
+            let result = BinaryReader::from(&output[..length]).read_matrix();
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn wrong_magic_is_rejected() {
+        let mut output = write(&matrix());
+        output[0] = b'X';
+        let result = BinaryReader::from(output.as_slice()).read_matrix();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let input = [MAGIC.to_vec(), values(&[2, 0, 0, 0])].concat();
+        let result = BinaryReader::from(input.as_slice()).read_matrix();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn out_of_bounds_and_duplicated_bits_are_rejected() {
+        let out_of_bounds = [MAGIC.to_vec(), values(&[1, 1, 2, 1, 1, 2])].concat();
+        assert!(BinaryReader::from(out_of_bounds.as_slice())
+            .read_matrix()
+            .is_err());
+
+        let duplicated = [MAGIC.to_vec(), values(&[1, 1, 2, 2, 2, 1, 1])].concat();
+        assert!(BinaryReader::from(duplicated.as_slice())
+            .read_matrix()
+            .is_err());
+
+        let wrong_n_edges = [MAGIC.to_vec(), values(&[1, 1, 2, 2, 1, 1])].concat();
+        assert!(BinaryReader::from(wrong_n_edges.as_slice())
+            .read_matrix()
+            .is_err());
+    }
+}
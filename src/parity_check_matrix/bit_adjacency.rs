@@ -0,0 +1,100 @@
+use super::ParityCheckMatrix;
+
+// The column major companion of a parity check matrix. For each bit, it stores the sorted list of
+// checks connected to that bit.
+#[derive(Debug, Clone)]
+pub(super) struct BitAdjacency {
+    bit_ranges: Vec<usize>,
+    check_indices: Vec<usize>,
+}
+
+impl BitAdjacency {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        let bit_ranges = Self::get_bit_ranges_of(matrix);
+        let check_indices = Self::get_check_indices_of(matrix, &bit_ranges);
+        Self {
+            bit_ranges,
+            check_indices,
+        }
+    }
+
+    fn get_bit_ranges_of(matrix: &ParityCheckMatrix) -> Vec<usize> {
+        let mut bit_ranges = Vec::with_capacity(matrix.get_n_bits() + 1);
+        bit_ranges.push(0);
+        matrix.get_bit_degrees().iter().for_each(|degree| {
+            bit_ranges.push(bit_ranges.last().unwrap_or(&0) + degree);
+        });
+        bit_ranges
+    }
+
+    // Checks are visited in increasing order, so the checks of each bit end up sorted.
+    fn get_check_indices_of(matrix: &ParityCheckMatrix, bit_ranges: &[usize]) -> Vec<usize> {
+        let mut positions = bit_ranges.to_vec();
+        let mut check_indices = vec![0; matrix.get_n_edges()];
+        matrix.edges_iter().for_each(|(check, bit)| {
+            check_indices[positions[bit]] = check;
+            positions[bit] += 1;
+        });
+        check_indices
+    }
+
+    pub(super) fn get_checks_adjacent_to(&self, bit: usize) -> Option<&[usize]> {
+        self.bit_ranges.get(bit).and_then(|&bit_start| {
+            self.bit_ranges
+                .get(bit + 1)
+                .map(|&bit_end| &self.check_indices[bit_start..bit_end])
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn adjacency_of_empty_matrix() {
+        let adjacency = BitAdjacency::from(&ParityCheckMatrix::new());
+        assert_eq!(adjacency.get_checks_adjacent_to(0), None);
+    }
+
+    #[test]
+    fn adjacency_with_unconnected_bits() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 3],
+            vec![],
+            vec![0, 1, 3],
+        ]);
+        let adjacency = BitAdjacency::from(&matrix);
+
+        assert_eq!(adjacency.get_checks_adjacent_to(0), Some(&[0, 2][..]));
+        assert_eq!(adjacency.get_checks_adjacent_to(1), Some(&[2][..]));
+        assert_eq!(adjacency.get_checks_adjacent_to(2), Some(&[][..]));
+        assert_eq!(adjacency.get_checks_adjacent_to(3), Some(&[0, 2][..]));
+        assert_eq!(adjacency.get_checks_adjacent_to(4), Some(&[][..]));
+        assert_eq!(adjacency.get_checks_adjacent_to(5), None);
+    }
+
+    #[test]
+    fn adjacency_visits_the_same_edges_as_the_checks() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![0, 1, 4],
+            vec![2, 3, 5],
+            vec![1, 2],
+            vec![0, 5],
+        ]);
+        let adjacency = BitAdjacency::from(&matrix);
+
+        let mut edges: Vec<(usize, usize)> = (0..matrix.get_n_bits())
+            .flat_map(|bit| {
+                adjacency
+                    .get_checks_adjacent_to(bit)
+                    .unwrap()
+                    .iter()
+                    .map(move |check| (*check, bit))
+            })
+            .collect();
+        edges.sort();
+
+        assert_eq!(edges, matrix.edges_iter().collect::<Vec<_>>());
+    }
+}
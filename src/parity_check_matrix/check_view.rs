@@ -29,7 +29,7 @@ impl<'a> CheckView<'a> {
     }
 
     /// An iterator over the bits in `self`.
-    pub fn iter(&self) -> std::slice::Iter<usize> {
+    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
         self.bits.iter()
     }
 
@@ -53,6 +53,20 @@ impl<'a> Concatener<'a> {
             .collect()
     }
 
+    pub(super) fn concat_vertically(&self) -> ParityCheckMatrix {
+        let n_bits = std::cmp::max(self.left_matrix.get_n_bits(), self.right_matrix.get_n_bits());
+        let checks = self.get_checks_of_vertical_concatenation();
+        ParityCheckMatrix::with_n_bits(n_bits).with_checks(checks)
+    }
+
+    fn get_checks_of_vertical_concatenation(&self) -> Vec<Check> {
+        self.left_matrix
+            .checks_iter()
+            .chain(self.right_matrix.checks_iter())
+            .map(|check| check.to_vec())
+            .collect()
+    }
+
     pub(super) fn concat_diagonally(&self) -> ParityCheckMatrix {
         let n_bits = self.left_matrix.get_n_bits() + self.right_matrix.get_n_bits();
         let checks = self.get_checks_of_diagonal_concatenation();
@@ -165,6 +179,55 @@ mod test {
         assert_eq!(concatened, expected);
     }
 
+    #[test]
+    fn horizontal_concat_with_empty_checks() {
+        let left_matrix =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![], vec![2]]);
+        let right_matrix =
+            ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![], vec![], vec![0, 1], vec![]]);
+
+        let concatened = Concatener::from(&left_matrix, &right_matrix).concat_horizontally();
+        let expected = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1],
+            vec![],
+            vec![2, 3, 4],
+            vec![],
+        ]);
+
+        assert_eq!(concatened, expected);
+        assert_eq!(concatened.get_n_checks(), 4);
+    }
+
+    #[test]
+    fn vertical_concat_with_empty_matrix() {
+        let top_matrix =
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
+        let bottom_matrix = ParityCheckMatrix::new();
+
+        let concatened = Concatener::from(&top_matrix, &bottom_matrix).concat_vertically();
+
+        assert_eq!(concatened, top_matrix);
+    }
+
+    #[test]
+    fn vertical_concat_with_different_number_of_bits() {
+        let top_matrix =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![], vec![1, 2]]);
+        let bottom_matrix =
+            ParityCheckMatrix::with_n_bits(5).with_checks(vec![vec![2, 4], vec![0, 3]]);
+
+        let concatened = Concatener::from(&top_matrix, &bottom_matrix).concat_vertically();
+        let expected = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1],
+            vec![],
+            vec![1, 2],
+            vec![2, 4],
+            vec![0, 3],
+        ]);
+
+        assert_eq!(concatened, expected);
+    }
+
     #[test]
     fn diagonal_concat_with_empty_matrix() {
         let left_matrix =
@@ -206,4 +269,21 @@ mod test {
 
         assert_eq!(concatened, expected);
     }
+
+    #[test]
+    fn diagonal_concat_shifts_right_bits_past_unused_left_bits() {
+        let left_matrix =
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let right_matrix = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![0, 1]]);
+
+        let concatened = Concatener::from(&left_matrix, &right_matrix).concat_diagonally();
+        let expected = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![0, 1],
+            vec![1, 2],
+            vec![4, 5],
+        ]);
+
+        assert_eq!(concatened, expected);
+        assert_eq!(concatened.get_checks_adjacent_to_bit(3), Some(&[][..]));
+    }
 }
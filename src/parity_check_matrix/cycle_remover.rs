@@ -0,0 +1,162 @@
+use super::{Check, ParityCheckMatrix};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// A tool to remove the 4-cycles of a parity check matrix while preserving all degrees.
+//
+// At each pass, an edge (check, bit) of a random 4-cycle is swapped with a random other edge
+// (other_check, other_bit) to give the edges (check, other_bit) and (other_check, bit). The
+// swap is kept only if it reduces the number of 4-cycles.
+pub(super) struct CycleRemover {
+    checks: Vec<Check>,
+    n_bits: usize,
+    n_cycles: usize,
+}
+
+impl CycleRemover {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        Self {
+            checks: matrix.checks_iter().map(|check| check.to_vec()).collect(),
+            n_bits: matrix.get_n_bits(),
+            n_cycles: matrix.get_n_4_cycles(),
+        }
+    }
+
+    pub(super) fn remove_cycles_with_rng<R: Rng>(
+        mut self,
+        rng: &mut R,
+        max_passes: usize,
+    ) -> (ParityCheckMatrix, bool) {
+        for _ in 0..max_passes {
+            if self.n_cycles == 0 {
+                break;
+            }
+            self.try_to_remove_a_cycle_with_rng(rng);
+        }
+        let is_cycle_free = self.n_cycles == 0;
+        (self.get_matrix(), is_cycle_free)
+    }
+
+    fn get_matrix(&self) -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(self.n_bits).with_checks(self.checks.clone())
+    }
+
+    fn try_to_remove_a_cycle_with_rng<R: Rng>(&mut self, rng: &mut R) {
+        let edge = self.get_random_cycle_edge_with_rng(rng);
+        let other_edge = self.get_random_edge_with_rng(rng);
+        if let (Some(edge), Some(other_edge)) = (edge, other_edge) {
+            if self.can_swap(edge, other_edge) {
+                self.swap(edge, other_edge);
+                let n_cycles = self.get_matrix().get_n_4_cycles();
+                if n_cycles < self.n_cycles {
+                    self.n_cycles = n_cycles;
+                } else {
+                    self.swap((edge.0, other_edge.1), (other_edge.0, edge.1));
+                }
+            }
+        }
+    }
+
+    fn get_random_cycle_edge_with_rng<R: Rng>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        self.get_matrix()
+            .get_4_cycles()
+            .choose(rng)
+            .map(|&(check_0, check_1, bit_0, bit_1)| {
+                let check = if rng.gen() { check_0 } else { check_1 };
+                let bit = if rng.gen() { bit_0 } else { bit_1 };
+                (check, bit)
+            })
+    }
+
+    fn get_random_edge_with_rng<R: Rng>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        let edges: Vec<(usize, usize)> = self
+            .checks
+            .iter()
+            .enumerate()
+            .flat_map(|(check, bits)| bits.iter().map(move |bit| (check, *bit)))
+            .collect();
+        edges.choose(rng).cloned()
+    }
+
+    // A swap is only possible if it doesn't create an edge that is already there.
+    fn can_swap(&self, edge: (usize, usize), other_edge: (usize, usize)) -> bool {
+        let (check, bit) = edge;
+        let (other_check, other_bit) = other_edge;
+        check != other_check
+            && bit != other_bit
+            && !self.checks[check].contains(&other_bit)
+            && !self.checks[other_check].contains(&bit)
+    }
+
+    fn swap(&mut self, edge: (usize, usize), other_edge: (usize, usize)) {
+        let (check, bit) = edge;
+        let (other_check, other_bit) = other_edge;
+        self.replace_bit(check, bit, other_bit);
+        self.replace_bit(other_check, other_bit, bit);
+    }
+
+    fn replace_bit(&mut self, check: usize, bit: usize, new_bit: usize) {
+        if let Some(position) = self.checks[check].iter().position(|b| *b == bit) {
+            self.checks[check][position] = new_bit;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn cycle_free_matrix_is_unchanged() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![2, 3],
+            vec![3, 4],
+        ]);
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let (result, is_cycle_free) =
+            CycleRemover::from(&matrix).remove_cycles_with_rng(&mut rng, 10);
+
+        assert!(is_cycle_free);
+        assert_eq!(result, matrix);
+    }
+
+    #[test]
+    fn removing_cycles_preserves_degrees() {
+        // Two copies of the same 3 checks give many 4-cycles.
+        let checks = vec![
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+            vec![0, 3, 6],
+            vec![1, 4, 7],
+            vec![2, 5, 8],
+            vec![0, 1, 2],
+            vec![3, 4, 5],
+            vec![6, 7, 8],
+        ];
+        let matrix = ParityCheckMatrix::with_n_bits(9).with_checks(checks);
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let (result, is_cycle_free) =
+            CycleRemover::from(&matrix).remove_cycles_with_rng(&mut rng, 1000);
+
+        assert_eq!(result.get_bit_degrees(), matrix.get_bit_degrees());
+        assert_eq!(result.get_check_degrees(), matrix.get_check_degrees());
+        assert!(result.get_n_4_cycles() < matrix.get_n_4_cycles());
+        assert_eq!(is_cycle_free, result.get_n_4_cycles() == 0);
+    }
+
+    #[test]
+    fn no_pass_leaves_the_cycles() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![0, 1], vec![2, 3]]);
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let (result, is_cycle_free) =
+            CycleRemover::from(&matrix).remove_cycles_with_rng(&mut rng, 0);
+
+        assert!(!is_cycle_free);
+        assert_eq!(result, matrix);
+    }
+}
@@ -0,0 +1,124 @@
+//! The node perspective degree distribution of a parity check matrix.
+//!
+//! # Example
+//!
+//! ```
+//! # use believer::ParityCheckMatrix;
+//! let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![0, 2, 3]]);
+//! let distribution = matrix.get_degree_distribution();
+//!
+//! assert_eq!(distribution.get_bit_fractions(), &[0.0, 0.75, 0.25]);
+//! assert_eq!(distribution.get_check_fractions(), &[0.0, 0.0, 0.5, 0.5]);
+//! ```
+
+use super::ParityCheckMatrix;
+
+/// The fractions of bits and checks of each degree in a parity check matrix.
+///
+/// The fraction of bits of degree `d` is the `d`-th element of the bit fractions
+/// and likewise for the checks. Unconnected bits and empty checks are counted as
+/// nodes of degree 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegreeDistribution {
+    bit_fractions: Vec<f64>,
+    check_fractions: Vec<f64>,
+}
+
+impl DegreeDistribution {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        Self {
+            bit_fractions: get_fractions_of(&matrix.get_bit_degrees()),
+            check_fractions: get_fractions_of(&matrix.get_check_degrees()),
+        }
+    }
+
+    /// Returns the fraction of bits of each degree, also known as lambda.
+    pub fn get_bit_fractions(&self) -> &[f64] {
+        &self.bit_fractions
+    }
+
+    /// Returns the fraction of checks of each degree, also known as rho.
+    pub fn get_check_fractions(&self) -> &[f64] {
+        &self.check_fractions
+    }
+
+    /// Returns the fraction of bits of the given `degree`.
+    pub fn get_bit_fraction_of_degree(&self, degree: usize) -> f64 {
+        self.bit_fractions.get(degree).cloned().unwrap_or(0.0)
+    }
+
+    /// Returns the fraction of checks of the given `degree`.
+    pub fn get_check_fraction_of_degree(&self, degree: usize) -> f64 {
+        self.check_fractions.get(degree).cloned().unwrap_or(0.0)
+    }
+
+    /// Checks if some bits are not connected to any check.
+    pub fn has_unconnected_bits(&self) -> bool {
+        self.get_bit_fraction_of_degree(0) > 0.0
+    }
+
+    /// Checks if all bits have degree `bit_degree` and all checks have degree
+    /// `check_degree`.
+    pub fn is_regular_with_degrees(&self, bit_degree: usize, check_degree: usize) -> bool {
+        self.get_bit_fraction_of_degree(bit_degree) == 1.0
+            && self.get_check_fraction_of_degree(check_degree) == 1.0
+    }
+}
+
+fn get_fractions_of(degrees: &[usize]) -> Vec<f64> {
+    if degrees.is_empty() {
+        return Vec::new();
+    }
+    let max_degree = degrees.iter().max().cloned().unwrap_or(0);
+    let mut counts = vec![0; max_degree + 1];
+    degrees.iter().for_each(|degree| counts[*degree] += 1);
+    counts
+        .into_iter()
+        .map(|count| count as f64 / degrees.len() as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distribution_of_empty_matrix() {
+        let distribution = DegreeDistribution::from(&ParityCheckMatrix::new());
+        assert!(distribution.get_bit_fractions().is_empty());
+        assert!(distribution.get_check_fractions().is_empty());
+        assert!(!distribution.has_unconnected_bits());
+    }
+
+    #[test]
+    fn unconnected_bits_and_empty_checks_have_degree_0() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![]]);
+        let distribution = DegreeDistribution::from(&matrix);
+
+        assert_eq!(distribution.get_bit_fractions(), &[0.5, 0.5]);
+        assert_eq!(distribution.get_check_fractions(), &[0.5, 0.0, 0.5]);
+        assert!(distribution.has_unconnected_bits());
+    }
+
+    #[test]
+    fn regular_matrix_with_many_bits() {
+        let checks = (0..10).map(|check| vec![check, (check + 1) % 10]).collect();
+        let matrix = ParityCheckMatrix::with_n_bits(10).with_checks(checks);
+        assert!(DegreeDistribution::from(&matrix).is_regular_with_degrees(2, 2));
+    }
+
+    #[test]
+    fn regular_matrix() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![0, 2, 3],
+            vec![0, 1, 3],
+        ]);
+        let distribution = DegreeDistribution::from(&matrix);
+
+        assert!(distribution.is_regular_with_degrees(3, 3));
+        assert!(!distribution.is_regular_with_degrees(3, 4));
+        assert!(!distribution.has_unconnected_bits());
+    }
+}
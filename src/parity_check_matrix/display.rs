@@ -0,0 +1,89 @@
+use super::ParityCheckMatrix;
+use std::fmt;
+
+// Matrices with more bits than this are displayed as a list of checks.
+const MAX_N_BITS_FOR_DENSE_DISPLAY: usize = 40;
+
+impl fmt::Display for ParityCheckMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Parity check matrix with {} bits, {} checks and {} edges",
+            self.get_n_bits(),
+            self.get_n_checks(),
+            self.get_n_edges()
+        )?;
+        if self.get_n_bits() <= MAX_N_BITS_FOR_DENSE_DISPLAY {
+            write_dense_checks(self, f)
+        } else {
+            write_sparse_checks(self, f)
+        }
+    }
+}
+
+fn write_dense_checks(matrix: &ParityCheckMatrix, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for check in matrix.checks_iter() {
+        let mut row = vec!["0"; matrix.get_n_bits()];
+        check.iter().for_each(|bit| row[*bit] = "1");
+        write!(f, "\n{}", row.join(" "))?;
+    }
+    Ok(())
+}
+
+fn write_sparse_checks(matrix: &ParityCheckMatrix, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, check) in matrix.checks_iter().enumerate() {
+        if check.as_ref().is_empty() {
+            write!(f, "\ncheck {}: no bits", index)?;
+        } else {
+            let bits: Vec<String> = check.iter().map(|bit| bit.to_string()).collect();
+            write!(f, "\ncheck {}: bits {}", index, bits.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+// Shows the internal representation, leaving out the cached bit adjacency.
+impl fmt::Debug for ParityCheckMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParityCheckMatrix")
+            .field("n_bits", &self.n_bits)
+            .field("check_ranges", &self.check_ranges)
+            .field("bit_indices", &self.bit_indices)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matrix() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]])
+    }
+
+    #[test]
+    fn small_matrix_is_displayed_densely() {
+        let expected = "Parity check matrix with 3 bits, 2 checks and 4 edges\n\
+                        1 1 0\n\
+                        0 1 1";
+        assert_eq!(matrix().to_string(), expected);
+    }
+
+    #[test]
+    fn large_matrix_is_displayed_sparsely() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(50).with_checks(vec![vec![1, 7, 12], vec![], vec![49]]);
+        let expected = "Parity check matrix with 50 bits, 3 checks and 4 edges\n\
+                        check 0: bits 1, 7, 12\n\
+                        check 1: no bits\n\
+                        check 2: bits 49";
+        assert_eq!(matrix.to_string(), expected);
+    }
+
+    #[test]
+    fn debug_shows_internal_representation() {
+        let expected =
+            "ParityCheckMatrix { n_bits: 3, check_ranges: [0, 2, 4], bit_indices: [0, 1, 1, 2] }";
+        assert_eq!(format!("{:?}", matrix()), expected);
+    }
+}
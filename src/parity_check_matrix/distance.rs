@@ -0,0 +1,154 @@
+use super::get_bitwise_sum;
+use super::ParityCheckMatrix;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// A tool to find the minimum distance of small codes.
+//
+// A set of bits is the support of a codeword if the sum of the checks connected to each of
+// these bits is empty. Sets of bits are enumerated by increasing size and the sums are built
+// incrementally while going down the search tree.
+pub(super) struct DistanceFinder {
+    bit_columns: Vec<Vec<usize>>,
+}
+
+impl DistanceFinder {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        Self {
+            bit_columns: matrix
+                .get_transposed_matrix()
+                .checks_iter()
+                .map(|bit| bit.to_vec())
+                .collect(),
+        }
+    }
+
+    pub(super) fn get_minimum_distance_up_to(&self, max_weight: usize) -> Option<usize> {
+        (1..=std::cmp::min(max_weight, self.get_n_bits()))
+            .find(|weight| self.has_codeword_of_weight(*weight))
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.bit_columns.len()
+    }
+
+    fn has_codeword_of_weight(&self, weight: usize) -> bool {
+        self.can_complete_codeword(0, weight, &[])
+    }
+
+    fn can_complete_codeword(
+        &self,
+        first_bit: usize,
+        n_missing_bits: usize,
+        sum: &[usize],
+    ) -> bool {
+        if n_missing_bits == 0 {
+            return sum.is_empty();
+        }
+        (first_bit..=self.get_n_bits() - n_missing_bits).any(|bit| {
+            let sum = get_bitwise_sum(sum, &self.bit_columns[bit]);
+            self.can_complete_codeword(bit + 1, n_missing_bits - 1, &sum)
+        })
+    }
+}
+
+// Finds low weight codewords of large codes by sampling random information sets.
+//
+// For a random order of the bits, the generator matrix obtained from the reduced row echelon
+// form has a generator for each information bit that is only supported on this bit and on the
+// pivots. Codewords of low weight are likely to show up as one of these generators when most of
+// their support falls on the pivots.
+pub(super) struct InformationSetSampler<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> InformationSetSampler<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    pub(super) fn get_lowest_weight_with_rng<R: Rng>(
+        &self,
+        n_trials: usize,
+        rng: &mut R,
+    ) -> Option<usize> {
+        let mut permutation: Vec<usize> = (0..self.matrix.get_n_bits()).collect();
+        (0..n_trials)
+            .filter_map(|_| {
+                permutation.shuffle(rng);
+                self.matrix
+                    .get_bits_permuted_by(&permutation)
+                    .get_generator_matrix()
+                    .get_check_degrees()
+                    .into_iter()
+                    .min()
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn distance_of_hamming_code() {
+        let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let finder = DistanceFinder::from(&matrix);
+
+        assert_eq!(finder.get_minimum_distance_up_to(7), Some(3));
+        assert_eq!(finder.get_minimum_distance_up_to(3), Some(3));
+        assert_eq!(finder.get_minimum_distance_up_to(2), None);
+    }
+
+    #[test]
+    fn unchecked_bit_is_a_codeword_of_weight_1() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1]]);
+        assert_eq!(
+            DistanceFinder::from(&matrix).get_minimum_distance_up_to(3),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn full_rank_matrix_has_no_codeword() {
+        let matrix = ParityCheckMatrix::identity_with_n_bits(4);
+        assert_eq!(
+            DistanceFinder::from(&matrix).get_minimum_distance_up_to(10),
+            None
+        );
+    }
+
+    #[test]
+    fn information_sets_give_an_upper_bound_on_the_distance() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let matrix = ParityCheckMatrix::random_with_density_with_rng(20, 10, 0.3, true, &mut rng);
+        let distance = matrix.get_minimum_distance().unwrap();
+        let sampler = InformationSetSampler::from(&matrix);
+
+        for n_trials in 1..5 {
+            let approximation = sampler.get_lowest_weight_with_rng(n_trials, &mut rng).unwrap();
+            assert!(approximation >= distance);
+        }
+        assert_eq!(sampler.get_lowest_weight_with_rng(50, &mut rng), Some(distance));
+    }
+
+    #[test]
+    fn no_trials_or_no_codewords_give_none() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let sampler = InformationSetSampler::from(&repetition_code);
+        assert_eq!(sampler.get_lowest_weight_with_rng(0, &mut rng), None);
+        assert_eq!(sampler.get_lowest_weight_with_rng(1, &mut rng), Some(4));
+
+        let identity = ParityCheckMatrix::identity_with_n_bits(4);
+        let sampler = InformationSetSampler::from(&identity);
+        assert_eq!(sampler.get_lowest_weight_with_rng(10, &mut rng), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Writing the Tanner graph of a parity check matrix in the Graphviz DOT format.
+//!
+//! Bits are drawn as circles labeled `b0`, `b1`, ... and checks as squares labeled `c0`, `c1`,
+//! .... Bits and checks are laid out in two columns and highlighted bits are filled in red.
+
+use super::ParityCheckMatrix;
+use std::io::{self, Write};
+
+const HIGHLIGHT_COLOR: &str = "red";
+
+pub(super) struct DotWriter<'a> {
+    matrix: &'a ParityCheckMatrix,
+    is_highlighted: Vec<bool>,
+}
+
+impl<'a> DotWriter<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self {
+            matrix,
+            is_highlighted: vec![false; matrix.get_n_bits()],
+        }
+    }
+
+    pub(super) fn highlighting(mut self, bits: &[usize]) -> Self {
+        bits.iter()
+            .for_each(|bit| match self.is_highlighted.get_mut(*bit) {
+                Some(is_highlighted) => *is_highlighted = true,
+                None => panic!("some bits are out of bounds"),
+            });
+        self
+    }
+
+    pub(super) fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "graph tanner {{")?;
+        writeln!(writer, "    rankdir=LR;")?;
+        self.write_bits(&mut writer)?;
+        self.write_checks(&mut writer)?;
+        for (check, bit) in self.matrix.edges_iter() {
+            writeln!(writer, "    b{} -- c{};", bit, check)?;
+        }
+        writeln!(writer, "}}")?;
+        writer.flush()
+    }
+
+    pub(super) fn get_string(&self) -> String {
+        let mut output = Vec::new();
+        self.write_to(&mut output)
+            .expect("writing to a vector can't fail");
+        String::from_utf8(output).expect("output is valid utf8")
+    }
+
+    fn write_bits<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "    subgraph bits {{")?;
+        writeln!(writer, "        rank=same;")?;
+        for (bit, is_highlighted) in self.is_highlighted.iter().enumerate() {
+            if *is_highlighted {
+                writeln!(
+                    writer,
+                    "        b{0} [label=\"b{0}\", shape=circle, style=filled, fillcolor={1}];",
+                    bit, HIGHLIGHT_COLOR
+                )?;
+            } else {
+                writeln!(writer, "        b{0} [label=\"b{0}\", shape=circle];", bit)?;
+            }
+        }
+        writeln!(writer, "    }}")
+    }
+
+    fn write_checks<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "    subgraph checks {{")?;
+        writeln!(writer, "        rank=same;")?;
+        for check in 0..self.matrix.get_n_checks() {
+            writeln!(
+                writer,
+                "        c{0} [label=\"c{0}\", shape=square];",
+                check
+            )?;
+        }
+        writeln!(writer, "    }}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn repetition_code_golden_output() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let expected = "graph tanner {
+    rankdir=LR;
+    subgraph bits {
+        rank=same;
+        b0 [label=\"b0\", shape=circle];
+        b1 [label=\"b1\", shape=circle];
+        b2 [label=\"b2\", shape=circle];
+    }
+    subgraph checks {
+        rank=same;
+        c0 [label=\"c0\", shape=square];
+        c1 [label=\"c1\", shape=square];
+    }
+    b0 -- c0;
+    b1 -- c0;
+    b1 -- c1;
+    b2 -- c1;
+}
+";
+        assert_eq!(DotWriter::from(&code).get_string(), expected);
+    }
+
+    #[test]
+    fn highlighted_bits_are_filled() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let output = DotWriter::from(&code).highlighting(&[0, 2]).get_string();
+
+        let filled_lines: Vec<&str> = output
+            .lines()
+            .filter(|line| line.contains("fillcolor=red"))
+            .map(str::trim)
+            .collect();
+        assert_eq!(
+            filled_lines,
+            vec![
+                "b0 [label=\"b0\", shape=circle, style=filled, fillcolor=red];",
+                "b2 [label=\"b2\", shape=circle, style=filled, fillcolor=red];",
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn highlighting_out_of_bounds_bit_panics() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        DotWriter::from(&code).highlighting(&[3]);
+    }
+}
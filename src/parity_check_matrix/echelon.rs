@@ -0,0 +1,176 @@
+use super::get_bitwise_sum;
+use super::ParityCheckMatrix;
+
+type Row = Vec<usize>;
+
+// Computes the reduced row echelon form in two passes. First, each row is reduced against the
+// pivots found so far, as done to compute the rank. Then, pivots are visited from the last
+// column to the first and the other pivot columns are cleared from them. Since the pivots
+// visited before are already reduced, clearing a pivot column never adds another one.
+pub(super) struct EchelonReducer {
+    n_bits: usize,
+    pivots: Vec<Option<Row>>,
+}
+
+impl EchelonReducer {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        let mut reducer = Self {
+            n_bits: matrix.get_n_bits(),
+            pivots: vec![None; matrix.get_n_bits()],
+        };
+        matrix
+            .checks_iter()
+            .for_each(|check| {
+                reducer.insert(check.to_vec());
+            });
+        reducer
+    }
+
+    pub(super) fn get_reduced_form(mut self) -> (ParityCheckMatrix, Vec<usize>) {
+        self.reduce_pivots();
+        let pivot_columns = self.get_pivot_columns();
+        let rows = self.pivots.into_iter().flatten().collect();
+        let matrix = ParityCheckMatrix::with_n_bits(self.n_bits).with_checks_unchecked(rows);
+        (matrix, pivot_columns)
+    }
+
+    // Returns the checks of the matrix that are not linear combinations of the previous ones.
+    pub(super) fn get_independent_checks_of(matrix: &ParityCheckMatrix) -> Vec<usize> {
+        let mut reducer = Self {
+            n_bits: matrix.get_n_bits(),
+            pivots: vec![None; matrix.get_n_bits()],
+        };
+        matrix
+            .checks_iter()
+            .enumerate()
+            .filter(|(_, check)| reducer.insert(check.to_vec()))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Returns false if the row is a combination of the rows inserted before.
+    fn insert(&mut self, row: Row) -> bool {
+        let reduced = self.reduce(row);
+        if let Some(&leading_column) = reduced.first() {
+            self.pivots[leading_column] = Some(reduced);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn reduce(&self, mut row: Row) -> Row {
+        while let Some(pivot) = row
+            .first()
+            .and_then(|leading_column| self.pivots[*leading_column].as_ref())
+        {
+            row = get_bitwise_sum(pivot, &row);
+        }
+        row
+    }
+
+    fn reduce_pivots(&mut self) {
+        for column in (0..self.n_bits).rev() {
+            if let Some(pivot) = self.pivots[column].take() {
+                self.pivots[column] = Some(self.clear_other_pivot_columns(pivot));
+            }
+        }
+    }
+
+    fn clear_other_pivot_columns(&self, mut row: Row) -> Row {
+        while let Some(pivot) = row[1..]
+            .iter()
+            .find_map(|column| self.pivots[*column].as_ref())
+        {
+            row = get_bitwise_sum(pivot, &row);
+        }
+        row
+    }
+
+    fn get_pivot_columns(&self) -> Vec<usize> {
+        (0..self.n_bits)
+            .filter(|column| self.pivots[*column].is_some())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reduced_form_of_empty_matrix() {
+        let (matrix, pivots) = EchelonReducer::from(&ParityCheckMatrix::new()).get_reduced_form();
+        assert_eq!(matrix, ParityCheckMatrix::new());
+        assert!(pivots.is_empty());
+    }
+
+    #[test]
+    fn reduced_form_of_hamming_code() {
+        let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![3, 4, 5, 6],
+            vec![1, 2, 5, 6],
+            vec![0, 2, 4, 6],
+        ]);
+        let (reduced, pivots) = EchelonReducer::from(&matrix).get_reduced_form();
+
+        let expected = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 2, 4, 6],
+            vec![1, 2, 5, 6],
+            vec![3, 4, 5, 6],
+        ]);
+        assert_eq!(reduced, expected);
+        assert_eq!(pivots, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn reduced_form_clears_pivot_columns_and_drops_redundant_rows() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![0, 3],
+            vec![],
+            vec![2, 3, 4],
+        ]);
+        let (reduced, pivots) = EchelonReducer::from(&matrix).get_reduced_form();
+
+        let expected = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 3],
+            vec![1, 4],
+            vec![2, 3, 4],
+        ]);
+        assert_eq!(reduced, expected);
+        assert_eq!(pivots, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn reduced_form_is_idempotent() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![1, 2, 5],
+            vec![0, 1, 3],
+            vec![0, 2, 3, 5],
+            vec![3, 4],
+        ]);
+        let (reduced, pivots) = EchelonReducer::from(&matrix).get_reduced_form();
+        let (reduced_twice, pivots_twice) = EchelonReducer::from(&reduced).get_reduced_form();
+
+        assert_eq!(reduced_twice, reduced);
+        assert_eq!(pivots_twice, pivots);
+        assert_eq!(pivots.len(), matrix.get_rank());
+    }
+
+    #[test]
+    fn independent_checks_skip_combinations_of_previous_checks() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![0, 3],
+            vec![],
+            vec![2, 3, 4],
+            vec![0, 1, 2],
+        ]);
+        let independent_checks = EchelonReducer::get_independent_checks_of(&matrix);
+
+        assert_eq!(independent_checks, vec![0, 1, 4]);
+    }
+}
@@ -11,12 +11,14 @@ pub struct EdgesIter<'a> {
 
 impl<'a> EdgesIter<'a> {
     pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
-        Self {
+        let mut iter = Self {
             active_check: 0,
             index: 0,
             check_ranges: &matrix.check_ranges,
             bit_indices: &matrix.bit_indices,
-        }
+        };
+        iter.skip_empty_checks();
+        iter
     }
 
     fn get_active_edge(&self) -> Option<(usize, usize)> {
@@ -27,7 +29,13 @@ impl<'a> EdgesIter<'a> {
 
     fn go_to_next_edge(&mut self) {
         self.index += 1;
-        if self.has_reached_end_of_a_check() {
+        self.skip_empty_checks();
+    }
+
+    // Moves to the check containing the active index. That is, skips the end of the active check
+    // and all following empty checks.
+    fn skip_empty_checks(&mut self) {
+        while self.has_reached_end_of_a_check() {
             self.go_to_next_check();
         }
     }
@@ -35,7 +43,7 @@ impl<'a> EdgesIter<'a> {
     fn has_reached_end_of_a_check(&self) -> bool {
         self.get_end_of_active_check()
             .map(|check_end| self.index >= check_end)
-            .unwrap_or(true)
+            .unwrap_or(false)
     }
 
     fn get_end_of_active_check(&self) -> Option<usize> {
@@ -74,4 +82,20 @@ mod test {
         assert_eq!(iter.next(), Some((1, 2)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn edges_iterator_with_empty_checks() {
+        let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(vec![
+            vec![],
+            vec![0, 1],
+            vec![],
+            vec![],
+            vec![2],
+            vec![],
+        ]);
+
+        let edges: Vec<(usize, usize)> = parity_check.edges_iter().collect();
+
+        assert_eq!(edges, vec![(1, 0), (1, 1), (4, 2)]);
+    }
 }
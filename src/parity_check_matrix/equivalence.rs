@@ -0,0 +1,365 @@
+//! Equivalence of codes up to a permutation of the bits.
+//!
+//! Two parity check matrices define equivalent codes if there is a permutation of the bits of
+//! the first one that gives a matrix with the same linear combinations of checks as the second
+//! one.
+//!
+//! # Example
+//!
+//! ```
+//! # use believer::{Equivalence, ParityCheckMatrix};
+//! let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+//! let permuted_code = code.get_bits_permuted_by(&[6, 5, 4, 3, 2, 1, 0]);
+//!
+//! assert_eq!(code.get_equivalence_with(&permuted_code, 1000), Equivalence::Equivalent);
+//!
+//! let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(7);
+//! assert_eq!(code.get_equivalence_with(&repetition_code, 1000), Equivalence::NotEquivalent);
+//! ```
+
+use super::{get_bitwise_sum, Check, ParityCheckMatrix};
+use std::collections::HashMap;
+
+// Codewords are only enumerated to compute the bit invariants if there are at most 2^16 of them.
+const MAX_DIMENSION_FOR_INVARIANTS: usize = 16;
+
+/// The outcome of an equivalence test between two codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Equivalence {
+    /// The codes are equal up to a permutation of the bits.
+    Equivalent,
+    /// No permutation of the bits makes the codes equal.
+    NotEquivalent,
+    /// The search was stopped before finding an answer.
+    Unknown,
+}
+
+// The columns of the reduced row echelon forms are vectors in the space spanned by the pivots.
+// Two codes are equivalent if there is an invertible linear map sending the columns of the left
+// form to a permutation of the columns of the right form.
+//
+// Left columns are visited in order and the map is fixed pivot by pivot. A pivot can be sent to
+// any available right column that is independent of the previous images. Since the other left
+// columns only depend on the previous pivots, their images are forced. Bits are also labeled by
+// the number of codewords of each weight containing them, and only bits with the same label can
+// be matched.
+pub(super) struct EquivalenceFinder {
+    columns: Vec<Check>,
+    pivot_rows: Vec<Option<usize>>,
+    labels: Vec<Vec<u64>>,
+    groups: Vec<ColumnGroup>,
+    group_of_vector: HashMap<Check, usize>,
+    images: Vec<Check>,
+    image_basis: Vec<Option<Check>>,
+    n_steps: usize,
+    max_n_steps: usize,
+}
+
+// Identical right columns are interchangeable, so only the number of used ones is tracked.
+struct ColumnGroup {
+    vector: Check,
+    label: Vec<u64>,
+    size: usize,
+    n_used: usize,
+}
+
+impl EquivalenceFinder {
+    pub(super) fn get_equivalence_between(
+        left: &ParityCheckMatrix,
+        right: &ParityCheckMatrix,
+        max_n_steps: usize,
+    ) -> Equivalence {
+        if left.get_n_bits() != right.get_n_bits() {
+            return Equivalence::NotEquivalent;
+        }
+        if left == right {
+            return Equivalence::Equivalent;
+        }
+        let (left_form, left_pivots) = left.get_reduced_row_echelon_form();
+        let (right_form, right_pivots) = right.get_reduced_row_echelon_form();
+        if left_pivots.len() != right_pivots.len() {
+            return Equivalence::NotEquivalent;
+        }
+        if left_form == right_form {
+            return Equivalence::Equivalent;
+        }
+        let left_labels = get_labels_of(&left_form);
+        let right_labels = get_labels_of(&right_form);
+        if !have_same_elements(&left_labels, &right_labels) {
+            return Equivalence::NotEquivalent;
+        }
+        let mut finder = Self::from(
+            &left_form,
+            &left_pivots,
+            left_labels,
+            &right_form,
+            right_labels,
+            max_n_steps,
+        );
+        match finder.search_from(0) {
+            Some(true) => Equivalence::Equivalent,
+            Some(false) => Equivalence::NotEquivalent,
+            None => Equivalence::Unknown,
+        }
+    }
+
+    fn from(
+        left_form: &ParityCheckMatrix,
+        left_pivots: &[usize],
+        left_labels: Vec<Vec<u64>>,
+        right_form: &ParityCheckMatrix,
+        right_labels: Vec<Vec<u64>>,
+        max_n_steps: usize,
+    ) -> Self {
+        let mut pivot_rows = vec![None; left_form.get_n_bits()];
+        left_pivots
+            .iter()
+            .enumerate()
+            .for_each(|(row, pivot)| pivot_rows[*pivot] = Some(row));
+
+        let mut groups: Vec<ColumnGroup> = Vec::new();
+        let mut group_of_vector = HashMap::new();
+        let right_columns = right_form.get_transposed_matrix();
+        for (column, label) in right_columns.checks_iter().zip(right_labels) {
+            let group = *group_of_vector.entry(column.to_vec()).or_insert_with(|| {
+                groups.push(ColumnGroup {
+                    vector: column.to_vec(),
+                    label,
+                    size: 0,
+                    n_used: 0,
+                });
+                groups.len() - 1
+            });
+            groups[group].size += 1;
+        }
+
+        Self {
+            columns: left_form
+                .get_transposed_matrix()
+                .checks_iter()
+                .map(|column| column.to_vec())
+                .collect(),
+            pivot_rows,
+            labels: left_labels,
+            groups,
+            group_of_vector,
+            images: vec![Vec::new(); left_pivots.len()],
+            image_basis: vec![None; left_pivots.len()],
+            n_steps: 0,
+            max_n_steps,
+        }
+    }
+
+    // Returns None if the maximal number of steps is reached.
+    fn search_from(&mut self, column: usize) -> Option<bool> {
+        if column == self.columns.len() {
+            return Some(true);
+        }
+        match self.pivot_rows[column] {
+            Some(row) => self.search_pivot_image(column, row),
+            None => self.search_forced_image(column),
+        }
+    }
+
+    fn search_pivot_image(&mut self, column: usize, row: usize) -> Option<bool> {
+        for group in 0..self.groups.len() {
+            if !self.is_available_for(group, column) {
+                continue;
+            }
+            self.n_steps += 1;
+            if self.n_steps > self.max_n_steps {
+                return None;
+            }
+            let vector = self.groups[group].vector.clone();
+            if let Some(leading_row) = self.insert_image(&vector) {
+                self.images[row] = vector;
+                self.groups[group].n_used += 1;
+                let found = self.search_from(column + 1);
+                self.groups[group].n_used -= 1;
+                self.image_basis[leading_row] = None;
+                if found != Some(false) {
+                    return found;
+                }
+            }
+        }
+        Some(false)
+    }
+
+    fn search_forced_image(&mut self, column: usize) -> Option<bool> {
+        let image = self.columns[column].iter().fold(Vec::new(), |image, row| {
+            get_bitwise_sum(&image, &self.images[*row])
+        });
+        match self.group_of_vector.get(&image).cloned() {
+            Some(group) if self.is_available_for(group, column) => {
+                self.groups[group].n_used += 1;
+                let found = self.search_from(column + 1);
+                self.groups[group].n_used -= 1;
+                found
+            }
+            _ => Some(false),
+        }
+    }
+
+    fn is_available_for(&self, group: usize, column: usize) -> bool {
+        let group = &self.groups[group];
+        group.n_used < group.size && group.label == self.labels[column]
+    }
+
+    // Returns the leading row under which the reduced image is stored, or None if the image is
+    // a combination of the previous ones.
+    fn insert_image(&mut self, image: &[usize]) -> Option<usize> {
+        let mut reduced = image.to_vec();
+        while let Some(basis_vector) = reduced
+            .first()
+            .and_then(|leading_row| self.image_basis[*leading_row].as_ref())
+        {
+            reduced = get_bitwise_sum(basis_vector, &reduced);
+        }
+        let leading_row = *reduced.first()?;
+        self.image_basis[leading_row] = Some(reduced);
+        Some(leading_row)
+    }
+}
+
+// The label of a bit is the number of codewords of each weight that contain it, counted in the
+// smallest of the code and its dual. Labels are empty if both are too large.
+fn get_labels_of(reduced_form: &ParityCheckMatrix) -> Vec<Vec<u64>> {
+    let n_bits = reduced_form.get_n_bits();
+    let rank = reduced_form.get_n_checks();
+    if std::cmp::min(rank, n_bits - rank) > MAX_DIMENSION_FOR_INVARIANTS {
+        return vec![Vec::new(); n_bits];
+    }
+    let basis = if rank <= n_bits - rank {
+        reduced_form.clone()
+    } else {
+        reduced_form.get_generator_matrix()
+    };
+    let basis: Vec<Vec<bool>> = basis
+        .checks_iter()
+        .map(|vector| {
+            let mut dense = vec![false; n_bits];
+            vector.iter().for_each(|bit| dense[*bit] = true);
+            dense
+        })
+        .collect();
+
+    // Codewords are visited in Gray code order so that each one is a single sum away from the
+    // previous one.
+    let mut labels = vec![vec![0; n_bits + 1]; n_bits];
+    let mut codeword = vec![false; n_bits];
+    for index in 1..(1_u64 << basis.len()) {
+        let changed_vector = &basis[index.trailing_zeros() as usize];
+        codeword
+            .iter_mut()
+            .zip(changed_vector)
+            .for_each(|(value, changed)| *value ^= changed);
+        let weight = codeword.iter().filter(|value| **value).count();
+        codeword
+            .iter()
+            .zip(labels.iter_mut())
+            .filter(|(value, _)| **value)
+            .for_each(|(_, label)| label[weight] += 1);
+    }
+    labels
+}
+
+fn have_same_elements(left: &[Vec<u64>], right: &[Vec<u64>]) -> bool {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    left.sort_unstable();
+    right.sort_unstable();
+    left == right
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CodeGenerator, RegularLDPCCodeGenerator};
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn random_code_is_equivalent_to_its_permutations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 5, 4);
+        for _ in 0..5 {
+            let code = generator.generate_with_rng(&mut rng);
+            let mut permutation: Vec<usize> = (0..code.get_n_bits()).collect();
+            permutation.shuffle(&mut rng);
+            let permuted_code = code.get_bits_permuted_by(&permutation);
+
+            assert_eq!(
+                code.get_equivalence_with(&permuted_code, 100_000),
+                Equivalence::Equivalent
+            );
+        }
+    }
+
+    #[test]
+    fn random_codes_are_not_equivalent() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let generator = RegularLDPCCodeGenerator::new(3, 4, 5, 4);
+        for _ in 0..5 {
+            let code = generator.generate_with_rng(&mut rng);
+            let other_code = generator.generate_with_rng(&mut rng);
+
+            assert_eq!(
+                code.get_equivalence_with(&other_code, 100_000),
+                Equivalence::NotEquivalent
+            );
+        }
+    }
+
+    #[test]
+    fn different_matrices_of_the_same_code_are_equivalent() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut other_matrix = code.get_bits_permuted_by(&[1, 0, 2, 3, 4, 5, 6]);
+        other_matrix.add_check_to(0, 1);
+        other_matrix.add_check_to(2, 0);
+
+        assert_eq!(
+            code.get_equivalence_with(&other_matrix, 1000),
+            Equivalence::Equivalent
+        );
+    }
+
+    #[test]
+    fn search_stops_after_the_maximal_number_of_steps() {
+        let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let permuted_code = code.get_bits_permuted_by(&[6, 5, 4, 3, 2, 1, 0]);
+
+        assert_ne!(
+            code.get_reduced_row_echelon_form(),
+            permuted_code.get_reduced_row_echelon_form()
+        );
+        assert_eq!(
+            code.get_equivalence_with(&permuted_code, 0),
+            Equivalence::Unknown
+        );
+    }
+
+    #[test]
+    fn codes_with_different_dimensions_are_not_equivalent() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let other_code = ParityCheckMatrix::single_parity_check_code_with_n_bits(4);
+
+        assert_eq!(
+            code.get_equivalence_with(&other_code, 0),
+            Equivalence::NotEquivalent
+        );
+        assert_eq!(
+            code.get_equivalence_with(&ParityCheckMatrix::repetition_code_with_n_bits(5), 0),
+            Equivalence::NotEquivalent
+        );
+    }
+
+    #[test]
+    fn labels_count_codewords_by_weight() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let (reduced_form, _) = code.get_reduced_row_echelon_form();
+
+        // The code {000, 111} is smaller than its dual.
+        assert_eq!(get_labels_of(&reduced_form), vec![vec![0, 0, 0, 1]; 3]);
+    }
+}
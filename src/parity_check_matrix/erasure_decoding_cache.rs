@@ -0,0 +1,277 @@
+//! Precomputed data to check many erasure patterns of the same code.
+//!
+//! # Example
+//!
+//! ```
+//! # use believer::ParityCheckMatrix;
+//! let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+//! let mut cache = code.get_erasure_decoding_cache();
+//!
+//! assert!(cache.can_correct_erasure_of(&[0, 1]));
+//! assert!(!cache.can_correct_erasure_of(&[0, 1, 2]));
+//! ```
+
+use super::{BitAdjacency, Check, ParityCheckMatrix};
+use std::sync::Arc;
+
+/// The checks adjacent to each bit of a code together with buffers to decode erasures.
+///
+/// The adjacency is shared between clones, so cloning a cache is cheap and each thread can
+/// decode with its own clone.
+#[derive(Debug, Clone)]
+pub struct ErasureDecodingCache {
+    adjacency: Arc<BitAdjacency>,
+    n_bits: usize,
+    // The reduced erased columns indexed by their first check.
+    pivots: Vec<Option<Check>>,
+    used_pivots: Vec<usize>,
+    // The erased positions whose columns sum to each pivot. Only kept up to date when the
+    // unresolved bits are computed.
+    combinations: Vec<Check>,
+    // Buffers reused between columns to avoid allocations.
+    free_columns: Vec<Check>,
+    combination: Check,
+    sum: Check,
+}
+
+impl ErasureDecodingCache {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        Self {
+            adjacency: Arc::new(matrix.get_bit_adjacency().clone()),
+            n_bits: matrix.get_n_bits(),
+            pivots: vec![None; matrix.get_n_checks()],
+            used_pivots: Vec::new(),
+            combinations: vec![Check::new(); matrix.get_n_checks()],
+            free_columns: Vec::new(),
+            combination: Check::new(),
+            sum: Check::new(),
+        }
+    }
+
+    /// Returns the number of bits of the code.
+    pub fn get_n_bits(&self) -> usize {
+        self.n_bits
+    }
+
+    /// Checks if the erasure of the given `bits` can be corrected.
+    ///
+    /// The erased columns are reduced one by one and the work is proportional to the size of
+    /// the erased part of the matrix.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bounds.
+    pub fn can_correct_erasure_of(&mut self, bits: &[usize]) -> bool {
+        let can_correct = bits.iter().all(|bit| self.insert_column_of(*bit));
+        self.free_pivots();
+        can_correct
+    }
+
+    /// Returns the erased `bits` that can't be recovered, in the order of `bits`.
+    ///
+    /// These are the bits in the support of a codeword restricted to the erased bits. The
+    /// result is empty if and only if the erasure can be corrected. The erased columns are
+    /// reduced like for `can_correct_erasure_of` while keeping track of the columns summed into
+    /// each pivot, so every column reduced to zero gives such a codeword and these codewords
+    /// span all the others.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::ParityCheckMatrix;
+    /// let code = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let mut cache = code.get_erasure_decoding_cache();
+    ///
+    /// assert_eq!(cache.get_unresolved_bits_of(&[3, 0, 1, 2]), vec![3, 0, 1, 2]);
+    /// assert_eq!(cache.get_unresolved_bits_of(&[3, 0]), vec![3]);
+    /// assert!(cache.get_unresolved_bits_of(&[0, 2]).is_empty());
+    /// ```
+    pub fn get_unresolved_bits_of(&mut self, bits: &[usize]) -> Vec<usize> {
+        let mut is_unresolved = vec![false; bits.len()];
+        for (position, bit) in bits.iter().enumerate() {
+            if !self.insert_tracked_column_of(*bit, position) {
+                self.combination
+                    .iter()
+                    .for_each(|position| is_unresolved[*position] = true);
+            }
+        }
+        self.free_pivots();
+        bits.iter()
+            .zip(is_unresolved)
+            .filter(|(_, is_unresolved)| *is_unresolved)
+            .map(|(bit, _)| *bit)
+            .collect()
+    }
+
+    fn free_pivots(&mut self) {
+        for check in self.used_pivots.drain(..) {
+            if let Some(column) = self.pivots[check].take() {
+                self.free_columns.push(column);
+            }
+        }
+    }
+
+    fn get_free_column_of(&mut self, bit: usize) -> Check {
+        let mut column = self.free_columns.pop().unwrap_or_default();
+        column.clear();
+        column.extend_from_slice(
+            self.adjacency
+                .get_checks_adjacent_to(bit)
+                .expect("some bits are out of bounds"),
+        );
+        column
+    }
+
+    // Returns false if the column is a combination of the previous ones.
+    fn insert_column_of(&mut self, bit: usize) -> bool {
+        let mut column = self.get_free_column_of(bit);
+        let pivots = &mut self.pivots;
+        while let Some(pivot) = column.first().and_then(|check| pivots[*check].as_ref()) {
+            add_into(pivot, &column, &mut self.sum);
+            std::mem::swap(&mut column, &mut self.sum);
+        }
+        match column.first().cloned() {
+            Some(check) => {
+                pivots[check] = Some(column);
+                self.used_pivots.push(check);
+                true
+            }
+            None => {
+                self.free_columns.push(column);
+                false
+            }
+        }
+    }
+
+    // Same as insert_column_of, but the positions of the columns summed into the column are
+    // kept in `self.combination`. If the column is reduced to zero, they are a codeword.
+    fn insert_tracked_column_of(&mut self, bit: usize, position: usize) -> bool {
+        let mut column = self.get_free_column_of(bit);
+        self.combination.clear();
+        self.combination.push(position);
+        let pivots = &mut self.pivots;
+        while let Some(check) = column.first().cloned().filter(|c| pivots[*c].is_some()) {
+            add_into(pivots[check].as_ref().unwrap(), &column, &mut self.sum);
+            std::mem::swap(&mut column, &mut self.sum);
+            add_into(&self.combinations[check], &self.combination, &mut self.sum);
+            std::mem::swap(&mut self.combination, &mut self.sum);
+        }
+        match column.first().cloned() {
+            Some(check) => {
+                pivots[check] = Some(column);
+                self.combinations[check].clear();
+                self.combinations[check].extend_from_slice(&self.combination);
+                self.used_pivots.push(check);
+                true
+            }
+            None => {
+                self.free_columns.push(column);
+                false
+            }
+        }
+    }
+}
+
+// Writes the sum of two sorted columns in `sum`.
+fn add_into(left: &[usize], right: &[usize], sum: &mut Check) {
+    sum.clear();
+    let mut left = left.iter().peekable();
+    let mut right = right.iter().peekable();
+    loop {
+        match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) if l < r => sum.push(*left.next().unwrap()),
+            (Some(l), Some(r)) if l > r => sum.push(*right.next().unwrap()),
+            (Some(_), Some(_)) => {
+                left.next();
+                right.next();
+            }
+            (Some(_), None) => sum.extend(left.by_ref()),
+            (None, Some(_)) => sum.extend(right.by_ref()),
+            (None, None) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Decoder, ErasureDecoder};
+
+    #[test]
+    fn cache_agrees_with_the_rank_of_erased_bits() {
+        let code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+            vec![1, 2, 5, 6],
+        ]);
+        let mut cache = code.get_erasure_decoding_cache();
+        for pattern in 0..(1 << 7) {
+            let bits: Vec<usize> = (0..7).filter(|bit| pattern & (1 << bit) != 0).collect();
+            assert_eq!(
+                cache.can_correct_erasure_of(&bits),
+                code.can_correct_erasure_of(&bits)
+            );
+        }
+    }
+
+    #[test]
+    fn unresolved_bits_agree_with_the_reduced_form_of_erased_bits() {
+        let code = ParityCheckMatrix::with_n_bits(8).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+            vec![4, 5],
+        ]);
+        let mut cache = code.get_erasure_decoding_cache();
+        let mut decoder = ErasureDecoder::with_prob(0.5).for_code(code);
+        for pattern in 0..(1 << 8) {
+            let bits: Vec<usize> = (0..8).filter(|bit| pattern & (1 << bit) != 0).collect();
+            let unresolved_bits = cache.get_unresolved_bits_of(&bits);
+            assert_eq!(unresolved_bits.is_empty(), cache.can_correct_erasure_of(&bits));
+            assert_eq!(&unresolved_bits, decoder.decode(&bits).get_unresolved_bits());
+        }
+    }
+
+    #[test]
+    fn unprotected_and_duplicated_bits_are_not_correctable() {
+        let code = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let mut cache = code.get_erasure_decoding_cache();
+
+        assert!(!cache.can_correct_erasure_of(&[3]));
+        assert!(!cache.can_correct_erasure_of(&[0, 0]));
+        assert!(cache.can_correct_erasure_of(&[0, 1]));
+        assert!(cache.can_correct_erasure_of(&[]));
+    }
+
+    #[test]
+    fn columns_are_added_as_sorted_sets() {
+        let mut sum = vec![42];
+        add_into(&[0, 2, 5, 7], &[0, 3, 5, 8, 9], &mut sum);
+        assert_eq!(sum, vec![2, 3, 7, 8, 9]);
+
+        add_into(&[1, 2], &[1, 2], &mut sum);
+        assert!(sum.is_empty());
+    }
+
+    #[test]
+    fn clones_share_the_adjacency() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let cache = code.get_erasure_decoding_cache();
+        let mut clone = cache.clone();
+
+        assert!(Arc::ptr_eq(&cache.adjacency, &clone.adjacency));
+        assert!(!clone.can_correct_erasure_of(&[0, 1, 2]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_bits_panic() {
+        let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        code.get_erasure_decoding_cache().can_correct_erasure_of(&[3]);
+    }
+}
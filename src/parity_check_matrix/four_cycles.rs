@@ -0,0 +1,116 @@
+use super::{CheckView, ParityCheckMatrix};
+use itertools::Itertools;
+
+// A tool to find the cycles of length 4 in the Tanner graph of a parity check matrix.
+//
+// Two checks sharing the bits in a set form a 4-cycle with every pair of bits in that set.
+pub(super) struct FourCyclesFinder<'a> {
+    checks: Vec<CheckView<'a>>,
+}
+
+impl<'a> FourCyclesFinder<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self {
+            checks: matrix.checks_iter().collect(),
+        }
+    }
+
+    pub(super) fn get_n_cycles(&self) -> usize {
+        self.get_shared_bits_of_check_pairs()
+            .map(|(_, _, shared_bits)| shared_bits.len() * shared_bits.len().saturating_sub(1) / 2)
+            .sum()
+    }
+
+    pub(super) fn get_cycles(&self) -> Vec<(usize, usize, usize, usize)> {
+        self.get_shared_bits_of_check_pairs()
+            .flat_map(|(check_0, check_1, shared_bits)| {
+                shared_bits
+                    .into_iter()
+                    .tuple_combinations()
+                    .map(move |(bit_0, bit_1)| (check_0, check_1, bit_0, bit_1))
+            })
+            .collect()
+    }
+
+    fn get_shared_bits_of_check_pairs(
+        &self,
+    ) -> impl Iterator<Item = (usize, usize, Vec<usize>)> + '_ {
+        (0..self.checks.len())
+            .tuple_combinations()
+            .map(move |(check_0, check_1)| {
+                (check_0, check_1, self.get_shared_bits_of(check_0, check_1))
+            })
+            .filter(|(_, _, shared_bits)| shared_bits.len() >= 2)
+    }
+
+    fn get_shared_bits_of(&self, check_0: usize, check_1: usize) -> Vec<usize> {
+        get_intersection(self.checks[check_0].as_ref(), self.checks[check_1].as_ref())
+    }
+}
+
+fn get_intersection(bits_0: &[usize], bits_1: &[usize]) -> Vec<usize> {
+    let mut intersection = Vec::new();
+    let mut iter_0 = bits_0.iter().dedup().peekable();
+    let mut iter_1 = bits_1.iter().dedup().peekable();
+    while let (Some(bit_0), Some(bit_1)) = (iter_0.peek(), iter_1.peek()) {
+        match bit_0.cmp(bit_1) {
+            std::cmp::Ordering::Less => {
+                iter_0.next();
+            }
+            std::cmp::Ordering::Greater => {
+                iter_1.next();
+            }
+            std::cmp::Ordering::Equal => {
+                intersection.push(**bit_0);
+                iter_0.next();
+                iter_1.next();
+            }
+        }
+    }
+    intersection
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tree_has_no_4_cycles() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![2, 3],
+            vec![3, 4],
+        ]);
+        let finder = FourCyclesFinder::from(&matrix);
+
+        assert_eq!(finder.get_n_cycles(), 0);
+        assert!(finder.get_cycles().is_empty());
+    }
+
+    #[test]
+    fn checks_sharing_many_bits() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![],
+            vec![0, 1, 3, 4],
+            vec![1, 3],
+        ]);
+        let finder = FourCyclesFinder::from(&matrix);
+
+        assert_eq!(finder.get_n_cycles(), 4);
+        assert_eq!(
+            finder.get_cycles(),
+            vec![(0, 2, 0, 1), (0, 2, 0, 4), (0, 2, 1, 4), (2, 3, 1, 3)]
+        );
+    }
+
+    #[test]
+    fn duplicated_bits_do_not_create_cycles() {
+        let matrix = ParityCheckMatrix::with_n_bits(3)
+            .with_checks_unchecked(vec![vec![0, 0, 1], vec![0, 0, 2]]);
+        let finder = FourCyclesFinder::from(&matrix);
+
+        assert_eq!(finder.get_n_cycles(), 0);
+        assert!(finder.get_cycles().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use super::ParityCheckMatrix;
+use std::collections::VecDeque;
+
+// A tool to find the shortest cycles in the Tanner graph of a parity check matrix.
+//
+// Nodes are indexed with the bits first followed by the checks. For a given source bit, a breadth
+// first search labels every node with the neighbor of the source it was reached from. An edge
+// between two nodes with different labels closes a cycle through the source.
+pub(super) struct GirthFinder<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> GirthFinder<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    pub(super) fn get_girth(&self) -> Option<usize> {
+        (0..self.matrix.get_n_bits())
+            .filter_map(|bit| self.get_girth_at_bit(bit))
+            .min()
+    }
+
+    pub(super) fn get_girth_at_bit(&self, bit: usize) -> Option<usize> {
+        if bit >= self.matrix.get_n_bits() {
+            return None;
+        }
+        BreadthFirstSearch::from(self, bit).get_shortest_cycle()
+    }
+
+    fn get_n_nodes(&self) -> usize {
+        self.matrix.get_n_bits() + self.matrix.get_n_checks()
+    }
+
+    fn get_neighbors(&self, node: usize) -> Vec<usize> {
+        let n_bits = self.matrix.get_n_bits();
+        if node < n_bits {
+            self.matrix
+                .get_checks_adjacent_to_bit(node)
+                .map(|checks| checks.iter().map(|check| check + n_bits).collect())
+                .unwrap_or_default()
+        } else {
+            self.matrix
+                .get_check(node - n_bits)
+                .map(|bits| bits.to_vec())
+                .unwrap_or_default()
+        }
+    }
+}
+
+struct BreadthFirstSearch<'a, 'b> {
+    finder: &'b GirthFinder<'a>,
+    source: usize,
+    distances: Vec<Option<usize>>,
+    labels: Vec<usize>,
+    queue: VecDeque<usize>,
+    shortest_cycle: Option<usize>,
+}
+
+impl<'a, 'b> BreadthFirstSearch<'a, 'b> {
+    fn from(finder: &'b GirthFinder<'a>, source: usize) -> Self {
+        let mut distances = vec![None; finder.get_n_nodes()];
+        distances[source] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        Self {
+            finder,
+            source,
+            distances,
+            labels: vec![source; finder.get_n_nodes()],
+            queue,
+            shortest_cycle: None,
+        }
+    }
+
+    fn get_shortest_cycle(mut self) -> Option<usize> {
+        while let Some(node) = self.queue.pop_front() {
+            if self.cannot_find_shorter_cycle_from(node) {
+                break;
+            }
+            self.visit_neighbors_of(node);
+        }
+        self.shortest_cycle
+    }
+
+    // Any cycle found from a node at distance d has length at least 2d.
+    fn cannot_find_shorter_cycle_from(&self, node: usize) -> bool {
+        match (self.shortest_cycle, self.distances[node]) {
+            (Some(cycle), Some(distance)) => 2 * distance >= cycle,
+            _ => false,
+        }
+    }
+
+    fn visit_neighbors_of(&mut self, node: usize) {
+        let distance = self.distances[node].unwrap_or(0);
+        for neighbor in self.finder.get_neighbors(node) {
+            match self.distances[neighbor] {
+                None => {
+                    self.distances[neighbor] = Some(distance + 1);
+                    self.labels[neighbor] = if node == self.source {
+                        neighbor
+                    } else {
+                        self.labels[node]
+                    };
+                    self.queue.push_back(neighbor);
+                }
+                Some(neighbor_distance) => {
+                    if neighbor != self.source && self.labels[neighbor] != self.labels[node] {
+                        self.update_shortest_cycle(distance + neighbor_distance + 1);
+                    }
+                }
+            }
+        }
+    }
+
+    fn update_shortest_cycle(&mut self, length: usize) {
+        self.shortest_cycle = Some(
+            self.shortest_cycle
+                .map(|cycle| std::cmp::min(cycle, length))
+                .unwrap_or(length),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tree_has_no_girth() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![2, 3],
+            vec![3, 4],
+        ]);
+        let finder = GirthFinder::from(&matrix);
+
+        assert_eq!(finder.get_girth(), None);
+        (0..5).for_each(|bit| assert_eq!(finder.get_girth_at_bit(bit), None));
+    }
+
+    #[test]
+    fn two_bits_sharing_two_checks_make_a_4_cycle() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1, 2], vec![0, 1, 3]]);
+        let finder = GirthFinder::from(&matrix);
+
+        assert_eq!(finder.get_girth(), Some(4));
+        assert_eq!(finder.get_girth_at_bit(0), Some(4));
+        assert_eq!(finder.get_girth_at_bit(1), Some(4));
+        assert_eq!(finder.get_girth_at_bit(2), None);
+        assert_eq!(finder.get_girth_at_bit(3), None);
+    }
+
+    #[test]
+    fn girth_at_bit_only_counts_cycles_through_the_bit() {
+        // Bit 0 hangs on a 4-cycle made of bits 1 and 2, but is itself on a 6-cycle.
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1],
+            vec![1, 2],
+            vec![1, 2, 3],
+            vec![0, 3],
+        ]);
+        let finder = GirthFinder::from(&matrix);
+
+        assert_eq!(finder.get_girth(), Some(4));
+        assert_eq!(finder.get_girth_at_bit(0), Some(6));
+        assert_eq!(finder.get_girth_at_bit(1), Some(4));
+    }
+
+    #[test]
+    fn cycle_code_has_girth_twice_the_number_of_bits() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1],
+            vec![1, 2],
+            vec![2, 3],
+            vec![3, 0],
+        ]);
+        let finder = GirthFinder::from(&matrix);
+
+        assert_eq!(finder.get_girth(), Some(8));
+        assert_eq!(finder.get_girth_at_bit(4), None);
+    }
+}
@@ -0,0 +1,137 @@
+use super::{Check, ParityCheckMatrix};
+
+// Builds the X and Z checks of the hypergraph product of a left and a right matrix.
+//
+// The bits of the product are the pairs (left bit, right bit) followed by the pairs
+// (left check, right check). The X checks are the pairs (left check, right bit) and the Z checks
+// are the pairs (left bit, right check). This gives the block matrices
+//
+//   X = [ L ⊗ I | I ⊗ Rᵀ ]
+//   Z = [ I ⊗ R | Lᵀ ⊗ I ]
+//
+// where L and R are the left and right matrices.
+pub(super) struct HypergraphProduct<'a> {
+    left: &'a ParityCheckMatrix,
+    right: &'a ParityCheckMatrix,
+}
+
+impl<'a> HypergraphProduct<'a> {
+    pub(super) fn from(left: &'a ParityCheckMatrix, right: &'a ParityCheckMatrix) -> Self {
+        Self { left, right }
+    }
+
+    pub(super) fn get_x_and_z_checks(&self) -> (ParityCheckMatrix, ParityCheckMatrix) {
+        (self.get_x_checks(), self.get_z_checks())
+    }
+
+    fn get_n_bits(&self) -> usize {
+        self.left.get_n_bits() * self.right.get_n_bits()
+            + self.left.get_n_checks() * self.right.get_n_checks()
+    }
+
+    fn get_x_checks(&self) -> ParityCheckMatrix {
+        let checks =
+            self.left
+                .checks_iter()
+                .enumerate()
+                .flat_map(|(left_check, left_bits)| {
+                    (0..self.right.get_n_bits()).map(move |right_bit| {
+                        let mut check: Check = left_bits
+                            .iter()
+                            .map(|left_bit| self.get_bit_bit_index(*left_bit, right_bit))
+                            .collect();
+                        check.extend(self.get_right_checks_adjacent_to(right_bit).iter().map(
+                            |right_check| self.get_check_check_index(left_check, *right_check),
+                        ));
+                        check
+                    })
+                })
+                .collect();
+        ParityCheckMatrix::with_n_bits(self.get_n_bits()).with_checks(checks)
+    }
+
+    fn get_z_checks(&self) -> ParityCheckMatrix {
+        let checks = (0..self.left.get_n_bits())
+            .flat_map(|left_bit| {
+                self.right
+                    .checks_iter()
+                    .enumerate()
+                    .map(move |(right_check, right_bits)| {
+                        let mut check: Check = right_bits
+                            .iter()
+                            .map(|right_bit| self.get_bit_bit_index(left_bit, *right_bit))
+                            .collect();
+                        check.extend(self.get_left_checks_adjacent_to(left_bit).iter().map(
+                            |left_check| self.get_check_check_index(*left_check, right_check),
+                        ));
+                        check
+                    })
+            })
+            .collect();
+        ParityCheckMatrix::with_n_bits(self.get_n_bits()).with_checks(checks)
+    }
+
+    fn get_left_checks_adjacent_to(&self, left_bit: usize) -> &[usize] {
+        self.left
+            .get_checks_adjacent_to_bit(left_bit)
+            .unwrap_or(&[])
+    }
+
+    fn get_right_checks_adjacent_to(&self, right_bit: usize) -> &[usize] {
+        self.right
+            .get_checks_adjacent_to_bit(right_bit)
+            .unwrap_or(&[])
+    }
+
+    fn get_bit_bit_index(&self, left_bit: usize, right_bit: usize) -> usize {
+        left_bit * self.right.get_n_bits() + right_bit
+    }
+
+    fn get_check_check_index(&self, left_check: usize, right_check: usize) -> usize {
+        self.left.get_n_bits() * self.right.get_n_bits()
+            + left_check * self.right.get_n_checks()
+            + right_check
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn product_of_repetition_codes_is_the_surface_code() {
+        let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let (x_checks, z_checks) =
+            HypergraphProduct::from(&repetition_code, &repetition_code).get_x_and_z_checks();
+
+        assert_eq!(x_checks.get_n_bits(), 13);
+        assert_eq!(z_checks.get_n_bits(), 13);
+        assert_eq!(x_checks.get_n_checks(), 6);
+        assert_eq!(z_checks.get_n_checks(), 6);
+        assert!(x_checks.is_orthogonal_to(&z_checks));
+        assert_eq!(13 - x_checks.get_rank() - z_checks.get_rank(), 1);
+
+        // Check (0, 0) acts on the bits (0, 0) and (1, 0) and on the bit (check 0, check 0).
+        assert_eq!(x_checks.get_check(0).unwrap().as_ref(), &[0, 3, 9]);
+        // Check (0, 0) acts on the bits (0, 0) and (0, 1) and on the bit (check 0, check 0).
+        assert_eq!(z_checks.get_check(0).unwrap().as_ref(), &[0, 1, 9]);
+    }
+
+    #[test]
+    fn product_of_different_codes_is_orthogonal() {
+        let hamming_code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 4],
+            vec![],
+            vec![1, 2, 3],
+            vec![0, 3],
+        ]);
+        let (x_checks, z_checks) =
+            HypergraphProduct::from(&hamming_code, &matrix).get_x_and_z_checks();
+
+        assert_eq!(x_checks.get_n_bits(), 7 * 5 + 3 * 4);
+        assert_eq!(x_checks.get_n_checks(), 3 * 5);
+        assert_eq!(z_checks.get_n_checks(), 7 * 4);
+        assert!(x_checks.is_orthogonal_to(&z_checks));
+    }
+}
@@ -0,0 +1,270 @@
+//! Reading and writing parity check matrices in the Matrix Market coordinate format.
+//!
+//! Only the binary flavor is supported. Its layout is
+//!
+//! ```text
+//! %%MatrixMarket matrix coordinate pattern general
+//! % any number of comment lines
+//! n_checks n_bits n_edges
+//! check bit (n_edges lines, 1-based)
+//! ```
+
+use super::ParityCheckMatrix;
+use std::io::{self, BufRead, Write};
+
+const HEADER: &str = "%%MatrixMarket matrix coordinate pattern general";
+
+// Each check takes some memory even without entries, so the numbers of checks and bits are
+// capped to prevent a hostile header from allocating a huge matrix.
+const MAX_DIMENSION: usize = 1 << 24;
+
+pub(super) struct MatrixMarketReader<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> MatrixMarketReader<R> {
+    pub(super) fn from(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+        }
+    }
+
+    pub(super) fn read_matrix(mut self) -> io::Result<ParityCheckMatrix> {
+        self.read_header()?;
+        let (n_checks, n_bits, n_edges) = self.read_dimensions()?;
+        // The entries are only stored as they are read, so a hostile number of entries can't
+        // allocate more memory than the size of the data.
+        let mut edges = Vec::new();
+        for _ in 0..n_edges {
+            edges.push(self.read_edge(n_checks, n_bits)?);
+        }
+        if self.next_data_line()?.is_some() {
+            return Err(invalid_data("more entries than announced"));
+        }
+        Ok(ParityCheckMatrix::with_n_bits(n_bits).with_edges(n_checks, edges))
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        let header = self
+            .lines
+            .next()
+            .ok_or_else(|| invalid_data("missing header"))??;
+        let words: Vec<String> = header
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect();
+        match words
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .as_slice()
+        {
+            ["%%matrixmarket", "matrix", "coordinate", "pattern", "general"] => Ok(()),
+            ["%%matrixmarket", "matrix", "coordinate", _, "general"] => {
+                Err(invalid_data("only pattern matrices are supported"))
+            }
+            ["%%matrixmarket", "matrix", "coordinate", _, _] => {
+                Err(invalid_data("only general matrices are supported"))
+            }
+            ["%%matrixmarket", "matrix", _, _, _] => {
+                Err(invalid_data("only coordinate matrices are supported"))
+            }
+            _ => Err(invalid_data("invalid header")),
+        }
+    }
+
+    fn read_dimensions(&mut self) -> io::Result<(usize, usize, usize)> {
+        let line = self
+            .next_data_line()?
+            .ok_or_else(|| invalid_data("missing dimensions"))?;
+        match parse_integers(&line)?.as_slice() {
+            [n_checks, n_bits, _] if *n_checks > MAX_DIMENSION || *n_bits > MAX_DIMENSION => {
+                Err(invalid_data("dimensions are too large"))
+            }
+            [n_checks, n_bits, n_edges] => Ok((*n_checks, *n_bits, *n_edges)),
+            _ => Err(invalid_data("dimensions must be 3 integers")),
+        }
+    }
+
+    fn read_edge(&mut self, n_checks: usize, n_bits: usize) -> io::Result<(usize, usize)> {
+        let line = self
+            .next_data_line()?
+            .ok_or_else(|| invalid_data("fewer entries than announced"))?;
+        match parse_integers(&line)?.as_slice() {
+            [check, bit] if (1..=n_checks).contains(check) && (1..=n_bits).contains(bit) => {
+                Ok((check - 1, bit - 1))
+            }
+            [_, _] => Err(invalid_data("index out of bounds")),
+            _ => Err(invalid_data("entries must be 2 integers")),
+        }
+    }
+
+    // Skips comments and blank lines.
+    fn next_data_line(&mut self) -> io::Result<Option<String>> {
+        for line in &mut self.lines {
+            let line = line?;
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('%') {
+                return Ok(Some(trimmed.to_string()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+pub(super) struct MatrixMarketWriter<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> MatrixMarketWriter<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    // Edges are written in check then bit order.
+    pub(super) fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(
+            writer,
+            "{} {} {}",
+            self.matrix.get_n_checks(),
+            self.matrix.get_n_bits(),
+            self.matrix.get_n_edges()
+        )?;
+        for (check, bit) in self.matrix.edges_iter() {
+            writeln!(writer, "{} {}", check + 1, bit + 1)?;
+        }
+        writer.flush()
+    }
+}
+
+fn parse_integers(line: &str) -> io::Result<Vec<usize>> {
+    line.split_whitespace()
+        .map(|token| {
+            token
+                .parse::<usize>()
+                .map_err(|_| invalid_data("non integer value"))
+        })
+        .collect()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn read(input: &str) -> io::Result<ParityCheckMatrix> {
+        MatrixMarketReader::from(input.as_bytes()).read_matrix()
+    }
+
+    #[test]
+    fn write_matrix_with_empty_check() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![1, 0], vec![], vec![3]]);
+        let mut output = Vec::new();
+        MatrixMarketWriter::from(&matrix)
+            .write_to(&mut output)
+            .unwrap();
+        let expected = "%%MatrixMarket matrix coordinate pattern general\n\
+                        3 4 3\n\
+                        1 1\n\
+                        1 2\n\
+                        3 4\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn read_unsorted_entries_with_comments() {
+        let input = "%%MatrixMarket matrix coordinate pattern general\n\
+                     % A comment\n\
+                     %\n\
+                     2 3 4\n\
+                     2 3\n\
+                     1 1\n\
+                     \n\
+                     2 2\n\
+                     1 2\n";
+        let expected = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        assert_eq!(read(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let matrix = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let mut output = Vec::new();
+        MatrixMarketWriter::from(&matrix)
+            .write_to(&mut output)
+            .unwrap();
+        let read = MatrixMarketReader::from(output.as_slice())
+            .read_matrix()
+            .unwrap();
+        assert_eq!(read, matrix);
+    }
+
+    #[test]
+    fn non_pattern_and_non_coordinate_files_are_rejected() {
+        for header in &[
+            "%%MatrixMarket matrix coordinate real general",
+            "%%MatrixMarket matrix coordinate complex general",
+            "%%MatrixMarket matrix array pattern general",
+            "%%MatrixMarket matrix coordinate pattern symmetric",
+            "not a header",
+        ] {
+            let input = format!("{}\n1 1 1\n1 1\n", header);
+            assert!(read(&input).is_err());
+        }
+    }
+
+    #[test]
+    fn wrong_number_of_entries_is_rejected() {
+        let header = "%%MatrixMarket matrix coordinate pattern general\n";
+        assert!(read(&format!("{}2 2 2\n1 1\n", header)).is_err());
+        assert!(read(&format!("{}2 2 1\n1 1\n2 2\n", header)).is_err());
+    }
+
+    #[test]
+    fn empty_checks_are_read_back() {
+        let header = "%%MatrixMarket matrix coordinate pattern general\n";
+        let expected =
+            ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![], vec![0, 1], vec![]]);
+        assert_eq!(read(&format!("{}3 2 2\n2 1\n2 2\n", header)).unwrap(), expected);
+
+        let expected = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![], vec![1], vec![]]);
+        assert_eq!(read(&format!("{}3 2 1\n2 2\n", header)).unwrap(), expected);
+    }
+
+    #[test]
+    fn round_trip_with_more_empty_checks_than_entries() {
+        let matrix = ParityCheckMatrix::with_n_bits(3)
+            .with_checks(vec![vec![], vec![], vec![0, 2], vec![], vec![]]);
+        let mut output = Vec::new();
+        MatrixMarketWriter::from(&matrix)
+            .write_to(&mut output)
+            .unwrap();
+        let read = MatrixMarketReader::from(output.as_slice())
+            .read_matrix()
+            .unwrap();
+        assert_eq!(read, matrix);
+    }
+
+    #[test]
+    fn hostile_dimensions_are_rejected_without_allocating() {
+        let header = "%%MatrixMarket matrix coordinate pattern general\n";
+        let too_many_checks = format!("{}{} 1 0\n", header, MAX_DIMENSION + 1);
+        assert!(read(&too_many_checks).is_err());
+        let too_many_bits = format!("{}1 {} 0\n", header, MAX_DIMENSION + 1);
+        assert!(read(&too_many_bits).is_err());
+        assert!(read(&format!("{}4294967295 1 1\n4294967295 1\n", header)).is_err());
+        assert!(read(&format!("{}1 1 4294967295\n1 1\n", header)).is_err());
+    }
+
+    #[test]
+    fn out_of_bounds_entries_are_rejected() {
+        let header = "%%MatrixMarket matrix coordinate pattern general\n";
+        assert!(read(&format!("{}2 2 1\n3 1\n", header)).is_err());
+        assert!(read(&format!("{}2 2 1\n1 0\n", header)).is_err());
+    }
+}
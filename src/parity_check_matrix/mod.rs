@@ -1,35 +1,140 @@
 //! A sparse implementation of a parity check matrix.
 
 use crate::GF2;
+use itertools::Itertools;
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+mod alist;
+use alist::{AlistReader, AlistWriter};
+
+mod matrix_market;
+use matrix_market::{MatrixMarketReader, MatrixMarketWriter};
+
+mod binary;
+use binary::{BinaryReader, BinaryWriter};
+
+mod dot;
+use dot::DotWriter;
+
+mod npy;
+use npy::NpyWriter;
 
 pub mod check;
-use check::get_bitwise_sum;
+use check::{get_bitwise_sum, get_dot_product};
 pub use check::{Check, CheckSlice};
 
 pub mod check_view;
 pub use check_view::CheckView;
 
+pub mod degree_distribution;
+pub use degree_distribution::DegreeDistribution;
+
+pub mod packed;
+pub use packed::PackedParityCheckMatrix;
+
+pub mod erasure_decoding_cache;
+pub use erasure_decoding_cache::ErasureDecodingCache;
+
+pub mod equivalence;
+use equivalence::EquivalenceFinder;
+pub use equivalence::Equivalence;
+
 pub mod checks_iter;
 pub use checks_iter::ChecksIter;
 
 pub mod edges_iter;
 pub use edges_iter::EdgesIter;
 
+mod bit_adjacency;
+use bit_adjacency::BitAdjacency;
+
+mod girth;
+use girth::GirthFinder;
+
+mod four_cycles;
+use four_cycles::FourCyclesFinder;
+
+mod cycle_remover;
+use cycle_remover::CycleRemover;
+
+mod random_matrix;
+use random_matrix::RandomMatrixSampler;
+
+mod distance;
+use distance::{DistanceFinder, InformationSetSampler};
+
+mod weight_enumerator;
+use weight_enumerator::WeightEnumerator;
+
+pub mod stopping_sets;
+use stopping_sets::StoppingSetFinder;
+pub use stopping_sets::{AnalyticEstimate, StoppingSetSize, StoppingSetSpectrum};
+
+#[cfg(feature = "serde")]
+mod serialization;
+
 mod ranker;
 use ranker::Ranker;
 
+mod echelon;
+use echelon::EchelonReducer;
+
 mod transposer;
 use transposer::Transposer;
 
 mod concatener;
 use concatener::Concatener;
 
+mod hypergraph_product;
+use hypergraph_product::HypergraphProduct;
+
+mod display;
+
 /// A sparse implementation of a parity check matrix.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "serialization::SerializedParityCheckMatrix",
+        try_from = "serialization::SerializedParityCheckMatrix"
+    )
+)]
 pub struct ParityCheckMatrix {
     check_ranges: Vec<usize>,
     bit_indices: Vec<usize>,
     n_bits: usize,
+    // Built on first use by bit oriented methods.
+    bit_adjacency: OnceLock<BitAdjacency>,
+}
+
+impl PartialEq for ParityCheckMatrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.check_ranges == other.check_ranges
+            && self.bit_indices == other.bit_indices
+            && self.n_bits == other.n_bits
+    }
+}
+
+impl Eq for ParityCheckMatrix {}
+
+impl std::hash::Hash for ParityCheckMatrix {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.check_ranges.hash(state);
+        self.bit_indices.hash(state);
+        self.n_bits.hash(state);
+    }
+}
+
+impl Default for ParityCheckMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ParityCheckMatrix {
@@ -48,6 +153,7 @@ impl ParityCheckMatrix {
             check_ranges: Vec::new(),
             bit_indices: Vec::new(),
             n_bits: 0,
+            bit_adjacency: OnceLock::new(),
         }
     }
 
@@ -63,11 +169,15 @@ impl ParityCheckMatrix {
         Self {
             check_ranges: Vec::new(),
             bit_indices: Vec::new(),
-            n_bits: n_bits,
+            n_bits,
+            bit_adjacency: OnceLock::new(),
         }
     }
 
-    /// Set the checks of `self` consuming `checks`.
+    /// Set the checks of `self` consuming `checks`, replacing the previous checks.
+    ///
+    /// The bits of each check are sorted and a bit appearing many times in the same check is
+    /// only kept once. Empty checks are kept.
     ///
     /// # Panic
     ///
@@ -80,19 +190,91 @@ impl ParityCheckMatrix {
     /// use believer::ParityCheckMatrix;
     /// let checks = vec![vec![0, 1], vec![1, 2]];
     /// let mut matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// let unsorted_checks = vec![vec![1, 0, 1], vec![2, 1]];
+    /// assert_eq!(ParityCheckMatrix::with_n_bits(3).with_checks(unsorted_checks), matrix);
     /// ```
     pub fn with_checks(mut self, checks: Vec<Check>) -> Self {
-       if !checks.is_empty() {
+        self.clear_checks();
+        if !checks.is_empty() {
             if self.some_checks_are_out_of_bounds(&checks) {
                 panic!("some checks are out of bounds");
             }
             self.init_bit_indices(&checks);
             self.init_check_ranges(&checks);
             self.fill_with(checks);
-       }
+        }
+        self
+    }
+
+    /// Set the checks of `self` consuming `checks` without sorting nor validating them.
+    ///
+    /// This avoids the cost of sorting when the caller knows that the bits of each check are
+    /// sorted, distinct and lower than `self.get_n_bits()`. Otherwise, the resulting matrix is
+    /// invalid and methods called on it may panic or return wrong results.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks_unchecked(checks.clone());
+    ///
+    /// assert_eq!(matrix, ParityCheckMatrix::with_n_bits(3).with_checks(checks));
+    /// ```
+    pub fn with_checks_unchecked(mut self, checks: Vec<Check>) -> Self {
+        self.clear_checks();
+        if !checks.is_empty() {
+            self.init_bit_indices(&checks);
+            self.init_check_ranges(&checks);
+            checks.into_iter().for_each(|mut check| {
+                self.bit_indices.append(&mut check);
+                self.close_check_range();
+            });
+        }
         self
     }
 
+    /// Set the `n_checks` checks of `self` from a list of (check, bit) `edges` given in any
+    /// order.
+    ///
+    /// Checks without edges are empty, so this rebuilds every matrix from its number of checks
+    /// and the result of `positions`. Duplicated edges are only kept once.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some edges are connected to a check that is greater or equal than `n_checks` or
+    /// to a bit that is greater or equal than `self.get_n_bits()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let matrix = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![]]);
+    /// let edges = matrix.positions().collect();
+    ///
+    /// assert_eq!(ParityCheckMatrix::with_n_bits(3).with_edges(3, edges), matrix);
+    /// ```
+    pub fn with_edges(self, n_checks: usize, edges: Vec<(usize, usize)>) -> Self {
+        if edges.iter().any(|(check, _)| *check >= n_checks) {
+            panic!("some checks are out of bounds");
+        }
+        let mut checks = vec![Vec::new(); n_checks];
+        edges
+            .into_iter()
+            .for_each(|(check, bit)| checks[check].push(bit));
+        self.with_checks(checks)
+    }
+
+    // The previous checks are replaced, so the cached bit adjacency, which may have been filled
+    // or cloned from an other matrix, is reset.
+    fn clear_checks(&mut self) {
+        self.check_ranges = Vec::new();
+        self.bit_indices = Vec::new();
+        self.bit_adjacency = OnceLock::new();
+    }
+
     fn some_checks_are_out_of_bounds(&self, checks: &[Check]) -> bool {
         checks.iter().any(|check| self.is_out_of_bounds(check))
     }
@@ -120,17 +302,17 @@ impl ParityCheckMatrix {
     }
 
     fn add_check(&mut self, check: Check) {
-        self.add_check_range(&check);
         self.add_bit_indices(check);
+        self.close_check_range();
     }
 
-    fn add_check_range(&mut self, check: CheckSlice) {
-        let n_elements_before = self.bit_indices.len();
-        self.check_ranges.push(n_elements_before + check.len());
+    fn close_check_range(&mut self) {
+        self.check_ranges.push(self.bit_indices.len());
     }
 
     fn add_bit_indices(&mut self, mut check: Check) {
         check.sort();
+        check.dedup();
         self.bit_indices.append(&mut check);
     }
 
@@ -154,792 +336,3703 @@ impl ParityCheckMatrix {
             bit_indices: (0..n_bits).collect(),
             check_ranges: (0..n_bits + 1).collect(),
             n_bits,
+            bit_adjacency: OnceLock::new(),
         }
     }
 
-    // ***** Getters *****
-
-    /// Returns the number of bits in `self`.
-    pub fn get_n_bits(&self) -> usize {
-        self.n_bits
+    /// Creates the parity check matrix of the repetition code on `n_bits`.
+    ///
+    /// There are `n_bits - 1` checks and each check `i` is connected to the bits `i` and
+    /// `i + 1`. Thus, there are no checks if `n_bits` is 0 or 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// assert_eq!(matrix, ParityCheckMatrix::with_n_bits(3).with_checks(checks));
+    /// ```
+    pub fn repetition_code_with_n_bits(n_bits: usize) -> ParityCheckMatrix {
+        let checks = (1..n_bits).map(|bit| vec![bit - 1, bit]).collect();
+        Self::with_n_bits(n_bits).with_checks(checks)
     }
 
-    /// Returns the number of checks in `self`.
-    pub fn get_n_checks(&self) -> usize {
-        if self.check_ranges().len() > 0 {
-            self.check_ranges().len() - 1
+    /// Creates the parity check matrix of the single parity check code on `n_bits`.
+    ///
+    /// There is a single check connected to all bits, except if `n_bits` is 0 in which case
+    /// there are no checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::single_parity_check_code_with_n_bits(3);
+    ///
+    /// assert_eq!(matrix, ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1, 2]]));
+    /// ```
+    pub fn single_parity_check_code_with_n_bits(n_bits: usize) -> ParityCheckMatrix {
+        let checks = if n_bits == 0 {
+            Vec::new()
         } else {
-            0
-        }
+            vec![(0..n_bits).collect()]
+        };
+        Self::with_n_bits(n_bits).with_checks(checks)
     }
 
-    /// Returns the number of edges in `self`.
-    pub fn get_n_edges(&self) -> usize {
-        self.bit_indices.len()
+    /// Creates the parity check matrix of the Hamming code with `n_checks` checks.
+    ///
+    /// There are `2^n_checks - 1` bits and the checks connected to bit `i` are given by the
+    /// binary expansion of `i + 1`. That is, check `j` is connected to bit `i` if the `j`-th
+    /// binary digit of `i + 1` is 1.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `n_checks` is less than 2 or if the number of bits doesn't fit in a `usize`,
+    /// that is if `n_checks` is at least `usize::BITS`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::*;
+    ///
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    ///
+    /// assert_eq!(code.get_n_bits(), 7);
+    /// assert_eq!(code.get_minimum_distance(), Some(3));
+    ///
+    /// let mut decoder = ErasureDecoder::with_prob(0.1).for_code(code);
+    /// assert_eq!(decoder.decode(&vec![4]).get_outcome(), ErasureResult::Success);
+    /// ```
+    pub fn hamming_code_with_n_checks(n_checks: usize) -> ParityCheckMatrix {
+        if n_checks < 2 {
+            panic!("hamming codes need at least 2 checks");
+        }
+        if n_checks >= usize::BITS as usize {
+            panic!("too many checks");
+        }
+        let n_bits = (1 << n_checks) - 1;
+        let checks = (0..n_checks)
+            .map(|check| {
+                (0..n_bits)
+                    .filter(|bit| (bit + 1) & (1 << check) != 0)
+                    .collect()
+            })
+            .collect();
+        Self::with_n_bits(n_bits).with_checks(checks)
     }
 
-    /// Returns the degree of each bit in `self`.
+    /// Creates a quasi-cyclic parity check matrix by lifting the base matrix `exponents`.
+    ///
+    /// Each entry of the base matrix is expanded into a `lift_size` by `lift_size` block. An
+    /// entry `Some(shift)` gives the identity with its columns cyclically shifted by `shift`,
+    /// that is check `r` of the block is connected to bit `(r + shift) % lift_size`, and `None`
+    /// gives the zero block.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the rows of `exponents` have different lengths or if a shift is not smaller
+    /// than `lift_size`.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
     ///
-    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
-    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
-    /// assert_eq!(matrix.get_bit_degrees(), vec![2, 2, 2, 1, 2, 3, 0]);
+    /// let exponents = vec![vec![Some(0), Some(1)], vec![None, Some(2)]];
+    /// let matrix = ParityCheckMatrix::quasi_cyclic_code_with_exponents(&exponents, 3);
+    ///
+    /// let checks = vec![
+    ///     vec![0, 4],
+    ///     vec![1, 5],
+    ///     vec![2, 3],
+    ///     vec![5],
+    ///     vec![3],
+    ///     vec![4],
+    /// ];
+    /// assert_eq!(matrix, ParityCheckMatrix::with_n_bits(6).with_checks(checks));
     /// ```
-    pub fn get_bit_degrees(&self) -> Vec<usize> {
-        let mut degrees = vec![0; self.n_bits];
-        self.checks_iter()
-            .for_each(|check| check.iter().for_each(|bit| degrees[*bit] += 1));
-        degrees
+    pub fn quasi_cyclic_code_with_exponents(
+        exponents: &[Vec<Option<usize>>],
+        lift_size: usize,
+    ) -> ParityCheckMatrix {
+        let n_bit_blocks = exponents.first().map(|row| row.len()).unwrap_or(0);
+        if exponents.iter().any(|row| row.len() != n_bit_blocks) {
+            panic!("rows of exponents have different lengths");
+        }
+        if exponents.iter().flatten().flatten().any(|shift| *shift >= lift_size) {
+            panic!("some shifts are not smaller than the lift size");
+        }
+        let checks = exponents
+            .iter()
+            .flat_map(|row| {
+                (0..lift_size).map(move |check| {
+                    row.iter()
+                        .enumerate()
+                        .filter_map(|(block, shift)| {
+                            shift.map(|shift| block * lift_size + (check + shift) % lift_size)
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        Self::with_n_bits(n_bit_blocks * lift_size).with_checks(checks)
     }
 
-    /// Returns the degree of each check in `self`.
+    /// Creates a random parity check matrix where each bit is connected to each check with
+    /// probability `density` using the random number generator `rng`.
+    ///
+    /// If `without_empty_nodes` is true, empty checks are sampled again and bits that are not
+    /// connected to any check get a new sampled column until they are connected.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `density` is not between 0 and 1, or if it is 0 while `without_empty_nodes` is
+    /// true and the matrix has some bits and checks.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
     ///
-    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
-    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
-    /// assert_eq!(matrix.get_check_degrees(), vec![4, 3, 3, 2]);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let matrix = ParityCheckMatrix::random_with_density_with_rng(20, 10, 0.1, true, &mut rng);
+    ///
+    /// assert_eq!(matrix.get_n_bits(), 20);
+    /// assert_eq!(matrix.get_n_checks(), 10);
+    /// assert!(!matrix.get_degree_distribution().has_unconnected_bits());
     /// ```
-    pub fn get_check_degrees(&self) -> Vec<usize> {
-        self.checks_iter().map(|check| check.get_n_bits()).collect()
+    pub fn random_with_density_with_rng<R: Rng>(
+        n_bits: usize,
+        n_checks: usize,
+        density: f64,
+        without_empty_nodes: bool,
+        rng: &mut R,
+    ) -> ParityCheckMatrix {
+        let sampler = RandomMatrixSampler::from(n_bits, n_checks, density);
+        if without_empty_nodes {
+            sampler.sample_without_empty_nodes_with_rng(rng)
+        } else {
+            sampler.sample_with_rng(rng)
+        }
     }
 
-    /// Returns `Some` view over the given `check` in `self`. Returns `None` if
-    /// `check` is out of bound.
+    /// Creates a random parity check matrix where each bit is connected to each check with
+    /// probability `density` using the thread random number generator.
+    ///
+    /// See `random_with_density_with_rng` for more details.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
     ///
-    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let matrix = ParityCheckMatrix::random_with_density(20, 10, 1.0, false);
+    /// assert_eq!(matrix.get_n_edges(), 200);
+    /// ```
+    pub fn random_with_density(
+        n_bits: usize,
+        n_checks: usize,
+        density: f64,
+        without_empty_nodes: bool,
+    ) -> ParityCheckMatrix {
+        Self::random_with_density_with_rng(
+            n_bits,
+            n_checks,
+            density,
+            without_empty_nodes,
+            &mut thread_rng(),
+        )
+    }
+
+    /// Creates a parity check matrix from dense `rows`. Each row is a check and the bits are the
+    /// positions of the `GF2::B1` elements.
     ///
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    /// # Panic
     ///
-    /// let check = parity_check.get_check(0).unwrap();
-    /// assert_eq!(check.as_ref(), &[0, 1]);
+    /// Panics if the rows don't all have the same length.
     ///
-    /// let check = parity_check.get_check(1).unwrap();
-    /// assert_eq!(check.as_ref(), &[1, 2]);
+    /// # Example
     ///
-    /// assert!(parity_check.get_check(2).is_none());
     /// ```
-    pub fn get_check(&self, check: usize) -> Option<CheckView> {
-        self.check_ranges.get(check).and_then(|&check_start| {
-            self.check_ranges
-                .get(check + 1)
-                .map(|&check_end| CheckView::from_slice(&self.bit_indices[check_start..check_end]))
-        })
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let rows = vec![
+    ///     vec![GF2::B1, GF2::B1, GF2::B0, GF2::B0],
+    ///     vec![GF2::B0, GF2::B1, GF2::B1, GF2::B0],
+    /// ];
+    /// let matrix = ParityCheckMatrix::from_dense(&rows);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// assert_eq!(matrix, expected);
+    /// ```
+    pub fn from_dense(rows: &[Vec<GF2>]) -> Self {
+        let n_bits = rows.first().map(|row| row.len()).unwrap_or(0);
+        if rows.iter().any(|row| row.len() != n_bits) {
+            panic!("rows have different lengths");
+        }
+        let checks = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, value)| **value == GF2::B1)
+                    .map(|(bit, _)| bit)
+                    .collect()
+            })
+            .collect();
+        Self::with_n_bits(n_bits).with_checks(checks)
     }
 
-    /// Computes the syndrome of a given `message`.
+    /// Reads a parity check matrix from an alist file.
+    ///
+    /// Index lists may be padded with zeros. Returns an error if the file can't be read, if it is
+    /// not a valid alist file or if the bit and check lists don't describe the same matrix.
     ///
     /// # Example
     ///
     /// ```
-    /// use believer::{GF2, ParityCheckMatrix};
+    /// use believer::ParityCheckMatrix;
     ///
-    /// let checks = vec![vec![0, 1], vec![1, 2]];
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    /// let matrix = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
     ///
-    /// let message = vec![GF2::B0, GF2::B1, GF2::B1];
+    /// let path = std::env::temp_dir().join("believer_from_alist_example.alist");
+    /// matrix.to_alist(&path).unwrap();
     ///
-    /// assert_eq!(parity_check.get_syndrome_of(&message), vec![GF2::B1, GF2::B0]);
+    /// assert_eq!(ParityCheckMatrix::from_alist(&path).unwrap(), matrix);
+    /// # std::fs::remove_file(&path).unwrap();
     /// ```
-    pub fn get_syndrome_of(&self, message: &[GF2]) -> Vec<GF2> {
-        self.checks_iter()
-            .map(|check| check.compute_syndrome(message))
-            .collect()
+    pub fn from_alist(path: &Path) -> io::Result<Self> {
+        AlistReader::from(BufReader::new(File::open(path)?))?.read_matrix()
     }
 
-    /// Computes the rank of `self`.
+    /// Writes `self` to an alist file, padding index lists with zeros.
+    ///
+    /// The output can be read back with `from_alist`.
+    pub fn to_alist(&self, path: &Path) -> io::Result<()> {
+        AlistWriter::from(self).write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// Reads a parity check matrix from a Matrix Market file.
+    ///
+    /// This is the same as `read_mtx` on the content of the file at `path`.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
     ///
-    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    /// let matrix = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
     ///
-    /// assert_eq!(parity_check.get_rank(), 2);
+    /// let path = std::env::temp_dir().join("believer_from_mtx_example.mtx");
+    /// matrix.to_mtx(&path).unwrap();
+    ///
+    /// assert_eq!(ParityCheckMatrix::from_mtx(&path).unwrap(), matrix);
+    /// # std::fs::remove_file(&path).unwrap();
     /// ```
-    pub fn get_rank(&self) -> usize {
-        self.rank()
+    pub fn from_mtx(path: &Path) -> io::Result<Self> {
+        Self::read_mtx(BufReader::new(File::open(path)?))
     }
 
-    pub fn rank(&self) -> usize{
-        
-        let n_cols = self.n_bits;
-        let n_rows = self.get_n_checks();
-        let mut rank = 0;
-
-        let mut tmp_matrix: Vec<Vec<usize>> = Vec::with_capacity(n_rows); 
-
-        for i in 0..n_rows {
-
-            tmp_matrix.push(self.bit_indices[self.check_ranges[i]..self.check_ranges[i+1]].to_vec()); // we store the original matrix as a vector of sparse rows
+    /// Writes `self` to a Matrix Market file.
+    ///
+    /// This is the same as `write_mtx` into the file at `path`. The output can be read back with
+    /// `from_mtx`.
+    pub fn to_mtx(&self, path: &Path) -> io::Result<()> {
+        self.write_mtx(BufWriter::new(File::create(path)?))
+    }
 
-        }
-       
-        for j in 0..n_cols {
+    /// Reads a parity check matrix in the Matrix Market format from `reader`.
+    ///
+    /// Only coordinate pattern general matrices are supported, where the rows are the checks
+    /// and the columns are the bits. Returns an error if the reader fails or if the data is not
+    /// a valid matrix of this kind. To prevent a hostile header from allocating a huge matrix,
+    /// matrices with more than `2^24` checks or bits are also rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let input = "%%MatrixMarket matrix coordinate pattern general\n\
+    ///              % The repetition code on 3 bits\n\
+    ///              2 3 4\n\
+    ///              1 1\n\
+    ///              1 2\n\
+    ///              2 2\n\
+    ///              2 3\n";
+    /// let matrix = ParityCheckMatrix::read_mtx(input.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(matrix, ParityCheckMatrix::repetition_code_with_n_bits(3));
+    /// ```
+    pub fn read_mtx<R: BufRead>(reader: R) -> io::Result<Self> {
+        MatrixMarketReader::from(reader).read_matrix()
+    }
 
-            for i in 0..n_rows { 
+    /// Writes `self` to `writer` in the Matrix Market format as a coordinate pattern matrix
+    /// with the edges sorted by check and then by bit.
+    ///
+    /// Returns an error if the writer fails. The output can be read back with `read_mtx`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![2, 0], vec![], vec![]]);
+    ///
+    /// let mut output = Vec::new();
+    /// matrix.write_mtx(&mut output).unwrap();
+    ///
+    /// let expected = "%%MatrixMarket matrix coordinate pattern general\n\
+    ///                 3 3 2\n\
+    ///                 1 1\n\
+    ///                 1 3\n";
+    /// assert_eq!(String::from_utf8(output.clone()).unwrap(), expected);
+    /// assert_eq!(ParityCheckMatrix::read_mtx(output.as_slice()).unwrap(), matrix);
+    /// ```
+    pub fn write_mtx<W: Write>(&self, writer: W) -> io::Result<()> {
+        MatrixMarketWriter::from(self).write_to(writer)
+    }
 
-                if tmp_matrix[i].len() > 0 && tmp_matrix[i][0] == j    { // select a NEW pivot
+    /// Writes the dense form of `self` to a NumPy .npy file.
+    ///
+    /// The array has type `uint8` and shape `(n_checks, n_bits)`, and it can be loaded with
+    /// `numpy.load`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    ///
+    /// let path = std::env::temp_dir().join("believer_to_npy_example.npy");
+    /// matrix.to_npy(&path).unwrap();
+    ///
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// assert!(bytes.starts_with(b"\x93NUMPY"));
+    /// assert!(bytes.ends_with(&[0, 0, 0, 1, 1, 1, 1]));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn to_npy(&self, path: &Path) -> io::Result<()> {
+        NpyWriter::from(self).write_to(BufWriter::new(File::create(path)?))
+    }
 
-                    for k in 0..i {
+    /// Reads a parity check matrix written by `write_binary` from `reader`.
+    ///
+    /// No bytes are consumed after the end of the matrix, so consecutive matrices can be read
+    /// from the same reader. Returns an error if the reader fails, if it ends before the end of
+    /// the matrix or if the data is not a valid binary matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let codes = vec![
+    ///     ParityCheckMatrix::hamming_code_with_n_checks(3),
+    ///     ParityCheckMatrix::repetition_code_with_n_bits(5),
+    /// ];
+    ///
+    /// let mut bytes = Vec::new();
+    /// codes.iter().for_each(|code| code.write_binary(&mut bytes).unwrap());
+    ///
+    /// let mut reader = bytes.as_slice();
+    /// let read_codes: Vec<ParityCheckMatrix> = (0..2)
+    ///     .map(|_| ParityCheckMatrix::read_binary(&mut reader).unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(read_codes, codes);
+    /// ```
+    pub fn read_binary<R: Read>(reader: R) -> io::Result<Self> {
+        BinaryReader::from(reader).read_matrix()
+    }
 
-                        if  tmp_matrix[k].len() > 0 && tmp_matrix[k][0] == j  {
-
-                            tmp_matrix[k] = add_checks(&tmp_matrix[i],&tmp_matrix[k]);
-
-                        }
-
-                    }
-                    for k in (i+1)..n_rows {
-
-                        if tmp_matrix[k].len() > 0 && tmp_matrix[k][0] == j   {
-
-                            tmp_matrix[k] = add_checks(&tmp_matrix[i],&tmp_matrix[k]);
-
-                        }
-
-
-                    }
-
-                    unsafe {tmp_matrix[i].set_len(0)};
-                    rank += 1;
-
-                    break
-                }
-            }
-
-            
-        }
-       
-        rank
+    /// Writes `self` to `writer` in a compact binary format.
+    ///
+    /// The format has a versioned header followed by the check degrees and the bits of each
+    /// check, all encoded as little endian `u32`s. Returns an error if the writer fails or if a
+    /// dimension doesn't fit in a `u32`. The output can be read back with `read_binary`.
+    pub fn write_binary<W: Write>(&self, writer: W) -> io::Result<()> {
+        BinaryWriter::from(self).write_to(writer)
+    }
 
+    /// Returns the Tanner graph of `self` in the Graphviz DOT format.
+    ///
+    /// Bits are drawn as circles and checks as squares, each labeled by its index and laid out
+    /// in two columns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let graph = ParityCheckMatrix::repetition_code_with_n_bits(3).get_dot_graph();
+    ///
+    /// assert!(graph.starts_with("graph tanner {"));
+    /// assert!(graph.contains("b1 -- c0;"));
+    /// ```
+    pub fn get_dot_graph(&self) -> String {
+        DotWriter::from(self).get_string()
     }
 
-    pub fn tmp_rank_pcm(&self) -> Vec<Vec<usize>>{
-        let n_rows = self.get_n_checks();
-        let mut tmp_matrix: Vec<Vec<usize>> = Vec::with_capacity(n_rows); 
-        for _ in 0..n_rows {
-
-            let new_row = Vec::with_capacity(n_rows);
-
-            tmp_matrix.push(new_row); 
-
-        }
-
-        tmp_matrix
+    /// Returns the Tanner graph of `self` in the Graphviz DOT format with the given `bits`
+    /// filled in red. This is useful to look at an erasure pattern or a stopping set.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let graph = code.get_dot_graph_highlighting(&[1]);
+    ///
+    /// assert!(graph.contains("b1 [label=\"b1\", shape=circle, style=filled, fillcolor=red];"));
+    /// ```
+    pub fn get_dot_graph_highlighting(&self, bits: &[usize]) -> String {
+        DotWriter::from(self).highlighting(bits).get_string()
     }
 
-    pub fn init_rank_tmp(&self, tmp_matrix: &mut Vec<Vec<usize>>) {
-
-        for i in 0..tmp_matrix.len() {
-            let new_row = &self.bit_indices[self.check_ranges[i]..self.check_ranges[i+1]];
-
-            tmp_matrix[i].clear();
-            
-            for j in 0..new_row.len() {
-                tmp_matrix[i].push(new_row[j]);
-            }
-
-
-        }
-
+    /// Writes the Tanner graph of `self` to a Graphviz DOT file.
+    ///
+    /// The content of the file is given by `get_dot_graph`.
+    pub fn to_dot(&self, path: &Path) -> io::Result<()> {
+        DotWriter::from(self).write_to(BufWriter::new(File::create(path)?))
     }
 
+    // ***** Getters *****
 
-    pub fn rank_mut(&self, tmp_matrix: &mut Vec<Vec<usize>>, tmp_sum: &mut Vec<usize>) -> usize{
-        
-        let n_cols = self.n_bits;
-        let n_rows = self.get_n_checks();
-        let mut rank = 0;
-
-        self.init_rank_tmp(tmp_matrix);
-       
-        for j in 0..n_cols {
-
-            for i in 0..n_rows { 
-
-                if tmp_matrix[i].len() > 0 && tmp_matrix[i][0] == j    { // select a NEW pivot
-
-                    for k in 0..i {
-
-                        if  tmp_matrix[k].len() > 0 && tmp_matrix[k][0] == j  {
-                            
-                            add_checks_mut(&tmp_matrix[i], &tmp_matrix[k],tmp_sum);
-                            transfer_to(tmp_sum, &mut tmp_matrix[k]);
-                            tmp_sum.clear();
-                           // println!("{:?}",tmp_matrix[k]);
-
-                        }
-
-                    }
-                    for k in (i+1)..n_rows {
-
-                        if tmp_matrix[k].len() > 0 && tmp_matrix[k][0] == j   {
-
-                            add_checks_mut(&tmp_matrix[i], &tmp_matrix[k],tmp_sum);
-                            transfer_to(tmp_sum, &mut tmp_matrix[k]);
-                            tmp_sum.clear();
-                            //println!("{:?}",tmp_matrix[k]);
-                        }
-
-
-                    }
-
-                    unsafe {tmp_matrix[i].set_len(0)};
-                    rank += 1;
-
-                    break
-                }
-            }
+    /// Returns the number of bits in `self`.
+    pub fn get_n_bits(&self) -> usize {
+        self.n_bits
+    }
 
-            
+    /// Returns the number of checks in `self`.
+    pub fn get_n_checks(&self) -> usize {
+        if !self.check_ranges().is_empty() {
+            self.check_ranges().len() - 1
+        } else {
+            0
         }
-       
-        rank
+    }
 
+    /// Returns the number of edges in `self`.
+    pub fn get_n_edges(&self) -> usize {
+        self.bit_indices.len()
     }
 
-    /// Gets the transposed version of `self` by swapping the bits with the checks.
+    /// Returns the degree of each bit in `self`.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
     ///
-    /// let checks = vec![vec![0, 1, 2], vec![1, 3], vec![0, 2, 3]];
-    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
+    /// assert_eq!(matrix.get_bit_degrees(), vec![2, 2, 2, 1, 2, 3, 0]);
+    /// ```
+    pub fn get_bit_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.n_bits];
+        self.checks_iter()
+            .for_each(|check| check.iter().for_each(|bit| degrees[*bit] += 1));
+        degrees
+    }
+
+    /// Returns the degree of each check in `self`.
     ///
-    /// let transposed_matrix = matrix.get_transposed_matrix();
+    /// # Example
     ///
-    /// let expected_checks = vec![vec![0, 2], vec![0, 1], vec![0, 2], vec![1, 2]];
-    /// let expected_matrix = ParityCheckMatrix::with_n_bits(3).with_checks(expected_checks);
+    /// ```
+    /// use believer::ParityCheckMatrix;
     ///
-    /// assert_eq!(transposed_matrix, expected_matrix);
+    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
+    /// assert_eq!(matrix.get_check_degrees(), vec![4, 3, 3, 2]);
     /// ```
-    pub fn get_transposed_matrix(&self) -> Self {
-        Transposer::from(self).get_transposed_matrix()
+    pub fn get_check_degrees(&self) -> Vec<usize> {
+        self.checks_iter().map(|check| check.get_n_bits()).collect()
     }
 
-    /// Returns the horizontal concatenation of `self` with `other`.
+    /// Returns the degree of the given `bit` or `None` if it is out of bound.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
-    /// let left_matrix = ParityCheckMatrix::with_n_bits(3)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
-    /// let right_matrix = ParityCheckMatrix::with_n_bits(4)
-    ///     .with_checks(vec![vec![1, 2, 3], vec![0, 1], vec![2, 3]]);
-    ///
-    /// let concatened = left_matrix.get_horizontal_concat_with(&right_matrix);
     ///
-    /// let expected = ParityCheckMatrix::with_n_bits(7)
-    ///     .with_checks(vec![vec![0, 1, 4, 5, 6], vec![1, 2, 3, 4], vec![5, 6]]);
+    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
     ///
-    /// assert_eq!(concatened, expected);
+    /// assert_eq!(matrix.get_bit_degree(5), Some(3));
+    /// assert_eq!(matrix.get_bit_degree(6), Some(0));
+    /// assert_eq!(matrix.get_bit_degree(7), None);
     /// ```
-    pub fn get_horizontal_concat_with(&self, other: &ParityCheckMatrix) -> ParityCheckMatrix {
-        Concatener::from(self, other).concat_horizontally()
+    pub fn get_bit_degree(&self, bit: usize) -> Option<usize> {
+        self.get_checks_adjacent_to_bit(bit).map(|checks| checks.len())
     }
 
-    /// Returns the diagonal concatenation of `self` with `other`.
+    /// Returns the degree of the given `check` or `None` if it is out of bound.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
-    /// let left_matrix = ParityCheckMatrix::with_n_bits(3)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
-    /// let right_matrix = ParityCheckMatrix::with_n_bits(4)
-    ///     .with_checks(vec![vec![1, 2, 3], vec![0, 1], vec![2, 3]]);
-    ///
-    /// let concatened = left_matrix.get_diagonal_concat_with(&right_matrix);
     ///
-    /// let expected = ParityCheckMatrix::with_n_bits(7)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![4, 5, 6], vec![3, 4], vec![5, 6]]);
+    /// let checks = vec![vec![0, 1, 2, 5], vec![], vec![2, 4, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
     ///
-    /// assert_eq!(concatened, expected);
+    /// assert_eq!(matrix.get_check_degree(0), Some(4));
+    /// assert_eq!(matrix.get_check_degree(1), Some(0));
+    /// assert_eq!(matrix.get_check_degree(3), None);
     /// ```
-    pub fn get_diagonal_concat_with(&self, other: &ParityCheckMatrix) -> ParityCheckMatrix {
-        Concatener::from(self, other).concat_diagonally()
+    pub fn get_check_degree(&self, check: usize) -> Option<usize> {
+        self.get_check(check).map(|bits| bits.get_n_bits())
     }
 
-    // ***** Iterators *****
-
-    /// Returns an iterator that yields a slice for each check of `self`.
+    /// Returns the bits of `self` that are not connected to any check. Erasures of these bits
+    /// can never be corrected.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
     ///
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 2, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
     ///
-    /// let mut iter = parity_check.checks_iter();
+    /// assert_eq!(matrix.get_unprotected_bits(), vec![3, 4, 6]);
+    /// ```
+    pub fn get_unprotected_bits(&self) -> Vec<usize> {
+        self.get_bit_degrees()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, degree)| *degree == 0)
+            .map(|(bit, _)| bit)
+            .collect()
+    }
+
+    /// Checks if some bits of `self` are not connected to any check.
+    ///
+    /// # Example
     ///
-    /// assert_eq!(iter.next(), parity_check.get_check(0));
-    /// assert_eq!(iter.next(), parity_check.get_check(1));
-    /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn checks_iter(&self) -> ChecksIter {
-        ChecksIter::from(self)
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks.clone());
+    /// let wider_matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// assert!(!matrix.has_unprotected_bits());
+    /// assert!(wider_matrix.has_unprotected_bits());
+    /// ```
+    pub fn has_unprotected_bits(&self) -> bool {
+        self.get_bit_degrees().contains(&0)
     }
 
-    /// An iterators over all edges in `self` ordered by check first.
+    /// Returns the fractions of bits and checks of each degree in `self`.
     ///
     /// # Example
     ///
     /// ```
-    /// # use believer::*;
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// use believer::ParityCheckMatrix;
     ///
-    /// let mut iter = parity_check.edges_iter();
+    /// let checks = vec![vec![0, 1, 2, 5], vec![1, 3, 4], vec![2, 4, 5], vec![0, 5]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(checks);
+    /// let distribution = matrix.get_degree_distribution();
     ///
-    /// assert_eq!(iter.next(), Some((0, 0)));
-    /// assert_eq!(iter.next(), Some((0, 1)));
-    /// assert_eq!(iter.next(), Some((1, 1)));
-    /// assert_eq!(iter.next(), Some((1, 2)));
-    /// assert_eq!(iter.next(), None);
+    /// assert!(distribution.has_unconnected_bits());
+    /// assert_eq!(distribution.get_check_fraction_of_degree(3), 0.5);
     /// ```
-    pub fn edges_iter(&self) -> EdgesIter {
-        EdgesIter::from(self)
+    pub fn get_degree_distribution(&self) -> DegreeDistribution {
+        DegreeDistribution::from(self)
     }
 
-    /// Checks if a given `message` is a codeword of `self`.
+    /// Returns the dense representation of `self`. There is one row of length
+    /// `self.get_n_bits()` for each check.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::{GF2, ParityCheckMatrix};
-    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
-    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
-    /// let message = vec![GF2::B0, GF2::B1, GF2::B1];
-    /// let codeword = vec![GF2::B0; 3];
     ///
-    /// assert_eq!(parity_check.has_codeword(&message), false);
-    /// assert_eq!(parity_check.has_codeword(&codeword), true);
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// let expected = vec![
+    ///     vec![GF2::B1, GF2::B1, GF2::B0, GF2::B0],
+    ///     vec![GF2::B0, GF2::B1, GF2::B1, GF2::B0],
+    /// ];
+    /// assert_eq!(matrix.to_dense(), expected);
     /// ```
-    pub fn has_codeword(&self, message: &[GF2]) -> bool {
+    pub fn to_dense(&self) -> Vec<Vec<GF2>> {
         self.checks_iter()
-            .all(|check| check.compute_syndrome(message) == GF2::B0)
+            .map(|check| {
+                let mut row = vec![GF2::B0; self.get_n_bits()];
+                check.iter().for_each(|bit| row[*bit] = GF2::B1);
+                row
+            })
+            .collect()
     }
 
-    /// Returns a truncated parity check matrix with only the column of the given `bits`.
+    /// Returns `Some` slice of the checks connected to the given `bit` in `self`. Returns `None`
+    /// if `bit` is out of bound.
+    ///
+    /// The bit adjacency is computed the first time a bit oriented method is called and is then
+    /// reused.
     ///
     /// # Example
     ///
     /// ```
     /// use believer::ParityCheckMatrix;
-    /// let checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
-    ///     vec![0, 1, 2],
-    ///     vec![2, 3, 4],
-    ///     vec![0, 2, 4],
-    ///     vec![1, 3],
-    /// ]);
     ///
-    /// let truncated_checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
-    ///     vec![0, 1],
-    ///     vec![4],
-    ///     vec![0, 4],
-    ///     vec![1],
-    /// ]);
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 1, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(5).with_checks(checks);
     ///
-    /// assert_eq!(checks.keep(&[0, 1, 4]), truncated_checks);
+    /// assert_eq!(parity_check.get_checks_adjacent_to_bit(1), Some(&[0, 1, 2][..]));
+    /// assert_eq!(parity_check.get_checks_adjacent_to_bit(4), Some(&[][..]));
+    /// assert_eq!(parity_check.get_checks_adjacent_to_bit(5), None);
     /// ```
-    pub fn keep(&self, bits: &[usize]) -> Self {
-        let checks = self
-            .checks_iter()
-            .map(|check| {
-                check
-                    .iter()
-                    .filter(|&bit| bits.iter().any(|b| b == bit))
-                    .cloned()
-                    .collect()
-            })
-            .collect();
-        Self::with_n_bits(self.get_n_bits()).with_checks(checks)
+    pub fn get_checks_adjacent_to_bit(&self, bit: usize) -> Option<&[usize]> {
+        self.get_bit_adjacency().get_checks_adjacent_to(bit)
     }
 
-    pub fn keep_merged(&self, bits: &[usize]) -> ParityCheckMatrix{
-        
-        let mut tot_nb_checks = 0;
-        let mut target = ParityCheckMatrix::with_n_bits(self.get_n_bits());
-        target.check_ranges.push(0);
-
-        for check in self.checks_iter() {
-
-            let mut nb_check = 0;
-
-            check
-                .iter()
-                .filter(|&bit| {
-
-                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
-
-                    let mut found = false;
-                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
-                    if bit < &(self.get_n_bits()/2) {
-                        found = binary_search(bits, bit);
-                    } else {
-                        found = binary_search(bits, &(bit - (self.get_n_bits()/2)));// do we found the check in either the Z part or the X part. Both have width n_bits/2
-                    }
-                    
-                    if found {
-                        target.bit_indices.push(*bit);
-                        nb_check += 1;
-                    }
-                    found
-                    }).count();
-
-            tot_nb_checks += nb_check;
-            target.check_ranges.push(tot_nb_checks);
+    fn get_bit_adjacency(&self) -> &BitAdjacency {
+        self.bit_adjacency.get_or_init(|| BitAdjacency::from(self))
+    }
 
+    /// Returns `Some` view over the given `check` in `self`. Returns `None` if
+    /// `check` is out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// let check = parity_check.get_check(0).unwrap();
+    /// assert_eq!(check.as_ref(), &[0, 1]);
+    ///
+    /// let check = parity_check.get_check(1).unwrap();
+    /// assert_eq!(check.as_ref(), &[1, 2]);
+    ///
+    /// assert!(parity_check.get_check(2).is_none());
+    /// ```
+    pub fn get_check(&self, check: usize) -> Option<CheckView<'_>> {
+        self.check_ranges.get(check).and_then(|&check_start| {
+            self.check_ranges
+                .get(check + 1)
+                .map(|&check_end| CheckView::from_slice(&self.bit_indices[check_start..check_end]))
+        })
+    }
+
+    /// Checks if `bit` is connected to `check` in `self`.
+    ///
+    /// Since the bits of each check are sorted, this is a binary search in the check. Returns
+    /// false if `check` or `bit` is out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// assert!(parity_check.has_edge(1, 2));
+    /// assert!(!parity_check.has_edge(0, 2));
+    /// assert!(!parity_check.has_edge(2, 0));
+    /// ```
+    pub fn has_edge(&self, check: usize, bit: usize) -> bool {
+        self.get_check(check)
+            .map(|bits| bits.as_ref().binary_search(&bit).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// Returns the entry of `self` at the given `check` and `bit`, that is `GF2::B1` if they
+    /// are connected and `GF2::B0` otherwise.
+    ///
+    /// Returns `GF2::B0` if `check` or `bit` is out of bound instead of panicking.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// assert_eq!(parity_check.get_value_at(0, 1), GF2::B1);
+    /// assert_eq!(parity_check.get_value_at(0, 2), GF2::B0);
+    /// assert_eq!(parity_check.get_value_at(5, 1), GF2::B0);
+    /// ```
+    pub fn get_value_at(&self, check: usize, bit: usize) -> GF2 {
+        if self.has_edge(check, bit) {
+            GF2::B1
+        } else {
+            GF2::B0
         }
+    }
 
-        target
+    /// Computes the syndrome of a given `message`. That is, the value of every check of `self`
+    /// for `message`.
+    ///
+    /// To only know if the syndrome is zero, use `has_codeword` which stops at the first
+    /// unsatisfied check.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// let message = vec![GF2::B0, GF2::B1, GF2::B1];
+    ///
+    /// assert_eq!(parity_check.get_syndrome_of(&message), vec![GF2::B1, GF2::B0]);
+    /// ```
+    pub fn get_syndrome_of(&self, message: &[GF2]) -> Vec<GF2> {
+        self.panic_if_message_has_wrong_length(message);
+        self.checks_iter()
+            .map(|check| check.compute_syndrome(message))
+            .collect()
+    }
 
+    /// Computes the syndromes of all `messages` in parallel.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of some message is not the number of bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let parity_check = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let messages = vec![vec![GF2::B1, GF2::B1, GF2::B1], vec![GF2::B0, GF2::B1, GF2::B0]];
+    ///
+    /// let syndromes = parity_check.get_syndromes_of_batch(&messages);
+    ///
+    /// assert_eq!(syndromes, vec![vec![GF2::B0, GF2::B0], vec![GF2::B1, GF2::B1]]);
+    /// ```
+    pub fn get_syndromes_of_batch(&self, messages: &[Vec<GF2>]) -> Vec<Vec<GF2>> {
+        messages
+            .par_iter()
+            .map(|message| self.get_syndrome_of(message))
+            .collect()
     }
 
-    pub fn without_merged(&self, bits: &[usize]) -> ParityCheckMatrix{
-        
-        let mut tot_nb_checks = 0;
-        let mut target = ParityCheckMatrix::with_n_bits(self.get_n_bits());
-        target.check_ranges.push(0);
+    /// Counts in parallel the `messages` that have a non zero syndrome, that is the messages
+    /// that are not codewords.
+    ///
+    /// This is cheaper than computing all the syndromes since each message is only checked
+    /// until its first unsatisfied check.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of some message is not the number of bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let parity_check = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let messages = vec![
+    ///     vec![GF2::B1, GF2::B1, GF2::B1],
+    ///     vec![GF2::B0, GF2::B1, GF2::B0],
+    ///     vec![GF2::B1, GF2::B0, GF2::B0],
+    /// ];
+    ///
+    /// assert_eq!(parity_check.get_n_nonzero_syndromes_of_batch(&messages), 2);
+    /// ```
+    pub fn get_n_nonzero_syndromes_of_batch(&self, messages: &[Vec<GF2>]) -> usize {
+        messages
+            .par_iter()
+            .filter(|message| !self.has_codeword(message))
+            .count()
+    }
 
-        for check in self.checks_iter() {
+    /// Returns a copy of `self` where each check is stored as packed bits.
+    ///
+    /// The packed matrix computes syndromes with bitwise operations, which is faster than
+    /// `get_syndrome_of` for dense matrices or long messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let parity_check = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    /// let packed_parity_check = parity_check.get_packed_matrix();
+    ///
+    /// let message = vec![GF2::B1, GF2::B0, GF2::B0, GF2::B0, GF2::B0, GF2::B0, GF2::B1];
+    ///
+    /// assert_eq!(
+    ///     packed_parity_check.get_syndrome_of(&message),
+    ///     parity_check.get_syndrome_of(&message)
+    /// );
+    /// ```
+    pub fn get_packed_matrix(&self) -> PackedParityCheckMatrix {
+        PackedParityCheckMatrix::from(self)
+    }
 
-            let mut nb_check = 0;
+    /// Computes the rank of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// assert_eq!(parity_check.get_rank(), 2);
+    /// ```
+    pub fn get_rank(&self) -> usize {
+        self.rank()
+    }
 
-            check
-                .iter()
-                .filter(|&bit| {
+    fn panic_if_message_has_wrong_length(&self, message: &[GF2]) {
+        if message.len() != self.get_n_bits() {
+            panic!("message doesn't have the right length");
+        }
+    }
 
-                    let mut found = false;
-                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
-                    if bit < &(self.get_n_bits()/2) {
-                        found = !binary_search(bits, bit);
-                    } else {
-                        found = !binary_search(bits, &(bit - (self.get_n_bits()/2)));// do we found the check in either the Z part or the X part. Both have width n_bits/2
-                    }
+    /// Computes the rank of `self` over GF(2) using a sparse Gaussian elimination.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// assert_eq!(parity_check.rank(), 3);
+    /// ```
+    pub fn rank(&self) -> usize {
+        Ranker::from_parity_check_matrix(self).get_rank()
+    }
 
-                    if found {
-                        target.bit_indices.push(*bit);
-                        nb_check += 1;
-                    }
-                    found
-                    }).count();
+    /// Returns the number of checks of `self` that are linear combinations of the other checks.
+    /// That is, the number of checks minus the rank.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// assert_eq!(parity_check.get_n_redundant_checks(), 1);
+    /// ```
+    pub fn get_n_redundant_checks(&self) -> usize {
+        self.get_n_checks() - self.rank()
+    }
 
-            tot_nb_checks += nb_check;
-            target.check_ranges.push(tot_nb_checks);
+    /// Returns a copy of `self` without its redundant checks and the list of removed checks in
+    /// increasing order.
+    ///
+    /// Checks are visited in order and a check is removed if it is a linear combination of the
+    /// previous checks, including empty checks. The remaining checks are kept unchanged, so the
+    /// result has full rank and defines the same code as `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// let (matrix, removed_checks) = parity_check.get_matrix_without_redundant_checks();
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![2, 3]]);
+    /// assert_eq!(matrix, expected);
+    /// assert_eq!(removed_checks, vec![2]);
+    /// ```
+    pub fn get_matrix_without_redundant_checks(&self) -> (Self, Vec<usize>) {
+        let independent_checks = EchelonReducer::get_independent_checks_of(self);
+        let mut is_independent = vec![false; self.get_n_checks()];
+        independent_checks
+            .iter()
+            .for_each(|check| is_independent[*check] = true);
+        let removed_checks = is_independent
+            .iter()
+            .enumerate()
+            .filter(|(_, independent)| !**independent)
+            .map(|(check, _)| check)
+            .collect();
+        (self.keep_checks(&independent_checks), removed_checks)
+    }
 
-        }
+    /// Computes the reduced row echelon form of `self` over GF(2) and the list of pivot
+    /// columns in increasing order.
+    ///
+    /// There is one row for each pivot and each pivot column is only connected to its row.
+    /// The other rows of `self`, which are linear combinations of these, are dropped. The pivot
+    /// columns form a set of `rank` bits whose values are fixed by the other bits in every
+    /// codeword.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// let (reduced, pivots) = parity_check.get_reduced_row_echelon_form();
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 3], vec![1, 3], vec![2, 3]]);
+    /// assert_eq!(reduced, expected);
+    /// assert_eq!(pivots, vec![0, 1, 2]);
+    /// ```
+    pub fn get_reduced_row_echelon_form(&self) -> (Self, Vec<usize>) {
+        EchelonReducer::from(self).get_reduced_form()
+    }
 
-        target
+    /// Computes the reduced row echelon form of `self` over GF(2) where the pivots are chosen
+    /// in the order given by `bit_order` instead of the order of the bits.
+    ///
+    /// The pivots are the first `rank` bits of `bit_order` that are linearly independent
+    /// columns of `self`. The `i`-th returned pivot is the pivot of row `i` and the pivots are
+    /// listed in the order of `bit_order`. As for `get_reduced_row_echelon_form`, each pivot
+    /// column is only connected to its row and the redundant rows are dropped.
+    ///
+    /// This is used to find an information set made of the most reliable bits, since the
+    /// values of the bits that are not pivots fix the values of the pivots in every codeword.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `bit_order` is not a permutation of the bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// let (reduced, pivots) =
+    ///     parity_check.get_reduced_row_echelon_form_with_bit_order(&[2, 1, 0]);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 2], vec![0, 1]]);
+    /// assert_eq!(reduced, expected);
+    /// assert_eq!(pivots, vec![2, 1]);
+    /// ```
+    pub fn get_reduced_row_echelon_form_with_bit_order(
+        &self,
+        bit_order: &[usize],
+    ) -> (Self, Vec<usize>) {
+        if !is_permutation_of(bit_order, self.get_n_bits()) {
+            panic!("not a permutation of the bits");
+        }
+        let mut positions = vec![0; self.get_n_bits()];
+        bit_order
+            .iter()
+            .enumerate()
+            .for_each(|(position, bit)| positions[*bit] = position);
+        let (reduced, pivots) = self
+            .get_bits_permuted_by(&positions)
+            .get_reduced_row_echelon_form();
+        let pivots = pivots.into_iter().map(|pivot| bit_order[pivot]).collect();
+        (reduced.get_bits_permuted_by(bit_order), pivots)
+    }
 
+    /// Computes an equivalent matrix of `self` in systematic form `[P | I]` and the permutation
+    /// of the bits that was applied to get it.
+    ///
+    /// The last `rank` bits of the systematic form are only connected to their own check, the
+    /// `i`-th of these being connected to check `i`. Redundant checks of `self` are dropped,
+    /// so there are `self.get_n_redundant_checks()` fewer checks in the systematic form. The
+    /// permutation follows the convention of `get_bits_permuted_by`, that is bit `b` of `self`
+    /// is bit `permutation[b]` of the systematic form.
+    ///
+    /// Returns None if `self` has rank 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// let (systematic, permutation) = parity_check.get_systematic_form().unwrap();
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![0, 2], vec![0, 3]]);
+    /// assert_eq!(systematic, expected);
+    /// assert_eq!(permutation, vec![1, 2, 3, 0]);
+    /// assert_eq!(parity_check.get_n_checks() - systematic.get_n_checks(), 1);
+    /// ```
+    pub fn get_systematic_form(&self) -> Option<(Self, Vec<usize>)> {
+        let (reduced, pivots) = self.get_reduced_row_echelon_form();
+        if pivots.is_empty() {
+            return None;
+        }
+        let mut permutation = vec![0; self.get_n_bits()];
+        let mut is_pivot = vec![false; self.get_n_bits()];
+        pivots.iter().for_each(|pivot| is_pivot[*pivot] = true);
+        (0..self.get_n_bits())
+            .filter(|bit| !is_pivot[*bit])
+            .chain(pivots.iter().cloned())
+            .enumerate()
+            .for_each(|(position, bit)| permutation[bit] = position);
+        Some((reduced.get_bits_permuted_by(&permutation), permutation))
     }
 
-    /// Returns a truncated parity check matrix where the column of the given `bits` are remove.
+    /// Computes a generator matrix of the code defined by `self`. That is, a matrix whose
+    /// checks are a basis of the codewords of `self`.
+    ///
+    /// There is one generator for each bit that is not a pivot of the reduced row echelon form
+    /// of `self`, so the number of generators is the number of bits minus the rank even if some
+    /// checks of `self` are redundant.
     ///
     /// # Example
     ///
     /// ```
-    /// # use believer::*;
-    /// let checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
-    ///     vec![0, 1, 2],
-    ///     vec![2, 3, 4],
-    ///     vec![0, 2, 4],
-    ///     vec![1, 3],
-    /// ]);
+    /// use believer::{GF2, ParityCheckMatrix};
     ///
-    /// let truncated_checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
-    ///     vec![1],
-    ///     vec![3, 4],
-    ///     vec![4],
-    ///     vec![1, 3],
-    /// ]);
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
     ///
-    /// assert_eq!(checks.without(&[0, 2]), truncated_checks);
+    /// let generator = parity_check.get_generator_matrix();
+    ///
+    /// assert_eq!(generator, ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1, 2]]));
+    /// assert_eq!(generator.encode(&[GF2::B1]), vec![GF2::B1; 3]);
     /// ```
-    pub fn without(&self, bits: &[usize]) -> Self {
-        let to_keep: Vec<usize> = (0..9).filter(|x| !bits.contains(x)).collect();
-        self.keep(&to_keep)
+    pub fn get_generator_matrix(&self) -> Self {
+        let (reduced, pivots) = self.get_reduced_row_echelon_form();
+        let mut is_pivot = vec![false; self.get_n_bits()];
+        pivots.iter().for_each(|pivot| is_pivot[*pivot] = true);
+
+        let mut generators: Vec<Check> = (0..self.get_n_bits()).map(|bit| vec![bit]).collect();
+        reduced.checks_iter().zip(pivots.iter()).for_each(|(row, pivot)| {
+            row.iter()
+                .filter(|bit| !is_pivot[**bit])
+                .for_each(|bit| generators[*bit].push(*pivot));
+        });
+        let generators = generators
+            .into_iter()
+            .enumerate()
+            .filter(|(bit, _)| !is_pivot[*bit])
+            .map(|(_, generator)| generator)
+            .collect();
+        Self::with_n_bits(self.get_n_bits()).with_checks(generators)
     }
 
-    pub fn gbc(&self, b: &ParityCheckMatrix) -> ParityCheckMatrix {
-        // should check that A and B commute and that Hx*Hz^T = 0
-        let hx = self.get_horizontal_concat_with(&b);
-        let hz = b
-            .get_transposed_matrix()
-            .get_horizontal_concat_with(&self.get_transposed_matrix());
-        hx.get_diagonal_concat_with(&hz)
+    /// Computes a parity check matrix of the dual code of `self`.
+    ///
+    /// The codewords of the dual code are the linear combinations of the checks of `self`, so
+    /// its parity check matrix is a generator matrix of `self` as given by
+    /// `get_generator_matrix`. The checks are independent even if `self` has redundant checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(4);
+    ///
+    /// assert_eq!(
+    ///     repetition_code.get_dual_matrix(),
+    ///     ParityCheckMatrix::single_parity_check_code_with_n_bits(4)
+    /// );
+    /// ```
+    pub fn get_dual_matrix(&self) -> Self {
+        self.get_generator_matrix()
+    }
+
+    /// Encodes `message` using `self` as a generator matrix. That is, returns the sum of the
+    /// checks of `self` corresponding to the non zero positions of `message`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of checks of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    ///
+    /// let generator = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
+    ///
+    /// let codeword = generator.encode(&[GF2::B1, GF2::B1]);
+    ///
+    /// assert_eq!(codeword, vec![GF2::B1, GF2::B0, GF2::B1, GF2::B1]);
+    /// ```
+    pub fn encode(&self, message: &[GF2]) -> Vec<GF2> {
+        if message.len() != self.get_n_checks() {
+            panic!("message doesn't have the right length");
+        }
+        let mut codeword = vec![GF2::B0; self.get_n_bits()];
+        self.checks_iter()
+            .zip(message.iter())
+            .filter(|(_, value)| **value == GF2::B1)
+            .for_each(|(check, _)| {
+                check
+                    .iter()
+                    .for_each(|bit| codeword[*bit] = codeword[*bit] + GF2::B1)
+            });
+        codeword
+    }
+
+    pub fn tmp_rank_pcm(&self) -> Vec<Vec<usize>>{
+        let n_rows = self.get_n_checks();
+        self.rows().map(|_| Vec::with_capacity(n_rows)).collect()
+    }
+
+    pub fn init_rank_tmp(&self, tmp_matrix: &mut [Vec<usize>]) {
+
+        for (row, new_row) in tmp_matrix.iter_mut().zip(self.rows()) {
+            row.clear();
+            row.extend_from_slice(new_row.as_ref());
+        }
+
+    }
+
+
+    pub fn rank_mut(&self, tmp_matrix: &mut [Vec<usize>], tmp_sum: &mut Vec<usize>) -> usize{
+        
+        let tmp_matrix = &mut tmp_matrix[..self.get_n_checks()];
+        let mut rank = 0;
+
+        self.init_rank_tmp(tmp_matrix);
+       
+        for j in 0..self.n_bits {
+
+            // select a NEW pivot
+            if let Some(i) = tmp_matrix.iter().position(|row| row.first() == Some(&j)) {
+
+                let mut pivot = std::mem::take(&mut tmp_matrix[i]);
+
+                tmp_matrix
+                    .iter_mut()
+                    .filter(|row| row.first() == Some(&j))
+                    .for_each(|row| {
+                        add_checks_mut(&pivot, row, tmp_sum);
+                        transfer_to(tmp_sum, row);
+                        tmp_sum.clear();
+                    });
+
+                pivot.clear();
+                tmp_matrix[i] = pivot;
+                rank += 1;
+            }
+        }
+       
+        rank
+
+    }
+
+    /// Returns the length of the shortest cycle in the Tanner graph of `self` or `None` if there
+    /// is no cycle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![1, 2, 3], vec![2, 4]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(checks);
+    /// assert_eq!(matrix.girth(), Some(4));
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    /// assert_eq!(matrix.girth(), None);
+    /// ```
+    pub fn girth(&self) -> Option<usize> {
+        GirthFinder::from(self).get_girth()
+    }
+
+    /// Returns the length of the shortest cycle going through the given `bit` in the Tanner
+    /// graph of `self`. Returns `None` if `bit` is not part of any cycle or is out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![1, 2, 3], vec![2, 4]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(checks);
+    ///
+    /// assert_eq!(matrix.girth_at_bit(1), Some(4));
+    /// assert_eq!(matrix.girth_at_bit(4), None);
+    /// ```
+    pub fn girth_at_bit(&self, bit: usize) -> Option<usize> {
+        GirthFinder::from(self).get_girth_at_bit(bit)
+    }
+
+    /// Computes the minimum distance of the code defined by `self`. That is, the smallest
+    /// number of bits of a non zero codeword.
+    ///
+    /// Returns `None` if the only codeword is zero. This enumerates sets of bits by increasing
+    /// size, so it is only tractable for small codes. Use `get_minimum_distance_up_to` to bound
+    /// the work on larger codes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let hamming_code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    ///
+    /// assert_eq!(hamming_code.get_minimum_distance(), Some(3));
+    /// assert_eq!(ParityCheckMatrix::identity_with_n_bits(3).get_minimum_distance(), None);
+    /// ```
+    pub fn get_minimum_distance(&self) -> Option<usize> {
+        // By the Singleton bound, the distance is at most the rank plus one when there is a non
+        // zero codeword.
+        let rank = self.get_rank();
+        if rank == self.get_n_bits() {
+            None
+        } else {
+            self.get_minimum_distance_up_to(rank + 1)
+        }
+    }
+
+    /// Computes the minimum distance of the code defined by `self` if it is at most
+    /// `max_weight`.
+    ///
+    /// Returns `None` if there is no non zero codeword of weight at most `max_weight`. That
+    /// is, if the minimum distance is greater than `max_weight` or if the only codeword is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let hamming_code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    ///
+    /// assert_eq!(hamming_code.get_minimum_distance_up_to(2), None);
+    /// assert_eq!(hamming_code.get_minimum_distance_up_to(4), Some(3));
+    /// ```
+    pub fn get_minimum_distance_up_to(&self, max_weight: usize) -> Option<usize> {
+        DistanceFinder::from(self).get_minimum_distance_up_to(max_weight)
+    }
+
+    /// Computes an upper bound on the minimum distance of the code defined by `self` by
+    /// looking for low weight codewords with `n_trials` random information sets sampled with
+    /// the random number generator `rng`.
+    ///
+    /// Each trial orders the bits at random and computes the generator matrix of the reordered
+    /// code. The weight of the lightest generator found is returned. This is not the exact
+    /// minimum distance, but it gets closer as the number of trials increases and it stays
+    /// tractable for large codes.
+    ///
+    /// Returns `None` if `n_trials` is 0 or if the only codeword is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(5);
+    ///
+    /// let approximation = code.get_approximate_minimum_distance_with_rng(10, &mut rng);
+    /// assert!(approximation.unwrap() >= 3);
+    /// ```
+    pub fn get_approximate_minimum_distance_with_rng<R: Rng>(
+        &self,
+        n_trials: usize,
+        rng: &mut R,
+    ) -> Option<usize> {
+        InformationSetSampler::from(self).get_lowest_weight_with_rng(n_trials, rng)
+    }
+
+    /// Computes an upper bound on the minimum distance of the code defined by `self` by
+    /// looking for low weight codewords with `n_trials` random information sets sampled with
+    /// the thread random number generator.
+    ///
+    /// See `get_approximate_minimum_distance_with_rng` for more details.
+    pub fn get_approximate_minimum_distance(&self, n_trials: usize) -> Option<usize> {
+        self.get_approximate_minimum_distance_with_rng(n_trials, &mut thread_rng())
+    }
+
+    /// Counts the codewords of each weight up to `max_weight` in the code defined by `self`.
+    ///
+    /// The `w`-th element is the number of codewords of weight `w`, starting with the zero
+    /// codeword. All codewords are enumerated in parallel from a generator matrix, so this is
+    /// only tractable for codes of small dimension.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the code has dimension 64 or more.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let hamming_code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    ///
+    /// assert_eq!(hamming_code.get_weight_enumerator_up_to(4), vec![1, 0, 0, 7, 7]);
+    /// ```
+    pub fn get_weight_enumerator_up_to(&self, max_weight: usize) -> Vec<u64> {
+        WeightEnumerator::from(self).get_counts_up_to(max_weight)
+    }
+
+    /// Returns the data needed to check many erasure patterns of `self` quickly.
+    ///
+    /// The cache stores the checks adjacent to each bit once, so that checking an erasure
+    /// pattern only visits the erased bits. This is what the erasure decoder uses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let mut cache = code.get_erasure_decoding_cache();
+    ///
+    /// assert_eq!(cache.can_correct_erasure_of(&[0, 2]), code.can_correct_erasure_of(&[0, 2]));
+    /// ```
+    pub fn get_erasure_decoding_cache(&self) -> ErasureDecodingCache {
+        ErasureDecodingCache::from(self)
+    }
+
+    /// Checks if `self` and `other` define the same code up to a permutation of the bits.
+    ///
+    /// Fast checks on the number of bits, the rank and the reduced row echelon forms are done
+    /// first. Then, a backtracking search for the permutation is done, pruned with invariants of
+    /// the bits. The search is stopped and `Equivalence::Unknown` is returned after
+    /// `max_n_steps` tried assignments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{Equivalence, ParityCheckMatrix};
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let other_code = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 2], vec![1, 2]]);
+    ///
+    /// assert_eq!(code.get_equivalence_with(&other_code, 100), Equivalence::Equivalent);
+    /// ```
+    pub fn get_equivalence_with(&self, other: &Self, max_n_steps: usize) -> Equivalence {
+        EquivalenceFinder::get_equivalence_between(self, other, max_n_steps)
+    }
+
+    /// Checks if the erasure of the given `bits` can be corrected.
+    ///
+    /// This is the case when the matrix restricted to the erased bits has full rank, that is
+    /// when no non zero codeword is supported on the erased bits.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bound or duplicated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    ///
+    /// assert!(code.can_correct_erasure_of(&[0, 2]));
+    /// assert!(!code.can_correct_erasure_of(&[0, 1, 2]));
+    /// ```
+    pub fn can_correct_erasure_of(&self, bits: &[usize]) -> bool {
+        self.keep_bits(bits).get_rank() == bits.len()
+    }
+
+    /// Returns the largest weight up to `max_weight` such that all erasures of at most this
+    /// weight can be corrected.
+    ///
+    /// All erasure patterns are checked in parallel from the smallest weight, so this is only
+    /// practical for small weights. The result is the minimum distance minus 1, capped by
+    /// `max_weight` and by the number of bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    ///
+    /// assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(5), 2);
+    /// assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(1), 1);
+    /// ```
+    pub fn get_max_guaranteed_erasure_weight_up_to(&self, max_weight: usize) -> usize {
+        let max_weight = std::cmp::min(max_weight, self.get_n_bits());
+        (1..=max_weight)
+            .find(|weight| {
+                !(0..self.get_n_bits())
+                    .combinations(*weight)
+                    .par_bridge()
+                    .all(|bits| self.can_correct_erasure_of(&bits))
+            })
+            .map(|weight| weight - 1)
+            .unwrap_or(max_weight)
+    }
+
+    /// Enumerates the stopping sets of `self` with at most `max_weight` bits.
+    ///
+    /// A stopping set is a non empty set of bits such that every check connected to one of
+    /// these bits is connected to at least two of them. The sets are sorted by increasing
+    /// number of bits and then lexicographically, with the bits of each set sorted.
+    ///
+    /// This is a branch and bound search whose cost grows exponentially with `max_weight`, so
+    /// it is stopped and `None` is returned after visiting `max_n_steps` candidate sets. The
+    /// branches are explored in parallel, but the result doesn't depend on the number of
+    /// threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let hamming_code = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![0, 2, 3, 6],
+    /// ]);
+    /// let sets = hamming_code.get_stopping_sets_up_to(3, 1000).unwrap();
+    ///
+    /// assert_eq!(sets[0], vec![0, 1, 2]);
+    /// assert_eq!(sets.len(), 10);
+    /// assert_eq!(hamming_code.get_stopping_sets_up_to(3, 10), None);
+    /// ```
+    pub fn get_stopping_sets_up_to(
+        &self,
+        max_weight: usize,
+        max_n_steps: usize,
+    ) -> Option<Vec<Vec<usize>>> {
+        StoppingSetFinder::from(self, max_n_steps).get_stopping_sets_up_to(max_weight)
+    }
+
+    /// Computes the number of bits of the smallest stopping sets of `self` if it is at most
+    /// `max_weight`.
+    ///
+    /// The peeling decoder fails on an erasure if and only if it contains a stopping set, so
+    /// the failure rate at low erasure probability decreases as the erasure probability to the
+    /// power of this size. The stopping sets are searched by increasing weight and
+    /// `StoppingSetSize::Unknown` is returned after visiting `max_n_steps` candidate sets in
+    /// total.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{ParityCheckMatrix, StoppingSetSize};
+    ///
+    /// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+    ///
+    /// assert_eq!(code.get_minimum_stopping_set_size_up_to(7, 1000), StoppingSetSize::Exactly(3));
+    /// assert_eq!(
+    ///     code.get_minimum_stopping_set_size_up_to(2, 1000),
+    ///     StoppingSetSize::GreaterThan(2)
+    /// );
+    /// ```
+    pub fn get_minimum_stopping_set_size_up_to(
+        &self,
+        max_weight: usize,
+        max_n_steps: usize,
+    ) -> StoppingSetSize {
+        StoppingSetFinder::from(self, max_n_steps).get_minimum_size_up_to(max_weight)
+    }
+
+    /// Counts the stopping sets of `self` of each weight up to `max_weight`.
+    ///
+    /// The stopping sets are enumerated like in `get_stopping_sets_up_to` and `None` is
+    /// returned if the search visits more than `max_n_steps` candidate sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let spectrum = code.get_stopping_set_spectrum_up_to(5, 1000).unwrap();
+    ///
+    /// assert_eq!(spectrum.get_counts(), &[0, 0, 0, 1, 0, 0]);
+    /// ```
+    pub fn get_stopping_set_spectrum_up_to(
+        &self,
+        max_weight: usize,
+        max_n_steps: usize,
+    ) -> Option<StoppingSetSpectrum> {
+        let mut counts = vec![0; max_weight + 1];
+        self.get_stopping_sets_up_to(max_weight, max_n_steps)?
+            .iter()
+            .for_each(|set| counts[set.len()] += 1);
+        Some(StoppingSetSpectrum::with_counts(counts))
+    }
+
+    /// Computes the union bound on the failure rate of an erasure decoder of `self` when each
+    /// bit is erased with probability `p`.
+    ///
+    /// The decoder fails if the erased bits contain one of the sets counted by `spectrum` and
+    /// a given set of `w` bits is erased with probability `p^w`. Thus, the failure rate is at
+    /// most the sum of `A_w p^w` where `A_w` is the number of sets of weight `w`. The bound is
+    /// capped at 1. It is exact only when there is a single stopping set, such as for a
+    /// repetition code, and tight at low erasure probability where the smallest sets dominate.
+    /// If the spectrum stops before the number of bits, the sum is truncated and is only an
+    /// approximation at low erasure probability.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `p` is not between 0.0 and 1.0 or if the spectrum has sets with more bits
+    /// than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// let spectrum = code.get_stopping_set_spectrum_up_to(3, 1000).unwrap();
+    /// let estimate = code.erasure_failure_union_bound(0.1, &spectrum);
+    /// assert!((estimate.get_failure_rate() - 0.001).abs() < 1e-12);
+    ///
+    /// let result = SimulationResult::with_n_successes_and_failures(9998, 2);
+    /// assert!((estimate.get_ratio_to(&result).unwrap() - 0.2).abs() < 1e-9);
+    /// ```
+    pub fn erasure_failure_union_bound(
+        &self,
+        p: f64,
+        spectrum: &StoppingSetSpectrum,
+    ) -> AnalyticEstimate {
+        if !(0.0..=1.0).contains(&p) {
+            panic!("invalid probability");
+        }
+        let counts = spectrum.get_counts();
+        if counts.len() > self.get_n_bits() + 1
+            && counts[self.get_n_bits() + 1..].iter().any(|count| *count > 0)
+        {
+            panic!("spectrum doesn't match the number of bits");
+        }
+        let bound: f64 = counts
+            .iter()
+            .enumerate()
+            .map(|(weight, count)| *count as f64 * p.powi(weight as i32))
+            .sum();
+        AnalyticEstimate::from(p, bound.min(1.0))
+    }
+
+    /// Returns the number of cycles of length 4 in the Tanner graph of `self`. That is, the
+    /// number of pairs of bits sharing a pair of checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![0, 1, 2], vec![2, 3]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// assert_eq!(matrix.get_n_4_cycles(), 3);
+    /// ```
+    pub fn get_n_4_cycles(&self) -> usize {
+        FourCyclesFinder::from(self).get_n_cycles()
+    }
+
+    /// Returns the cycles of length 4 in the Tanner graph of `self` as
+    /// `(check_0, check_1, bit_0, bit_1)` with `check_0 < check_1` and `bit_0 < bit_1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![1, 3], vec![0, 2, 3]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// assert_eq!(matrix.get_4_cycles(), vec![(0, 2, 0, 2)]);
+    /// ```
+    pub fn get_4_cycles(&self) -> Vec<(usize, usize, usize, usize)> {
+        FourCyclesFinder::from(self).get_cycles()
+    }
+
+    /// Tries to remove the cycles of length 4 of `self` by swapping edges at random while
+    /// preserving the degree of every bit and check.
+    ///
+    /// Each of the `max_passes` passes tries one swap between an edge of a random 4-cycle and
+    /// another random edge, keeping it only if it reduces the number of 4-cycles. Returns the
+    /// resulting matrix and whether it is free of 4-cycles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let checks = vec![vec![0, 1], vec![0, 1], vec![2, 3], vec![2, 3]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(123);
+    ///
+    /// let (result, is_cycle_free) = matrix.get_4_cycles_removed_with_rng(&mut rng, 100);
+    ///
+    /// assert!(is_cycle_free);
+    /// assert_eq!(result.get_n_4_cycles(), 0);
+    /// assert_eq!(result.get_bit_degrees(), matrix.get_bit_degrees());
+    /// ```
+    pub fn get_4_cycles_removed_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+        max_passes: usize,
+    ) -> (Self, bool) {
+        CycleRemover::from(self).remove_cycles_with_rng(rng, max_passes)
+    }
+
+    /// Replaces the check `target` of `self` by its sum with the check `source`.
+    ///
+    /// Adding a check to itself empties it. This rebuilds the whole matrix, so it takes a time
+    /// proportional to the number of edges.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `source` or `target` is not a check of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let mut matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    /// matrix.add_check_to(0, 1);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![0, 2]]);
+    /// assert_eq!(matrix, expected);
+    /// ```
+    pub fn add_check_to(&mut self, source: usize, target: usize) {
+        self.panic_if_check_is_out_of_bounds(source);
+        self.panic_if_check_is_out_of_bounds(target);
+        let mut checks = self.get_checks_as_vecs();
+        checks[target] = get_bitwise_sum(&checks[source], &checks[target]);
+        self.rebuild_with(checks);
+    }
+
+    /// Swaps the checks `check_0` and `check_1` of `self`.
+    ///
+    /// This rebuilds the whole matrix, so it takes a time proportional to the number of edges.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `check_0` or `check_1` is not a check of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let mut matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![2]]);
+    /// matrix.swap_checks(0, 1);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![2], vec![0, 1]]);
+    /// assert_eq!(matrix, expected);
+    /// ```
+    pub fn swap_checks(&mut self, check_0: usize, check_1: usize) {
+        self.panic_if_check_is_out_of_bounds(check_0);
+        self.panic_if_check_is_out_of_bounds(check_1);
+        let mut checks = self.get_checks_as_vecs();
+        checks.swap(check_0, check_1);
+        self.rebuild_with(checks);
+    }
+
+    /// Removes the check `check` of `self`. The following checks are shifted down by one.
+    ///
+    /// This rebuilds the whole matrix, so it takes a time proportional to the number of edges.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `check` is not a check of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
+    /// let mut matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    /// matrix.remove_check(1);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![0, 2]]);
+    /// assert_eq!(matrix, expected);
+    /// ```
+    pub fn remove_check(&mut self, check: usize) {
+        self.panic_if_check_is_out_of_bounds(check);
+        let mut checks = self.get_checks_as_vecs();
+        checks.remove(check);
+        self.rebuild_with(checks);
+    }
+
+    fn panic_if_check_is_out_of_bounds(&self, check: usize) {
+        if check >= self.get_n_checks() {
+            panic!("check is out of bounds");
+        }
+    }
+
+    fn get_checks_as_vecs(&self) -> Vec<Check> {
+        self.checks_iter().map(|check| check.to_vec()).collect()
+    }
+
+    // Also resets the cached bit adjacency.
+    fn rebuild_with(&mut self, checks: Vec<Check>) {
+        *self = Self::with_n_bits(self.get_n_bits()).with_checks_unchecked(checks);
+    }
+
+    /// Gets a canonical version of `self` where the checks are sorted.
+    ///
+    /// Checks are compared as sorted lists of bits, so two matrices with the same checks given
+    /// in different orders have the same normalized matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![1, 3], vec![0, 1]]);
+    /// let other_matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![3, 1]]);
+    ///
+    /// assert_ne!(matrix, other_matrix);
+    /// assert_eq!(matrix.get_normalized_matrix(), other_matrix.get_normalized_matrix());
+    /// ```
+    pub fn get_normalized_matrix(&self) -> Self {
+        let mut checks: Vec<Check> = self.checks_iter().map(|check| check.to_vec()).collect();
+        checks.sort();
+        Self::with_n_bits(self.get_n_bits()).with_checks_unchecked(checks)
+    }
+
+    /// Gets the matrix obtained by moving each bit `bit` of `self` to `permutation[bit]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `permutation` is not a permutation of the bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![1, 2], vec![0, 2]]);
+    /// assert_eq!(matrix.get_bits_permuted_by(&[1, 2, 0]), expected);
+    /// ```
+    pub fn get_bits_permuted_by(&self, permutation: &[usize]) -> Self {
+        if !is_permutation_of(permutation, self.get_n_bits()) {
+            panic!("not a permutation of the bits");
+        }
+        let checks = self
+            .checks_iter()
+            .map(|check| check.iter().map(|bit| permutation[*bit]).collect())
+            .collect();
+        Self::with_n_bits(self.get_n_bits()).with_checks(checks)
+    }
+
+    /// Gets the matrix obtained by moving each check `check` of `self` to `permutation[check]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `permutation` is not a permutation of the checks of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![], vec![2]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    ///
+    /// let expected_checks = vec![vec![], vec![2], vec![0, 1, 2]];
+    /// let expected = ParityCheckMatrix::with_n_bits(3).with_checks(expected_checks);
+    /// assert_eq!(matrix.get_checks_permuted_by(&[2, 0, 1]), expected);
+    /// ```
+    pub fn get_checks_permuted_by(&self, permutation: &[usize]) -> Self {
+        if !is_permutation_of(permutation, self.get_n_checks()) {
+            panic!("not a permutation of the checks");
+        }
+        let mut checks = vec![Vec::new(); self.get_n_checks()];
+        self.checks_iter()
+            .zip(permutation.iter())
+            .for_each(|(check, position)| checks[*position] = check.to_vec());
+        Self::with_n_bits(self.get_n_bits()).with_checks_unchecked(checks)
+    }
+
+    /// Gets the parity check matrix of the code extended by an overall parity bit.
+    ///
+    /// A new bit is appended after the bits of `self` and a new check connected to all bits,
+    /// including the new one, is appended after the checks of `self`. The checks of `self` are
+    /// not connected to the new bit. An [n, k, d] code with odd d becomes an [n + 1, k, d + 1]
+    /// code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![0, 1, 2, 3]]);
+    /// assert_eq!(code.get_extended_matrix(), expected);
+    /// ```
+    pub fn get_extended_matrix(&self) -> Self {
+        let n_bits = self.get_n_bits() + 1;
+        let mut checks = self.get_checks_as_vecs();
+        checks.push((0..n_bits).collect());
+        Self::with_n_bits(n_bits).with_checks_unchecked(checks)
+    }
+
+    /// Gets the transposed version of `self` by swapping the bits with the checks.
+    ///
+    /// Bits that are not connected to any check become empty checks and empty checks become
+    /// unconnected bits. Thus, transposing twice gives back the original matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let checks = vec![vec![0, 1, 2], vec![1, 3], vec![0, 2, 3]];
+    /// let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    ///
+    /// let transposed_matrix = matrix.get_transposed_matrix();
+    ///
+    /// let expected_checks = vec![vec![0, 2], vec![0, 1], vec![0, 2], vec![1, 2]];
+    /// let expected_matrix = ParityCheckMatrix::with_n_bits(3).with_checks(expected_checks);
+    ///
+    /// assert_eq!(transposed_matrix, expected_matrix);
+    /// ```
+    pub fn get_transposed_matrix(&self) -> Self {
+        Transposer::from(self).get_transposed_matrix()
+    }
+
+    /// Returns the horizontal concatenation of `self` with `other`.
+    ///
+    /// The bits of `other` are shifted by the number of bits of `self`. If the matrices don't have
+    /// the same number of checks, the shorter one is padded with empty checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let left_matrix = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let right_matrix = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![1, 2, 3], vec![0, 1], vec![2, 3]]);
+    ///
+    /// let concatened = left_matrix.get_horizontal_concat_with(&right_matrix);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(7)
+    ///     .with_checks(vec![vec![0, 1, 4, 5, 6], vec![1, 2, 3, 4], vec![5, 6]]);
+    ///
+    /// assert_eq!(concatened, expected);
+    /// ```
+    pub fn get_horizontal_concat_with(&self, other: &ParityCheckMatrix) -> ParityCheckMatrix {
+        Concatener::from(self, other).concat_horizontally()
+    }
+
+    /// Returns the vertical concatenation of `self` with `other`. That is, the checks of `other`
+    /// are added after the checks of `self`.
+    ///
+    /// The number of bits of the output is the maximum of the number of bits of both matrices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let top_matrix = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let bottom_matrix = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![1, 2, 3], vec![0, 1]]);
+    ///
+    /// let concatened = top_matrix.get_vertical_concat_with(&bottom_matrix);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![1, 2, 3], vec![0, 1]]);
+    ///
+    /// assert_eq!(concatened, expected);
+    /// ```
+    pub fn get_vertical_concat_with(&self, other: &ParityCheckMatrix) -> ParityCheckMatrix {
+        Concatener::from(self, other).concat_vertically()
+    }
+
+    /// Returns the diagonal concatenation of `self` with `other`.
+    ///
+    /// This is the direct sum of the codes. The checks of `other` come after the checks of
+    /// `self` and the bits of `other` are shifted by the number of bits of `self`, including
+    /// the bits of `self` that are not connected to any check. Concatenating copies of the
+    /// same code gives a code whose decoding simulates independent copies at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let left_matrix = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let right_matrix = ParityCheckMatrix::with_n_bits(4)
+    ///     .with_checks(vec![vec![1, 2, 3], vec![0, 1], vec![2, 3]]);
+    ///
+    /// let concatened = left_matrix.get_diagonal_concat_with(&right_matrix);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(7)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2], vec![4, 5, 6], vec![3, 4], vec![5, 6]]);
+    ///
+    /// assert_eq!(concatened, expected);
+    /// ```
+    pub fn get_diagonal_concat_with(&self, other: &ParityCheckMatrix) -> ParityCheckMatrix {
+        Concatener::from(self, other).concat_diagonally()
+    }
+
+    // ***** Iterators *****
+
+    /// Returns an iterator that yields a slice for each check of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// let mut iter = parity_check.checks_iter();
+    ///
+    /// assert_eq!(iter.next(), parity_check.get_check(0));
+    /// assert_eq!(iter.next(), parity_check.get_check(1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn checks_iter(&self) -> ChecksIter<'_> {
+        ChecksIter::from(self)
+    }
+
+    /// An iterators over all edges in `self` ordered by check first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// let mut iter = parity_check.edges_iter();
+    ///
+    /// assert_eq!(iter.next(), Some((0, 0)));
+    /// assert_eq!(iter.next(), Some((0, 1)));
+    /// assert_eq!(iter.next(), Some((1, 1)));
+    /// assert_eq!(iter.next(), Some((1, 2)));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn edges_iter(&self) -> EdgesIter<'_> {
+        EdgesIter::from(self)
+    }
+
+    /// Returns an iterator that yields a slice for each row of `self`. This is an alias of
+    /// `checks_iter`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// assert!(parity_check.rows().eq(parity_check.checks_iter()));
+    /// ```
+    pub fn rows(&self) -> ChecksIter<'_> {
+        self.checks_iter()
+    }
+
+    /// Returns an iterator over the (row, column) positions of the non zero entries of `self`
+    /// in row-major order, that is sorted by row first and then by column. This is an alias of
+    /// `edges_iter`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![2, 1], vec![0, 1]]);
+    ///
+    /// let positions: Vec<(usize, usize)> = parity_check.positions().collect();
+    ///
+    /// assert_eq!(positions, vec![(0, 1), (0, 2), (1, 0), (1, 1)]);
+    /// ```
+    pub fn positions(&self) -> EdgesIter<'_> {
+        self.edges_iter()
+    }
+
+    /// Checks if a given `message` is a codeword of `self`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let message = vec![GF2::B0, GF2::B1, GF2::B1];
+    /// let codeword = vec![GF2::B0; 3];
+    ///
+    /// assert_eq!(parity_check.has_codeword(&message), false);
+    /// assert_eq!(parity_check.has_codeword(&codeword), true);
+    /// ```
+    pub fn has_codeword(&self, message: &[GF2]) -> bool {
+        self.get_first_unsatisfied_check(message).is_none()
+    }
+
+    /// Computes the product of `self` with `other` over GF(2).
+    ///
+    /// The checks of `other` are the rows of the right matrix. Thus, each check of the product
+    /// is the sum of the checks of `other` indexed by the bits of the corresponding check of
+    /// `self`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the number of bits of `self` is not the number of checks of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+    /// let other_checks = vec![vec![0], vec![0, 1], vec![1]];
+    /// let other = ParityCheckMatrix::with_n_bits(2).with_checks(other_checks);
+    ///
+    /// let expected = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![1], vec![0]]);
+    /// assert_eq!(matrix.get_product_with(&other), expected);
+    ///
+    /// let generator = matrix.get_generator_matrix();
+    /// assert_eq!(generator.get_product_with(&matrix.get_transposed_matrix()).get_n_edges(), 0);
+    /// ```
+    pub fn get_product_with(&self, other: &Self) -> Self {
+        if self.get_n_bits() != other.get_n_checks() {
+            panic!("inner dimensions don't match");
+        }
+        let checks = self
+            .checks_iter()
+            .map(|check| {
+                let mut bits: Vec<usize> = check
+                    .iter()
+                    .flat_map(|row| {
+                        other
+                            .get_check(*row)
+                            .map(|bits| bits.to_vec())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                bits.sort_unstable();
+                // Equal bits are consecutive and cancel in pairs.
+                let mut sum = Vec::with_capacity(bits.len());
+                bits.into_iter().for_each(|bit| {
+                    if sum.last() == Some(&bit) {
+                        sum.pop();
+                    } else {
+                        sum.push(bit);
+                    }
+                });
+                sum
+            })
+            .collect();
+        Self::with_n_bits(other.get_n_bits()).with_checks_unchecked(checks)
+    }
+
+    /// Computes the X and Z checks of the hypergraph product of `self` with `other`.
+    ///
+    /// With `self` and `other` denoted `A` and `B`, the X checks are `[A ⊗ I | I ⊗ Bᵀ]` and the Z
+    /// checks are `[I ⊗ B | Aᵀ ⊗ I]`. Thus, the first bits are the pairs of bits of `self` and
+    /// `other` and the last bits are the pairs of checks of `self` and `other`. The X checks are
+    /// always orthogonal to the Z checks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+    ///
+    /// let (x_checks, z_checks) = repetition_code.get_hypergraph_product_with(&repetition_code);
+    ///
+    /// assert_eq!(x_checks.get_n_bits(), 13);
+    /// assert!(x_checks.is_orthogonal_to(&z_checks));
+    /// ```
+    pub fn get_hypergraph_product_with(&self, other: &Self) -> (Self, Self) {
+        HypergraphProduct::from(self, other).get_x_and_z_checks()
+    }
+
+    /// Checks if every check of `self` shares an even number of bits with every check of
+    /// `other`. That is, if the product of `self` with the transpose of `other` is zero.
+    ///
+    /// This is the condition for `self` and `other` to be the X and Z checks of a CSS code.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `self` and `other` have different numbers of bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let x_checks = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1, 2, 3]]);
+    /// let z_checks = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![2, 3]]);
+    ///
+    /// assert!(x_checks.is_orthogonal_to(&z_checks));
+    /// ```
+    pub fn is_orthogonal_to(&self, other: &Self) -> bool {
+        self.get_first_anticommuting_checks_with(other).is_none()
+    }
+
+    /// Returns the first pair `(check, other_check)` of a check of `self` and a check of `other`
+    /// sharing an odd number of bits or `None` if `self` is orthogonal to `other`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `self` and `other` have different numbers of bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    ///
+    /// let x_checks = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
+    /// let z_checks = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![2, 3]]);
+    ///
+    /// assert_eq!(x_checks.get_first_anticommuting_checks_with(&z_checks), Some((1, 0)));
+    /// ```
+    pub fn get_first_anticommuting_checks_with(&self, other: &Self) -> Option<(usize, usize)> {
+        if self.get_n_bits() != other.get_n_bits() {
+            panic!("matrices have different numbers of bits");
+        }
+        self.checks_iter().enumerate().find_map(|(check, bits)| {
+            // Only the checks of other sharing a bit with the check can anticommute with it.
+            let mut other_checks: Vec<usize> = bits
+                .iter()
+                .flat_map(|bit| other.get_checks_adjacent_to_bit(*bit).unwrap_or(&[]))
+                .cloned()
+                .collect();
+            other_checks.sort_unstable();
+            other_checks.dedup();
+            other_checks
+                .into_iter()
+                .find(|other_check| {
+                    other
+                        .get_check(*other_check)
+                        .map(|other_bits| get_dot_product(bits.as_ref(), other_bits.as_ref()))
+                        == Some(GF2::B1)
+                })
+                .map(|other_check| (check, other_check))
+        })
+    }
+
+    /// Returns the index of the first check of `self` with a non zero syndrome for `message` or
+    /// `None` if `message` is a codeword.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of bits of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::{GF2, ParityCheckMatrix};
+    /// let parity_check = ParityCheckMatrix::with_n_bits(3)
+    ///     .with_checks(vec![vec![0, 1], vec![1, 2]]);
+    ///
+    /// let message = vec![GF2::B1, GF2::B1, GF2::B0];
+    /// assert_eq!(parity_check.get_first_unsatisfied_check(&message), Some(1));
+    ///
+    /// let codeword = vec![GF2::B1; 3];
+    /// assert_eq!(parity_check.get_first_unsatisfied_check(&codeword), None);
+    /// ```
+    pub fn get_first_unsatisfied_check(&self, message: &[GF2]) -> Option<usize> {
+        self.panic_if_message_has_wrong_length(message);
+        self.checks_iter()
+            .position(|check| check.has_non_zero_syndrome(message))
+    }
+
+    /// Returns a truncated parity check matrix with only the column of the given `bits`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use believer::ParityCheckMatrix;
+    /// let checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2],
+    ///     vec![2, 3, 4],
+    ///     vec![0, 2, 4],
+    ///     vec![1, 3],
+    /// ]);
+    ///
+    /// let truncated_checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1],
+    ///     vec![4],
+    ///     vec![0, 4],
+    ///     vec![1],
+    /// ]);
+    ///
+    /// assert_eq!(checks.keep(&[0, 1, 4]), truncated_checks);
+    /// ```
+    pub fn keep(&self, bits: &[usize]) -> Self {
+        let checks = self
+            .checks_iter()
+            .map(|check| {
+                check
+                    .iter()
+                    .filter(|&bit| bits.iter().any(|b| b == bit))
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        Self::with_n_bits(self.get_n_bits()).with_checks(checks)
+    }
+
+    pub fn keep_merged(&self, bits: &[usize]) -> ParityCheckMatrix{
+        
+        let mut tot_nb_checks = 0;
+        let mut target = ParityCheckMatrix::with_n_bits(self.get_n_bits());
+        target.check_ranges.push(0);
+
+        for check in self.checks_iter() {
+
+            let mut nb_check = 0;
+
+            check
+                .iter()
+                .filter(|&bit| {
+
+                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
+
+                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
+                    let found = if bit < &(self.get_n_bits()/2) {
+                        binary_search(bits, bit)
+                    } else {
+                        binary_search(bits, &(bit - (self.get_n_bits()/2))) // do we found the check in either the Z part or the X part. Both have width n_bits/2
+                    };
+                    
+                    if found {
+                        target.bit_indices.push(*bit);
+                        nb_check += 1;
+                    }
+                    found
+                    }).count();
+
+            tot_nb_checks += nb_check;
+            target.check_ranges.push(tot_nb_checks);
+
+        }
+
+        target
+
+    }
+
+    pub fn without_merged(&self, bits: &[usize]) -> ParityCheckMatrix{
+        
+        let mut tot_nb_checks = 0;
+        let mut target = ParityCheckMatrix::with_n_bits(self.get_n_bits());
+        target.check_ranges.push(0);
+
+        for check in self.checks_iter() {
+
+            let mut nb_check = 0;
+
+            check
+                .iter()
+                .filter(|&bit| {
+
+                    //println!("binary search, bits:{:?}, bit:{}, res:{}",bits, bit + (self.get_n_bits()/2), binary_search(bits, &(bit + (self.get_n_bits()/2))));
+                    let found = if bit < &(self.get_n_bits()/2) {
+                        !binary_search(bits, bit)
+                    } else {
+                        !binary_search(bits, &(bit - (self.get_n_bits()/2))) // do we found the check in either the Z part or the X part. Both have width n_bits/2
+                    };
+
+                    if found {
+                        target.bit_indices.push(*bit);
+                        nb_check += 1;
+                    }
+                    found
+                    }).count();
+
+            tot_nb_checks += nb_check;
+            target.check_ranges.push(tot_nb_checks);
+
+        }
+
+        target
+
+    }
+
+    /// Returns a truncated parity check matrix where the column of the given `bits` are remove.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2],
+    ///     vec![2, 3, 4],
+    ///     vec![0, 2, 4],
+    ///     vec![1, 3],
+    /// ]);
+    ///
+    /// let truncated_checks = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![1],
+    ///     vec![3, 4],
+    ///     vec![4],
+    ///     vec![1, 3],
+    /// ]);
+    ///
+    /// assert_eq!(checks.without(&[0, 2]), truncated_checks);
+    /// ```
+    pub fn without(&self, bits: &[usize]) -> Self {
+        let to_keep: Vec<usize> = (0..self.get_n_bits()).filter(|x| !bits.contains(x)).collect();
+        self.keep(&to_keep)
+    }
+
+    /// Returns the punctured matrix where the columns of the given `bits` are removed and the
+    /// remaining bits are relabeled contiguously in increasing order.
+    ///
+    /// Unlike `without`, the number of bits decreases. Checks that become empty are kept, so
+    /// the number of checks is preserved. Duplicated bits are only removed once.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2],
+    ///     vec![2, 3, 4],
+    ///     vec![0, 2],
+    /// ]);
+    ///
+    /// let punctured = ParityCheckMatrix::with_n_bits(3).with_checks(vec![
+    ///     vec![0, 1],
+    ///     vec![2],
+    ///     vec![0],
+    /// ]);
+    ///
+    /// assert_eq!(matrix.without_bits(&[2, 3]), punctured);
+    /// ```
+    pub fn without_bits(&self, bits: &[usize]) -> Self {
+        if bits.iter().any(|bit| *bit >= self.get_n_bits()) {
+            panic!("some bits are out of bounds");
+        }
+        let mut new_labels: Vec<Option<usize>> = vec![Some(0); self.get_n_bits()];
+        bits.iter().for_each(|bit| new_labels[*bit] = None);
+        let mut n_bits = 0;
+        new_labels.iter_mut().for_each(|label| {
+            if label.is_some() {
+                *label = Some(n_bits);
+                n_bits += 1;
+            }
+        });
+        let checks = self
+            .checks_iter()
+            .map(|check| check.iter().filter_map(|bit| new_labels[*bit]).collect())
+            .collect();
+        Self::with_n_bits(n_bits).with_checks(checks)
+    }
+
+    /// Returns the parity check matrix restricted to the given `bits`. The bit `bits[i]` of
+    /// `self` becomes the bit `i` of the result.
+    ///
+    /// Unlike `keep`, the number of bits becomes the length of `bits` and the order of `bits` is
+    /// respected, so this can also reorder the bits. Checks that become empty are kept.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bound or duplicated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2],
+    ///     vec![2, 3, 4],
+    ///     vec![0, 2],
+    /// ]);
+    ///
+    /// let restricted = ParityCheckMatrix::with_n_bits(3).with_checks(vec![
+    ///     vec![1, 2],
+    ///     vec![0, 1],
+    ///     vec![1, 2],
+    /// ]);
+    ///
+    /// assert_eq!(matrix.keep_bits(&[4, 2, 0]), restricted);
+    /// ```
+    pub fn keep_bits(&self, bits: &[usize]) -> Self {
+        if bits.iter().any(|bit| *bit >= self.get_n_bits()) {
+            panic!("some bits are out of bounds");
+        }
+        let mut new_labels: Vec<Option<usize>> = vec![None; self.get_n_bits()];
+        bits.iter().enumerate().for_each(|(label, bit)| {
+            if new_labels[*bit].replace(label).is_some() {
+                panic!("some bits are duplicated");
+            }
+        });
+        let checks = self
+            .checks_iter()
+            .map(|check| check.iter().filter_map(|bit| new_labels[*bit]).collect())
+            .collect();
+        Self::with_n_bits(bits.len()).with_checks(checks)
+    }
+
+    /// Returns the parity check matrix with only the given `checks` in the given order. The
+    /// check `checks[i]` of `self` becomes the check `i` of the result.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some checks are out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2],
+    ///     vec![2, 3, 4],
+    ///     vec![0, 2],
+    /// ]);
+    ///
+    /// let restricted = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 2],
+    ///     vec![0, 1, 2],
+    /// ]);
+    ///
+    /// assert_eq!(matrix.keep_checks(&[2, 0]), restricted);
+    /// ```
+    pub fn keep_checks(&self, checks: &[usize]) -> Self {
+        let checks = checks
+            .iter()
+            .map(|check| {
+                self.get_check(*check)
+                    .map(|bits| bits.to_vec())
+                    .expect("some checks are out of bounds")
+            })
+            .collect();
+        Self::with_n_bits(self.get_n_bits()).with_checks_unchecked(checks)
+    }
+
+    /// Returns the parity check matrix of the code shortened on the given `bits` together with
+    /// the number of checks that were dropped.
+    ///
+    /// The columns of `bits` are removed as in `without_bits` and the checks that were only
+    /// connected to those bits are dropped instead of being kept as empty checks.
+    ///
+    /// # Panic
+    ///
+    /// Panics if some bits are out of bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use believer::*;
+    /// let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3, 5],
+    ///     vec![5, 6],
+    /// ]);
+    ///
+    /// // Puncturing keeps the emptied check.
+    /// let punctured = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3],
+    ///     vec![],
+    /// ]);
+    /// assert_eq!(matrix.without_bits(&[5, 6]), punctured);
+    ///
+    /// // Shortening drops it.
+    /// let shortened = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+    ///     vec![0, 1, 2, 4],
+    ///     vec![0, 1, 3],
+    /// ]);
+    /// assert_eq!(matrix.shortened_on(&[5, 6]), (shortened, 1));
+    /// ```
+    pub fn shortened_on(&self, bits: &[usize]) -> (Self, usize) {
+        let punctured = self.without_bits(bits);
+        let checks: Vec<Check> = self
+            .checks_iter()
+            .zip(punctured.checks_iter())
+            .filter(|(check, punctured_check)| {
+                check.get_n_bits() == 0 || punctured_check.get_n_bits() > 0
+            })
+            .map(|(_, punctured_check)| punctured_check.to_vec())
+            .collect();
+        let n_dropped_checks = self.get_n_checks() - checks.len();
+        (
+            Self::with_n_bits(punctured.get_n_bits()).with_checks(checks),
+            n_dropped_checks,
+        )
+    }
+
+    pub fn gbc(&self, b: &ParityCheckMatrix) -> ParityCheckMatrix {
+        // should check that A and B commute and that Hx*Hz^T = 0
+        let hx = self.get_horizontal_concat_with(b);
+        let hz = b
+            .get_transposed_matrix()
+            .get_horizontal_concat_with(&self.get_transposed_matrix());
+        hx.get_diagonal_concat_with(&hz)
+    }
+
+    pub fn gbc_from_poly(a: &[usize], b: &[usize], l: usize) -> ParityCheckMatrix { //l is n_bits_a = n_bits_b, and the number of physical qubits is 2*l
+        let w_a = a.len();
+        let w_b = b.len();
+        let w = w_a + w_b;
+        let check_ranges:Vec<usize> = (0..2*l*w+1).step_by(w).collect();
+        let mut bit_indices: Vec<usize> = Vec::with_capacity(2*l*w);
+
+        for i in 0..l {
+            for j in a {
+
+                bit_indices.push((j+i)%l);
+
+            }
+            bit_indices[i*w..i*w + w_a].sort_unstable();
+            for j in b {
+
+                bit_indices.push((j+i)%l + l);
+
+            }
+            bit_indices[i*w + w_a..(i+1)*w].sort_unstable();
+        }
+        for i in l..2*l {
+            for j in b {
+
+                bit_indices.push((l-j+i)%l + 2*l);
+
+            }
+            bit_indices[i*w..i*w + w_b].sort_unstable();
+            for j in a {
+
+                bit_indices.push((l-j+i)%l + 3*l);
+
+            }
+            bit_indices[i*w + w_b..(i+1)*w].sort_unstable();
+            
+        }
+
+        Self {
+            n_bits: 4*l,
+            check_ranges,
+            bit_indices,
+            bit_adjacency: OnceLock::new(),
+        } 
+
+    }
+
+    pub fn permu_matrix(l: usize) -> ParityCheckMatrix {
+        let ranges: Vec<usize> = (0..l + 1).collect(); // ranges = [0,1,...,l] because we have one entries per row
+        let mut indices: Vec<usize> = Vec::with_capacity(l);
+
+        indices.push(l - 1);
+
+        for i in 0..l - 1 {
+            // indices = [l-1,0,1,...,l-2]
+            indices.push(i);
+        }
+
+        Self {
+            bit_indices: indices,
+            check_ranges: ranges,
+            n_bits: l,
+            bit_adjacency: OnceLock::new(),
+        }
+    }
+
+    pub fn circulant_down(indices: &[usize], l: usize) -> ParityCheckMatrix {
+        let w = indices.len();
+        let mut checks: Vec<Vec<usize>> = Vec::with_capacity(l);
+
+        for i in 0..l {
+            let mut new_row: Vec<usize> = Vec::with_capacity(w);
+            for j in indices {
+                new_row.push((l - j + i) % l);
+            }
+            checks.push(new_row);
+        }
+
+        ParityCheckMatrix::with_n_bits(l).with_checks(checks)
+    }
+
+    pub fn circulant_right(indices: &[usize], l: usize) -> ParityCheckMatrix {
+        let w = indices.len();
+        let mut checks: Vec<Vec<usize>> = Vec::with_capacity(l);
+
+        for i in 0..l {
+            let mut new_row: Vec<usize> = Vec::with_capacity(w);
+            for j in indices {
+                new_row.push((j + i) % l);
+            }
+            checks.push(new_row);
+        }
+
+        ParityCheckMatrix::with_n_bits(l).with_checks(checks)
+    }
+
+    // Returns a reference to `self.check_ranges`.
+    pub(crate) fn check_ranges(&self) -> &[usize] {
+        &self.check_ranges
+    }
+
+    // Returns a reference to `self.bit_indices
+    #[allow(dead_code)]
+    pub(crate) fn bit_indices(&self) -> &[usize] {
+        &self.bit_indices
+    }
+}
+
+fn transfer_to(v1: &[usize], v2: &mut Vec<usize>){
+    v2.clear();
+    v2.extend_from_slice(v1);
+}
+
+pub fn add_checks_mut(check_0: &[usize], check_1: &[usize], sum: &mut Vec<usize>){
+    //let mut sum = Vec::with_capacity(check_0.len() + check_1.len());
+    sum.clear();
+    let mut iter_0 = check_0.iter().peekable();
+    let mut iter_1 = check_1.iter().peekable();
+    loop {
+        match (iter_0.peek(), iter_1.peek()) {
+            (Some(&&v), None) => {
+                sum.push(v);
+                iter_0.next();
+            }
+            (None, Some(&&v)) => {
+                sum.push(v);
+                iter_1.next();
+            }
+            (Some(&&v_0), Some(&&v_1)) => {
+                if v_0 < v_1 {
+                    sum.push(v_0);
+                    iter_0.next();
+                } else if v_0 > v_1 {
+                    sum.push(v_1);
+                    iter_1.next();
+                } else {
+                    iter_0.next();
+                    iter_1.next();
+                }
+            }
+            (None, None) => break,
+        }
+    }
+}
+
+pub fn binary_search(data: &[usize], target: &usize) -> bool {
+
+    // println!("data:{:?}",data);
+    // println!("target:{:?}",target);
+    if !data.is_empty() {
+
+        let mut high = data.len()-1;
+        let mut low = 0;
+        
+        if target < &data[0] || target > &data[high] {
+            return false
+        }
+    
+        while low <= high {
+            let mid = (low + high) / 2;
+
+            if data[mid] < *target {
+                low = mid + 1
+            } else if data[mid] > *target {
+                high = mid - 1
+            } else if data[mid] == *target {
+                return true
+            }
+
+        }
+        false
+    } else {
+        false
+    }
+}
+
+fn is_permutation_of(permutation: &[usize], length: usize) -> bool {
+    let mut is_used = vec![false; length];
+    permutation.len() == length
+        && permutation.iter().all(|position| {
+            *position < length && !std::mem::replace(&mut is_used[*position], true)
+        })
+}
+
+pub fn add_checks(check_0: &[usize], check_1: &[usize]) -> Vec<usize> {
+    let mut sum = Vec::with_capacity(check_0.len() + check_1.len());
+    let mut iter_0 = check_0.iter().peekable();
+    let mut iter_1 = check_1.iter().peekable();
+    loop {
+        match (iter_0.peek(), iter_1.peek()) {
+            (Some(&&v), None) => {
+                sum.push(v);
+                iter_0.next();
+            }
+            (None, Some(&&v)) => {
+                sum.push(v);
+                iter_1.next();
+            }
+            (Some(&&v_0), Some(&&v_1)) => {
+                if v_0 < v_1 {
+                    sum.push(v_0);
+                    iter_0.next();
+                } else if v_0 > v_1 {
+                    sum.push(v_1);
+                    iter_1.next();
+                } else {
+                    iter_0.next();
+                    iter_1.next();
+                }
+            }
+            (None, None) => break,
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn checks_are_sorted_on_construction() {
+        let checks = vec![vec![1, 0], vec![0, 2, 1], vec![1, 2, 3]];
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+
+        assert_eq!(matrix.get_check(0).unwrap().as_ref(), &[0, 1]);
+        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[0, 1, 2]);
+        assert_eq!(matrix.get_check(2).unwrap().as_ref(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn empty_checks_are_kept_on_construction() {
+        let checks = vec![vec![], vec![0, 1], vec![], vec![1, 2]];
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+
+        assert!(matrix.get_check(0).unwrap().as_ref().is_empty());
+        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[0, 1]);
+        assert!(matrix.get_check(2).unwrap().as_ref().is_empty());
+        assert_eq!(matrix.get_check(3).unwrap().as_ref(), &[1, 2]);
+        assert_eq!(matrix.get_n_checks(), 4);
+    }
+
+    #[test]
+    fn replacing_the_checks_resets_the_bit_adjacency() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        assert_eq!(matrix.get_checks_adjacent_to_bit(2), Some(&[1][..]));
+
+        let checks = vec![vec![0, 2], vec![1, 2]];
+        let other = matrix.clone().with_checks(checks.clone());
+        assert_eq!(other.get_checks_adjacent_to_bit(0), Some(&[0][..]));
+        assert_eq!(other.get_checks_adjacent_to_bit(2), Some(&[0, 1][..]));
+        assert_eq!(other.get_bit_degree(2), Some(2));
+
+        let other = matrix.clone().with_checks_unchecked(checks);
+        assert_eq!(other.get_bit_degree(2), Some(2));
+
+        let other = matrix.clone().with_edges(3, vec![(0, 2), (1, 2), (2, 2)]);
+        assert_eq!(other.get_checks_adjacent_to_bit(2), Some(&[0, 1, 2][..]));
+        assert_eq!(other.get_bit_degree(0), Some(0));
+
+        let other = matrix.with_checks(Vec::new());
+        assert_eq!(other.get_n_checks(), 0);
+        assert_eq!(other.get_bit_degree(1), Some(0));
+    }
+
+    #[test]
+    fn unchecked_last_bits_are_part_of_the_matrix() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![vec![0, 1], vec![1, 2]]);
+
+        assert_eq!(matrix.get_n_bits(), 6);
+        assert_eq!(matrix.get_bit_degrees(), vec![1, 2, 1, 0, 0, 0]);
+        assert_eq!(matrix.get_transposed_matrix().get_n_checks(), 6);
+        assert_eq!(matrix.get_transposed_matrix().get_transposed_matrix(), matrix);
+        assert!(matrix.has_codeword(&[GF2::B0, GF2::B0, GF2::B0, GF2::B1, GF2::B0, GF2::B1]));
+    }
+
+    #[test]
+    // The cached bit adjacency is not part of the hash.
+    #[allow(clippy::mutable_key_type)]
+    fn normalized_matrices_only_depend_on_the_set_of_checks() {
+        use std::collections::HashSet;
+
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![2, 4],
+            vec![],
+            vec![0, 1, 2],
+            vec![0, 3],
+        ]);
+        let other_matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![3, 0],
+            vec![2, 1, 0, 1],
+            vec![4, 2],
+            vec![],
+        ]);
+        let expected = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![],
+            vec![0, 1, 2],
+            vec![0, 3],
+            vec![2, 4],
+        ]);
+
+        assert_eq!(matrix.get_normalized_matrix(), expected);
+        assert_eq!(other_matrix.get_normalized_matrix(), expected);
+
+        let codes: HashSet<ParityCheckMatrix> = vec![matrix, other_matrix]
+            .into_iter()
+            .map(|code| code.get_normalized_matrix())
+            .collect();
+        assert_eq!(codes.len(), 1);
+    }
+
+    #[test]
+    fn generators_are_codewords_even_with_redundant_checks() {
+        let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+            vec![1, 2, 5, 6],
+            vec![],
+            vec![0, 1, 2, 4],
+        ]);
+        let generator = matrix.get_generator_matrix();
+
+        assert_eq!(generator.get_n_checks(), 7 - matrix.get_rank());
+        assert_eq!(generator.get_rank(), generator.get_n_checks());
+        generator.checks_iter().for_each(|check| {
+            let mut codeword = vec![GF2::B0; 7];
+            check.iter().for_each(|bit| codeword[*bit] = GF2::B1);
+            assert!(matrix.has_codeword(&codeword));
+        });
+    }
+
+    #[test]
+    fn generator_of_matrix_without_checks_is_the_identity() {
+        let generator = ParityCheckMatrix::with_n_bits(3).get_generator_matrix();
+        assert_eq!(generator, ParityCheckMatrix::identity_with_n_bits(3));
+    }
+
+    #[test]
+    fn encoded_messages_are_codewords() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2],
+            vec![2, 3],
+            vec![0, 1, 3],
+            vec![3, 4],
+        ]);
+        let generator = matrix.get_generator_matrix();
+        assert_eq!(generator.get_n_checks(), 2);
+
+        for message in &[[GF2::B0, GF2::B1], [GF2::B1, GF2::B0], [GF2::B1, GF2::B1]] {
+            let codeword = generator.encode(message);
+            assert!(matrix.has_codeword(&codeword));
+            assert!(codeword.contains(&GF2::B1));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn encoding_panics_if_message_has_wrong_length() {
+        let generator = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1, 2]]);
+        generator.encode(&[GF2::B1, GF2::B0]);
+    }
+
+    #[test]
+    fn permuting_checks_of_different_lengths() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![0, 1, 2, 3, 4],
+            vec![1],
+            vec![],
+            vec![2, 4],
+        ]);
+        let permuted = matrix.get_checks_permuted_by(&[3, 0, 2, 1]);
+
+        let expected = ParityCheckMatrix::with_n_bits(5).with_checks(vec![
+            vec![1],
+            vec![2, 4],
+            vec![],
+            vec![0, 1, 2, 3, 4],
+        ]);
+        assert_eq!(permuted, expected);
+        assert_eq!(permuted.get_check_degrees(), vec![1, 2, 0, 5]);
+        assert_eq!(permuted.get_checks_permuted_by(&[1, 3, 2, 0]), matrix);
+    }
+
+    #[test]
+    fn permuting_bits_preserves_codewords() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2, 3]]);
+        let permuted = matrix.get_bits_permuted_by(&[2, 0, 3, 1]);
+
+        assert!(matrix.has_codeword(&[GF2::B1, GF2::B1, GF2::B1, GF2::B0]));
+        assert!(permuted.has_codeword(&[GF2::B1, GF2::B0, GF2::B1, GF2::B1]));
+        assert_eq!(permuted.get_bits_permuted_by(&[1, 3, 0, 2]), matrix);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_if_bits_are_permuted_by_a_non_permutation() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1]]);
+        matrix.get_bits_permuted_by(&[0, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_if_checks_are_permuted_by_a_permutation_of_the_wrong_length() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![2]]);
+        matrix.get_checks_permuted_by(&[0, 1, 2]);
+    }
+
+    #[test]
+    fn elementary_codes_with_0_bits() {
+        for matrix in &[
+            ParityCheckMatrix::repetition_code_with_n_bits(0),
+            ParityCheckMatrix::single_parity_check_code_with_n_bits(0),
+            ParityCheckMatrix::identity_with_n_bits(0),
+        ] {
+            assert_eq!(matrix.get_n_bits(), 0);
+            assert_eq!(matrix.get_n_checks(), 0);
+        }
+    }
+
+    #[test]
+    fn elementary_codes_with_1_bit() {
+        let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(1);
+        assert_eq!(repetition_code, ParityCheckMatrix::with_n_bits(1));
+        assert!(repetition_code.has_codeword(&[GF2::B1]));
+
+        let single_parity_check_code = ParityCheckMatrix::single_parity_check_code_with_n_bits(1);
+        assert_eq!(single_parity_check_code, ParityCheckMatrix::identity_with_n_bits(1));
+        assert!(!single_parity_check_code.has_codeword(&[GF2::B1]));
+    }
+
+    #[test]
+    fn elementary_codes_have_the_expected_dimensions() {
+        for n_bits in 2..6 {
+            let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(n_bits);
+            assert_eq!(repetition_code.get_n_checks(), n_bits - 1);
+            assert_eq!(repetition_code.get_rank(), n_bits - 1);
+            assert_eq!(repetition_code.get_minimum_distance(), Some(n_bits));
+
+            let single_parity_check_code =
+                ParityCheckMatrix::single_parity_check_code_with_n_bits(n_bits);
+            assert_eq!(single_parity_check_code.get_rank(), 1);
+            assert_eq!(single_parity_check_code.get_minimum_distance(), Some(2));
+        }
+    }
+
+    #[test]
+    fn hamming_codes_have_the_expected_parameters() {
+        for n_checks in 2..5 {
+            let code = ParityCheckMatrix::hamming_code_with_n_checks(n_checks);
+            assert_eq!(code.get_n_bits(), (1 << n_checks) - 1);
+            assert_eq!(code.get_rank(), n_checks);
+            assert_eq!(code.get_minimum_distance(), Some(3));
+            assert_eq!(code.get_bit_degrees().iter().min(), Some(&1));
+        }
+    }
+
+    #[test]
+    fn hamming_code_with_3_checks() {
+        let expected = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 2, 4, 6],
+            vec![1, 2, 5, 6],
+            vec![3, 4, 5, 6],
+        ]);
+        assert_eq!(ParityCheckMatrix::hamming_code_with_n_checks(3), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn hamming_code_panics_with_1_check() {
+        ParityCheckMatrix::hamming_code_with_n_checks(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many checks")]
+    fn hamming_code_panics_if_the_number_of_bits_overflows() {
+        ParityCheckMatrix::hamming_code_with_n_checks(usize::BITS as usize);
+    }
+
+    #[test]
+    fn systematic_form_ends_with_an_identity_block() {
+        let checks = vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![0, 3],
+            vec![],
+            vec![2, 3, 4, 5],
+        ];
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(checks);
+        let (systematic, permutation) = matrix.get_systematic_form().unwrap();
+
+        let rank = matrix.get_rank();
+        let n_info_bits = matrix.get_n_bits() - rank;
+        assert_eq!(systematic.get_n_checks(), rank);
+        assert_eq!(matrix.get_n_checks() - systematic.get_n_checks(), 2);
+        for check in 0..rank {
+            assert_eq!(
+                systematic.get_checks_adjacent_to_bit(n_info_bits + check),
+                Some(&[check][..])
+            );
+        }
+
+        // Codewords of the systematic form are the permuted codewords of the matrix.
+        let generator = matrix.get_generator_matrix();
+        let product = generator
+            .get_bits_permuted_by(&permutation)
+            .get_product_with(&systematic.get_transposed_matrix());
+        assert_eq!(product.get_n_edges(), 0);
+    }
+
+    #[test]
+    fn reduced_form_with_bit_order_chooses_pivots_in_that_order() {
+        let checks = vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![0, 3],
+            vec![2, 3, 4, 5],
+        ];
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(checks);
+        let (reduced, pivots) =
+            matrix.get_reduced_row_echelon_form_with_bit_order(&[5, 3, 0, 4, 1, 2]);
+
+        assert_eq!(pivots, vec![5, 3, 0]);
+        for (row, pivot) in reduced.checks_iter().zip(pivots.iter()) {
+            let row = row.to_vec();
+            assert!(row.contains(pivot));
+            assert_eq!(pivots.iter().filter(|other| row.contains(other)).count(), 1);
+        }
+        let generator = matrix.get_generator_matrix();
+        let product = generator.get_product_with(&reduced.get_transposed_matrix());
+        assert_eq!(product.get_n_edges(), 0);
+        assert_eq!(reduced.get_rank(), matrix.get_rank());
+
+        let identity_order: Vec<usize> = (0..6).collect();
+        assert_eq!(
+            matrix.get_reduced_row_echelon_form_with_bit_order(&identity_order),
+            matrix.get_reduced_row_echelon_form()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn reduced_form_with_invalid_bit_order_panics() {
+        ParityCheckMatrix::repetition_code_with_n_bits(3)
+            .get_reduced_row_echelon_form_with_bit_order(&[0, 0, 1]);
+    }
+
+    #[test]
+    fn systematic_form_of_rank_0_matrix_is_none() {
+        assert!(ParityCheckMatrix::new().get_systematic_form().is_none());
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![], vec![]]);
+        assert!(matrix.get_systematic_form().is_none());
+    }
+
+    #[test]
+    fn extended_hamming_code_has_distance_4() {
+        let hamming_code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let extended = hamming_code.get_extended_matrix();
+
+        assert_eq!(extended.get_n_bits(), 8);
+        assert_eq!(extended.get_n_checks(), 4);
+        assert_eq!(extended.get_n_bits() - extended.get_rank(), 4);
+        assert_eq!(extended.get_minimum_distance(), Some(4));
+    }
+
+    #[test]
+    fn extended_empty_matrix_is_a_single_bit_check() {
+        let expected = ParityCheckMatrix::with_n_bits(1).with_checks(vec![vec![0]]);
+        assert_eq!(ParityCheckMatrix::new().get_extended_matrix(), expected);
+    }
+
+    #[test]
+    fn batch_syndromes_match_single_syndromes() {
+        let parity_check = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let messages: Vec<Vec<GF2>> = (0..128)
+            .map(|message: usize| {
+                (0..7)
+                    .map(|bit| if message & (1 << bit) != 0 { GF2::B1 } else { GF2::B0 })
+                    .collect()
+            })
+            .collect();
+
+        let syndromes = parity_check.get_syndromes_of_batch(&messages);
+
+        assert_eq!(syndromes.len(), 128);
+        messages.iter().zip(syndromes.iter()).for_each(|(message, syndrome)| {
+            assert_eq!(syndrome, &parity_check.get_syndrome_of(message));
+        });
+        // There are 16 codewords in the Hamming code.
+        assert_eq!(parity_check.get_n_nonzero_syndromes_of_batch(&messages), 112);
+    }
+
+    #[test]
+    #[should_panic]
+    fn batch_syndromes_panic_with_message_of_wrong_length() {
+        let parity_check = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        parity_check.get_syndromes_of_batch(&[vec![GF2::B0; 3], vec![GF2::B0; 2]]);
+    }
+
+    #[test]
+    fn erasures_of_unprotected_bits_are_not_correctable() {
+        let code = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+
+        assert!(code.can_correct_erasure_of(&[]));
+        assert!(code.can_correct_erasure_of(&[0, 2]));
+        assert!(!code.can_correct_erasure_of(&[3]));
+        assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(3), 0);
+    }
+
+    #[test]
+    fn max_guaranteed_erasure_weight_is_minimum_distance_minus_1() {
+        let codes = vec![
+            ParityCheckMatrix::repetition_code_with_n_bits(5),
+            ParityCheckMatrix::hamming_code_with_n_checks(3),
+            ParityCheckMatrix::hamming_code_with_n_checks(3).get_extended_matrix(),
+        ];
+        for code in codes {
+            let distance = code.get_minimum_distance().unwrap();
+            assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(10), distance - 1);
+        }
+    }
+
+    #[test]
+    fn max_guaranteed_erasure_weight_is_capped_by_the_number_of_bits() {
+        let code = ParityCheckMatrix::identity_with_n_bits(3);
+        assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(10), 3);
+        assert_eq!(code.get_max_guaranteed_erasure_weight_up_to(2), 2);
+    }
+
+    #[test]
+    fn removing_redundant_checks_keeps_the_same_code() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let top = ParityCheckMatrix::random_with_density_with_rng(12, 6, 0.3, false, &mut rng);
+        let empty_check = ParityCheckMatrix::with_n_bits(12).with_checks(vec![vec![]]);
+        let mut matrix = top
+            .get_vertical_concat_with(&top)
+            .get_vertical_concat_with(&empty_check);
+        matrix.add_check_to(0, 7);
+        matrix.add_check_to(2, 7);
+
+        let (reduced, removed_checks) = matrix.get_matrix_without_redundant_checks();
+
+        assert_eq!(reduced.get_n_checks(), matrix.get_rank());
+        assert_eq!(reduced.get_rank(), matrix.get_rank());
+        assert_eq!(removed_checks.len(), matrix.get_n_redundant_checks());
+        assert!(removed_checks.contains(&12));
+        for _ in 0..50 {
+            let message: Vec<GF2> = (0..12)
+                .map(|_| if rng.gen_bool(0.5) { GF2::B1 } else { GF2::B0 })
+                .collect();
+            assert_eq!(reduced.has_codeword(&message), matrix.has_codeword(&message));
+        }
+        for codeword in matrix.get_generator_matrix().checks_iter() {
+            let mut message = vec![GF2::B0; 12];
+            codeword.iter().for_each(|bit| message[*bit] = GF2::B1);
+            assert!(reduced.has_codeword(&message));
+        }
+    }
+
+    #[test]
+    fn dual_of_single_parity_check_code_is_repetition_code() {
+        let single_parity_check_code = ParityCheckMatrix::single_parity_check_code_with_n_bits(5);
+        let dual = single_parity_check_code.get_dual_matrix();
+
+        assert_eq!(
+            dual.get_reduced_row_echelon_form(),
+            ParityCheckMatrix::repetition_code_with_n_bits(5).get_reduced_row_echelon_form()
+        );
+        assert_eq!(
+            dual.get_dual_matrix(),
+            ParityCheckMatrix::single_parity_check_code_with_n_bits(5)
+        );
+    }
+
+    #[test]
+    fn dual_of_redundant_matrix_has_independent_checks() {
+        let checks = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![], vec![2, 3]];
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(checks);
+        let dual = matrix.get_dual_matrix();
+
+        assert_eq!(dual.get_n_checks(), 5 - matrix.get_rank());
+        assert_eq!(dual.get_rank(), dual.get_n_checks());
+        assert!(dual.is_orthogonal_to(&matrix));
+    }
+
+    #[test]
+    fn entries_match_the_dense_matrix() {
+        let checks = vec![vec![4, 0, 2], vec![], vec![3, 1, 4]];
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(checks);
+        let dense = matrix.to_dense();
+
+        for (check, row) in dense.iter().enumerate() {
+            for (bit, value) in row.iter().enumerate() {
+                assert_eq!(matrix.get_value_at(check, bit), *value);
+                assert_eq!(matrix.has_edge(check, bit), *value == GF2::B1);
+            }
+        }
+        assert_eq!(matrix.get_value_at(0, 6), GF2::B0);
+        assert_eq!(matrix.get_value_at(3, 0), GF2::B0);
+    }
+
+    #[test]
+    fn quasi_cyclic_code_with_a_single_zero_shift_is_the_identity() {
+        for lift_size in 1..5 {
+            assert_eq!(
+                ParityCheckMatrix::quasi_cyclic_code_with_exponents(&[vec![Some(0)]], lift_size),
+                ParityCheckMatrix::identity_with_n_bits(lift_size)
+            );
+        }
+    }
+
+    #[test]
+    fn quasi_cyclic_code_degrees_match_the_base_matrix_degrees() {
+        let exponents = vec![
+            vec![Some(0), Some(3), None, Some(1)],
+            vec![Some(4), None, None, Some(2)],
+            vec![None, Some(1), Some(0), Some(4)],
+        ];
+        let matrix = ParityCheckMatrix::quasi_cyclic_code_with_exponents(&exponents, 5);
+
+        assert_eq!(matrix.get_n_bits(), 20);
+        assert_eq!(matrix.get_n_checks(), 15);
+        assert_eq!(
+            matrix.get_check_degrees(),
+            [vec![3; 5], vec![2; 5], vec![3; 5]].concat()
+        );
+        assert_eq!(
+            matrix.get_bit_degrees(),
+            [vec![2; 5], vec![2; 5], vec![1; 5], vec![3; 5]].concat()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn quasi_cyclic_code_panics_with_too_large_shift() {
+        ParityCheckMatrix::quasi_cyclic_code_with_exponents(&[vec![Some(3)]], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn quasi_cyclic_code_panics_with_ragged_exponents() {
+        ParityCheckMatrix::quasi_cyclic_code_with_exponents(&[vec![Some(0)], vec![]], 3);
+    }
+
+    #[test]
+    fn orthogonality_of_the_steane_code() {
+        let hamming_code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        assert!(hamming_code.is_orthogonal_to(&hamming_code));
+
+        let repetition_code = ParityCheckMatrix::repetition_code_with_n_bits(7);
+        assert_eq!(
+            hamming_code.get_first_anticommuting_checks_with(&repetition_code),
+            Some((0, 0))
+        );
+        assert!(!repetition_code.is_orthogonal_to(&hamming_code));
     }
 
-    pub fn gbc_from_poly(a: &[usize], b: &[usize], l: usize) -> ParityCheckMatrix { //l is n_bits_a = n_bits_b, and the number of physical qubits is 2*l
-        let w_a = a.len();
-        let w_b = b.len();
-        let w = w_a + w_b;
-        let check_ranges:Vec<usize> = (0..2*l*w+1).step_by(w).collect();
-        let mut bit_indices: Vec<usize> = Vec::with_capacity(2*l*w);
+    #[test]
+    fn empty_checks_are_orthogonal_to_everything() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![], vec![]]);
+        let other = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0], vec![0, 1, 2]]);
+        assert!(matrix.is_orthogonal_to(&other));
+        assert!(other.is_orthogonal_to(&matrix));
+    }
 
-        for i in 0..l {
-            for j in a {
+    #[test]
+    #[should_panic]
+    fn orthogonality_panics_if_matrices_have_different_numbers_of_bits() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        matrix.is_orthogonal_to(&ParityCheckMatrix::repetition_code_with_n_bits(4));
+    }
 
-                bit_indices.push((j+i)%l);
+    #[test]
+    fn product_with_identity_is_the_same_matrix() {
+        let checks = vec![vec![0, 2], vec![], vec![1, 2, 3]];
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+        assert_eq!(matrix.get_product_with(&ParityCheckMatrix::identity_with_n_bits(4)), matrix);
+        assert_eq!(ParityCheckMatrix::identity_with_n_bits(3).get_product_with(&matrix), matrix);
+    }
 
-            }
-            (&mut bit_indices[i*w..i*w + w_a]).sort_unstable();
-            for j in b {
+    #[test]
+    fn entries_of_a_product_cancel_in_pairs() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1, 2], vec![0, 2]]);
+        let other_checks = vec![vec![0, 1], vec![1, 2], vec![0, 1]];
+        let other = ParityCheckMatrix::with_n_bits(3).with_checks(other_checks);
 
-                bit_indices.push((j+i)%l + l);
+        let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![1, 2], vec![]]);
+        assert_eq!(matrix.get_product_with(&other), expected);
+    }
 
-            }
-            (&mut bit_indices[i*w + w_a..(i+1)*w]).sort_unstable();
-        }
-        for i in l..2*l {
-            for j in b {
+    #[test]
+    fn hypergraph_product_satisfies_the_css_condition() {
+        let (x_checks, z_checks) = ParityCheckMatrix::hamming_code_with_n_checks(3)
+            .get_hypergraph_product_with(&ParityCheckMatrix::repetition_code_with_n_bits(4));
+        let product = x_checks.get_product_with(&z_checks.get_transposed_matrix());
+
+        assert_eq!(product.get_n_checks(), x_checks.get_n_checks());
+        assert_eq!(product.get_n_bits(), z_checks.get_n_checks());
+        assert_eq!(product.get_n_edges(), 0);
+    }
 
-                bit_indices.push((l-j+i)%l + 2*l);
+    #[test]
+    #[should_panic]
+    fn product_panics_if_inner_dimensions_do_not_match() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        matrix.get_product_with(&matrix);
+    }
 
-            }
-            (&mut bit_indices[i*w..i*w + w_b]).sort_unstable();
-            for j in a {
+    #[test]
+    fn adding_a_check_to_itself_empties_it() {
+        let mut matrix = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        matrix.add_check_to(1, 1);
+
+        let expected_checks = vec![vec![0, 1], vec![], vec![2, 3]];
+        let expected = ParityCheckMatrix::with_n_bits(4).with_checks(expected_checks);
+        assert_eq!(matrix, expected);
+        assert_eq!(matrix.get_checks_adjacent_to_bit(1), Some(&[0][..]));
+    }
 
-                bit_indices.push((l-j+i)%l + 3*l);
+    #[test]
+    fn adding_checks_transforms_syndromes_consistently() {
+        let mut matrix = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let message = vec![GF2::B1, GF2::B0, GF2::B1, GF2::B1, GF2::B0, GF2::B0, GF2::B1];
+        let syndrome = matrix.get_syndrome_of(&message);
 
-            }
-            (&mut bit_indices[i*w + w_b..(i+1)*w]).sort_unstable();
-            
-        }
+        matrix.add_check_to(2, 0);
+        let new_syndrome = matrix.get_syndrome_of(&message);
 
-        Self {
-            n_bits: 4*l,
-            check_ranges,
-            bit_indices
-        } 
+        assert_eq!(new_syndrome[0], syndrome[0] + syndrome[2]);
+        assert_eq!(new_syndrome[1..], syndrome[1..]);
+        assert_eq!(matrix.get_rank(), 3);
+    }
 
+    #[test]
+    fn swapping_and_removing_checks() {
+        let mut matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1, 2],
+            vec![3],
+            vec![],
+        ]);
+        matrix.swap_checks(0, 2);
+        assert_eq!(matrix.get_check_degrees(), vec![0, 1, 3]);
+        assert_eq!(matrix.get_checks_adjacent_to_bit(0), Some(&[2][..]));
+
+        matrix.remove_check(1);
+        let expected = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![], vec![0, 1, 2]]);
+        assert_eq!(matrix, expected);
+        assert_eq!(matrix.get_checks_adjacent_to_bit(3), Some(&[][..]));
     }
 
-    pub fn permu_matrix(l: usize) -> ParityCheckMatrix {
-        let ranges: Vec<usize> = (0..l + 1).collect(); // ranges = [0,1,...,l] because we have one entries per row
-        let mut indices: Vec<usize> = Vec::with_capacity(l);
+    #[test]
+    #[should_panic]
+    fn removing_a_check_out_of_bounds_panics() {
+        let mut matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        matrix.remove_check(2);
+    }
 
-        indices.push(l - 1);
+    #[test]
+    fn keeping_bits_respects_their_order() {
+        let matrix = ParityCheckMatrix::hamming_code_with_n_checks(3);
+        let permutation = [3, 6, 0, 1, 5, 2, 4];
+        let restricted = matrix.keep_bits(&permutation);
+
+        let mut inverse = [0; 7];
+        permutation.iter().enumerate().for_each(|(i, bit)| inverse[*bit] = i);
+        assert_eq!(restricted, matrix.get_bits_permuted_by(&inverse));
+    }
 
-        for i in 0..l - 1 {
-            // indices = [l-1,0,1,...,l-2]
-            indices.push(i);
-        }
+    #[test]
+    fn keeping_no_bits_gives_empty_checks() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3).keep_bits(&[]);
+        assert_eq!(matrix.get_n_bits(), 0);
+        assert_eq!(matrix.get_check_degrees(), vec![0, 0]);
+    }
 
-        Self {
-            bit_indices: indices,
-            check_ranges: ranges,
-            n_bits: l,
-        }
+    #[test]
+    #[should_panic]
+    fn keeping_duplicated_bits_panics() {
+        ParityCheckMatrix::repetition_code_with_n_bits(3).keep_bits(&[0, 1, 0]);
     }
 
-    pub fn circulant_down(indices: &[usize], l: usize) -> ParityCheckMatrix {
-        let w = indices.len();
-        let mut checks: Vec<Vec<usize>> = Vec::with_capacity(l);
+    #[test]
+    fn keeping_checks_respects_their_order() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(4);
+        let expected_checks = vec![vec![2, 3], vec![0, 1], vec![2, 3]];
+        let expected = ParityCheckMatrix::with_n_bits(4).with_checks(expected_checks);
+        assert_eq!(matrix.keep_checks(&[2, 0, 2]), expected);
+        assert_eq!(matrix.keep_checks(&[]), ParityCheckMatrix::with_n_bits(4));
+    }
 
-        for i in 0..l {
-            let mut new_row: Vec<usize> = Vec::with_capacity(w);
-            for j in indices {
-                new_row.push((l - j + i) % l);
-            }
-            checks.push(new_row);
-        }
+    #[test]
+    #[should_panic]
+    fn keeping_checks_out_of_bounds_panics() {
+        ParityCheckMatrix::repetition_code_with_n_bits(3).keep_checks(&[2]);
+    }
 
-        ParityCheckMatrix::with_n_bits(l).with_checks(checks)
+    #[test]
+    fn single_degrees_match_all_degrees() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![0, 1, 4],
+            vec![],
+            vec![1, 2],
+            vec![1, 3, 4],
+        ]);
+        let bit_degrees: Vec<usize> =
+            (0..6).filter_map(|bit| matrix.get_bit_degree(bit)).collect();
+        let check_degrees: Vec<usize> =
+            (0..4).filter_map(|check| matrix.get_check_degree(check)).collect();
+
+        assert_eq!(bit_degrees, matrix.get_bit_degrees());
+        assert_eq!(check_degrees, matrix.get_check_degrees());
+        assert_eq!(matrix.get_bit_degree(6), None);
+        assert_eq!(matrix.get_check_degree(4), None);
     }
 
-    pub fn circulant_right(indices: &[usize], l: usize) -> ParityCheckMatrix {
-        let w = indices.len();
-        let mut checks: Vec<Vec<usize>> = Vec::with_capacity(l);
+    #[test]
+    fn trailing_bits_without_checks_are_unprotected() {
+        let checks = vec![vec![0, 1], vec![], vec![1, 2]];
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(checks);
+
+        assert!(matrix.has_unprotected_bits());
+        assert_eq!(matrix.get_unprotected_bits(), vec![3, 4, 5]);
+        assert!(!ParityCheckMatrix::repetition_code_with_n_bits(3).has_unprotected_bits());
+        assert!(!ParityCheckMatrix::new().has_unprotected_bits());
+    }
 
-        for i in 0..l {
-            let mut new_row: Vec<usize> = Vec::with_capacity(w);
-            for j in indices {
-                new_row.push((j + i) % l);
-            }
-            checks.push(new_row);
-        }
+    #[test]
+    fn bits_are_sorted_on_construction() {
+        let matrix = ParityCheckMatrix::with_n_bits(5).with_checks(vec![vec![4, 0, 2], vec![3, 1]]);
 
-        ParityCheckMatrix::with_n_bits(l).with_checks(checks)
+        assert_eq!(matrix.get_check(0).unwrap().as_ref(), &[0, 2, 4]);
+        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[1, 3]);
     }
 
-    // Returns a reference to `self.check_ranges`.
-    pub(crate) fn check_ranges(&self) -> &[usize] {
-        &self.check_ranges
+    #[test]
+    fn duplicated_bits_are_kept_once_on_construction() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1, 0], vec![2, 2]]);
+
+        assert_eq!(matrix.get_check(0).unwrap().as_ref(), &[0, 1]);
+        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[2]);
+        assert_eq!(matrix.get_n_edges(), 3);
+        assert_eq!(
+            matrix.get_syndrome_of(&[GF2::B1, GF2::B0, GF2::B1]),
+            vec![GF2::B1, GF2::B1]
+        );
     }
 
-    // Returns a reference to `self.bit_indices
-    #[allow(dead_code)]
-    pub(crate) fn bit_indices(&self) -> &[usize] {
-        &self.bit_indices
+    #[test]
+    fn many_consecutive_empty_checks_are_kept_on_construction() {
+        let checks = vec![vec![0], vec![], vec![], vec![], vec![1, 0]];
+        let matrix = ParityCheckMatrix::with_n_bits(2).with_checks(checks);
+
+        assert_eq!(matrix.get_n_checks(), 5);
+        assert_eq!(matrix.get_check_degrees(), vec![1, 0, 0, 0, 2]);
+        assert_eq!(matrix.get_check(4).unwrap().as_ref(), &[0, 1]);
+        assert_eq!(matrix.edges_iter().collect::<Vec<_>>(), vec![(0, 0), (4, 0), (4, 1)]);
     }
-}
 
-fn transfer_to(v1: &[usize], v2: &mut Vec<usize>){
+    #[test]
+    fn construction_from_unsorted_edges() {
+        let edges = vec![(3, 1), (0, 2), (3, 0), (0, 0), (3, 1)];
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_edges(4, edges);
 
-    unsafe{ v2.set_len(v1.len())}
-    for i in 0..v1.len() {
-        v2[i] = v1[i];
+        let expected =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 2], vec![], vec![], vec![0, 1]]);
+        assert_eq!(matrix, expected);
     }
-}
 
-pub fn add_checks_mut(check_0: &[usize], check_1: &[usize], sum: &mut Vec<usize>){
-    //let mut sum = Vec::with_capacity(check_0.len() + check_1.len());
-    sum.clear();
-    let mut iter_0 = check_0.iter().peekable();
-    let mut iter_1 = check_1.iter().peekable();
-    loop {
-        match (iter_0.peek(), iter_1.peek()) {
-            (Some(&&v), None) => {
-                sum.push(v);
-                iter_0.next();
-            }
-            (None, Some(&&v)) => {
-                sum.push(v);
-                iter_1.next();
-            }
-            (Some(&&v_0), Some(&&v_1)) => {
-                if v_0 < v_1 {
-                    sum.push(v_0);
-                    iter_0.next();
-                } else if v_0 > v_1 {
-                    sum.push(v_1);
-                    iter_1.next();
-                } else {
-                    iter_0.next();
-                    iter_1.next();
-                }
-            }
-            (None, None) => break,
+    #[test]
+    fn positions_round_trip_through_edges_for_every_matrix() {
+        let matrices = vec![
+            ParityCheckMatrix::new(),
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![], vec![]]),
+            ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![], vec![1, 3], vec![], vec![]]),
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![2, 0], vec![1, 2]]),
+            ParityCheckMatrix::hamming_code_with_n_checks(3),
+        ];
+        for matrix in matrices {
+            let positions: Vec<(usize, usize)> = matrix.positions().collect();
+            let mut sorted = positions.clone();
+            sorted.sort_unstable();
+            assert_eq!(positions, sorted);
+            assert!(matrix.rows().eq(matrix.checks_iter()));
+
+            let rebuilt = ParityCheckMatrix::with_n_bits(matrix.get_n_bits())
+                .with_edges(matrix.get_n_checks(), positions);
+            assert_eq!(rebuilt, matrix);
         }
     }
-}
 
-pub fn binary_search(data: &[usize], target: &usize) -> bool {
+    #[test]
+    #[should_panic]
+    fn panics_on_construction_from_edges_if_checks_are_out_of_bound() {
+        ParityCheckMatrix::with_n_bits(3).with_edges(2, vec![(0, 1), (2, 0)]);
+    }
 
-    // println!("data:{:?}",data);
-    // println!("target:{:?}",target);
-    if data.len() > 0 {
+    #[test]
+    #[should_panic]
+    fn panics_on_construction_if_checks_are_out_of_bound() {
+        let checks = vec![vec![0, 1, 5], vec![2, 3, 4]];
+        ParityCheckMatrix::with_n_bits(5).with_checks(checks);
+    }
 
-        let mut high = data.len()-1;
-        let mut low = 0;
-        
-        if target < &data[0] || target > &data[high] {
-            return false
-        }
-    
-        while low <= high {
-            let mid = (low + high) / 2;
+    #[test]
+    fn puncturing_the_repetition_code() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
 
-            if data[mid] < *target {
-                low = mid + 1
-            } else if data[mid] > *target {
-                high = mid - 1
-            } else if data[mid] == *target {
-                return true
-            }
+        let expected = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![0], vec![1]]);
+        assert_eq!(matrix.without_bits(&[1]), expected);
 
-        }
-        return false
-    } else {
-        return false
+        let expected = ParityCheckMatrix::with_n_bits(2).with_checks(vec![vec![0, 1], vec![1]]);
+        assert_eq!(matrix.without_bits(&[2, 2]), expected);
+
+        let expected = ParityCheckMatrix::with_n_bits(0).with_checks(vec![vec![], vec![]]);
+        assert_eq!(matrix.without_bits(&[0, 1, 2]), expected);
     }
-}
 
-pub fn add_checks(check_0: &[usize], check_1: &[usize]) -> Vec<usize> {
-    let mut sum = Vec::with_capacity(check_0.len() + check_1.len());
-    let mut iter_0 = check_0.iter().peekable();
-    let mut iter_1 = check_1.iter().peekable();
-    loop {
-        match (iter_0.peek(), iter_1.peek()) {
-            (Some(&&v), None) => {
-                sum.push(v);
-                iter_0.next();
-            }
-            (None, Some(&&v)) => {
-                sum.push(v);
-                iter_1.next();
-            }
-            (Some(&&v_0), Some(&&v_1)) => {
-                if v_0 < v_1 {
-                    sum.push(v_0);
-                    iter_0.next();
-                } else if v_0 > v_1 {
-                    sum.push(v_1);
-                    iter_1.next();
-                } else {
-                    iter_0.next();
-                    iter_1.next();
-                }
-            }
-            (None, None) => break,
-        }
+    #[test]
+    #[should_panic]
+    fn puncturing_panics_on_out_of_bound_bits() {
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        matrix.without_bits(&[3]);
     }
-    sum
-}
 
-impl std::fmt::Display for ParityCheckMatrix {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for check in self.checks_iter() {
-            write!(f, "[ ")?;
-            for bit in check.iter() {
-                write!(f, "{} ", bit)?;
-            }
-            write!(f, "]")?;
-        }
-        Ok(())
+    #[test]
+    fn shortening_keeps_checks_that_were_already_empty() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1],
+            vec![],
+            vec![2],
+            vec![2, 3],
+        ]);
+
+        let expected = ParityCheckMatrix::with_n_bits(3).with_checks(vec![
+            vec![0, 1],
+            vec![],
+            vec![2],
+        ]);
+        assert_eq!(matrix.shortened_on(&[2]), (expected, 1));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    fn dense_round_trip_with_empty_checks_and_unused_bits() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![],
+            vec![0, 2],
+            vec![],
+            vec![1, 2, 3],
+        ]);
+        let dense = matrix.to_dense();
+
+        assert_eq!(dense.len(), 4);
+        assert!(dense.iter().all(|row| row.len() == 6));
+        assert_eq!(ParityCheckMatrix::from_dense(&dense), matrix);
+    }
 
     #[test]
-    fn checks_are_sorted_on_construction() {
-        let checks = vec![vec![1, 0], vec![0, 2, 1], vec![1, 2, 3]];
-        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(checks);
+    #[should_panic]
+    fn panics_on_dense_construction_if_rows_have_different_lengths() {
+        ParityCheckMatrix::from_dense(&[vec![GF2::B0, GF2::B1], vec![GF2::B1]]);
+    }
 
-        assert_eq!(matrix.get_check(0).unwrap().as_ref(), &[0, 1]);
-        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[0, 1, 2]);
-        assert_eq!(matrix.get_check(2).unwrap().as_ref(), &[1, 2, 3]);
+    #[test]
+    fn syndrome_with_empty_checks() {
+        let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1, 2],
+            vec![],
+            vec![2, 3],
+        ]);
+        let bits = vec![GF2::B1, GF2::B1, GF2::B1, GF2::B0];
+
+        assert_eq!(
+            parity_check.get_syndrome_of(&bits),
+            vec![GF2::B1, GF2::B0, GF2::B1]
+        );
     }
 
     #[test]
-    fn empty_checks_are_removed_on_construction() {
-        let checks = vec![vec![], vec![0, 1], vec![], vec![1, 2]];
-        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+    #[should_panic]
+    fn syndrome_panics_if_message_is_too_short() {
+        let parity_check =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        parity_check.get_syndrome_of(&[GF2::B0, GF2::B1]);
+    }
 
-        assert_eq!(matrix.get_check(0).unwrap().as_ref(), &[0, 1]);
-        assert_eq!(matrix.get_check(1).unwrap().as_ref(), &[1, 2]);
-        assert_eq!(matrix.get_n_checks(), 2);
+    #[test]
+    #[should_panic]
+    fn syndrome_panics_if_message_is_too_long() {
+        let parity_check =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        parity_check.get_syndrome_of(&[GF2::B0; 4]);
     }
 
     #[test]
     #[should_panic]
-    fn panics_on_construction_if_checks_are_out_of_bound() {
-        let checks = vec![vec![0, 1, 5], vec![2, 3, 4]];
-        ParityCheckMatrix::with_n_bits(5).with_checks(checks);
+    fn codeword_check_panics_if_message_has_wrong_length() {
+        let parity_check =
+            ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        parity_check.has_codeword(&[GF2::B0; 2]);
+    }
+
+    #[test]
+    fn first_unsatisfied_check_skips_satisfied_and_empty_checks() {
+        let parity_check = ParityCheckMatrix::with_n_bits(4).with_checks(vec![
+            vec![0, 1],
+            vec![],
+            vec![1, 2],
+            vec![2, 3],
+        ]);
+        let message = vec![GF2::B1, GF2::B1, GF2::B0, GF2::B1];
+
+        assert_eq!(parity_check.get_first_unsatisfied_check(&message), Some(2));
+        assert!(!parity_check.has_codeword(&message));
     }
 
     #[test]
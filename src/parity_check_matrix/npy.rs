@@ -0,0 +1,106 @@
+//! Writing the dense form of a parity check matrix in the NumPy .npy format.
+//!
+//! The file uses version 1.0 of the format. It is made of the magic string `\x93NUMPY`, the
+//! version, the length of the header as a little endian `u16` and a header describing a
+//! `uint8` array of shape `(n_checks, n_bits)` in C order. The header is padded with spaces
+//! so that the data starts on a multiple of 64 bytes. Then, each check is written as a row of
+//! 0 and 1 bytes.
+
+use super::ParityCheckMatrix;
+use std::io::{self, Write};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const VERSION: [u8; 2] = [1, 0];
+const ALIGNMENT: usize = 64;
+
+pub(super) struct NpyWriter<'a> {
+    matrix: &'a ParityCheckMatrix,
+}
+
+impl<'a> NpyWriter<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix) -> Self {
+        Self { matrix }
+    }
+
+    pub(super) fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let header = self.get_header();
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION)?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+        for check in self.matrix.checks_iter() {
+            let mut row = vec![0; self.matrix.get_n_bits()];
+            check.iter().for_each(|bit| row[*bit] = 1);
+            writer.write_all(&row)?;
+        }
+        writer.flush()
+    }
+
+    fn get_header(&self) -> String {
+        let mut header = format!(
+            "{{'descr': '|u1', 'fortran_order': False, 'shape': ({}, {}), }}",
+            self.matrix.get_n_checks(),
+            self.matrix.get_n_bits()
+        );
+        // The header ends with a new line that is counted in the padding.
+        let prefix_length = MAGIC.len() + VERSION.len() + 2;
+        let unpadded_length = prefix_length + header.len() + 1;
+        let padding = (ALIGNMENT - unpadded_length % ALIGNMENT) % ALIGNMENT;
+        header.push_str(&" ".repeat(padding));
+        header.push('\n');
+        header
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(matrix: &ParityCheckMatrix) -> Vec<u8> {
+        let mut output = Vec::new();
+        NpyWriter::from(matrix).write_to(&mut output).unwrap();
+        output
+    }
+
+    fn split_header(output: &[u8]) -> (String, &[u8]) {
+        assert_eq!(&output[..6], MAGIC);
+        assert_eq!(&output[6..8], &VERSION);
+        let header_length = u16::from_le_bytes([output[8], output[9]]) as usize;
+        let header = String::from_utf8(output[10..10 + header_length].to_vec()).unwrap();
+        (header, &output[10 + header_length..])
+    }
+
+    #[test]
+    fn header_describes_the_shape_and_is_aligned() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(5).with_checks(vec![vec![0, 4], vec![], vec![2]]);
+        let output = write(&matrix);
+        let (header, data) = split_header(&output);
+
+        let expected_start = "{'descr': '|u1', 'fortran_order': False, 'shape': (3, 5), }";
+        assert!(header.starts_with(expected_start));
+        assert!(header.ends_with('\n'));
+        assert_eq!((output.len() - data.len()) % ALIGNMENT, 0);
+        assert_eq!(data.len(), 15);
+    }
+
+    #[test]
+    fn data_is_the_dense_matrix_in_row_major_order() {
+        let matrix =
+            ParityCheckMatrix::with_n_bits(5).with_checks(vec![vec![0, 4], vec![], vec![2]]);
+        let output = write(&matrix);
+        let (_, data) = split_header(&output);
+
+        let expected = [1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0];
+        assert_eq!(data, &expected[..]);
+    }
+
+    #[test]
+    fn empty_matrix_has_no_data() {
+        let output = write(&ParityCheckMatrix::with_n_bits(4));
+        let (header, data) = split_header(&output);
+
+        assert!(header.contains("'shape': (0, 4)"));
+        assert!(data.is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! A dense bit packed representation of a parity check matrix.
+//!
+//! Each check is stored as a row of 64 bits words, so the syndrome of a message is computed with
+//! a few bitwise and and popcount operations per check instead of following bit indices. This is
+//! faster for dense matrices or for long messages.
+//!
+//! # Example
+//!
+//! ```
+//! # use believer::{GF2, ParityCheckMatrix};
+//! let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(vec![vec![0, 1], vec![1, 2]]);
+//! let packed_matrix = matrix.get_packed_matrix();
+//!
+//! let message = vec![GF2::B0, GF2::B1, GF2::B1];
+//! let packed_message = packed_matrix.pack(&message);
+//!
+//! assert_eq!(packed_matrix.get_syndrome_of_packed(&packed_message), vec![GF2::B1, GF2::B0]);
+//! assert_eq!(packed_matrix.get_syndrome_of(&message), matrix.get_syndrome_of(&message));
+//! ```
+
+use super::ParityCheckMatrix;
+use crate::GF2;
+
+const BITS_PER_WORD: usize = 64;
+
+/// A parity check matrix where each check is stored as packed bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedParityCheckMatrix {
+    rows: Vec<Vec<u64>>,
+    n_bits: usize,
+}
+
+impl PackedParityCheckMatrix {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        let n_words = get_n_words_for(matrix.get_n_bits());
+        let rows = matrix
+            .checks_iter()
+            .map(|check| pack_positions(check.iter().cloned(), n_words))
+            .collect();
+        Self {
+            rows,
+            n_bits: matrix.get_n_bits(),
+        }
+    }
+
+    /// Returns the number of bits in `self`.
+    pub fn get_n_bits(&self) -> usize {
+        self.n_bits
+    }
+
+    /// Returns the number of checks in `self`.
+    pub fn get_n_checks(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of words of a packed message.
+    pub fn get_n_words(&self) -> usize {
+        get_n_words_for(self.n_bits)
+    }
+
+    /// Packs `message` into words. Bit `b` of the message is the bit `b % 64` of the word
+    /// `b / 64`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of bits of `self`.
+    pub fn pack(&self, message: &[GF2]) -> Vec<u64> {
+        if message.len() != self.n_bits {
+            panic!("message doesn't have the right length");
+        }
+        let positions = message
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value == GF2::B1)
+            .map(|(bit, _)| bit);
+        pack_positions(positions, self.get_n_words())
+    }
+
+    /// Computes the syndrome of `message`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `message` is not the number of bits of `self`.
+    pub fn get_syndrome_of(&self, message: &[GF2]) -> Vec<GF2> {
+        self.get_syndrome_of_packed(&self.pack(message))
+    }
+
+    /// Computes the syndrome of a message already packed with `pack`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `packed_message` doesn't have the number of words of a packed message.
+    pub fn get_syndrome_of_packed(&self, packed_message: &[u64]) -> Vec<GF2> {
+        if packed_message.len() != self.get_n_words() {
+            panic!("message doesn't have the right length");
+        }
+        self.rows
+            .iter()
+            .map(|row| get_packed_dot_product(row, packed_message))
+            .collect()
+    }
+}
+
+fn get_n_words_for(n_bits: usize) -> usize {
+    n_bits.div_ceil(BITS_PER_WORD)
+}
+
+fn pack_positions<I: Iterator<Item = usize>>(positions: I, n_words: usize) -> Vec<u64> {
+    let mut words = vec![0; n_words];
+    positions.for_each(|bit| words[bit / BITS_PER_WORD] |= 1 << (bit % BITS_PER_WORD));
+    words
+}
+
+// The parity of the number of ones is the parity of the xor of all words.
+fn get_packed_dot_product(row: &[u64], message: &[u64]) -> GF2 {
+    let sum = row
+        .iter()
+        .zip(message)
+        .fold(0, |sum, (row_word, message_word)| {
+            sum ^ (row_word & message_word)
+        });
+    if sum.count_ones() & 1 == 0 {
+        GF2::B0
+    } else {
+        GF2::B1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn bits_are_packed_in_little_endian_words() {
+        let matrix = ParityCheckMatrix::with_n_bits(130).with_checks(vec![vec![0, 63, 64, 129]]);
+        let packed_matrix = PackedParityCheckMatrix::from(&matrix);
+
+        assert_eq!(packed_matrix.get_n_words(), 3);
+        assert_eq!(packed_matrix.rows, vec![vec![1 | 1 << 63, 1, 1 << 1]]);
+    }
+
+    #[test]
+    fn syndromes_match_the_sparse_matrix_on_random_matrices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        for &(n_bits, n_checks, density) in &[(10, 5, 0.3), (64, 20, 0.5), (200, 50, 0.05)] {
+            let matrix = ParityCheckMatrix::random_with_density_with_rng(
+                n_bits, n_checks, density, false, &mut rng,
+            );
+            let packed_matrix = matrix.get_packed_matrix();
+            for _ in 0..20 {
+                let message: Vec<GF2> = (0..n_bits)
+                    .map(|_| if rng.gen_bool(0.5) { GF2::B1 } else { GF2::B0 })
+                    .collect();
+                assert_eq!(
+                    packed_matrix.get_syndrome_of(&message),
+                    matrix.get_syndrome_of(&message)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_without_bits_has_empty_messages() {
+        let matrix = ParityCheckMatrix::with_n_bits(0).with_checks(vec![vec![], vec![]]);
+        let packed_matrix = matrix.get_packed_matrix();
+
+        assert_eq!(packed_matrix.get_n_words(), 0);
+        assert_eq!(packed_matrix.get_syndrome_of(&[]), vec![GF2::B0; 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn packing_message_of_wrong_length_panics() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        matrix.get_packed_matrix().pack(&[GF2::B0; 4]);
+    }
+}
@@ -0,0 +1,136 @@
+use super::{Check, ParityCheckMatrix};
+use rand::Rng;
+
+// Samples parity check matrices where each edge is present independently with a given density.
+//
+// Empty checks are sampled again until they are connected to some bits. Then, bits that are not
+// connected to any check get a new column sampled until it is not empty. Adding edges to a bit
+// never empties a check, so both kinds of nodes end up connected.
+pub(super) struct RandomMatrixSampler {
+    n_bits: usize,
+    n_checks: usize,
+    density: f64,
+}
+
+impl RandomMatrixSampler {
+    pub(super) fn from(n_bits: usize, n_checks: usize, density: f64) -> Self {
+        if !(0.0..=1.0).contains(&density) {
+            panic!("density is not between 0 and 1");
+        }
+        Self {
+            n_bits,
+            n_checks,
+            density,
+        }
+    }
+
+    pub(super) fn sample_with_rng<R: Rng>(&self, rng: &mut R) -> ParityCheckMatrix {
+        let checks = (0..self.n_checks)
+            .map(|_| self.sample_check_with_rng(rng))
+            .collect();
+        ParityCheckMatrix::with_n_bits(self.n_bits).with_checks_unchecked(checks)
+    }
+
+    pub(super) fn sample_without_empty_nodes_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> ParityCheckMatrix {
+        if self.density == 0.0 && self.n_bits > 0 && self.n_checks > 0 {
+            panic!("density is 0 so all nodes are empty");
+        }
+        let mut checks: Vec<Check> = (0..self.n_checks)
+            .map(|_| self.sample_non_empty_check_with_rng(rng))
+            .collect();
+        self.connect_empty_bits_with_rng(&mut checks, rng);
+        ParityCheckMatrix::with_n_bits(self.n_bits).with_checks(checks)
+    }
+
+    fn sample_check_with_rng<R: Rng>(&self, rng: &mut R) -> Check {
+        (0..self.n_bits)
+            .filter(|_| rng.gen_bool(self.density))
+            .collect()
+    }
+
+    fn sample_non_empty_check_with_rng<R: Rng>(&self, rng: &mut R) -> Check {
+        loop {
+            let check = self.sample_check_with_rng(rng);
+            if !check.is_empty() || self.n_bits == 0 {
+                return check;
+            }
+        }
+    }
+
+    fn connect_empty_bits_with_rng<R: Rng>(&self, checks: &mut [Check], rng: &mut R) {
+        if checks.is_empty() {
+            return;
+        }
+        let mut bit_is_connected = vec![false; self.n_bits];
+        checks
+            .iter()
+            .flatten()
+            .for_each(|bit| bit_is_connected[*bit] = true);
+        for bit in (0..self.n_bits).filter(|bit| !bit_is_connected[*bit]) {
+            let column = self.sample_non_empty_column_with_rng(rng);
+            column.into_iter().for_each(|check| checks[check].push(bit));
+        }
+    }
+
+    fn sample_non_empty_column_with_rng<R: Rng>(&self, rng: &mut R) -> Vec<usize> {
+        loop {
+            let column: Vec<usize> = (0..self.n_checks)
+                .filter(|_| rng.gen_bool(self.density))
+                .collect();
+            if !column.is_empty() {
+                return column;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn extreme_densities() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+
+        let empty = RandomMatrixSampler::from(5, 3, 0.0).sample_with_rng(&mut rng);
+        assert_eq!(empty.get_n_checks(), 3);
+        assert_eq!(empty.get_n_edges(), 0);
+
+        let full = RandomMatrixSampler::from(5, 3, 1.0).sample_with_rng(&mut rng);
+        assert_eq!(full.get_check_degrees(), vec![5, 5, 5]);
+    }
+
+    #[test]
+    fn density_is_about_right() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let matrix = RandomMatrixSampler::from(100, 50, 0.1).sample_with_rng(&mut rng);
+
+        assert_eq!(matrix.get_n_bits(), 100);
+        assert_eq!(matrix.get_n_checks(), 50);
+        assert!(matrix.get_n_edges() > 400 && matrix.get_n_edges() < 600);
+    }
+
+    #[test]
+    fn resampling_removes_empty_nodes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let sampler = RandomMatrixSampler::from(30, 20, 0.02);
+
+        let matrix = sampler.sample_with_rng(&mut rng);
+        assert!(matrix.get_degree_distribution().has_unconnected_bits());
+
+        let matrix = sampler.sample_without_empty_nodes_with_rng(&mut rng);
+        assert!(!matrix.get_bit_degrees().contains(&0));
+        assert!(!matrix.get_check_degrees().contains(&0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_if_density_is_greater_than_1() {
+        RandomMatrixSampler::from(3, 3, 1.5);
+    }
+}
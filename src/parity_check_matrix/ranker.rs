@@ -3,75 +3,47 @@ use super::ParityCheckMatrix;
 
 type Row = Vec<usize>;
 
+// Computes the rank by reducing each row against the pivots found so far. Pivots are stored by
+// their leading column so that only the columns actually present in a row are ever visited.
 pub(super) struct Ranker {
-    n_columns: usize,
-    n_rows: usize,
-    rank: usize,
-    active_column: usize,
     rows: Vec<Row>,
+    pivots: Vec<Option<Row>>,
+    rank: usize,
 }
 
 impl Ranker {
     pub(super) fn from_parity_check_matrix(matrix: &ParityCheckMatrix) -> Self {
         Self {
-            n_columns: matrix.get_n_bits(),
-            n_rows: matrix.get_n_checks(),
-            rank: 0,
-            active_column: 0,
             rows: matrix.checks_iter().map(|check| check.to_vec()).collect(),
+            pivots: vec![None; matrix.get_n_bits()],
+            rank: 0,
         }
     }
 
     pub(super) fn get_rank(mut self) -> usize {
-        while self.is_not_in_echelon_form() {
-            self.pivot_active_column();
-            self.go_to_next_column();
-        }
+        let rows = std::mem::take(&mut self.rows);
+        rows.into_iter().for_each(|row| self.insert(row));
         self.rank
     }
 
-    fn is_not_in_echelon_form(&self) -> bool {
-        self.active_column < self.n_columns
-    }
-
-    fn pivot_active_column(&mut self) {
-        if let Some(pivot) = self.find_and_remove_pivot() {
-            self.pivot_rows_that_start_in_active_column_with(pivot);
+    fn insert(&mut self, row: Row) {
+        let reduced = self.reduce(row);
+        if let Some(&leading_column) = reduced.first() {
+            self.pivots[leading_column] = Some(reduced);
             self.rank += 1;
         }
     }
 
-    fn find_and_remove_pivot(&mut self) -> Option<Row> {
-        for row_index in 0..self.n_rows {
-            if self.row_at_index_start_at_active_column(row_index) {
-                let row = self.get_and_remove_row_at_index(row_index);
-                return Some(row);
-            }
-        }
-        None
-    }
-
-    fn row_at_index_start_at_active_column(&self, index: usize) -> bool {
-        self.rows[index]
-            .first()
-            .map(|column| *column == self.active_column)
-            .unwrap_or(false)
-    }
-
-    fn get_and_remove_row_at_index(&mut self, index: usize) -> Row {
-        std::mem::replace(&mut self.rows[index], Vec::new())
-    }
-
-    fn pivot_rows_that_start_in_active_column_with(&mut self, pivot: Row) {
-        for row_index in 0..self.n_rows {
-            if self.row_at_index_start_at_active_column(row_index) {
-                self.rows[row_index] = get_bitwise_sum(&pivot, &self.rows[row_index])
-            }
+    fn reduce(&self, mut row: Row) -> Row {
+        while let Some(pivot) = self.pivot_for(&row) {
+            row = get_bitwise_sum(pivot, &row);
         }
+        row
     }
 
-    fn go_to_next_column(&mut self) {
-        self.active_column += 1;
+    fn pivot_for(&self, row: &[usize]) -> Option<&Row> {
+        row.first()
+            .and_then(|leading_column| self.pivots[*leading_column].as_ref())
     }
 }
 
@@ -116,4 +88,12 @@ mod test {
         let rank = Ranker::from_parity_check_matrix(&matrix).get_rank();
         assert_eq!(rank, 4);
     }
+
+    #[test]
+    fn rank_ignores_empty_and_duplicated_rows() {
+        let checks = vec![vec![0, 2], vec![], vec![0, 2], vec![1, 2], vec![0, 1]];
+        let matrix = ParityCheckMatrix::with_n_bits(3).with_checks(checks);
+        let rank = Ranker::from_parity_check_matrix(&matrix).get_rank();
+        assert_eq!(rank, 2);
+    }
 }
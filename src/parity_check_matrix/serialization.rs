@@ -0,0 +1,77 @@
+//! Serde support for parity check matrices.
+//!
+//! A matrix is serialized as its dimensions and the list of its edges, independently of the
+//! internal representation.
+
+use super::ParityCheckMatrix;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct SerializedParityCheckMatrix {
+    n_bits: usize,
+    n_checks: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+impl From<ParityCheckMatrix> for SerializedParityCheckMatrix {
+    fn from(matrix: ParityCheckMatrix) -> Self {
+        Self {
+            n_bits: matrix.get_n_bits(),
+            n_checks: matrix.get_n_checks(),
+            edges: matrix.edges_iter().collect(),
+        }
+    }
+}
+
+impl TryFrom<SerializedParityCheckMatrix> for ParityCheckMatrix {
+    type Error = String;
+
+    fn try_from(serialized: SerializedParityCheckMatrix) -> Result<Self, Self::Error> {
+        let mut checks = vec![Vec::new(); serialized.n_checks];
+        for (check, bit) in serialized.edges {
+            if check >= serialized.n_checks || bit >= serialized.n_bits {
+                return Err(format!("edge ({}, {}) is out of bounds", check, bit));
+            }
+            checks[check].push(bit);
+        }
+        Ok(ParityCheckMatrix::with_n_bits(serialized.n_bits).with_checks(checks))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matrix() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![0, 1, 4],
+            vec![],
+            vec![1, 2],
+        ])
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = serde_json::to_string(&matrix()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"n_bits":6,"n_checks":3,"edges":[[0,0],[0,1],[0,4],[2,1],[2,2]]}"#
+        );
+        let deserialized: ParityCheckMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, matrix());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let bytes = bincode::serialize(&matrix()).unwrap();
+        let deserialized: ParityCheckMatrix = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, matrix());
+    }
+
+    #[test]
+    fn out_of_bounds_edges_are_rejected() {
+        let json = r#"{"n_bits":2,"n_checks":1,"edges":[[0,2]]}"#;
+        assert!(serde_json::from_str::<ParityCheckMatrix>(json).is_err());
+    }
+}
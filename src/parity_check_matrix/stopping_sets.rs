@@ -0,0 +1,522 @@
+//! Stopping sets of the Tanner graph of a code.
+//!
+//! A stopping set is a non empty set of bits such that every check connected to one of these
+//! bits is connected to at least two of them. The peeling decoder of the erasure channel fails
+//! exactly when the erased bits contain a stopping set, so the smallest stopping sets dominate
+//! the failures at low erasure probability.
+//!
+//! # Example
+//!
+//! ```
+//! # use believer::{ParityCheckMatrix, StoppingSetSize};
+//! let code = ParityCheckMatrix::repetition_code_with_n_bits(3);
+//!
+//! assert_eq!(code.get_stopping_sets_up_to(3, 1000), Some(vec![vec![0, 1, 2]]));
+//! assert_eq!(code.get_minimum_stopping_set_size_up_to(3, 1000), StoppingSetSize::Exactly(3));
+//! ```
+
+use super::ParityCheckMatrix;
+use crate::SimulationResult;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The outcome of a search for the smallest stopping set of a code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoppingSetSize {
+    /// The smallest stopping sets have this number of bits.
+    Exactly(usize),
+    /// There is no stopping set with at most this number of bits.
+    GreaterThan(usize),
+    /// The search was stopped before finding an answer.
+    Unknown,
+}
+
+/// The number of sets of each weight whose erasure makes an erasure decoder fail.
+///
+/// For the peeling decoder, these are the stopping sets. For maximum likelihood decoding, these
+/// are the supports of the non zero codewords, counted by the weight enumerator. The counts are
+/// only known up to a maximum weight.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// let code = ParityCheckMatrix::hamming_code_with_n_checks(3);
+/// let spectrum = code.get_stopping_set_spectrum_up_to(4, 1000).unwrap();
+/// assert_eq!(spectrum.get_counts(), &[0, 0, 0, 10, 23]);
+///
+/// let spectrum = StoppingSetSpectrum::from_weight_enumerator(&[1, 0, 0, 7, 7]);
+/// assert_eq!(spectrum.get_counts(), &[0, 0, 0, 7, 7]);
+/// assert_eq!(spectrum.get_max_weight(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoppingSetSpectrum {
+    counts: Vec<u64>,
+}
+
+impl StoppingSetSpectrum {
+    /// Creates a spectrum where the number of sets of weight `w` is `counts[w]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `counts` is empty or if it has sets of weight 0.
+    pub fn with_counts(counts: Vec<u64>) -> Self {
+        if counts.first() != Some(&0) {
+            panic!("invalid spectrum");
+        }
+        Self { counts }
+    }
+
+    /// Creates the spectrum of maximum likelihood decoding from the number of codewords of each
+    /// weight, such as the output of `get_weight_enumerator_up_to`.
+    ///
+    /// The zero codeword is not counted since erasing no bits never makes the decoder fail.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `enumerator` is empty.
+    pub fn from_weight_enumerator(enumerator: &[u64]) -> Self {
+        if enumerator.is_empty() {
+            panic!("invalid spectrum");
+        }
+        let mut counts = enumerator.to_vec();
+        counts[0] = 0;
+        Self { counts }
+    }
+
+    /// Returns the number of sets of each weight from 0 to the maximum weight.
+    pub fn get_counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Returns the largest weight whose number of sets is known.
+    pub fn get_max_weight(&self) -> usize {
+        self.counts.len() - 1
+    }
+}
+
+/// An analytic estimate of the failure rate of an erasure decoder that can be compared to a
+/// `SimulationResult`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnalyticEstimate {
+    erasure_prob: f64,
+    failure_rate: f64,
+}
+
+impl AnalyticEstimate {
+    pub(super) fn from(erasure_prob: f64, failure_rate: f64) -> Self {
+        Self {
+            erasure_prob,
+            failure_rate,
+        }
+    }
+
+    /// Returns the erasure probability of the estimate.
+    pub fn get_erasure_prob(&self) -> f64 {
+        self.erasure_prob
+    }
+
+    /// Returns the estimated failure rate.
+    pub fn get_failure_rate(&self) -> f64 {
+        self.failure_rate
+    }
+
+    /// Returns the simulated failure rate of `result` divided by the estimated failure rate, or
+    /// `None` if the estimated failure rate is 0.
+    ///
+    /// For a union bound on the complete spectrum, the ratio is at most 1 up to the noise of
+    /// the simulation and gets close to 1 at low erasure probability. The estimate is 0 at an
+    /// erasure probability of 0 or if there are no stopping sets up to the searched weight.
+    pub fn get_ratio_to(&self, result: &SimulationResult) -> Option<f64> {
+        if self.failure_rate == 0.0 {
+            return None;
+        }
+        Some(result.get_failure_rate() / self.failure_rate)
+    }
+}
+
+// The stopping sets are enumerated by a branch and bound search. Each branch starts from its
+// smallest bit and only adds larger bits. If some check is connected to a single bit of the
+// current set, every stopping set containing the set also contains one of the other allowed
+// bits of this check. Thus, the search branches on these bits, excluding the previous ones from
+// the next branches so that each set is found once. Otherwise, the set is a stopping set and
+// the search branches on all the allowed bits to find the larger stopping sets containing it.
+//
+// The branches of the smallest bits are explored in parallel and share the count of visited
+// sets, so the search stops everywhere once it exceeds its budget.
+pub(super) struct StoppingSetFinder<'a> {
+    matrix: &'a ParityCheckMatrix,
+    max_n_steps: usize,
+    n_steps: AtomicUsize,
+}
+
+// The set being extended. A bit is allowed if it can still be added to the set.
+struct Branch {
+    bits: Vec<usize>,
+    is_allowed: Vec<bool>,
+    n_bits_in_check: Vec<usize>,
+}
+
+impl<'a> StoppingSetFinder<'a> {
+    pub(super) fn from(matrix: &'a ParityCheckMatrix, max_n_steps: usize) -> Self {
+        Self {
+            matrix,
+            max_n_steps,
+            n_steps: AtomicUsize::new(0),
+        }
+    }
+
+    // Returns None if the search visits more than `max_n_steps` sets. Since the search only
+    // stops once the budget is exceeded, this doesn't depend on the scheduling of the threads.
+    pub(super) fn get_stopping_sets_up_to(&self, max_weight: usize) -> Option<Vec<Vec<usize>>> {
+        let n_bits = self.matrix.get_n_bits();
+        let sets_of_branches: Option<Vec<Vec<Vec<usize>>>> = (0..n_bits)
+            .into_par_iter()
+            .map(|first_bit| {
+                let mut branch = Branch {
+                    bits: Vec::with_capacity(max_weight),
+                    is_allowed: (0..n_bits).map(|bit| bit > first_bit).collect(),
+                    n_bits_in_check: vec![0; self.matrix.get_n_checks()],
+                };
+                let mut sets = Vec::new();
+                if max_weight == 0 {
+                    return Some(sets);
+                }
+                if !self.visit() {
+                    return None;
+                }
+                self.add_bit(first_bit, &mut branch);
+                if self.extend(&mut branch, max_weight, &mut sets) {
+                    Some(sets)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let mut sets: Vec<Vec<usize>> = sets_of_branches?.into_iter().flatten().collect();
+        sets.sort_by(|set, other| set.len().cmp(&other.len()).then_with(|| set.cmp(other)));
+        Some(sets)
+    }
+
+    // The weights are tried in increasing order, so the first weight with a stopping set is the
+    // minimum size. The budget is shared by all weights.
+    pub(super) fn get_minimum_size_up_to(&self, max_weight: usize) -> StoppingSetSize {
+        for weight in 1..=max_weight.min(self.matrix.get_n_bits()) {
+            match self.get_stopping_sets_up_to(weight) {
+                None => return StoppingSetSize::Unknown,
+                Some(sets) if !sets.is_empty() => return StoppingSetSize::Exactly(weight),
+                Some(_) => (),
+            }
+        }
+        StoppingSetSize::GreaterThan(max_weight)
+    }
+
+    // Returns false if the search was stopped.
+    fn extend(&self, branch: &mut Branch, max_weight: usize, sets: &mut Vec<Vec<usize>>) -> bool {
+        let candidates = match self.get_deficient_check_of(branch) {
+            Some(check) => {
+                let candidates = self.get_allowed_bits_of_check(check, branch);
+                if candidates.is_empty() || branch.bits.len() == max_weight {
+                    return true;
+                }
+                candidates
+            }
+            None => {
+                let mut set = branch.bits.clone();
+                set.sort_unstable();
+                sets.push(set);
+                if branch.bits.len() == max_weight {
+                    return true;
+                }
+                (0..branch.is_allowed.len())
+                    .filter(|bit| branch.is_allowed[*bit])
+                    .collect()
+            }
+        };
+        let mut is_complete = true;
+        for bit in candidates.iter() {
+            if !self.visit() {
+                is_complete = false;
+                break;
+            }
+            self.add_bit(*bit, branch);
+            let is_branch_complete = self.extend(branch, max_weight, sets);
+            self.remove_bit(*bit, branch);
+            if !is_branch_complete {
+                is_complete = false;
+                break;
+            }
+        }
+        candidates
+            .iter()
+            .for_each(|bit| branch.is_allowed[*bit] = true);
+        is_complete
+    }
+
+    // Counts a visited set and returns false if the budget is exceeded.
+    fn visit(&self) -> bool {
+        self.n_steps.fetch_add(1, Ordering::Relaxed) < self.max_n_steps
+    }
+
+    // The excluded bits stay excluded once removed, until the candidates are restored by
+    // `extend`.
+    fn add_bit(&self, bit: usize, branch: &mut Branch) {
+        branch.bits.push(bit);
+        branch.is_allowed[bit] = false;
+        self.get_checks_of(bit)
+            .iter()
+            .for_each(|check| branch.n_bits_in_check[*check] += 1);
+    }
+
+    fn remove_bit(&self, bit: usize, branch: &mut Branch) {
+        branch.bits.pop();
+        self.get_checks_of(bit)
+            .iter()
+            .for_each(|check| branch.n_bits_in_check[*check] -= 1);
+    }
+
+    // The check connected to a single bit of the set with the fewest allowed bits, which gives
+    // the fewest branches. Ties go to the smallest check.
+    fn get_deficient_check_of(&self, branch: &Branch) -> Option<usize> {
+        branch
+            .bits
+            .iter()
+            .flat_map(|bit| self.get_checks_of(*bit).iter().cloned())
+            .filter(|check| branch.n_bits_in_check[*check] == 1)
+            .map(|check| (self.get_allowed_bits_of_check(check, branch).len(), check))
+            .min()
+            .map(|(_, check)| check)
+    }
+
+    fn get_allowed_bits_of_check(&self, check: usize, branch: &Branch) -> Vec<usize> {
+        self.matrix
+            .get_check(check)
+            .unwrap()
+            .iter()
+            .filter(|bit| branch.is_allowed[**bit])
+            .cloned()
+            .collect()
+    }
+
+    fn get_checks_of(&self, bit: usize) -> &[usize] {
+        self.matrix.get_checks_adjacent_to_bit(bit).unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        CodeGenerator, Decoder, DecodingResult, ErasureDecoder, ErasureStrategy,
+        RegularLDPCCodeGenerator,
+    };
+    use itertools::Itertools;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn is_stopping_set(matrix: &ParityCheckMatrix, bits: &[usize]) -> bool {
+        !bits.is_empty()
+            && matrix
+                .checks_iter()
+                .all(|check| check.iter().filter(|bit| bits.contains(bit)).count() != 1)
+    }
+
+    fn get_stopping_sets_by_brute_force(
+        matrix: &ParityCheckMatrix,
+        max_weight: usize,
+    ) -> Vec<Vec<usize>> {
+        (1..=max_weight)
+            .flat_map(|weight| (0..matrix.get_n_bits()).combinations(weight))
+            .filter(|bits| is_stopping_set(matrix, bits))
+            .collect()
+    }
+
+    // Sums the probabilities of the erasures that the decoder doesn't recover.
+    fn get_exact_failure_rate_of(
+        matrix: &ParityCheckMatrix,
+        strategy: ErasureStrategy,
+        prob: f64,
+    ) -> f64 {
+        let n_bits = matrix.get_n_bits();
+        let mut decoder = ErasureDecoder::with_prob(prob)
+            .with_strategy(strategy)
+            .for_code(matrix.clone());
+        (0..=n_bits)
+            .flat_map(|weight| (0..n_bits).combinations(weight))
+            .filter(|erasure| decoder.decode(erasure).is_failure())
+            .map(|erasure| {
+                let weight = erasure.len();
+                prob.powi(weight as i32) * (1.0 - prob).powi((n_bits - weight) as i32)
+            })
+            .sum()
+    }
+
+    fn hamming_code() -> ParityCheckMatrix {
+        ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ])
+    }
+
+    #[test]
+    fn stopping_sets_of_hamming_code() {
+        let matrix = hamming_code();
+        let sets = StoppingSetFinder::from(&matrix, 100_000)
+            .get_stopping_sets_up_to(7)
+            .unwrap();
+        assert_eq!(sets, get_stopping_sets_by_brute_force(&matrix, 7));
+        assert_eq!(sets[0], vec![0, 1, 2]);
+        assert_eq!(sets.iter().filter(|set| set.len() == 3).count(), 10);
+    }
+
+    #[test]
+    fn stopping_sets_of_ldpc_code_up_to_a_weight() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let matrix = RegularLDPCCodeGenerator::new(3, 6, 4, 6).generate_with_rng(&mut rng);
+        let sets = StoppingSetFinder::from(&matrix, 1_000_000)
+            .get_stopping_sets_up_to(6)
+            .unwrap();
+        assert_eq!(sets, get_stopping_sets_by_brute_force(&matrix, 6));
+    }
+
+    #[test]
+    fn peeling_fails_exactly_on_erasures_containing_a_stopping_set() {
+        let matrix = hamming_code();
+        let sets = matrix.get_stopping_sets_up_to(7, 100_000).unwrap();
+        let mut decoder = ErasureDecoder::with_prob(0.1)
+            .with_strategy(ErasureStrategy::Peeling)
+            .for_code(matrix);
+        for erasure in (0..=7).flat_map(|weight| (0..7).combinations(weight)) {
+            let contains_stopping_set = sets
+                .iter()
+                .any(|set| set.iter().all(|bit| erasure.contains(bit)));
+            assert_eq!(decoder.decode(&erasure).is_failure(), contains_stopping_set);
+        }
+    }
+
+    #[test]
+    fn bits_without_checks_are_stopping_sets() {
+        let matrix = ParityCheckMatrix::with_n_bits(4).with_checks(vec![vec![0, 1], vec![1, 2]]);
+        let finder = StoppingSetFinder::from(&matrix, 1000);
+        assert_eq!(finder.get_stopping_sets_up_to(1), Some(vec![vec![3]]));
+        assert_eq!(
+            finder.get_minimum_size_up_to(4),
+            StoppingSetSize::Exactly(1)
+        );
+    }
+
+    #[test]
+    fn minimum_size_of_hamming_code() {
+        let matrix = hamming_code();
+        assert_eq!(
+            StoppingSetFinder::from(&matrix, 1000).get_minimum_size_up_to(7),
+            StoppingSetSize::Exactly(3)
+        );
+        assert_eq!(
+            StoppingSetFinder::from(&matrix, 1000).get_minimum_size_up_to(2),
+            StoppingSetSize::GreaterThan(2)
+        );
+        assert_eq!(
+            StoppingSetFinder::from(&ParityCheckMatrix::identity_with_n_bits(3), 1000)
+                .get_minimum_size_up_to(5),
+            StoppingSetSize::GreaterThan(5)
+        );
+    }
+
+    // The only stopping set and the only non zero codeword of a repetition code is the set of
+    // all the bits.
+    #[test]
+    fn union_bound_of_repetition_code_is_exact() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(5);
+        let stopping_sets = matrix.get_stopping_set_spectrum_up_to(5, 1000).unwrap();
+        let codewords = StoppingSetSpectrum::from_weight_enumerator(
+            &matrix.get_weight_enumerator_up_to(5),
+        );
+        assert_eq!(stopping_sets, codewords);
+
+        for prob in [0.0, 0.05, 0.3, 0.8, 1.0].iter() {
+            let estimate = matrix.erasure_failure_union_bound(*prob, &stopping_sets);
+            assert_eq!(estimate.get_erasure_prob(), *prob);
+            for strategy in [ErasureStrategy::MaximumLikelihood, ErasureStrategy::Peeling].iter() {
+                let expected = get_exact_failure_rate_of(&matrix, *strategy, *prob);
+                assert!((estimate.get_failure_rate() - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn union_bounds_of_hamming_code_bound_the_exact_failure_rates() {
+        let matrix = hamming_code();
+        let stopping_sets = matrix.get_stopping_set_spectrum_up_to(7, 100_000).unwrap();
+        let codewords = StoppingSetSpectrum::from_weight_enumerator(
+            &matrix.get_weight_enumerator_up_to(7),
+        );
+        for prob in [0.001, 0.01, 0.1, 0.3].iter() {
+            let peeling = get_exact_failure_rate_of(&matrix, ErasureStrategy::Peeling, *prob);
+            let ml = get_exact_failure_rate_of(&matrix, ErasureStrategy::MaximumLikelihood, *prob);
+            let peeling_bound = matrix.erasure_failure_union_bound(*prob, &stopping_sets);
+            let ml_bound = matrix.erasure_failure_union_bound(*prob, &codewords);
+            assert!(peeling <= peeling_bound.get_failure_rate() + 1e-12);
+            assert!(ml <= ml_bound.get_failure_rate() + 1e-12);
+        }
+        // At low erasure probability, the smallest sets dominate and the bounds are tight.
+        let prob = 0.001;
+        let ml = get_exact_failure_rate_of(&matrix, ErasureStrategy::MaximumLikelihood, prob);
+        let ml_bound = matrix.erasure_failure_union_bound(prob, &codewords);
+        assert!(ml / ml_bound.get_failure_rate() > 0.99);
+        assert_eq!(matrix.erasure_failure_union_bound(0.9, &codewords).get_failure_rate(), 1.0);
+    }
+
+    #[test]
+    fn simulated_failure_rates_are_close_to_the_union_bound() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let spectrum = matrix.get_stopping_set_spectrum_up_to(3, 1000).unwrap();
+        let estimate = matrix.erasure_failure_union_bound(0.3, &spectrum);
+        let mut decoder = ErasureDecoder::with_prob(0.3).for_code(matrix);
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let result = decoder.simulate_n_iterations_with_rng(100_000, &mut rng);
+
+        let rate = estimate.get_failure_rate();
+        let relative_std = ((1.0 - rate) / (rate * 100_000.0)).sqrt();
+        assert!((estimate.get_ratio_to(&result).unwrap() - 1.0).abs() < 4.0 * relative_std);
+    }
+
+    #[test]
+    fn ratios_to_zero_estimates_are_none() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(3);
+        let result = SimulationResult::with_n_successes_and_failures(10, 0);
+        let spectrum = matrix.get_stopping_set_spectrum_up_to(3, 1000).unwrap();
+        assert_eq!(matrix.erasure_failure_union_bound(0.0, &spectrum).get_ratio_to(&result), None);
+
+        let spectrum = matrix.get_stopping_set_spectrum_up_to(2, 1000).unwrap();
+        assert_eq!(matrix.erasure_failure_union_bound(0.5, &spectrum).get_ratio_to(&result), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn spectra_with_sets_of_weight_zero_panic() {
+        StoppingSetSpectrum::with_counts(vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn spectra_with_too_many_bits_panic() {
+        let matrix = ParityCheckMatrix::repetition_code_with_n_bits(2);
+        let spectrum = StoppingSetSpectrum::with_counts(vec![0, 0, 0, 1]);
+        matrix.erasure_failure_union_bound(0.1, &spectrum);
+    }
+
+    #[test]
+    fn search_is_stopped_after_the_budget() {
+        let matrix = hamming_code();
+        assert_eq!(
+            StoppingSetFinder::from(&matrix, 10).get_stopping_sets_up_to(7),
+            None
+        );
+        assert_eq!(
+            StoppingSetFinder::from(&matrix, 10).get_minimum_size_up_to(7),
+            StoppingSetSize::Unknown
+        );
+    }
+}
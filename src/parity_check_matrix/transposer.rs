@@ -82,9 +82,25 @@ mod test {
             vec![0, 2],
             vec![0, 2],
             vec![1, 2],
+            vec![],
             vec![0, 1],
         ]);
 
         assert_eq!(transposed, expected);
     }
+
+    #[test]
+    fn transposing_twice_gives_back_the_original_matrix() {
+        let matrix = ParityCheckMatrix::with_n_bits(6).with_checks(vec![
+            vec![0, 1, 4],
+            vec![],
+            vec![1, 2],
+            vec![],
+        ]);
+        let transposed = Transposer::from(&matrix).get_transposed_matrix();
+
+        assert_eq!(transposed.get_n_bits(), 4);
+        assert_eq!(transposed.get_n_checks(), 6);
+        assert_eq!(Transposer::from(&transposed).get_transposed_matrix(), matrix);
+    }
 }
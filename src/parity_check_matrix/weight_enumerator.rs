@@ -0,0 +1,117 @@
+use super::ParityCheckMatrix;
+use rayon::prelude::*;
+
+const BITS_PER_WORD: usize = 64;
+
+// Counts the codewords of each weight by encoding every message with a generator matrix.
+//
+// Generators are stored as packed bits so that a codeword is obtained with a few xor. Messages
+// are split between threads and each thread only keeps its own counts.
+pub(super) struct WeightEnumerator {
+    generators: Vec<Vec<u64>>,
+    n_words: usize,
+}
+
+impl WeightEnumerator {
+    pub(super) fn from(matrix: &ParityCheckMatrix) -> Self {
+        let n_words = matrix.get_n_bits().div_ceil(BITS_PER_WORD);
+        let generators = matrix
+            .get_generator_matrix()
+            .checks_iter()
+            .map(|generator| {
+                let mut words = vec![0; n_words];
+                generator.iter().for_each(|bit| {
+                    words[bit / BITS_PER_WORD] ^= 1 << (bit % BITS_PER_WORD);
+                });
+                words
+            })
+            .collect();
+        Self {
+            generators,
+            n_words,
+        }
+    }
+
+    pub(super) fn get_counts_up_to(&self, max_weight: usize) -> Vec<u64> {
+        if self.generators.len() >= BITS_PER_WORD {
+            panic!("too many codewords to enumerate");
+        }
+        let n_messages: u64 = 1 << self.generators.len();
+        (0..n_messages)
+            .into_par_iter()
+            .fold(
+                || vec![0; max_weight + 1],
+                |mut counts, message| {
+                    let weight = self.get_weight_of_codeword(message);
+                    if weight <= max_weight {
+                        counts[weight] += 1;
+                    }
+                    counts
+                },
+            )
+            .reduce(
+                || vec![0; max_weight + 1],
+                |mut counts, other_counts| {
+                    counts
+                        .iter_mut()
+                        .zip(other_counts)
+                        .for_each(|(count, other_count)| *count += other_count);
+                    counts
+                },
+            )
+    }
+
+    fn get_weight_of_codeword(&self, message: u64) -> usize {
+        let mut codeword = vec![0_u64; self.n_words];
+        self.generators
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| message & (1 << index) != 0)
+            .for_each(|(_, generator)| {
+                codeword
+                    .iter_mut()
+                    .zip(generator)
+                    .for_each(|(word, generator_word)| *word ^= generator_word)
+            });
+        codeword.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weight_distribution_of_hamming_code() {
+        let matrix = ParityCheckMatrix::with_n_bits(7).with_checks(vec![
+            vec![0, 1, 2, 4],
+            vec![0, 1, 3, 5],
+            vec![0, 2, 3, 6],
+        ]);
+        let enumerator = WeightEnumerator::from(&matrix);
+
+        assert_eq!(enumerator.get_counts_up_to(7), vec![1, 0, 0, 7, 7, 0, 0, 1]);
+        assert_eq!(enumerator.get_counts_up_to(3), vec![1, 0, 0, 7]);
+    }
+
+    #[test]
+    fn weight_distribution_of_code_longer_than_a_word() {
+        // The repetition code on 70 bits.
+        let checks = (0..69).map(|bit| vec![bit, bit + 1]).collect();
+        let matrix = ParityCheckMatrix::with_n_bits(70).with_checks(checks);
+        let counts = WeightEnumerator::from(&matrix).get_counts_up_to(70);
+
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts[70], 1);
+        assert_eq!(counts.iter().sum::<u64>(), 2);
+    }
+
+    #[test]
+    fn weight_distribution_without_codeword() {
+        let matrix = ParityCheckMatrix::identity_with_n_bits(3);
+        assert_eq!(
+            WeightEnumerator::from(&matrix).get_counts_up_to(2),
+            vec![1, 0, 0]
+        );
+    }
+}
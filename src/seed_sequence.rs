@@ -0,0 +1,98 @@
+//! Independent random number generators for reproducible parallel simulations.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A sequence of independent random number generators derived from a single random number
+/// generator.
+///
+/// The sequence draws a 256 bits ChaCha key from the random number generator. The generator of
+/// index `i` uses this key with the stream `i`, so the generators of different indices never
+/// share their random numbers. Giving one generator to each unit of work, such as a code or a
+/// chunk of errors, makes a parallel simulation reproducible whatever the number of threads and
+/// the order in which the units are run.
+///
+/// # Example
+///
+/// ```
+/// # use believer::*;
+/// # use rand::{Rng, SeedableRng};
+/// # use rand_chacha::ChaCha8Rng;
+/// let mut rng = ChaCha8Rng::seed_from_u64(123);
+/// let seeds = SeedSequence::from_rng(&mut rng);
+///
+/// let first: Vec<u64> = seeds.get_n_rngs(3).iter_mut().map(|rng| rng.gen()).collect();
+/// let second: Vec<u64> = (0..3).map(|index| seeds.get_rng(index).gen()).collect();
+/// assert_eq!(first, second);
+/// assert_ne!(first[0], first[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedSequence {
+    key: [u8; 32],
+}
+
+impl SeedSequence {
+    /// Creates a sequence from a key drawn from `rng`.
+    pub fn from_rng<R: Rng>(rng: &mut R) -> Self {
+        Self { key: rng.gen() }
+    }
+
+    /// Returns the random number generator of the given index.
+    ///
+    /// The same index always gives a generator in the same state.
+    pub fn get_rng(&self, index: u64) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::from_seed(self.key);
+        rng.set_stream(index);
+        rng
+    }
+
+    /// Returns the random number generators of the indices from 0 to `n_rngs - 1`.
+    pub fn get_n_rngs(&self, n_rngs: usize) -> Vec<ChaCha8Rng> {
+        (0..n_rngs as u64)
+            .map(|index| self.get_rng(index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_rng_gives_the_same_sequence() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let seeds = SeedSequence::from_rng(&mut rng.clone());
+        assert_eq!(seeds, SeedSequence::from_rng(&mut rng.clone()));
+
+        let numbers: Vec<u64> = (0..100).map(|_| seeds.get_rng(7).gen()).collect();
+        assert!(numbers.iter().all(|number| *number == numbers[0]));
+
+        rng.gen::<u64>();
+        assert_ne!(seeds, SeedSequence::from_rng(&mut rng));
+    }
+
+    // The generators of different indices share the key but not the stream, so their outputs
+    // never overlap.
+    #[test]
+    fn generators_of_different_indices_are_different() {
+        let seeds = SeedSequence::from_rng(&mut ChaCha8Rng::seed_from_u64(123));
+        let outputs: Vec<Vec<u64>> = seeds
+            .get_n_rngs(50)
+            .iter_mut()
+            .map(|rng| (0..20).map(|_| rng.gen()).collect())
+            .collect();
+        let mut numbers: Vec<u64> = outputs.iter().flatten().cloned().collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        assert_eq!(numbers.len(), 50 * 20);
+    }
+
+    // Unlike a 64 bits seed, every bit of the key changes the generators.
+    #[test]
+    fn generators_depend_on_the_whole_key() {
+        let seeds = SeedSequence::from_rng(&mut ChaCha8Rng::seed_from_u64(123));
+        let mut other = seeds.clone();
+        other.key[31] ^= 1;
+        assert_ne!(seeds.get_rng(0).gen::<u64>(), other.get_rng(0).gen::<u64>());
+    }
+}